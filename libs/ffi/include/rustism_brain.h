@@ -0,0 +1,30 @@
+/* runs networks evolved by the sim, link against librustism_brain */
+#ifndef RUSTISM_BRAIN_H
+#define RUSTISM_BRAIN_H
+
+#include <stddef.h>
+#include <sys/types.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct Brain Brain;
+
+/* `layers` are the neuron counts from the inputs to the outputs, `data` the weights as the sim
+ * exports them, returns NULL if they do not fit each other */
+Brain *rustism_brain_new(const size_t *layers, size_t layer_count, const float *data, size_t data_len);
+
+size_t rustism_brain_inputs(const Brain *brain);
+size_t rustism_brain_outputs(const Brain *brain);
+
+/* writes the outputs for `inputs` and returns how many there are, -1 on bad arguments */
+ssize_t rustism_brain_propagate(const Brain *brain, const float *inputs, size_t input_len, float *outputs, size_t output_len);
+
+void rustism_brain_free(Brain *brain);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,146 @@
+use std::ptr;
+use std::slice;
+use lib_neural_network::{LayerTopology, Network};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+//a C ABI for running exported brains in other applications, see include/rustism_brain.h,
+//every function checks its arguments and reports bad ones instead of panicking across the
+//boundary
+
+//an evolved network with the neuron counts it was made with
+pub struct Brain {
+    layers: Vec<usize>,
+    network: Network,
+}
+
+impl Brain {
+    fn new(layers: &[usize], data: &[f32]) -> Option<Self> {
+        if layers.len() < 2 || layers.contains(&0) {
+            return None;
+        }
+
+        let topology: Vec<_> = layers.iter().map(|&neurons| LayerTopology::new(neurons)).collect();
+        let mut network = Network::random(&mut ChaCha8Rng::seed_from_u64(0), &topology);
+        network.load_data(data.iter().copied()).ok()?;
+        Some(Self { layers: layers.to_vec(), network })
+    }
+
+    fn inputs(&self) -> usize {
+        self.layers[0]
+    }
+
+    fn outputs(&self) -> usize {
+        self.layers[self.layers.len() - 1]
+    }
+}
+
+/// # Safety
+/// `layers` points to `layer_count` neuron counts and `data` to `data_len` weights laid out like
+/// Network::data(), both only have to live for the call
+///
+/// returns null if the layers are invalid or the data does not fit them
+#[no_mangle]
+pub unsafe extern "C" fn rustism_brain_new(layers: *const usize, layer_count: usize, data: *const f32, data_len: usize) -> *mut Brain {
+    if layers.is_null() || (data.is_null() && data_len > 0) {
+        return ptr::null_mut();
+    }
+    let layers = slice::from_raw_parts(layers, layer_count);
+    let data = if data_len == 0 { &[] } else { slice::from_raw_parts(data, data_len) };
+
+    match Brain::new(layers, data) {
+        Some(brain) => Box::into_raw(Box::new(brain)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// # Safety
+/// `brain` is null or was returned by rustism_brain_new() and not freed yet
+#[no_mangle]
+pub unsafe extern "C" fn rustism_brain_inputs(brain: *const Brain) -> usize {
+    brain.as_ref().map_or(0, Brain::inputs)
+}
+
+/// # Safety
+/// `brain` is null or was returned by rustism_brain_new() and not freed yet
+#[no_mangle]
+pub unsafe extern "C" fn rustism_brain_outputs(brain: *const Brain) -> usize {
+    brain.as_ref().map_or(0, Brain::outputs)
+}
+
+/// # Safety
+/// `brain` was returned by rustism_brain_new() and not freed yet, `inputs` points to
+/// `input_len` values and `outputs` has room for `output_len`
+///
+/// writes the outputs and returns how many there are, or -1 if an argument is null or a length
+/// does not match the brain
+#[no_mangle]
+pub unsafe extern "C" fn rustism_brain_propagate(brain: *const Brain,
+                                                 inputs: *const f32,
+                                                 input_len: usize,
+                                                 outputs: *mut f32,
+                                                 output_len: usize) -> isize {
+    let Some(brain) = brain.as_ref() else {
+        return -1;
+    };
+    if inputs.is_null() || outputs.is_null() || input_len != brain.inputs() || output_len < brain.outputs() {
+        return -1;
+    }
+
+    let result = brain.network.propagate(slice::from_raw_parts(inputs, input_len).to_vec());
+    slice::from_raw_parts_mut(outputs, result.len()).copy_from_slice(&result);
+    result.len() as isize
+}
+
+/// # Safety
+/// `brain` is null or was returned by rustism_brain_new() and not freed yet
+#[no_mangle]
+pub unsafe extern "C" fn rustism_brain_free(brain: *mut Brain) {
+    if !brain.is_null() {
+        drop(Box::from_raw(brain));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(layers: &[usize]) -> Vec<f32> {
+        let topology: Vec<_> = layers.iter().map(|&neurons| LayerTopology::new(neurons)).collect();
+        Network::random(&mut ChaCha8Rng::seed_from_u64(1), &topology).data().collect()
+    }
+
+    #[test]
+    fn test_propagate() {
+        let layers = [3, 4, 2];
+        let data = data(&layers);
+        let expected = Network::from_data(&[LayerTopology::new(3), LayerTopology::new(4), LayerTopology::new(2)], data.clone())
+            .propagate(vec![0.1, 0.2, 0.3]);
+
+        unsafe {
+            let brain = rustism_brain_new(layers.as_ptr(), layers.len(), data.as_ptr(), data.len());
+            assert!(!brain.is_null());
+            assert_eq!((rustism_brain_inputs(brain), rustism_brain_outputs(brain)), (3, 2));
+
+            let mut outputs = [0.0; 2];
+            let written = rustism_brain_propagate(brain, [0.1, 0.2, 0.3].as_ptr(), 3, outputs.as_mut_ptr(), outputs.len());
+            assert_eq!(written, 2);
+            assert_eq!(outputs.to_vec(), expected);
+
+            assert_eq!(rustism_brain_propagate(brain, [0.1].as_ptr(), 1, outputs.as_mut_ptr(), outputs.len()), -1);
+            rustism_brain_free(brain);
+        }
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        let layers = [3, 4, 2];
+        let data = data(&layers);
+
+        unsafe {
+            assert!(rustism_brain_new(layers.as_ptr(), layers.len(), data.as_ptr(), data.len() - 1).is_null());
+            assert!(rustism_brain_new(layers.as_ptr(), 1, data.as_ptr(), data.len()).is_null());
+            assert!(rustism_brain_new(ptr::null(), 0, data.as_ptr(), data.len()).is_null());
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use std::ops::Range;
+
+use crate::*;
+
+//fills the genes of a newcomer
+pub(crate) type Newcomer = Box<dyn Fn(&mut dyn RngCore, &mut [f32])>;
+
+//the age-layered population structure (ALPS, hornby 2006): the population is split into layers
+//that only breed from themselves and the layer below, each with a limit on the age of the
+//genetic material it may keep, and the bottom layer is replaced by newcomers every `age_gap`
+//generations, so fresh genes always get time to improve before they compete with the old ones
+pub struct AgeLayers<S> {
+    //breeds the children of every layer with its selection, crossover and mutation
+    ga: GeneticAlgorithm<S>,
+    layers: usize,
+    age_gap: usize,
+    newcomer: Newcomer,
+    //of every individual, the generations since its oldest ancestor was a newcomer
+    ages: Vec<usize>,
+}
+
+impl<S> AgeLayers<S>
+where
+    S: SelectionMethod,
+{
+    pub fn new(ga: GeneticAlgorithm<S>,
+               layers: usize,
+               age_gap: usize,
+               newcomer: impl Fn(&mut dyn RngCore, &mut [f32]) + 'static) -> Self {
+        assert!(layers > 0 && age_gap > 0);
+        Self { ga, layers, age_gap, newcomer: Box::new(newcomer), ages: Vec::new() }
+    }
+
+    pub fn layers(&self) -> usize {
+        self.layers
+    }
+
+    //the oldest an individual of `layer` may be to breed in it, the polynomial scheme of the
+    //paper: 1, 2, 4, 9, 16… times the age gap, the top layer keeps everybody
+    pub fn max_age(&self, layer: usize) -> Option<usize> {
+        let factor = match layer {
+            0 => 1,
+            1 => 2,
+            _ => layer * layer,
+        };
+        (layer + 1 < self.layers).then_some(factor * self.age_gap)
+    }
+
+    //the individuals of the last evolved population the layer at `layer` holds
+    pub fn members(&self, layer: usize) -> Range<usize> {
+        tier(layer, self.layers, self.ages.len())
+    }
+
+    //of every individual of the last evolved population
+    pub fn ages(&self) -> &[usize] {
+        &self.ages
+    }
+
+    //like GeneticAlgorithm::evolve_in_place(), but every layer of the population is bred from
+    //itself and the layer below
+    pub fn evolve_in_place<V>(&mut self, rng: &mut dyn RngCore, population: &mut [V])
+    where
+        V: InPlaceIndividual,
+    {
+        //the first population is made of newcomers
+        self.ages.resize(population.len(), 0);
+
+        let max_ages: Vec<_> = (0..self.layers).map(|layer| self.max_age(layer).unwrap_or(usize::MAX)).collect();
+        let reseed = self.ga.generation > 0 && self.ga.generation.is_multiple_of(self.age_gap);
+        let (layers, newcomer) = (self.layers, &self.newcomer);
+        let mut ages = vec![0; population.len()];
+
+        self.ga.evolve_structured(rng, population, |ga, rng, rated, population, statistics| {
+            let mut selected_fitness = Vec::with_capacity(population.len() * 2);
+            for (layer, &max_age) in max_ages.iter().enumerate() {
+                //the members of the layer and the one below that are still young enough for it
+                let pool: Vec<_> = (tier(layer.saturating_sub(1), layers, population.len()).start..tier(layer, layers, population.len()).end)
+                    .filter(|&member| self.ages[member] <= max_age)
+                    .map(|member| Aged { individual: rated[member], age: self.ages[member] })
+                    .collect();
+
+                for member in tier(layer, layers, population.len()) {
+                    let child = population[member].genes_mut();
+                    if pool.is_empty() || (layer == 0 && reseed) {
+                        newcomer(rng, child);
+                        continue;
+                    }
+
+                    let parent_a = ga.selection_method.select(rng, &pool);
+                    let parent_b = ga.selection_method.select(rng, &pool);
+                    selected_fitness.extend([parent_a.fitness(), parent_b.fitness()]);
+
+                    ga.breed_child(rng, parent_a.chromosome(), parent_b.chromosome(), child, statistics);
+                    ages[member] = parent_a.age.max(parent_b.age) + 1;
+                }
+            }
+            selected_fitness
+        });
+
+        self.ages = ages;
+    }
+}
+
+impl<S> Optimizer for AgeLayers<S>
+where
+    S: SelectionMethod,
+{
+    fn evolve_in_place(&mut self, rng: &mut dyn RngCore, population: &mut [&mut dyn InPlaceIndividual]) {
+        AgeLayers::evolve_in_place(self, rng, population)
+    }
+
+    fn generation(&self) -> usize {
+        self.ga.generation
+    }
+
+    fn statistics(&self) -> Option<&Statistics> {
+        self.ga.statistics.as_ref()
+    }
+
+    fn add_observer(&mut self, observer: Box<dyn Observer>) {
+        self.ga.observers.push(observer);
+    }
+}
+
+//the individuals of a population of `len` that the tier at `index` of `tiers` equal ones holds,
+//the age layers or fitness bands
+pub(crate) fn tier(index: usize, tiers: usize, len: usize) -> Range<usize> {
+    index * len / tiers..(index + 1) * len / tiers
+}
+
+//an individual of a breeding pool with its age
+#[derive(Clone, Copy)]
+struct Aged<I> {
+    individual: I,
+    age: usize,
+}
+
+impl<I> Individual for Aged<I>
+where
+    I: Individual,
+{
+    fn create(_chromosome: Chromosome) -> Self {
+        unreachable!("aged individuals are only selected, never created")
+    }
+
+    fn fitness(&self) -> f32 {
+        self.individual.fitness()
+    }
+
+    fn fitness_stderr(&self) -> f32 {
+        self.individual.fitness_stderr()
+    }
+
+    fn chromosome(&self) -> &Chromosome {
+        self.individual.chromosome()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    struct Genes(Vec<f32>);
+
+    impl InPlaceIndividual for Genes {
+        fn fitness(&self) -> f32 {
+            self.0.iter().sum()
+        }
+
+        fn genes(&self) -> &[f32] {
+            &self.0
+        }
+
+        fn genes_mut(&mut self) -> &mut [f32] {
+            &mut self.0
+        }
+    }
+
+    fn alps() -> AgeLayers<RouletteWheelSelection> {
+        let ga = GeneticAlgorithm::new(RouletteWheelSelection::new(), UniformCrossover, GaussianMutation::new(0.0, 0.0));
+        //newcomers are recognizable by their genes of 0.5
+        AgeLayers::new(ga, 3, 2, |_, genes| genes.fill(0.5))
+    }
+
+    #[test]
+    fn test_max_age() {
+        let alps = AgeLayers::new(GeneticAlgorithm::new(RouletteWheelSelection::new(), UniformCrossover, GaussianMutation::new(0.0, 0.0)), 5, 3, |_, _| ());
+
+        let limits: Vec<_> = (0..5).map(|layer| alps.max_age(layer)).collect();
+        assert_eq!(limits, [Some(3), Some(6), Some(12), Some(27), None]);
+    }
+
+    #[test]
+    fn test_layers() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut alps = alps();
+        let mut population: Vec<_> = (0..9).map(|n| Genes(vec![1.0 + n as f32; 2])).collect();
+
+        alps.evolve_in_place(&mut rng, &mut population);
+        assert_eq!(alps.members(1), 3..6);
+        //everybody started as a newcomer and has bred once since
+        assert_eq!(alps.ages(), [1; 9]);
+        //the bottom layer only breeds from itself, the others from the layer below as well
+        assert!(population[0..3].iter().all(|individual| individual.0.iter().all(|&gene| gene <= 3.0)));
+        assert!(population[3..6].iter().all(|individual| individual.0.iter().all(|&gene| gene <= 6.0)));
+
+        alps.evolve_in_place(&mut rng, &mut population);
+        assert_eq!(alps.ages(), [2; 9]);
+
+        //the bottom layer gets newcomers every age gap
+        alps.evolve_in_place(&mut rng, &mut population);
+        assert!(population[0..3].iter().all(|individual| individual.0 == [0.5, 0.5]));
+        assert_eq!(&alps.ages()[0..3], [0; 3]);
+        assert_eq!(&alps.ages()[3..6], [3; 3]);
+        assert_eq!(alps.generation(), 3);
+        assert_eq!(alps.statistics().unwrap().generation, 2);
+    }
+
+    #[test]
+    fn test_old_genes_move_up() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut alps = alps();
+        let mut population: Vec<_> = (0..9).map(|n| Genes(vec![1.0 + n as f32; 2])).collect();
+
+        for _ in 0..5 {
+            alps.evolve_in_place(&mut rng, &mut population);
+        }
+        //nobody in a layer is older than it allows, beyond the one generation of breeding
+        for layer in 0..alps.layers() {
+            let max_age = alps.max_age(layer).unwrap_or(usize::MAX);
+            assert!(alps.ages()[alps.members(layer)].iter().all(|&age| age <= max_age.saturating_add(1)), "{:?}", alps.ages());
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//how often the values of every gene fall into each of `bins` equal bins over -range..range in
+//a population, the outer bins also count the values beyond it
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlleleHistogram {
+    genes: usize,
+    bins: usize,
+    range: f32,
+    //the bins of gene 0, then those of gene 1 and so on
+    counts: Vec<u16>,
+}
+
+impl AlleleHistogram {
+    pub fn new<'a>(genomes: impl IntoIterator<Item = &'a [f32]>, bins: usize, range: f32) -> Self {
+        assert!(bins > 0 && range > 0.0);
+
+        let mut histogram = Self { genes: 0, bins, range, counts: Vec::new() };
+        for genome in genomes {
+            if histogram.counts.is_empty() {
+                histogram.genes = genome.len();
+                histogram.counts = vec![0; genome.len() * bins];
+            }
+            assert_eq!(genome.len(), histogram.genes, "genomes of different lengths");
+            for (gene, &value) in genome.iter().enumerate() {
+                let index = gene * bins + histogram.bin(value);
+                histogram.counts[index] = histogram.counts[index].saturating_add(1);
+            }
+        }
+        histogram
+    }
+
+    pub fn genes(&self) -> usize {
+        self.genes
+    }
+
+    pub fn bins(&self) -> usize {
+        self.bins
+    }
+
+    pub fn range(&self) -> f32 {
+        self.range
+    }
+
+    //the bin `value` falls into
+    pub fn bin(&self, value: f32) -> usize {
+        let bin = ((value + self.range) / (2.0 * self.range) * self.bins as f32).floor();
+        if bin.is_nan() { 0 } else { (bin.max(0.0) as usize).min(self.bins - 1) }
+    }
+
+    //how many genomes have `gene` in each bin
+    pub fn counts(&self, gene: usize) -> &[u16] {
+        &self.counts[gene * self.bins..(gene + 1) * self.bins]
+    }
+
+    //the shannon entropy of the bins of `gene` in bits, 0.0 once every genome has it in the same
+    //bin and log2(bins) at most
+    pub fn entropy(&self, gene: usize) -> f32 {
+        let counts = self.counts(gene);
+        let total: f32 = counts.iter().map(|&count| count as f32).sum();
+        counts
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let p = count as f32 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    //of every gene
+    pub fn mean_entropy(&self) -> f32 {
+        (0..self.genes).map(|gene| self.entropy(gene)).sum::<f32>() / self.genes.max(1) as f32
+    }
+
+    //the counts of every gene's bins, laid out like counts()
+    pub fn as_slice(&self) -> &[u16] {
+        &self.counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alleles() {
+        let genomes = [[0.1, -5.0], [0.2, 0.0], [0.15, 1.9]];
+        let histogram = AlleleHistogram::new(genomes.iter().map(|genome| genome.as_slice()), 4, 2.0);
+
+        assert_eq!(histogram.genes(), 2);
+        assert_eq!(histogram.counts(0), &[0, 0, 3, 0]);
+        assert_eq!(histogram.counts(1), &[1, 0, 1, 1]);
+        assert_eq!(histogram.entropy(0), 0.0);
+        assert!((histogram.entropy(1) - 3f32.log2()).abs() < 1e-6);
+        assert!((histogram.mean_entropy() - 3f32.log2() / 2.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,189 @@
+pub use self::{flip::*, one_point::*, uniform::*};
+
+use crate::*;
+
+mod flip;
+mod one_point;
+mod uniform;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+//a packed bitset genome, bits past len are always zero so equal genomes compare equal
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BitChromosome {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitChromosome {
+    pub fn zeros(len: usize) -> Self {
+        Self { words: vec![0; len.div_ceil(WORD_BITS)], len }
+    }
+
+    pub fn random(rng: &mut dyn RngCore, len: usize) -> Self {
+        let mut chromosome = Self::zeros(len);
+        for word in &mut chromosome.words {
+            *word = rng.next_u64();
+        }
+        chromosome.clear_unused();
+        chromosome
+    }
+
+    //every gene at or above the threshold becomes a set bit
+    pub fn from_genes(genes: &Chromosome, threshold: f32) -> Self {
+        genes.iter().map(|gene| *gene >= threshold).collect()
+    }
+
+    //set bits become 1.0 genes, the others 0.0
+    pub fn to_genes(&self) -> Chromosome {
+        self.iter().map(|bit| if bit { 1.0 } else { 0.0 }).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.len, "bit {index} is out of range for {} bits", self.len);
+        self.words[index / WORD_BITS] >> (index % WORD_BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, index: usize, bit: bool) {
+        assert!(index < self.len, "bit {index} is out of range for {} bits", self.len);
+        let mask = 1 << (index % WORD_BITS);
+        if bit {
+            self.words[index / WORD_BITS] |= mask;
+        } else {
+            self.words[index / WORD_BITS] &= !mask;
+        }
+    }
+
+    pub fn flip(&mut self, index: usize) {
+        assert!(index < self.len, "bit {index} is out of range for {} bits", self.len);
+        self.words[index / WORD_BITS] ^= 1 << (index % WORD_BITS);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=bool> + '_ {
+        (0..self.len).map(|index| self.get(index))
+    }
+
+    fn clear_unused(&mut self) {
+        if !self.len.is_multiple_of(WORD_BITS) {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << (self.len % WORD_BITS)) - 1;
+            }
+        }
+    }
+}
+
+impl FromIterator<bool> for BitChromosome {
+    fn from_iter<T: IntoIterator<Item=bool>>(iter: T) -> Self {
+        let mut chromosome = Self::default();
+        for bit in iter {
+            if chromosome.len.is_multiple_of(WORD_BITS) {
+                chromosome.words.push(0);
+            }
+            chromosome.len += 1;
+            chromosome.set(chromosome.len - 1, bit);
+        }
+        chromosome
+    }
+}
+
+pub trait BitCrossoverMethod {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &BitChromosome,
+        parent_b: &BitChromosome,
+    ) -> BitChromosome;
+}
+
+pub trait BitMutationMethod {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut BitChromosome);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn bits(pattern: &str) -> BitChromosome {
+        pattern.chars().map(|c| c == '1').collect()
+    }
+
+    mod bit_access {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut chromosome = BitChromosome::zeros(130);
+            assert_eq!(chromosome.len(), 130);
+            assert_eq!(chromosome.count_ones(), 0);
+
+            chromosome.set(0, true);
+            chromosome.set(64, true);
+            chromosome.flip(129);
+            chromosome.flip(0);
+
+            assert!(!chromosome.get(0));
+            assert!(chromosome.get(64));
+            assert!(chromosome.get(129));
+            assert_eq!(chromosome.count_ones(), 2);
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_out_of_range() {
+            BitChromosome::zeros(10).get(10);
+        }
+    }
+
+    mod from_iterator {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let chromosome = bits("1011");
+            assert_eq!(chromosome.iter().collect::<Vec<_>>(), vec![true, false, true, true]);
+        }
+    }
+
+    mod random {
+        use super::*;
+
+        #[test]
+        fn test_unused_bits_are_clear() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let chromosome = BitChromosome::random(&mut rng, 70);
+
+            let rebuilt: BitChromosome = chromosome.iter().collect();
+            assert_eq!(chromosome, rebuilt);
+        }
+    }
+
+    mod genes {
+        use super::*;
+
+        #[test]
+        fn test_from_genes() {
+            let genes: Chromosome = vec![0.2, 0.5, -1.0, 3.0].into_iter().collect();
+            assert_eq!(BitChromosome::from_genes(&genes, 0.5), bits("0101"));
+        }
+
+        #[test]
+        fn test_round_trip() {
+            let chromosome = bits("1100101");
+            assert_eq!(BitChromosome::from_genes(&chromosome.to_genes(), 0.5), chromosome);
+        }
+    }
+}
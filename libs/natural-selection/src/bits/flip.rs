@@ -0,0 +1,54 @@
+use crate::*;
+
+#[derive(Clone, Debug)]
+pub struct BitFlipMutation {
+    //the chance of every single bit to flip 0.0..1.0
+    chance: f32,
+}
+
+impl BitFlipMutation {
+    pub fn new(chance: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        Self { chance }
+    }
+}
+
+impl BitMutationMethod for BitFlipMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut BitChromosome) {
+        for index in 0..child.len() {
+            if rng.gen_bool(self.chance as _) {
+                child.flip(index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn mutate(chance: f32) -> BitChromosome {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut child = BitChromosome::zeros(1000);
+        BitFlipMutation::new(chance).mutate(&mut rng, &mut child);
+        child
+    }
+
+    #[test]
+    fn test_zero_chance() {
+        assert_eq!(mutate(0.0).count_ones(), 0);
+    }
+
+    #[test]
+    fn test_full_chance() {
+        assert_eq!(mutate(1.0).count_ones(), 1000);
+    }
+
+    #[test]
+    fn test_some_chance() {
+        let flipped = mutate(0.1).count_ones();
+        assert!((70..130).contains(&flipped), "{flipped}");
+    }
+}
@@ -0,0 +1,42 @@
+use crate::*;
+
+//takes the bits before a random point from parent a and the rest from parent b
+#[derive(Clone, Debug, Default)]
+pub struct OnePointCrossover;
+
+impl BitCrossoverMethod for OnePointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &BitChromosome,
+        parent_b: &BitChromosome,
+    ) -> BitChromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        let point = rng.gen_range(0..=parent_a.len());
+        parent_a.iter().take(point).chain(parent_b.iter().skip(point)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let parent_a: BitChromosome = (0..100).map(|_| true).collect();
+        let parent_b = BitChromosome::zeros(100);
+
+        for _ in 0..20 {
+            let child = OnePointCrossover.crossover(&mut rng, &parent_a, &parent_b);
+
+            //a run of ones followed by a run of zeros
+            let ones = child.count_ones();
+            assert!(child.iter().take(ones).all(|bit| bit));
+            assert!(child.iter().skip(ones).all(|bit| !bit));
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::*;
+
+#[derive(Clone, Debug, Default)]
+pub struct BitUniformCrossover;
+
+impl BitCrossoverMethod for BitUniformCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &BitChromosome,
+        parent_b: &BitChromosome,
+    ) -> BitChromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        //a random mask per word picks the parent of every bit
+        let mut child = parent_a.clone();
+        for (word, &other) in child.words.iter_mut().zip(&parent_b.words) {
+            let mask = rng.next_u64();
+            *word = (*word & mask) | (other & !mask);
+        }
+        child
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let parent_a: BitChromosome = (0..1000).map(|_| true).collect();
+        let parent_b = BitChromosome::zeros(1000);
+
+        let child = BitUniformCrossover.crossover(&mut rng, &parent_a, &parent_b);
+
+        let from_a = child.count_ones();
+        assert!((450..550).contains(&from_a), "{from_a}");
+    }
+}
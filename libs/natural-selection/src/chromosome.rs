@@ -1,4 +1,40 @@
-use std::ops::{Add, Index};
+use std::fmt;
+use std::ops::Index;
+use std::str::FromStr;
+
+//the version of the dna written by Display, dna before it was versioned is version 1, the
+//legacy encoding, later versions are prefixed with their number and a ':'
+pub const DNA_VERSION: u32 = 2;
+//marks the exact f32 encoding, legacy dna never contains a ':'
+const DNA_PREFIX: &str = "2:";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DnaError {
+    //a gene contained a character outside of a-z and A-Z
+    InvalidCharacter(char),
+    //two dashes without a gene in between
+    EmptyGene,
+    //a gene encodes a value that does not fit the encoding
+    Overflow,
+    //a byte encoding whose length is not a multiple of 4
+    InvalidLength(usize),
+    //dna of a version newer than DNA_VERSION, written by a newer build
+    UnsupportedVersion(String),
+}
+
+impl fmt::Display for DnaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCharacter(c) => write!(f, "unexpected character '{c}' in dna"),
+            Self::EmptyGene => write!(f, "empty gene in dna"),
+            Self::Overflow => write!(f, "gene value out of range"),
+            Self::InvalidLength(len) => write!(f, "{len} bytes are not a whole number of genes"),
+            Self::UnsupportedVersion(version) => write!(f, "dna version {version} is newer than {DNA_VERSION}"),
+        }
+    }
+}
+
+impl std::error::Error for DnaError {}
 
 #[derive(Clone, Debug)]
 pub struct Chromosome {
@@ -10,6 +46,10 @@ impl Chromosome {
         self.genes.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.genes.is_empty()
+    }
+
     pub fn dosomething() -> Vec<f32> {
         todo!()
     }
@@ -22,12 +62,26 @@ impl Chromosome {
         self.genes.iter_mut()
     }
 
+    pub fn as_slice(&self) -> &[f32] {
+        &self.genes
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [f32] {
+        &mut self.genes
+    }
+
+    //replaces the genes, reusing the allocation
+    pub(crate) fn assign(&mut self, genes: &[f32]) {
+        self.genes.clear();
+        self.genes.extend_from_slice(genes);
+    }
+
     fn value_to_string(mut x: u32) -> String {
         let mut result = vec![];
 
         loop {
             let m = x % 52;
-            x = x / 52;
+            x /= 52;
 
             if m<26 {
                 result.push((m as u8 + b'a') as char);
@@ -41,35 +95,94 @@ impl Chromosome {
         result.into_iter().rev().collect()
     }
 
-    fn string_to_value(s: &str) -> u32 {
+    fn string_to_value(s: &str) -> Result<u32, DnaError> {
+        if s.is_empty() {
+            return Err(DnaError::EmptyGene);
+        }
+
         let mut v : u32 = 0;
         for c in s.chars() {
-            v*=52;
-            if c>='A' && c<='Z' {
-                v += 26 + (c as u8 - b'A') as u32;
-            } else if c>='a' && c<='z' {
-                v += (c as u8 - b'a') as u32;
+            let digit = if c.is_ascii_uppercase() {
+                26 + (c as u8 - b'A') as u32
+            } else if c.is_ascii_lowercase() {
+                (c as u8 - b'a') as u32
             } else {
-                panic!("unexpected character encountered!")
-            }
+                return Err(DnaError::InvalidCharacter(c));
+            };
+            v = v.checked_mul(52)
+                .and_then(|v| v.checked_add(digit))
+                .ok_or(DnaError::Overflow)?;
         }
-        v
+        Ok(v)
     }
 
-    pub fn to_dna(&self) -> String {
-        let mut result = self.genes.iter().fold(String::new(), |mut result, &f| {
-            let f = ((f + 1000.0) * 1000.0) as u32;
-            result = result.add(&Self::value_to_string(f));
-            result.push('-');
-            result
-        });
-        result.pop(); // remove the last dash
-        result
+    fn from_legacy_dna(dna: &str) -> Result<Self, DnaError> {
+        if dna.is_empty() {
+            return Ok(Self { genes: vec![] });
+        }
+
+        let genes = dna
+            .split('-')
+            .map(|s| Ok((Self::string_to_value(s)? as f32 / 1000.0) - 1000.0))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { genes })
+    }
+
+    //the genes as little endian f32s, nothing is lost
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.genes.iter().flat_map(|gene| gene.to_le_bytes()).collect()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DnaError> {
+        if !bytes.len().is_multiple_of(4) {
+            return Err(DnaError::InvalidLength(bytes.len()));
+        }
+
+        let genes = bytes
+            .chunks_exact(4)
+            .map(|gene| f32::from_le_bytes([gene[0], gene[1], gene[2], gene[3]]))
+            .collect();
+        Ok(Self { genes })
+    }
+}
+
+//dna: the format version, then the bits of every gene as dash separated base-52 strings, so
+//every f32 comes back exactly, even infinities and NaNs
+impl fmt::Display for Chromosome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(DNA_PREFIX)?;
+        for (i, gene) in self.genes.iter().enumerate() {
+            if i > 0 {
+                f.write_str("-")?;
+            }
+            f.write_str(&Self::value_to_string(gene.to_bits()))?;
+        }
+        Ok(())
     }
+}
+
+//reads the current dna and the legacy one without a prefix, which stored (gene + 1000) * 1000,
+//so its genes come back to 0.001 and only within -1000.0..=1000.0
+impl FromStr for Chromosome {
+    type Err = DnaError;
+
+    fn from_str(dna: &str) -> Result<Self, Self::Err> {
+        let Some(dna) = dna.strip_prefix(DNA_PREFIX) else {
+            return match dna.split_once(':') {
+                Some((version, _)) => Err(DnaError::UnsupportedVersion(version.to_string())),
+                None => Self::from_legacy_dna(dna),
+            };
+        };
+
+        if dna.is_empty() {
+            return Ok(Self { genes: vec![] });
+        }
 
-    pub fn from_dna(dna: String) -> Self {
-        let genes = dna.split('-').map(|s| (Self::string_to_value(s) as f32 / 1000.0) - 1000.0).collect();
-        Self { genes }
+        let genes = dna
+            .split('-')
+            .map(|s| Ok(f32::from_bits(Self::string_to_value(s)?)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { genes })
     }
 }
 
@@ -112,7 +225,7 @@ impl PartialEq for Chromosome {
 
 #[cfg(test)]
 mod tests {
-    use crate::chromosome::Chromosome;
+    use crate::chromosome::{Chromosome, DnaError};
 
     fn chromosome() -> Chromosome {
         Chromosome {
@@ -209,15 +322,20 @@ mod tests {
     mod dna {
         use super::*;
 
-        mod to_dna {
+        mod display {
             use super::*;
 
             #[test]
             fn test() {
                 let dna = Chromosome {
                     genes: vec![-100.0, -50.0, 0.0, 1.0, 2.0, 3.0, 50.0, 100.0],
-                }.to_dna();
-                assert_eq!(dna, "guRK-gNrm-hfQO-hgka-hgDm-hgWy-hyqq-hQPS");
+                }.to_string();
+                assert_eq!(dna, "2:iEXggO-iDPxRu-a-cPKNDS-cQSvTm-cRwnaW-cWeGeW-cXmouq");
+            }
+
+            #[test]
+            fn test_empty() {
+                assert_eq!(Chromosome { genes: vec![] }.to_string(), "2:");
             }
         }
 
@@ -235,22 +353,127 @@ mod tests {
 
             #[test]
             fn test_string_to_value() {
-                assert_eq!(Chromosome::string_to_value("a"), 0);
-                assert_eq!(Chromosome::string_to_value("tm"), 1000);
-                assert_eq!(Chromosome::string_to_value("My"), 2000);
-                assert_eq!(Chromosome::string_to_value("hgau") as f64 / 1000.0 - 1000.0, 0.5);
+                assert_eq!(Chromosome::string_to_value("a"), Ok(0));
+                assert_eq!(Chromosome::string_to_value("tm"), Ok(1000));
+                assert_eq!(Chromosome::string_to_value("My"), Ok(2000));
+                assert_eq!(Chromosome::string_to_value("hgau").unwrap() as f64 / 1000.0 - 1000.0, 0.5);
+            }
+
+            #[test]
+            fn test_string_to_value_errors() {
+                assert_eq!(Chromosome::string_to_value(""), Err(DnaError::EmptyGene));
+                assert_eq!(Chromosome::string_to_value("a1"), Err(DnaError::InvalidCharacter('1')));
+                assert_eq!(Chromosome::string_to_value("ZZZZZZZ"), Err(DnaError::Overflow));
             }
         }
 
-        mod from_dna {
+        mod from_str {
             use super::*;
 
             #[test]
             fn test() {
-                let actual = Chromosome::from_dna("guRK-gNrm-hfQO-hgka-hgDm-hgWy-hyqq-hQPS".to_string());
+                let actual = "2:iEXggO-iDPxRu-a-cPKNDS-cQSvTm-cRwnaW-cWeGeW-cXmouq".parse::<Chromosome>();
+                let dna = Chromosome {
+                    genes: vec![-100.0, -50.0, 0.0, 1.0, 2.0, 3.0, 50.0, 100.0],};
+                assert_eq!(actual, Ok(dna));
+            }
+
+            #[test]
+            fn test_out_of_legacy_range() {
+                let actual = "2:coXsYn-iMVwhQ-fGDjxd".parse::<Chromosome>().unwrap();
+                let genes: Vec<_> = actual.into_iter().collect();
+                assert_eq!(genes, vec![1e-7, -12345.678, f32::MAX]);
+            }
+
+            #[test]
+            fn test_legacy() {
+                let actual = "guRK-gNrm-hfQO-hgka-hgDm-hgWy-hyqq-hQPS".parse::<Chromosome>();
                 let dna = Chromosome {
                     genes: vec![-100.0, -50.0, 0.0, 1.0, 2.0, 3.0, 50.0, 100.0],};
-                assert_eq!(actual, dna);
+                assert_eq!(actual, Ok(dna));
+            }
+
+            #[test]
+            fn test_empty() {
+                assert_eq!("2:".parse::<Chromosome>(), Ok(Chromosome { genes: vec![] }));
+                assert_eq!("".parse::<Chromosome>(), Ok(Chromosome { genes: vec![] }));
+            }
+
+            #[test]
+            fn test_invalid() {
+                assert_eq!("guRK--gNrm".parse::<Chromosome>(), Err(DnaError::EmptyGene));
+                assert_eq!("guRK-g Nrm".parse::<Chromosome>(), Err(DnaError::InvalidCharacter(' ')));
+                assert_eq!("2:coXsYn-".parse::<Chromosome>(), Err(DnaError::EmptyGene));
+                assert_eq!("2:ZZZZZZZ".parse::<Chromosome>(), Err(DnaError::Overflow));
+            }
+
+            #[test]
+            fn test_unsupported_version() {
+                assert_eq!("3:coXsYn".parse::<Chromosome>(), Err(DnaError::UnsupportedVersion("3".to_string())));
+            }
+        }
+
+        mod bytes {
+            use super::*;
+
+            #[test]
+            fn test_invalid_length() {
+                assert_eq!(Chromosome::from_bytes(&[0, 0, 0]), Err(DnaError::InvalidLength(3)));
+            }
+        }
+
+        mod round_trip {
+            use super::*;
+            use proptest::prelude::*;
+
+            fn to_legacy_dna(genes: &[f32]) -> String {
+                genes
+                    .iter()
+                    .map(|&f| Chromosome::value_to_string(((f + 1000.0) * 1000.0) as u32))
+                    .collect::<Vec<_>>()
+                    .join("-")
+            }
+
+            proptest! {
+                #[test]
+                fn dna_is_exact(bits in prop::collection::vec(any::<u32>(), 0..32)) {
+                    let chromosome: Chromosome = bits.iter().map(|&bits| f32::from_bits(bits)).collect();
+                    let actual = chromosome.to_string().parse::<Chromosome>().unwrap();
+
+                    let actual: Vec<_> = actual.iter().map(|gene| gene.to_bits()).collect();
+                    prop_assert_eq!(actual, bits);
+                }
+
+                #[test]
+                fn legacy_dna_within_quantization_error(genes in prop::collection::vec(-1000.0f32..=1000.0, 0..32)) {
+                    let actual = to_legacy_dna(&genes).parse::<Chromosome>().unwrap();
+
+                    prop_assert_eq!(actual.len(), genes.len());
+                    for (actual, expected) in actual.iter().zip(&genes) {
+                        // 0.001 truncation plus f32 rounding of the scaled value
+                        prop_assert!((actual - expected).abs() < 0.0015, "{} != {}", actual, expected);
+                    }
+                }
+
+                #[test]
+                fn bytes_are_exact(genes in prop::collection::vec(prop::num::f32::NORMAL | prop::num::f32::SUBNORMAL | prop::num::f32::ZERO, 0..32)) {
+                    let chromosome = Chromosome { genes: genes.clone() };
+                    let actual = Chromosome::from_bytes(&chromosome.to_bytes()).unwrap();
+
+                    let actual: Vec<_> = actual.iter().map(|gene| gene.to_bits()).collect();
+                    let expected: Vec<_> = genes.iter().map(|gene| gene.to_bits()).collect();
+                    prop_assert_eq!(actual, expected);
+                }
+
+                #[test]
+                fn invalid_dna_does_not_panic(dna in ".*") {
+                    let _ = dna.parse::<Chromosome>();
+                }
+
+                #[test]
+                fn invalid_bytes_do_not_panic(bytes in prop::collection::vec(any::<u8>(), 0..64)) {
+                    let _ = Chromosome::from_bytes(&bytes);
+                }
             }
         }
     }
@@ -0,0 +1,20 @@
+pub use self::{penalty::*, repair::*};
+
+use crate::*;
+
+mod penalty;
+mod repair;
+
+//keeps offspring inside the valid part of the search space, invalid chromosomes are either
+//repaired right after mutation or lose fitness when they are selected
+pub trait ConstraintHandler {
+    fn is_valid(&self, chromosome: &Chromosome) -> bool;
+
+    //fitness taken from an invalid individual during selection
+    fn penalty(&self, _chromosome: &Chromosome) -> f32 {
+        0.0
+    }
+
+    //turns an invalid child into a valid one
+    fn repair(&self, _child: &mut Chromosome) {}
+}
@@ -0,0 +1,55 @@
+use crate::*;
+
+//leaves invalid chromosomes alone but makes them less likely to become parents
+pub struct PenaltyConstraint<V, P> {
+    is_valid: V,
+    penalty: P,
+}
+
+impl<V, P> PenaltyConstraint<V, P>
+where
+    V: Fn(&Chromosome) -> bool,
+    P: Fn(&Chromosome) -> f32,
+{
+    pub fn new(is_valid: V, penalty: P) -> Self {
+        Self { is_valid, penalty }
+    }
+}
+
+impl<V, P> ConstraintHandler for PenaltyConstraint<V, P>
+where
+    V: Fn(&Chromosome) -> bool,
+    P: Fn(&Chromosome) -> f32,
+{
+    fn is_valid(&self, chromosome: &Chromosome) -> bool {
+        (self.is_valid)(chromosome)
+    }
+
+    fn penalty(&self, chromosome: &Chromosome) -> f32 {
+        (self.penalty)(chromosome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chromosome(genes: &[f32]) -> Chromosome {
+        genes.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test() {
+        let constraint = PenaltyConstraint::new(
+            |chromosome: &Chromosome| chromosome.iter().all(|gene| gene.abs() <= 1.0),
+            |chromosome: &Chromosome| chromosome.iter().map(|gene| gene.abs() - 1.0).filter(|excess| *excess > 0.0).sum());
+
+        assert!(constraint.is_valid(&chromosome(&[0.5, -1.0])));
+        assert!(!constraint.is_valid(&chromosome(&[0.5, -3.0])));
+        assert_eq!(constraint.penalty(&chromosome(&[2.5, -3.0])), 3.5);
+
+        let mut child = chromosome(&[2.5]);
+        constraint.repair(&mut child);
+        assert_eq!(child, chromosome(&[2.5]));
+    }
+}
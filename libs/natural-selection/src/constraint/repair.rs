@@ -0,0 +1,55 @@
+use crate::*;
+
+//fixes invalid offspring before they join the population, e.g. by clamping weights
+pub struct RepairConstraint<V, R> {
+    is_valid: V,
+    repair: R,
+}
+
+impl<V, R> RepairConstraint<V, R>
+where
+    V: Fn(&Chromosome) -> bool,
+    R: Fn(&mut Chromosome),
+{
+    pub fn new(is_valid: V, repair: R) -> Self {
+        Self { is_valid, repair }
+    }
+}
+
+impl<V, R> ConstraintHandler for RepairConstraint<V, R>
+where
+    V: Fn(&Chromosome) -> bool,
+    R: Fn(&mut Chromosome),
+{
+    fn is_valid(&self, chromosome: &Chromosome) -> bool {
+        (self.is_valid)(chromosome)
+    }
+
+    fn repair(&self, child: &mut Chromosome) {
+        (self.repair)(child)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chromosome(genes: &[f32]) -> Chromosome {
+        genes.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test() {
+        let constraint = RepairConstraint::new(
+            |chromosome: &Chromosome| chromosome.iter().all(|gene| gene.abs() <= 1.0),
+            |child: &mut Chromosome| child.iter_mut().for_each(|gene| *gene = gene.clamp(-1.0, 1.0)));
+
+        let mut child = chromosome(&[0.5, -3.0, 2.0]);
+        assert!(!constraint.is_valid(&child));
+        assert_eq!(constraint.penalty(&child), 0.0);
+
+        constraint.repair(&mut child);
+        assert_eq!(child, chromosome(&[0.5, -1.0, 1.0]));
+        assert!(constraint.is_valid(&child));
+    }
+}
@@ -1,7 +1,8 @@
-pub use self::uniform::*;
+pub use self::{linked::*, uniform::*};
 
 use crate::*;
 
+mod linked;
 mod uniform;
 
 pub trait CrossoverMethod {
@@ -11,4 +12,73 @@ pub trait CrossoverMethod {
         parent_a: &Chromosome,
         parent_b: &Chromosome,
     ) -> Chromosome;
+
+    //like crossover(), but writes the child over an existing buffer, e.g. genes that live
+    //in a network, the default goes through crossover()
+    fn crossover_into(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &[f32],
+        parent_b: &[f32],
+        child: &mut [f32],
+    ) {
+        let parent_a = parent_a.iter().copied().collect();
+        let parent_b = parent_b.iter().copied().collect();
+
+        child.copy_from_slice(self.crossover(rng, &parent_a, &parent_b).as_slice());
+    }
+
+    //like crossover_into(), but also tells which parent every gene came from if the method
+    //knows, the default does not
+    fn crossover_traced(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &[f32],
+        parent_b: &[f32],
+        child: &mut [f32],
+    ) -> Option<Provenance> {
+        self.crossover_into(rng, parent_a, parent_b, child);
+        None
+    }
+}
+
+//which parent every gene of a child came from, true for parent a
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Provenance(pub Vec<bool>);
+
+impl Provenance {
+    pub fn from_a(&self) -> usize {
+        self.0.iter().filter(|&&from_a| from_a).count()
+    }
+
+    //the share of the genes that came from the parent which passed on fewer, 0.0 for a copy of
+    //one parent and 0.5 for an even mix
+    pub fn mixing(&self) -> f32 {
+        let from_a = self.from_a();
+        from_a.min(self.0.len() - from_a) as f32 / self.0.len().max(1) as f32
+    }
+
+    //whether the child is closer to parent a, as in more of its genes came from it
+    pub fn closer_to_a(&self) -> bool {
+        2 * self.from_a() >= self.0.len()
+    }
+}
+
+//the provenance of every child of a generation summed up
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProvenanceStatistics {
+    pub children: usize,
+    pub mixing: f32,
+}
+
+impl ProvenanceStatistics {
+    pub fn record(&mut self, provenance: &Provenance) {
+        self.children += 1;
+        self.mixing += provenance.mixing();
+    }
+
+    //how evenly the children mix their parents on average, see Provenance::mixing()
+    pub fn avg_mixing(&self) -> f32 {
+        self.mixing / self.children.max(1) as f32
+    }
 }
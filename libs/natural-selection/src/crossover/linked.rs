@@ -0,0 +1,174 @@
+use crate::chromosome::Chromosome;
+use crate::crossover::{CrossoverMethod, Provenance};
+use rand::{Rng, RngCore};
+
+//uniform crossover that inherits linkage groups (sets of gene indices, e.g. the weights
+//of one neuron) from a single parent, genes outside of any group are inherited one by one
+#[derive(Clone, Debug, Default)]
+pub struct LinkedUniformCrossover {
+    //the group of every gene, if it has one
+    groups: Vec<Option<usize>>,
+}
+
+impl LinkedUniformCrossover {
+    pub fn new<G>(groups: impl IntoIterator<Item = G>) -> Self
+    where
+        G: IntoIterator<Item = usize>,
+    {
+        let mut group_of = Vec::new();
+
+        for (group, genes) in groups.into_iter().enumerate() {
+            for gene in genes {
+                if group_of.len() <= gene {
+                    group_of.resize(gene + 1, None);
+                }
+                assert!(group_of[gene].is_none(), "gene {gene} is in more than one linkage group");
+                group_of[gene] = Some(group);
+            }
+        }
+
+        Self { groups: group_of }
+    }
+}
+
+impl CrossoverMethod for LinkedUniformCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        let mut child = parent_a.clone();
+        self.crossover_into(rng, parent_a.as_slice(), parent_b.as_slice(), child.as_mut_slice());
+        child
+    }
+
+    fn crossover_into(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &[f32],
+        parent_b: &[f32],
+        child: &mut [f32],
+    ) {
+        self.cross(rng, parent_a, parent_b, child, |_| ());
+    }
+
+    fn crossover_traced(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &[f32],
+        parent_b: &[f32],
+        child: &mut [f32],
+    ) -> Option<Provenance> {
+        let mut provenance = Provenance(Vec::with_capacity(child.len()));
+        self.cross(rng, parent_a, parent_b, child, |from_a| provenance.0.push(from_a));
+        Some(provenance)
+    }
+}
+
+impl LinkedUniformCrossover {
+    //tells `inherit` for every gene in order whether it came from parent a
+    fn cross(&self,
+             rng: &mut dyn RngCore,
+             parent_a: &[f32],
+             parent_b: &[f32],
+             child: &mut [f32],
+             mut inherit: impl FnMut(bool)) {
+        assert_eq!(parent_a.len(), parent_b.len());
+        assert_eq!(parent_a.len(), child.len());
+
+        //which parent each group is taken from, decided when its first gene comes up
+        let mut from_a: Vec<Option<bool>> = Vec::new();
+
+        for (gene, (child, (&a, &b))) in child.iter_mut().zip(parent_a.iter().zip(parent_b)).enumerate() {
+            let take_a = match self.groups.get(gene).copied().flatten() {
+                Some(group) => {
+                    if from_a.len() <= group {
+                        from_a.resize(group + 1, None);
+                    }
+                    *from_a[group].get_or_insert_with(|| rng.gen_bool(0.5))
+                }
+                None => rng.gen_bool(0.5),
+            };
+
+            *child = if take_a { a } else { b };
+            inherit(take_a);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn crossover(method: &LinkedUniformCrossover, seed: u64) -> Vec<f32> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let parent_a: Chromosome = (1..=10).map(|n| n as f32).collect();
+        let parent_b: Chromosome = (1..=10).map(|n| -n as f32).collect();
+
+        method.crossover(&mut rng, &parent_a, &parent_b).into_iter().collect()
+    }
+
+    #[test]
+    fn test_groups_travel_together() {
+        let method = LinkedUniformCrossover::new([0..4, 4..7, 7..10]);
+
+        for seed in 0..20 {
+            let child = crossover(&method, seed);
+
+            for group in [0..4, 4..7, 7..10] {
+                let signs: Vec<_> = child[group].iter().map(|gene| gene.is_sign_positive()).collect();
+                assert!(signs.iter().all(|sign| *sign == signs[0]), "{child:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_groups_can_be_scattered() {
+        let method = LinkedUniformCrossover::new([vec![0, 9], vec![1, 5]]);
+
+        for seed in 0..20 {
+            let child = crossover(&method, seed);
+
+            assert_eq!(child[0].is_sign_positive(), child[9].is_sign_positive());
+            assert_eq!(child[1].is_sign_positive(), child[5].is_sign_positive());
+        }
+    }
+
+    #[test]
+    fn test_without_groups_is_uniform() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let parent_a: Chromosome = (1..=100).map(|n| n as f32).collect();
+        let parent_b: Chromosome = (1..=100).map(|n| -n as f32).collect();
+
+        let child = LinkedUniformCrossover::new(Vec::<Vec<usize>>::new()).crossover(&mut rng, &parent_a, &parent_b);
+
+        let diff_a = child.iter().zip(parent_a).filter(|(c, p)| *c != p).count();
+        assert_eq!(diff_a, 49);
+    }
+
+    #[test]
+    fn test_provenance_follows_the_groups() {
+        let method = LinkedUniformCrossover::new([0..4, 4..7, 7..10]);
+        let parent_a: Vec<f32> = (1..=10).map(|n| n as f32).collect();
+        let parent_b: Vec<f32> = (1..=10).map(|n| -n as f32).collect();
+
+        for seed in 0..20 {
+            let mut child = vec![0.0; 10];
+            let provenance = method.crossover_traced(&mut ChaCha8Rng::seed_from_u64(seed), &parent_a, &parent_b, &mut child).unwrap();
+
+            assert_eq!(child, crossover(&method, seed));
+            for (gene, from_a) in child.iter().zip(&provenance.0) {
+                assert_eq!(gene.is_sign_positive(), *from_a);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_overlapping_groups() {
+        LinkedUniformCrossover::new([0..4, 3..6]);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::chromosome::Chromosome;
-use crate::crossover::CrossoverMethod;
+use crate::crossover::{CrossoverMethod, Provenance};
 use rand::{Rng, RngCore};
 
 #[derive(Clone, Debug, Default)]
@@ -12,15 +12,43 @@ impl CrossoverMethod for UniformCrossover {
         parent_a: &Chromosome,
         parent_b: &Chromosome,
     ) -> Chromosome {
+        let mut child = parent_a.clone();
+        self.crossover_into(rng, parent_a.as_slice(), parent_b.as_slice(), child.as_mut_slice());
+        child
+    }
+
+    fn crossover_into(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &[f32],
+        parent_b: &[f32],
+        child: &mut [f32],
+    ) {
         assert_eq!(parent_a.len(), parent_b.len());
+        assert_eq!(parent_a.len(), child.len());
 
-        let parent_a = parent_a.iter();
-        let parent_b = parent_b.iter();
+        for ((gene, &a), &b) in child.iter_mut().zip(parent_a).zip(parent_b) {
+            *gene = if rng.gen_bool(0.5) { a } else { b };
+        }
+    }
+
+    fn crossover_traced(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &[f32],
+        parent_b: &[f32],
+        child: &mut [f32],
+    ) -> Option<Provenance> {
+        assert_eq!(parent_a.len(), parent_b.len());
+        assert_eq!(parent_a.len(), child.len());
 
-        parent_a
-            .zip(parent_b)
-            .map(|(&a, &b)| if rng.gen_bool(0.5) { a } else { b })
-            .collect()
+        let mut provenance = Provenance(Vec::with_capacity(child.len()));
+        for ((gene, &a), &b) in child.iter_mut().zip(parent_a).zip(parent_b) {
+            let from_a = rng.gen_bool(0.5);
+            *gene = if from_a { a } else { b };
+            provenance.0.push(from_a);
+        }
+        Some(provenance)
     }
 }
 
@@ -36,7 +64,7 @@ mod tests {
         let parent_a: Chromosome = (1..=100).map(|n| n as f32).collect();
         let parent_b: Chromosome = (1..=100).map(|n| -n as f32).collect();
 
-        let child = UniformCrossover::default().crossover(&mut rng, &parent_a, &parent_b);
+        let child = UniformCrossover.crossover(&mut rng, &parent_a, &parent_b);
 
         let diff_a = child.iter().zip(parent_a).filter(|(c, p)| *c != p).count();
         let diff_b = child.iter().zip(parent_b).filter(|(c, p)| *c != p).count();
@@ -44,4 +72,24 @@ mod tests {
         assert_eq!(diff_a, 49);
         assert_eq!(diff_b, 51);
     }
+
+    #[test]
+    fn test_provenance() {
+        let parent_a: Chromosome = (1..=100).map(|n| n as f32).collect();
+        let parent_b: Chromosome = (1..=100).map(|n| -n as f32).collect();
+        let mut child = parent_a.clone();
+
+        let provenance = UniformCrossover
+            .crossover_traced(&mut ChaCha8Rng::from_seed(Default::default()), parent_a.as_slice(), parent_b.as_slice(), child.as_mut_slice())
+            .unwrap();
+
+        //the same genes as without tracing
+        assert_eq!(child, UniformCrossover.crossover(&mut ChaCha8Rng::from_seed(Default::default()), &parent_a, &parent_b));
+        for (gene, from_a) in child.iter().zip(&provenance.0) {
+            assert_eq!(gene.is_sign_positive(), *from_a);
+        }
+        assert_eq!(provenance.from_a(), 51);
+        assert_eq!(provenance.mixing(), 0.49);
+        assert!(provenance.closer_to_a());
+    }
 }
@@ -0,0 +1,271 @@
+use std::ops::Range;
+
+use rand::seq::SliceRandom;
+
+use crate::*;
+
+//the best genome found for a cell of the behavior space
+#[derive(Clone, Debug, PartialEq)]
+pub struct Elite {
+    pub genes: Vec<f32>,
+    pub fitness: f32,
+    pub descriptor: Vec<f32>,
+}
+
+//a grid over the space of behavior descriptors that keeps the fittest genome of every cell, the
+//outer cells also take the descriptors beyond the ranges
+#[derive(Clone, Debug, PartialEq)]
+pub struct EliteArchive {
+    ranges: Vec<Range<f32>>,
+    bins: Vec<usize>,
+    //the first dimension varies fastest
+    cells: Vec<Option<Elite>>,
+}
+
+impl EliteArchive {
+    //a dimension per range of descriptor values, split into the number of bins next to it
+    pub fn new(dimensions: impl IntoIterator<Item = (Range<f32>, usize)>) -> Self {
+        let (ranges, bins): (Vec<_>, Vec<_>) = dimensions.into_iter().unzip();
+        assert!(!bins.is_empty(), "need a dimension");
+        assert!(bins.iter().all(|&bins| bins > 0) && ranges.iter().all(|range| range.start < range.end));
+
+        let cells = bins.iter().product();
+        Self { ranges, bins, cells: vec![None; cells] }
+    }
+
+    pub fn bins(&self) -> &[usize] {
+        &self.bins
+    }
+
+    pub fn cells(&self) -> &[Option<Elite>] {
+        &self.cells
+    }
+
+    //the cell `descriptor` falls into
+    pub fn cell(&self, descriptor: &[f32]) -> usize {
+        assert_eq!(descriptor.len(), self.bins.len(), "descriptors of a different dimension");
+
+        let mut cell = 0;
+        for ((value, range), &bins) in descriptor.iter().zip(&self.ranges).zip(&self.bins).rev() {
+            let bin = ((value - range.start) / (range.end - range.start) * bins as f32).floor();
+            let bin = if bin.is_nan() { 0 } else { (bin.max(0.0) as usize).min(bins - 1) };
+            cell = cell * bins + bin;
+        }
+        cell
+    }
+
+    //keeps the genome if its cell is empty or it is fitter than the elite there, returns
+    //whether it was kept
+    pub fn insert(&mut self, genes: &[f32], fitness: f32, descriptor: &[f32]) -> bool {
+        let cell = self.cell(descriptor);
+        if self.cells[cell].as_ref().is_some_and(|elite| elite.fitness >= fitness) {
+            return false;
+        }
+
+        self.cells[cell] = Some(Elite { genes: genes.to_vec(), fitness, descriptor: descriptor.to_vec() });
+        true
+    }
+
+    pub fn elites(&self) -> impl Iterator<Item = &Elite> {
+        self.cells.iter().flatten()
+    }
+
+    pub fn len(&self) -> usize {
+        self.elites().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().all(Option::is_none)
+    }
+
+    //the share of the cells that have an elite
+    pub fn coverage(&self) -> f32 {
+        self.len() as f32 / self.cells.len() as f32
+    }
+
+    //the sum of the fitness of all elites, grows with both their quality and their diversity
+    pub fn qd_score(&self) -> f32 {
+        self.elites().map(|elite| elite.fitness).sum()
+    }
+
+    pub fn best(&self) -> Option<&Elite> {
+        self.elites().max_by(|a, b| a.fitness.total_cmp(&b.fitness))
+    }
+}
+
+//MAP-Elites (mouret and clune 2015): instead of a population that converges, every evaluated
+//genome competes only for the cell of its behavior in an archive, and the next genomes to be
+//evaluated are mutated copies of random elites, which illuminates what fitness every behavior
+//can reach, needs individuals with descriptors
+pub struct MapElites {
+    archive: EliteArchive,
+    mutation: Box<dyn MutationMethod>,
+    observers: Vec<Box<dyn Observer>>,
+    generation: usize,
+    statistics: Option<Statistics>,
+    //copies of the evaluated genomes for the statistics, kept to reuse their allocations
+    evaluated: Vec<Parent>,
+}
+
+impl MapElites {
+    pub fn new(archive: EliteArchive, mutation_method: impl MutationMethod + 'static) -> Self {
+        Self {
+            archive,
+            mutation: Box::new(mutation_method),
+            observers: Vec::new(),
+            generation: 0,
+            statistics: None,
+            evaluated: Vec::new(),
+        }
+    }
+
+    pub fn archive(&self) -> &EliteArchive {
+        &self.archive
+    }
+
+    //puts every individual into the archive and overwrites it with a mutated copy of an elite
+    pub fn evolve_in_place<V>(&mut self, rng: &mut dyn RngCore, population: &mut [V])
+    where
+        V: InPlaceIndividual,
+    {
+        assert!(!population.is_empty());
+
+        for observer in &mut self.observers {
+            observer.on_generation_start(self.generation);
+        }
+
+        let best = self.archive.best().map(|elite| elite.fitness);
+        self.evaluated.resize_with(population.len(), Parent::default);
+        for (evaluated, individual) in self.evaluated.iter_mut().zip(population.iter()) {
+            evaluated.chromosome.assign(individual.genes());
+            evaluated.fitness = individual.fitness();
+            evaluated.fitness_stderr = individual.fitness_stderr();
+            self.archive.insert(individual.genes(), individual.fitness(), individual.descriptor());
+        }
+        let statistics = Statistics::new(self.generation, &self.evaluated);
+
+        let elites: Vec<_> = self.archive.elites().collect();
+        for individual in population.iter_mut() {
+            let elite = elites.choose(&mut *rng).expect("the archive has the population in it");
+            let child = individual.genes_mut();
+            child.copy_from_slice(&elite.genes);
+            self.mutation.mutate_genes(rng, child);
+        }
+
+        let champion = self.archive.best().expect("the archive has the population in it");
+        if best.is_none_or(|best| champion.fitness > best) {
+            let chromosome = champion.genes.iter().copied().collect();
+            for observer in &mut self.observers {
+                observer.on_new_champion(self.generation, &chromosome, champion.fitness);
+            }
+        }
+        for observer in &mut self.observers {
+            observer.on_generation_end(&statistics);
+        }
+
+        self.statistics = Some(statistics);
+        self.generation += 1;
+    }
+}
+
+impl Optimizer for MapElites {
+    fn evolve_in_place(&mut self, rng: &mut dyn RngCore, population: &mut [&mut dyn InPlaceIndividual]) {
+        MapElites::evolve_in_place(self, rng, population)
+    }
+
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn statistics(&self) -> Option<&Statistics> {
+        self.statistics.as_ref()
+    }
+
+    fn add_observer(&mut self, observer: Box<dyn Observer>) {
+        self.observers.push(observer);
+    }
+
+    fn elites(&self) -> Option<&EliteArchive> {
+        Some(&self.archive)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    //the genes are the descriptor, the fitness their sum
+    struct Behaving(Vec<f32>);
+
+    impl InPlaceIndividual for Behaving {
+        fn fitness(&self) -> f32 {
+            self.0.iter().sum()
+        }
+
+        fn descriptor(&self) -> &[f32] {
+            &self.0
+        }
+
+        fn genes(&self) -> &[f32] {
+            &self.0
+        }
+
+        fn genes_mut(&mut self) -> &mut [f32] {
+            &mut self.0
+        }
+    }
+
+    fn archive() -> EliteArchive {
+        EliteArchive::new([(0.0..1.0, 4), (0.0..2.0, 2)])
+    }
+
+    #[test]
+    fn test_cells() {
+        let archive = archive();
+
+        assert_eq!(archive.cells().len(), 8);
+        assert_eq!(archive.cell(&[0.0, 0.0]), 0);
+        assert_eq!(archive.cell(&[0.3, 0.0]), 1);
+        assert_eq!(archive.cell(&[0.3, 1.5]), 5);
+        //beyond the ranges
+        assert_eq!(archive.cell(&[-1.0, 9.0]), 4);
+        assert_eq!(archive.cell(&[9.0, f32::NAN]), 3);
+    }
+
+    #[test]
+    fn test_keeps_the_fittest_per_cell() {
+        let mut archive = archive();
+
+        assert!(archive.insert(&[1.0], 1.0, &[0.1, 0.1]));
+        assert!(!archive.insert(&[2.0], 0.5, &[0.2, 0.2]));
+        assert!(archive.insert(&[3.0], 2.0, &[0.2, 0.2]));
+        assert!(archive.insert(&[4.0], 0.5, &[0.9, 1.9]));
+
+        assert_eq!(archive.len(), 2);
+        assert_eq!(archive.coverage(), 0.25);
+        assert_eq!(archive.qd_score(), 2.5);
+        assert_eq!(archive.best().unwrap().genes, [3.0]);
+        assert_eq!(archive.cells()[0].as_ref().unwrap().descriptor, [0.2, 0.2]);
+    }
+
+    #[test]
+    fn test_illuminates() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut map_elites = MapElites::new(archive(), GaussianMutation::new(1.0, 0.3));
+        let mut population: Vec<_> = (0..4).map(|_| Behaving(vec![0.5, 1.0])).collect();
+
+        map_elites.evolve_in_place(&mut rng, &mut population);
+        assert_eq!(map_elites.archive().len(), 1);
+        //every child is a mutated copy of the only elite
+        assert!(population.iter().all(|individual| individual.0 != [0.5, 1.0]));
+
+        for _ in 0..20 {
+            map_elites.evolve_in_place(&mut rng, &mut population);
+        }
+        assert!(map_elites.archive().len() > 4);
+        assert_eq!(map_elites.generation(), 21);
+        assert!(map_elites.elites().is_some());
+    }
+}
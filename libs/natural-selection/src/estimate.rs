@@ -0,0 +1,102 @@
+//the fitness of an individual scored over repeated evaluations, kept as a running mean and
+//variance (welford's algorithm) so scores can be added one at a time
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FitnessEstimate {
+    count: usize,
+    mean: f32,
+    //sum of squared differences from the mean
+    m2: f32,
+}
+
+impl FitnessEstimate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, score: f32) {
+        self.count += 1;
+        let delta = score - self.mean;
+        self.mean += delta / self.count as f32;
+        self.m2 += delta * (score - self.mean);
+    }
+
+    //the number of evaluations so far
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> f32 {
+        self.mean
+    }
+
+    //sample variance of the scores, 0.0 until there are two of them
+    pub fn variance(&self) -> f32 {
+        if self.count > 1 {
+            self.m2 / (self.count - 1) as f32
+        } else {
+            0.0
+        }
+    }
+
+    //standard error of the mean
+    pub fn stderr(&self) -> f32 {
+        if self.count > 0 {
+            (self.variance() / self.count as f32).sqrt()
+        } else {
+            0.0
+        }
+    }
+
+    //bounds of the confidence interval `z` standard errors around the mean
+    pub fn lower(&self, z: f32) -> f32 {
+        self.mean - z * self.stderr()
+    }
+
+    pub fn upper(&self, z: f32) -> f32 {
+        self.mean + z * self.stderr()
+    }
+}
+
+impl FromIterator<f32> for FitnessEstimate {
+    fn from_iter<T: IntoIterator<Item = f32>>(iter: T) -> Self {
+        let mut estimate = Self::new();
+        for score in iter {
+            estimate.add(score);
+        }
+        estimate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test() {
+        let estimate: FitnessEstimate = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].into_iter().collect();
+
+        assert_eq!(estimate.count(), 8);
+        assert_relative_eq!(estimate.mean(), 5.0);
+        assert_relative_eq!(estimate.variance(), 32.0 / 7.0);
+        assert_relative_eq!(estimate.stderr(), (32.0f32 / 7.0 / 8.0).sqrt());
+        assert_relative_eq!(estimate.lower(2.0), 5.0 - 2.0 * estimate.stderr());
+        assert_relative_eq!(estimate.upper(2.0), 5.0 + 2.0 * estimate.stderr());
+    }
+
+    #[test]
+    fn test_single_score_is_certain() {
+        let estimate: FitnessEstimate = [3.0].into_iter().collect();
+
+        assert_eq!(estimate.mean(), 3.0);
+        assert_eq!(estimate.stderr(), 0.0);
+    }
+
+    #[test]
+    fn test_empty() {
+        let estimate = FitnessEstimate::new();
+
+        assert_eq!(estimate.count(), 0);
+        assert_eq!(estimate.stderr(), 0.0);
+    }
+}
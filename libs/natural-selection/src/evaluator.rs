@@ -0,0 +1,279 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::*;
+
+//how the scores of a genome's trials are combined into its fitness
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Aggregation {
+    #[default]
+    Mean,
+    Median,
+    //rewards genomes that do well in every environment
+    Min,
+}
+
+impl FromStr for Aggregation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mean" => Ok(Self::Mean),
+            "median" => Ok(Self::Median),
+            "min" => Ok(Self::Min),
+            _ => Err(format!("unknown aggregation '{s}', expected mean, median or min")),
+        }
+    }
+}
+
+impl fmt::Display for Aggregation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Mean => "mean",
+            Self::Median => "median",
+            Self::Min => "min",
+        })
+    }
+}
+
+//scores every genome in several random environments, the fitness from a single one is noisy,
+//so this gives the selection a much better signal at the cost of `trials` times the evaluations
+#[derive(Clone, Debug)]
+pub struct MultiTrialEvaluator {
+    trials: usize,
+    aggregation: Aggregation,
+}
+
+impl MultiTrialEvaluator {
+    pub fn new(trials: usize, aggregation: Aggregation) -> Self {
+        assert!(trials > 0);
+        Self { trials, aggregation }
+    }
+
+    pub fn trials(&self) -> usize {
+        self.trials
+    }
+
+    pub fn aggregation(&self) -> Aggregation {
+        self.aggregation
+    }
+
+    //the environment seeds of one generation, every genome is scored on the same ones
+    pub fn seeds(&self, rng: &mut dyn RngCore) -> Vec<u64> {
+        (0..self.trials).map(|_| rng.next_u64()).collect()
+    }
+
+    //the fitness of every genome, `trial` scores a genome in the environment of a seed
+    pub fn evaluate<G>(&self, rng: &mut dyn RngCore, genomes: &[G], mut trial: impl FnMut(&G, u64) -> f32) -> Vec<f32> {
+        let seeds = self.seeds(rng);
+        let mut scores = Vec::with_capacity(self.trials);
+
+        genomes
+            .iter()
+            .map(|genome| {
+                scores.clear();
+                scores.extend(seeds.iter().map(|&seed| trial(genome, seed)));
+                self.aggregate(&mut scores)
+            })
+            .collect()
+    }
+
+    //like evaluate(), but races the genomes: after every round of trials the genomes whose
+    //confidence interval lies entirely below the best one's are not evaluated any further,
+    //the rest get all `trials` evaluations. the intervals are `z` standard errors around the
+    //aggregate of the trials so far, for the median and the min that is only an approximation.
+    //every genome gets its aggregated fitness and the estimate of the trials it got
+    pub fn race<G>(&self,
+                   rng: &mut dyn RngCore,
+                   genomes: &[G],
+                   z: f32,
+                   mut trial: impl FnMut(&G, u64) -> f32) -> Vec<(f32, FitnessEstimate)> {
+        let seeds = self.seeds(rng);
+        let mut scores = vec![Vec::with_capacity(self.trials); genomes.len()];
+        let mut results = vec![(0.0, FitnessEstimate::new()); genomes.len()];
+        let mut racing: Vec<usize> = (0..genomes.len()).collect();
+
+        for &seed in &seeds {
+            for &genome in &racing {
+                let score = trial(&genomes[genome], seed);
+                scores[genome].push(score);
+                //the order of the scores does not matter to any aggregation
+                results[genome].0 = self.aggregate(&mut scores[genome]);
+                results[genome].1.add(score);
+            }
+
+            let bounds = |genome: usize| {
+                let (fitness, estimate) = &results[genome];
+                (fitness - z * estimate.stderr(), fitness + z * estimate.stderr())
+            };
+            let best_lower = racing
+                .iter()
+                .map(|&genome| bounds(genome).0)
+                .fold(f32::NEG_INFINITY, f32::max);
+            racing.retain(|&genome| results[genome].1.count() < 2 || bounds(genome).1 >= best_lower);
+        }
+
+        results
+    }
+
+    //the scores of one genome's trials as mean and standard error
+    pub fn estimate(&self, scores: &[f32]) -> FitnessEstimate {
+        scores.iter().copied().collect()
+    }
+
+    //combines the scores of one genome's trials, for callers that run the trials themselves
+    pub fn aggregate(&self, scores: &mut [f32]) -> f32 {
+        assert!(!scores.is_empty());
+
+        match self.aggregation {
+            Aggregation::Mean => scores.iter().sum::<f32>() / scores.len() as f32,
+            Aggregation::Median => {
+                scores.sort_by(f32::total_cmp);
+                let middle = scores.len() / 2;
+                if scores.len().is_multiple_of(2) {
+                    (scores[middle - 1] + scores[middle]) / 2.0
+                } else {
+                    scores[middle]
+                }
+            }
+            Aggregation::Min => scores.iter().copied().fold(f32::INFINITY, f32::min),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    mod aggregate {
+        use super::*;
+
+        fn aggregate(aggregation: Aggregation, scores: &[f32]) -> f32 {
+            MultiTrialEvaluator::new(scores.len(), aggregation).aggregate(&mut scores.to_vec())
+        }
+
+        #[test]
+        fn test_mean() {
+            assert_eq!(aggregate(Aggregation::Mean, &[1.0, 2.0, 6.0]), 3.0);
+        }
+
+        #[test]
+        fn test_median() {
+            assert_eq!(aggregate(Aggregation::Median, &[6.0, 1.0, 2.0]), 2.0);
+            assert_eq!(aggregate(Aggregation::Median, &[6.0, 1.0, 2.0, 4.0]), 3.0);
+        }
+
+        #[test]
+        fn test_min() {
+            assert_eq!(aggregate(Aggregation::Min, &[6.0, 1.0, 2.0]), 1.0);
+        }
+
+        #[test]
+        fn test_single_trial() {
+            for aggregation in [Aggregation::Mean, Aggregation::Median, Aggregation::Min] {
+                assert_eq!(aggregate(aggregation, &[0.3]), 0.3);
+            }
+        }
+    }
+
+    mod evaluate {
+        use super::*;
+
+        #[test]
+        fn test_same_seeds_for_every_genome() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let evaluator = MultiTrialEvaluator::new(3, Aggregation::Mean);
+            let mut seen = Vec::new();
+
+            evaluator.evaluate(&mut rng, &[1, 2], |genome, seed| {
+                seen.push((*genome, seed));
+                0.0
+            });
+
+            assert_eq!(seen.len(), 6);
+            let seeds = |genome| seen.iter().filter(|(g, _)| *g == genome).map(|(_, seed)| *seed).collect::<Vec<_>>();
+            assert_eq!(seeds(1), seeds(2));
+        }
+
+        #[test]
+        fn test_aggregates_per_genome() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let evaluator = MultiTrialEvaluator::new(4, Aggregation::Min);
+            let mut trial = 0;
+
+            let fitness = evaluator.evaluate(&mut rng, &[1.0, 10.0], |genome: &f32, _| {
+                trial += 1;
+                genome * trial as f32
+            });
+
+            assert_eq!(fitness, vec![1.0, 50.0]);
+        }
+    }
+
+    mod race {
+        use super::*;
+
+        #[test]
+        fn test_stops_evaluating_clearly_worse_genomes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let evaluator = MultiTrialEvaluator::new(10, Aggregation::Mean);
+
+            //fitness is the genome plus a little noise
+            let estimates = evaluator.race(&mut rng, &[1.0, 1.1, 5.0], 2.0, |genome: &f32, seed| {
+                genome + (seed % 100) as f32 / 1000.0
+            });
+
+            assert_eq!(estimates[0].1.count(), 2);
+            assert_eq!(estimates[1].1.count(), 2);
+            assert_eq!(estimates[2].1.count(), 10);
+            assert!(estimates[2].1.mean() > 5.0);
+        }
+
+        #[test]
+        fn test_keeps_racing_close_genomes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let evaluator = MultiTrialEvaluator::new(6, Aggregation::Mean);
+
+            //the noise dwarfs the difference between the genomes
+            let estimates = evaluator.race(&mut rng, &[1.0, 1.01], 2.0, |genome: &f32, seed| {
+                genome + (seed % 1000) as f32 / 1000.0
+            });
+
+            assert!(estimates.iter().all(|(_, estimate)| estimate.count() == 6));
+        }
+
+        #[test]
+        fn test_races_on_the_aggregation() {
+            //the first genome has the better mean, but the second never does as badly
+            let race = |aggregation| {
+                let mut trials = [0; 2];
+                let evaluator = MultiTrialEvaluator::new(8, aggregation);
+                evaluator.race(&mut ChaCha8Rng::from_seed(Default::default()), &[0, 1], 1.0, |&genome: &usize, seed| {
+                    trials[genome] += 1;
+                    match genome {
+                        0 => 10.0 * (trials[0] % 2) as f32,
+                        _ => 4.0 + (seed % 3) as f32 / 100.0,
+                    }
+                })
+            };
+
+            let min = race(Aggregation::Min);
+            assert_eq!(min[0].0, 0.0);
+            assert!(min[1].0 >= 4.0, "{min:?}");
+            assert!(min[0].1.count() < 8 && min[1].1.count() == 8, "{min:?}");
+
+            let mean = race(Aggregation::Mean);
+            assert!(mean.iter().all(|(fitness, estimate)| *fitness == estimate.mean()), "{mean:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        for aggregation in [Aggregation::Mean, Aggregation::Median, Aggregation::Min] {
+            assert_eq!(aggregation.to_string().parse(), Ok(aggregation));
+        }
+        assert!("max".parse::<Aggregation>().is_err());
+    }
+}
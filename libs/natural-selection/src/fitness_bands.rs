@@ -0,0 +1,199 @@
+use std::ops::Range;
+
+use crate::*;
+
+//hierarchical fair competition (HFC, hu and goodman 2002): the population is split into bands
+//that only breed among themselves, each admitting individuals from a fitness threshold on, so
+//the newcomers of the bottom band compete with each other instead of with the best, and whoever
+//passes the threshold of a higher band is promoted to breed there
+pub struct FitnessBands<S> {
+    //breeds the children of every band with its selection, crossover and mutation
+    ga: GeneticAlgorithm<S>,
+    bands: usize,
+    //generations between the newcomers of the bottom band
+    reseed_interval: usize,
+    newcomer: Newcomer,
+    //the admission thresholds of the bands of the last generation
+    thresholds: Vec<f32>,
+    promoted: usize,
+}
+
+impl<S> FitnessBands<S>
+where
+    S: SelectionMethod,
+{
+    pub fn new(ga: GeneticAlgorithm<S>,
+               bands: usize,
+               reseed_interval: usize,
+               newcomer: impl Fn(&mut dyn RngCore, &mut [f32]) + 'static) -> Self {
+        assert!(bands > 0 && reseed_interval > 0);
+        Self { ga, bands, reseed_interval, newcomer: Box::new(newcomer), thresholds: Vec::new(), promoted: 0 }
+    }
+
+    pub fn bands(&self) -> usize {
+        self.bands
+    }
+
+    //the individuals of a population of `len` the band at `band` holds
+    pub fn members(&self, band: usize, len: usize) -> Range<usize> {
+        tier(band, self.bands, len)
+    }
+
+    //the fitness an individual needed to breed in every band in the last generation, they
+    //adapt to the population and split the range of its fitness evenly, the bottom band admits
+    //everybody
+    pub fn thresholds(&self) -> &[f32] {
+        &self.thresholds
+    }
+
+    //how many individuals of the last generation bred in a higher band than they were born in
+    pub fn promoted(&self) -> usize {
+        self.promoted
+    }
+
+    //like GeneticAlgorithm::evolve_in_place(), but every band of the population is bred from
+    //the individuals admitted to it
+    pub fn evolve_in_place<V>(&mut self, rng: &mut dyn RngCore, population: &mut [V])
+    where
+        V: InPlaceIndividual,
+    {
+        let reseed = self.ga.generation > 0 && self.ga.generation.is_multiple_of(self.reseed_interval);
+        let (bands, newcomer) = (self.bands, &self.newcomer);
+        let (mut thresholds, mut promoted) = (Vec::new(), 0);
+
+        self.ga.evolve_structured(rng, population, |ga, rng, rated, population, statistics| {
+            let len = population.len();
+            let min = rated.iter().map(Individual::fitness).fold(f32::INFINITY, f32::min);
+            let max = rated.iter().map(Individual::fitness).fold(f32::NEG_INFINITY, f32::max);
+            thresholds = (0..bands)
+                .map(|band| if band == 0 { f32::NEG_INFINITY } else { min + (max - min) * band as f32 / bands as f32 })
+                .collect();
+
+            //individuals are promoted to the highest band they pass the threshold of, but never
+            //leave the band they were born in for a lower one
+            let admitted: Vec<usize> = rated
+                .iter()
+                .enumerate()
+                .map(|(index, individual)| {
+                    let born_in = index * bands / len;
+                    let passed = thresholds.iter().rposition(|&threshold| individual.fitness() >= threshold).unwrap_or(0);
+                    passed.max(born_in)
+                })
+                .collect();
+            promoted = admitted.iter().enumerate().filter(|&(index, &band)| band > index * bands / len).count();
+
+            let mut selected_fitness = Vec::with_capacity(len * 2);
+            let mut pool = Vec::new();
+            for band in 0..bands {
+                //a band nobody was admitted to breeds from the next lower one that has members
+                let members: Vec<_> = (0..len).filter(|&index| admitted[index] == band).map(|index| rated[index]).collect();
+                if !members.is_empty() {
+                    pool = members;
+                }
+
+                for member in tier(band, bands, len) {
+                    let child = population[member].genes_mut();
+                    if pool.is_empty() || (band == 0 && reseed) {
+                        newcomer(rng, child);
+                        continue;
+                    }
+
+                    let parent_a = ga.selection_method.select(rng, &pool);
+                    let parent_b = ga.selection_method.select(rng, &pool);
+                    selected_fitness.extend([parent_a.fitness(), parent_b.fitness()]);
+
+                    ga.breed_child(rng, parent_a.chromosome(), parent_b.chromosome(), child, statistics);
+                }
+            }
+            selected_fitness
+        });
+
+        self.thresholds = thresholds;
+        self.promoted = promoted;
+    }
+}
+
+impl<S> Optimizer for FitnessBands<S>
+where
+    S: SelectionMethod,
+{
+    fn evolve_in_place(&mut self, rng: &mut dyn RngCore, population: &mut [&mut dyn InPlaceIndividual]) {
+        FitnessBands::evolve_in_place(self, rng, population)
+    }
+
+    fn generation(&self) -> usize {
+        self.ga.generation
+    }
+
+    fn statistics(&self) -> Option<&Statistics> {
+        self.ga.statistics.as_ref()
+    }
+
+    fn add_observer(&mut self, observer: Box<dyn Observer>) {
+        self.ga.observers.push(observer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    struct Genes(Vec<f32>);
+
+    impl InPlaceIndividual for Genes {
+        fn fitness(&self) -> f32 {
+            self.0.iter().sum()
+        }
+
+        fn genes(&self) -> &[f32] {
+            &self.0
+        }
+
+        fn genes_mut(&mut self) -> &mut [f32] {
+            &mut self.0
+        }
+    }
+
+    fn hfc() -> FitnessBands<RouletteWheelSelection> {
+        let ga = GeneticAlgorithm::new(RouletteWheelSelection::new(), UniformCrossover, GaussianMutation::new(0.0, 0.0));
+        //newcomers are recognizable by their genes of 0.5
+        FitnessBands::new(ga, 3, 2, |_, genes| genes.fill(0.5))
+    }
+
+    #[test]
+    fn test_bands() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut hfc = hfc();
+        //fitness 18 down to 2, the thresholds split it at 7.33 and 12.67
+        let mut population: Vec<_> = (0..9).map(|n| Genes(vec![9.0 - n as f32; 2])).collect();
+
+        hfc.evolve_in_place(&mut rng, &mut population);
+        assert_eq!(hfc.thresholds()[0], f32::NEG_INFINITY);
+        approx::assert_relative_eq!(hfc.thresholds()[1..], [22.0 / 3.0, 38.0 / 3.0][..]);
+        //the whole bottom band is promoted to the top one, which keeps its own weak members
+        assert_eq!(hfc.promoted(), 3);
+
+        //every band only breeds from those admitted to it, nobody is left in the bottom band
+        let genes = |band: Range<usize>| population[band].iter().flat_map(|individual| individual.0.clone()).collect::<Vec<_>>();
+        assert!(genes(hfc.members(0, 9)).iter().all(|&gene| gene == 0.5));
+        assert!(genes(hfc.members(1, 9)).iter().all(|&gene| (4.0..=6.0).contains(&gene)));
+        assert!(genes(hfc.members(2, 9)).iter().all(|&gene| !(4.0..=6.0).contains(&gene)));
+    }
+
+    #[test]
+    fn test_newcomers() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut hfc = hfc();
+        let mut population: Vec<_> = (0..9).map(|n| Genes(vec![1.0 + n as f32; 2])).collect();
+
+        hfc.evolve_in_place(&mut rng, &mut population);
+        hfc.evolve_in_place(&mut rng, &mut population);
+        assert!(population[0..3].iter().any(|individual| individual.0 != [0.5, 0.5]));
+
+        hfc.evolve_in_place(&mut rng, &mut population);
+        assert!(population[0..3].iter().all(|individual| individual.0 == [0.5, 0.5]));
+        assert_eq!(hfc.generation(), 3);
+    }
+}
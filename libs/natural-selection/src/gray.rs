@@ -0,0 +1,192 @@
+//gray codes of neighbouring integers differ in a single bit, so flipping one bit of a
+//gray coded gene tends to move its value a little instead of jumping across the range
+
+pub fn gray_encode(value: u32) -> u32 {
+    value ^ (value >> 1)
+}
+
+pub fn gray_decode(gray: u32) -> u32 {
+    let mut value = gray;
+    let mut shift = gray >> 1;
+    while shift != 0 {
+        value ^= shift;
+        shift >>= 1;
+    }
+    value
+}
+
+//an integer in min..=max, stored as a gray coded bitfield
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntegerRange {
+    min: i32,
+    max: i32,
+}
+
+impl IntegerRange {
+    pub fn new(min: i32, max: i32) -> Self {
+        assert!(min <= max);
+        Self { min, max }
+    }
+
+    fn span(&self) -> u32 {
+        (self.max as i64 - self.min as i64) as u32
+    }
+
+    //the number of bits needed for every value of the range
+    pub fn bits(&self) -> usize {
+        (u32::BITS - self.span().leading_zeros()) as usize
+    }
+
+    pub fn encode(&self, value: i32) -> u32 {
+        assert!((self.min..=self.max).contains(&value), "{value} is outside of {}..={}", self.min, self.max);
+        gray_encode((value as i64 - self.min as i64) as u32)
+    }
+
+    //bitfields past the end of the range decode to its maximum
+    pub fn decode(&self, gray: u32) -> i32 {
+        (self.min as i64 + gray_decode(gray).min(self.span()) as i64) as i32
+    }
+}
+
+//integer ranges laid out one after another in a bitfield, most significant bit first
+#[derive(Clone, Debug, Default)]
+pub struct GrayLayout {
+    ranges: Vec<IntegerRange>,
+}
+
+impl GrayLayout {
+    pub fn new(ranges: impl IntoIterator<Item = IntegerRange>) -> Self {
+        Self { ranges: ranges.into_iter().collect() }
+    }
+
+    pub fn bits(&self) -> usize {
+        self.ranges.iter().map(IntegerRange::bits).sum()
+    }
+
+    pub fn encode(&self, values: &[i32]) -> Vec<bool> {
+        assert_eq!(values.len(), self.ranges.len());
+
+        self.ranges
+            .iter()
+            .zip(values)
+            .flat_map(|(range, &value)| {
+                let gray = range.encode(value);
+                (0..range.bits()).rev().map(move |bit| gray >> bit & 1 == 1)
+            })
+            .collect()
+    }
+
+    pub fn decode(&self, bits: &[bool]) -> Vec<i32> {
+        assert_eq!(bits.len(), self.bits());
+
+        let mut bits = bits.iter();
+        self.ranges
+            .iter()
+            .map(|range| {
+                let gray = bits
+                    .by_ref()
+                    .take(range.bits())
+                    .fold(0, |gray, &bit| gray << 1 | bit as u32);
+                range.decode(gray)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod gray {
+        use super::*;
+
+        #[test]
+        fn test_known_values() {
+            let codes: Vec<_> = (0..8).map(gray_encode).collect();
+            assert_eq!(codes, vec![0b000, 0b001, 0b011, 0b010, 0b110, 0b111, 0b101, 0b100]);
+        }
+
+        #[test]
+        fn test_neighbours_differ_in_one_bit() {
+            for value in 0..1000 {
+                assert_eq!((gray_encode(value) ^ gray_encode(value + 1)).count_ones(), 1);
+            }
+        }
+
+        #[test]
+        fn test_round_trip() {
+            for value in (0..1000).chain([u32::MAX - 1, u32::MAX]) {
+                assert_eq!(gray_decode(gray_encode(value)), value);
+            }
+        }
+    }
+
+    mod integer_range {
+        use super::*;
+
+        #[test]
+        fn test_bits() {
+            assert_eq!(IntegerRange::new(3, 3).bits(), 0);
+            assert_eq!(IntegerRange::new(0, 1).bits(), 1);
+            assert_eq!(IntegerRange::new(-4, 3).bits(), 3);
+            assert_eq!(IntegerRange::new(0, 8).bits(), 4);
+            assert_eq!(IntegerRange::new(i32::MIN, i32::MAX).bits(), 32);
+        }
+
+        #[test]
+        fn test_round_trip() {
+            let range = IntegerRange::new(-5, 10);
+            for value in -5..=10 {
+                assert_eq!(range.decode(range.encode(value)), value);
+            }
+
+            let range = IntegerRange::new(i32::MIN, i32::MAX);
+            for value in [i32::MIN, -1, 0, 1, i32::MAX] {
+                assert_eq!(range.decode(range.encode(value)), value);
+            }
+        }
+
+        #[test]
+        fn test_decode_clamps() {
+            //5 values need 3 bits, the 3 unused codes mean the maximum
+            let range = IntegerRange::new(10, 14);
+            assert_eq!(range.decode(gray_encode(7)), 14);
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_encode_outside() {
+            IntegerRange::new(0, 3).encode(4);
+        }
+    }
+
+    mod layout {
+        use super::*;
+
+        #[test]
+        fn test_round_trip() {
+            let layout = GrayLayout::new([
+                IntegerRange::new(0, 3),
+                IntegerRange::new(-10, 10),
+                IntegerRange::new(7, 7),
+            ]);
+            assert_eq!(layout.bits(), 2 + 5);
+
+            let bits = layout.encode(&[2, -3, 7]);
+            assert_eq!(bits, vec![true, true, false, false, true, false, false]);
+            assert_eq!(layout.decode(&bits), vec![2, -3, 7]);
+        }
+
+        #[test]
+        fn test_bit_flip_moves_value_to_neighbour() {
+            let layout = GrayLayout::new([IntegerRange::new(0, 255)]);
+
+            //flipping the lowest bit always lands on an adjacent value
+            for value in 0..=255 {
+                let mut bits = layout.encode(&[value]);
+                *bits.last_mut().unwrap() ^= true;
+                assert_eq!((layout.decode(&bits)[0] - value).abs(), 1);
+            }
+        }
+    }
+}
@@ -3,9 +3,60 @@ use crate::*;
 pub trait Individual {
     fn create(chromosome: Chromosome) -> Self;
     fn fitness(&self) -> f32;
+
+    //standard error of fitness() if it is the mean of repeated noisy evaluations
+    fn fitness_stderr(&self) -> f32 {
+        0.0
+    }
+
     fn chromosome(&self) -> &Chromosome;
 }
 
+//an individual whose genes live somewhere else, e.g. in a network's weights, so the optimizer
+//can overwrite them with its offspring instead of creating new individuals
+pub trait InPlaceIndividual {
+    fn fitness(&self) -> f32;
+
+    //see Individual::fitness_stderr()
+    fn fitness_stderr(&self) -> f32 {
+        0.0
+    }
+
+    //the behavior of the individual for quality-diversity optimizers, e.g. how far it moved,
+    //nothing by default
+    fn descriptor(&self) -> &[f32] {
+        &[]
+    }
+
+    fn genes(&self) -> &[f32];
+    fn genes_mut(&mut self) -> &mut [f32];
+}
+
+impl<T> InPlaceIndividual for &mut T
+where
+    T: InPlaceIndividual + ?Sized,
+{
+    fn fitness(&self) -> f32 {
+        (**self).fitness()
+    }
+
+    fn fitness_stderr(&self) -> f32 {
+        (**self).fitness_stderr()
+    }
+
+    fn descriptor(&self) -> &[f32] {
+        (**self).descriptor()
+    }
+
+    fn genes(&self) -> &[f32] {
+        (**self).genes()
+    }
+
+    fn genes_mut(&mut self) -> &mut [f32] {
+        (**self).genes_mut()
+    }
+}
+
 #[cfg(test)]
 #[derive(Clone, Debug, PartialEq)]
 pub enum TestIndividual {
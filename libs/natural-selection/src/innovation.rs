@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+//a gene that was created by a structural mutation, e.g. a connection between two nodes
+pub trait StructuralGene {
+    fn innovation(&self) -> usize;
+}
+
+//hands out the same innovation number to the same structural mutation, no matter in which
+//genome or generation it happens, so crossover can tell which genes of two parents match
+#[derive(Clone, Debug, Default)]
+pub struct InnovationRegistry {
+    //innovation of the connection from the first node to the second
+    connections: HashMap<(usize, usize), usize>,
+    //the node that splits the connection with this innovation
+    splits: HashMap<usize, usize>,
+    next_innovation: usize,
+    next_node: usize,
+}
+
+impl InnovationRegistry {
+    //`nodes` is the number of nodes every genome starts with, e.g. its inputs and outputs,
+    //nodes added by splits are numbered after them
+    pub fn new(nodes: usize) -> Self {
+        Self { next_node: nodes, ..Self::default() }
+    }
+
+    pub fn connection(&mut self, from: usize, to: usize) -> usize {
+        let next = &mut self.next_innovation;
+        *self.connections.entry((from, to)).or_insert_with(|| {
+            *next += 1;
+            *next - 1
+        })
+    }
+
+    //the node that is added when the connection with this innovation is split in two, and
+    //the innovations of the connections into and out of it
+    pub fn split(&mut self, innovation: usize) -> (usize, usize, usize) {
+        let (from, to) = self
+            .connections
+            .iter()
+            .find(|(_, &other)| other == innovation)
+            .map(|(&connection, _)| connection)
+            .unwrap_or_else(|| panic!("innovation {innovation} is not a connection"));
+
+        let next = &mut self.next_node;
+        let node = *self.splits.entry(innovation).or_insert_with(|| {
+            *next += 1;
+            *next - 1
+        });
+
+        (node, self.connection(from, node), self.connection(node, to))
+    }
+
+    //the number of innovations handed out so far
+    pub fn innovations(&self) -> usize {
+        self.next_innovation
+    }
+
+    pub fn nodes(&self) -> usize {
+        self.next_node
+    }
+}
+
+//a gene of one parent lined up with the gene of the other parent that has the same
+//innovation, if there is one
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Aligned<'a, G> {
+    Matching(&'a G, &'a G),
+    //only in one parent, inside the innovation range of the other
+    DisjointA(&'a G),
+    DisjointB(&'a G),
+    //only in one parent, newer than every gene of the other
+    ExcessA(&'a G),
+    ExcessB(&'a G),
+}
+
+//lines up the structural genes of two parents by innovation, both need to be sorted by it
+pub fn align<'a, G>(a: &'a [G], b: &'a [G]) -> Vec<Aligned<'a, G>>
+where
+    G: StructuralGene,
+{
+    debug_assert!(a.windows(2).all(|genes| genes[0].innovation() < genes[1].innovation()));
+    debug_assert!(b.windows(2).all(|genes| genes[0].innovation() < genes[1].innovation()));
+
+    let last_a = a.last().map(StructuralGene::innovation);
+    let last_b = b.last().map(StructuralGene::innovation);
+    let only_a = |gene: &'a G| match last_b {
+        Some(last) if gene.innovation() < last => Aligned::DisjointA(gene),
+        _ => Aligned::ExcessA(gene),
+    };
+    let only_b = |gene: &'a G| match last_a {
+        Some(last) if gene.innovation() < last => Aligned::DisjointB(gene),
+        _ => Aligned::ExcessB(gene),
+    };
+
+    let mut aligned = Vec::with_capacity(a.len().max(b.len()));
+    let (mut a, mut b) = (a.iter().peekable(), b.iter().peekable());
+    loop {
+        match (a.peek(), b.peek()) {
+            (Some(gene_a), Some(gene_b)) if gene_a.innovation() == gene_b.innovation() => {
+                aligned.push(Aligned::Matching(*gene_a, *gene_b));
+                a.next();
+                b.next();
+            }
+            (Some(gene_a), Some(gene_b)) if gene_a.innovation() < gene_b.innovation() => {
+                aligned.push(only_a(gene_a));
+                a.next();
+            }
+            (_, Some(gene_b)) => {
+                aligned.push(only_b(gene_b));
+                b.next();
+            }
+            (Some(gene_a), None) => {
+                aligned.push(only_a(gene_a));
+                a.next();
+            }
+            (None, None) => return aligned,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl StructuralGene for usize {
+        fn innovation(&self) -> usize {
+            *self
+        }
+    }
+
+    #[test]
+    fn test_same_mutation_same_innovation() {
+        let mut registry = InnovationRegistry::new(3);
+        let a = registry.connection(0, 2);
+        let b = registry.connection(1, 2);
+
+        assert_eq!((a, b), (0, 1));
+        assert_eq!(registry.connection(0, 2), a);
+        assert_eq!(registry.innovations(), 2);
+    }
+
+    #[test]
+    fn test_split() {
+        let mut registry = InnovationRegistry::new(3);
+        let connection = registry.connection(0, 2);
+
+        assert_eq!(registry.split(connection), (3, 1, 2));
+        assert_eq!(registry.split(connection), (3, 1, 2));
+        assert_eq!(registry.connection(3, 2), 2);
+        assert_eq!(registry.nodes(), 4);
+    }
+
+    #[test]
+    fn test_align() {
+        let a = [1, 2, 4, 8, 9];
+        let b = [1, 3, 4, 5];
+
+        assert_eq!(align(&a, &b), vec![
+            Aligned::Matching(&1, &1),
+            Aligned::DisjointA(&2),
+            Aligned::DisjointB(&3),
+            Aligned::Matching(&4, &4),
+            Aligned::DisjointB(&5),
+            Aligned::ExcessA(&8),
+            Aligned::ExcessA(&9),
+        ]);
+    }
+}
@@ -1,17 +1,58 @@
-pub use self::{chromosome::*, crossover::*, individual::*, mutation::*, selection::*};
+pub use self::{age_layers::*, alleles::*, bits::*, chromosome::*, constraint::*, crossover::*, elites::*, estimate::*, evaluator::*, fitness_bands::*, gray::*, individual::*, innovation::*, mutation::*, observer::*, optimizer::*, permutation::*, scaling::*, selection::*, species::*, statistics::*, surrogate::*};
 
-use rand::RngCore;
+use rand::{Rng, RngCore};
 
+mod age_layers;
+mod alleles;
+mod bits;
 mod chromosome;
+mod constraint;
 mod crossover;
+mod elites;
+mod estimate;
+mod evaluator;
+mod fitness_bands;
+mod gray;
 mod individual;
+mod innovation;
 mod mutation;
+mod observer;
+mod optimizer;
+mod permutation;
+mod scaling;
 mod selection;
+mod species;
+mod statistics;
+mod surrogate;
+
+//species with at least this many members pass on their champion unchanged, as in NEAT
+pub const CHAMPION_SPECIES_SIZE: usize = 5;
 
 pub struct GeneticAlgorithm<S> {
     selection_method: S,
     crossover_method: Box<dyn CrossoverMethod>,
     mutation_method: Box<dyn MutationMethod>,
+    constraint_handler: Option<Box<dyn ConstraintHandler>>,
+    //lambda of the l2 penalty taken from the fitness, 0.0 turns it off
+    weight_decay: f32,
+    //what the selection sees of the fitness, after constraints and weight decay
+    scaling: FitnessScaling,
+    //sums up what mutation does to every child in the statistics
+    log_mutations: bool,
+    //sums up which parent the genes of every child came from in the statistics
+    trace_provenance: bool,
+    crowding: bool,
+    surrogate: Option<Surrogate>,
+    observers: Vec<Box<dyn Observer>>,
+    generation: usize,
+    champion_fitness: Option<f32>,
+    statistics: Option<Statistics>,
+    //copies of the parents for evolve_in_place(), kept to reuse their allocations
+    parents: Vec<Parent>,
+    //the parents of the last generation and the one of them every child of it is closer to,
+    //for deterministic crowding
+    ancestors: Vec<Parent>,
+    lineage: Vec<Option<usize>>,
 }
 
 impl<S> GeneticAlgorithm<S>
@@ -27,31 +68,591 @@ where
             selection_method,
             crossover_method: Box::new(crossover_method),
             mutation_method: Box::new(mutation_method),
+            constraint_handler: None,
+            weight_decay: 0.0,
+            scaling: FitnessScaling::None,
+            log_mutations: false,
+            trace_provenance: false,
+            crowding: false,
+            surrogate: None,
+            observers: Vec::new(),
+            generation: 0,
+            champion_fitness: None,
+            statistics: None,
+            parents: Vec::new(),
+            ancestors: Vec::new(),
+            lineage: Vec::new(),
         }
     }
 
-    pub fn evolve<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> Vec<I>
+    pub fn with_observer(mut self, observer: impl Observer + 'static) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    //the number of populations evolved so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    //statistics of the last evolved population
+    pub fn statistics(&self) -> Option<&Statistics> {
+        self.statistics.as_ref()
+    }
+
+    pub fn with_constraint_handler(mut self, constraint_handler: impl ConstraintHandler + 'static) -> Self {
+        self.constraint_handler = Some(Box::new(constraint_handler));
+        self
+    }
+
+    //selection sees the fitness minus `lambda` times the sum of the squared genes, so evolution
+    //prefers small weights unless bigger ones pay off
+    pub fn with_weight_decay(mut self, lambda: f32) -> Self {
+        assert!(lambda >= 0.0);
+        self.weight_decay = lambda;
+        self
+    }
+
+    //scales the fitness of every generation before selection, the statistics still show it
+    //unscaled
+    pub fn with_fitness_scaling(mut self, scaling: FitnessScaling) -> Self {
+        self.scaling = scaling;
+        self
+    }
+
+    //counts the genes mutation changes in every child and how far, see Statistics::mutations
+    pub fn with_mutation_logging(mut self) -> Self {
+        self.log_mutations = true;
+        self
+    }
+
+    //sums up how evenly the children mix their parents, see Statistics::provenance
+    pub fn with_provenance_tracking(mut self) -> Self {
+        self.trace_provenance = true;
+        self
+    }
+
+    //deterministic crowding: every child of evolve_in_place() competes with the parent whose
+    //genes it took more of, and that parent takes its place among the next parents if it was
+    //fitter, which keeps niches from being taken over, needs a crossover that knows the
+    //provenance
+    pub fn with_deterministic_crowding(mut self) -> Self {
+        self.crowding = true;
+        self
+    }
+
+    //learns the fitness of every generation, so that only the most promising of several
+    //candidates for every place in the next one needs a real evaluation, only evolve() and
+    //evolve_in_place() breed candidates
+    pub fn with_surrogate(mut self, surrogate: Surrogate) -> Self {
+        self.surrogate = Some(surrogate);
+        self
+    }
+
+    //whether selection sees a different fitness than the individuals report
+    fn rates(&self) -> bool {
+        self.constraint_handler.is_some() || self.weight_decay > 0.0 || self.scaling != FitnessScaling::None
+    }
+
+    fn rate<'a, I>(&self, individual: &'a I) -> Rated<'a, I>
+    where
+        I: Individual,
+    {
+        Rated::new(individual, self.constraint_handler.as_deref(), self.weight_decay)
+    }
+
+    fn rated_statistics<I>(&self, rated: &[Rated<I>]) -> Statistics
     where
         I: Individual,
+    {
+        let mut statistics = Statistics::new(self.generation, rated);
+        statistics.invalid = rated.iter().filter(|rated| !rated.valid).count();
+        statistics.avg_regularization = rated.iter().map(|rated| rated.regularization).sum::<f32>() / rated.len() as f32;
+        statistics
+    }
+
+    //turns the fitness the selection sees into the scaled one
+    fn scale<I>(&self, rated: &mut [Rated<I>]) {
+        if self.scaling == FitnessScaling::None {
+            return;
+        }
+        let mut fitness: Vec<f32> = rated.iter().map(|rated| rated.fitness).collect();
+        self.scaling.scale(&mut fitness);
+        for (rated, fitness) in rated.iter_mut().zip(fitness) {
+            rated.fitness = fitness;
+        }
+    }
+
+    pub fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> Vec<I>
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+
+        for observer in &mut self.observers {
+            observer.on_generation_start(self.generation);
+        }
+
+        let surrogate_error = self.learn(population);
+        let (offspring, mut statistics) = if self.rates() {
+            //selection and statistics see the penalized fitness
+            let mut rated: Vec<_> = population.iter().map(|individual| self.rate(individual)).collect();
+
+            let mut statistics = self.rated_statistics(&rated);
+            self.scale(&mut rated);
+            (self.breed(rng, &rated, &mut statistics), statistics)
+        } else {
+            let mut statistics = Statistics::new(self.generation, population);
+            (self.breed(rng, population, &mut statistics), statistics)
+        };
+        statistics.surrogate_error = surrogate_error;
+        self.lineage.clear();
+
+        self.report(population, &statistics);
+        self.statistics = Some(statistics);
+        self.generation += 1;
+
+        offspring
+    }
+
+    //like evolve(), but overwrites the genes of the population with their offspring, the
+    //parents are copied once into buffers that are reused every generation
+    pub fn evolve_in_place<V>(&mut self, rng: &mut dyn RngCore, population: &mut [V])
+    where
+        V: InPlaceIndividual,
     {
         assert!(!population.is_empty());
 
-        (0..population.len())
-            .map(|_| {
+        for observer in &mut self.observers {
+            observer.on_generation_start(self.generation);
+        }
+
+        //the parents of the last generation become the ancestors the children compete with
+        let ancestors = std::mem::take(&mut self.parents);
+        let mut parents = snapshot_parents(std::mem::take(&mut self.ancestors), population);
+        let surrogate_error = self.learn(&parents);
+        let crowded_out = self.crowd(&ancestors, &mut parents);
+
+        let children = population.iter_mut().map(|individual| individual.genes_mut());
+        let (mut statistics, lineage) = if self.rates() {
+            let mut rated: Vec<_> = parents.iter().map(|parent| self.rate(parent)).collect();
+
+            let mut statistics = self.rated_statistics(&rated);
+            self.scale(&mut rated);
+            let lineage = self.breed_into(rng, &rated, children, &mut statistics);
+            (statistics, lineage)
+        } else {
+            let mut statistics = Statistics::new(self.generation, &parents);
+            let lineage = self.breed_into(rng, &parents, children, &mut statistics);
+            (statistics, lineage)
+        };
+        statistics.crowded_out = crowded_out;
+        statistics.surrogate_error = surrogate_error;
+
+        self.report(&parents, &statistics);
+        self.parents = parents;
+        self.ancestors = ancestors;
+        self.lineage = lineage;
+        self.statistics = Some(statistics);
+        self.generation += 1;
+    }
+
+    //like evolve_in_place(), but `breed` writes the children, for population structures that
+    //decide themselves who breeds with whom, it gets the rated parents in the order of the
+    //population and returns the fitness of every parent it selected
+    fn evolve_structured<V>(&mut self,
+                            rng: &mut dyn RngCore,
+                            population: &mut [V],
+                            breed: impl FnOnce(&Self, &mut dyn RngCore, &[Rated<Parent>], &mut [V], &mut Statistics) -> Vec<f32>)
+    where
+        V: InPlaceIndividual,
+    {
+        assert!(!population.is_empty());
+
+        for observer in &mut self.observers {
+            observer.on_generation_start(self.generation);
+        }
+
+        let parents = snapshot_parents(std::mem::take(&mut self.parents), population);
+
+        let mut rated: Vec<_> = parents.iter().map(|parent| self.rate(parent)).collect();
+        let mut statistics = self.rated_statistics(&rated);
+        self.scale(&mut rated);
+
+        let selected_fitness = breed(self, rng, &rated, population, &mut statistics);
+        statistics.set_selection_intensity(&rated, &selected_fitness);
+
+        self.report(&parents, &statistics);
+        self.parents = parents;
+        self.lineage.clear();
+        self.statistics = Some(statistics);
+        self.generation += 1;
+    }
+
+    //like evolve_in_place(), but with the reproduction of NEAT: the population is sorted into
+    //species, stagnant species are pruned and every species gets offspring in proportion to
+    //its adjusted fitness, bred from its own members only
+    pub fn evolve_speciated_in_place<V>(&mut self,
+                                        rng: &mut dyn RngCore,
+                                        population: &mut [V],
+                                        speciation: &mut Speciation)
+    where
+        V: InPlaceIndividual,
+    {
+        assert!(!population.is_empty());
+
+        for observer in &mut self.observers {
+            observer.on_generation_start(self.generation);
+        }
+
+        let parents = snapshot_parents(std::mem::take(&mut self.parents), population);
+
+        //species and selection see the penalized and scaled fitness
+        let mut rated: Vec<_> = parents.iter().map(|parent| self.rate(parent)).collect();
+        let mut statistics = self.rated_statistics(&rated);
+        self.scale(&mut rated);
+        let fitness: Vec<f32> = rated.iter().map(Individual::fitness).collect();
+
+        speciation.speciate(parents.iter().map(|parent| parent.chromosome.as_slice()), &fitness);
+        speciation.prune_stagnant();
+        let quotas = speciation.offspring_quotas(&fitness, population.len());
+
+        let mut children = population.iter_mut().map(|individual| individual.genes_mut());
+        let mut selected_fitness = Vec::with_capacity(parents.len() * 2);
+        for (species, quota) in speciation.species().iter().zip(quotas) {
+            let members: Vec<_> = species
+                .members()
+                .iter()
+                .map(|&member| rated[member])
+                .collect();
+
+            for (index, child) in children.by_ref().take(quota).enumerate() {
+                //the champion of a big enough species survives unchanged
+                if index == 0 && members.len() >= CHAMPION_SPECIES_SIZE {
+                    let champion = members
+                        .iter()
+                        .max_by(|a, b| a.fitness.total_cmp(&b.fitness))
+                        .expect("species have members");
+                    child.copy_from_slice(champion.chromosome().as_slice());
+                    continue;
+                }
+
+                let parent_a = self.selection_method.select(rng, &members);
+                let parent_b = self.selection_method.select(rng, &members);
+                selected_fitness.extend([parent_a.fitness(), parent_b.fitness()]);
+
+                self.breed_child(rng, parent_a.chromosome(), parent_b.chromosome(), child, &mut statistics);
+            }
+        }
+        statistics.set_selection_intensity(&rated, &selected_fitness);
+        self.lineage.clear();
+
+        self.report(&parents, &statistics);
+        self.parents = parents;
+        self.statistics = Some(statistics);
+        self.generation += 1;
+    }
+
+    fn breed<P, I>(&self, rng: &mut dyn RngCore, parents: &[P], statistics: &mut Statistics) -> Vec<I>
+    where
+        P: Individual,
+        I: Individual,
+    {
+        let len = parents[0].chromosome().len();
+        let mut offspring: Vec<Chromosome> = (0..parents.len())
+            .map(|_| std::iter::repeat_n(0.0, len).collect())
+            .collect();
+
+        self.breed_into(rng, parents, offspring.iter_mut().map(Chromosome::as_mut_slice), statistics);
+
+        //create individuals
+        offspring.into_iter().map(I::create).collect()
+    }
+
+    //writes one child into every buffer of `children`, returns the index of the parent every
+    //child is closer to with deterministic crowding, nothing without
+    fn breed_into<'a, P>(&self,
+                         rng: &mut dyn RngCore,
+                         parents: &[P],
+                         children: impl Iterator<Item = &'a mut [f32]>,
+                         statistics: &mut Statistics) -> Vec<Option<usize>>
+    where
+        P: Individual,
+    {
+        let mut selected_fitness = Vec::with_capacity(parents.len() * 2);
+        let mut lineage = Vec::new();
+        let surrogate = self.surrogate.as_ref().filter(|surrogate| !surrogate.is_empty());
+        let mut candidate = Vec::new();
+
+        for child in children {
+            let mut chosen = None;
+            let mut best_outlook = f32::NEG_INFINITY;
+            for _ in 0..surrogate.map_or(1, Surrogate::candidates) {
                 //selection
-                let _parent_a = self.selection_method.select(rng, population).chromosome();
-                let _parent_b = self.selection_method.select(rng, population).chromosome();
+                let parent_a = self.selection_method.select(rng, parents);
+                let parent_b = self.selection_method.select(rng, parents);
+
+                let Some(surrogate) = surrogate else {
+                    let provenance = self.breed_child(rng, parent_a.chromosome(), parent_b.chromosome(), child, statistics);
+                    chosen = Some((parent_a, parent_b, provenance));
+                    continue;
+                };
+
+                //the candidate with the best outlook takes the place, mutation logging counts
+                //all of them
+                candidate.resize(child.len(), 0.0);
+                let provenance = self.breed_child(rng, parent_a.chromosome(), parent_b.chromosome(), &mut candidate, statistics);
+                let outlook = surrogate.outlook(&candidate);
+                if chosen.is_none() || outlook > best_outlook {
+                    child.copy_from_slice(&candidate);
+                    chosen = Some((parent_a, parent_b, provenance));
+                    best_outlook = outlook;
+                }
+            }
+
+            let (parent_a, parent_b, provenance) = chosen.expect("bred a candidate");
+            selected_fitness.extend([parent_a.fitness(), parent_b.fitness()]);
+            if self.crowding {
+                let closer = provenance.map(|provenance| if provenance.closer_to_a() { parent_a } else { parent_b });
+                lineage.push(closer.and_then(|closer| parents.iter().position(|parent| std::ptr::eq(parent, closer))));
+            }
+        }
+
+        statistics.set_selection_intensity(parents, &selected_fitness);
+        lineage
+    }
+
+    //teaches the surrogate the fitness of `population`, returns how far off it was
+    fn learn<I>(&mut self, population: &[I]) -> Option<f32>
+    where
+        I: Individual,
+    {
+        let surrogate = self.surrogate.as_mut()?;
+
+        let error = (!surrogate.is_empty()).then(|| {
+            population
+                .iter()
+                .filter_map(|individual| surrogate.predict(individual.chromosome().as_slice()))
+                .zip(population)
+                .map(|(prediction, individual)| (prediction.fitness - individual.fitness()).abs())
+                .sum::<f32>() / population.len() as f32
+        });
+        for individual in population {
+            surrogate.learn(individual.chromosome().as_slice(), individual.fitness());
+        }
+        error
+    }
+
+    //lets every ancestor that was fitter than the child closer to it take the child's place
+    //among `parents`, returns how many did
+    fn crowd(&self, ancestors: &[Parent], parents: &mut [Parent]) -> usize {
+        if !self.crowding || self.lineage.len() != parents.len() {
+            return 0;
+        }
+
+        let mut crowded_out = 0;
+        for (parent, closer) in parents.iter_mut().zip(&self.lineage) {
+            let Some(ancestor) = closer.and_then(|closer| ancestors.get(closer)) else {
+                continue;
+            };
+            if ancestor.fitness > parent.fitness {
+                parent.chromosome.assign(ancestor.chromosome.as_slice());
+                parent.fitness = ancestor.fitness;
+                parent.fitness_stderr = ancestor.fitness_stderr;
+                crowded_out += 1;
+            }
+        }
+        crowded_out
+    }
+
+    fn breed_child(&self,
+                   rng: &mut dyn RngCore,
+                   parent_a: &Chromosome,
+                   parent_b: &Chromosome,
+                   child: &mut [f32],
+                   statistics: &mut Statistics) -> Option<Provenance> {
+        //crossovers
+        let provenance = if self.trace_provenance || self.crowding {
+            self.crossover_method.crossover_traced(rng, parent_a.as_slice(), parent_b.as_slice(), child)
+        } else {
+            self.crossover_method.crossover_into(rng, parent_a.as_slice(), parent_b.as_slice(), child);
+            None
+        };
+        if let (true, Some(provenance)) = (self.trace_provenance, &provenance) {
+            statistics.provenance.get_or_insert_with(ProvenanceStatistics::default).record(provenance);
+        }
+
+        //mutation
+        if self.log_mutations {
+            let effect = self.mutation_method.mutate_genes_logged(rng, child);
+            statistics.mutations.get_or_insert_with(MutationStatistics::default).record(effect);
+        } else {
+            self.mutation_method.mutate_genes(rng, child);
+        }
+
+        //constraints
+        if let Some(constraint_handler) = &self.constraint_handler {
+            let mut chromosome: Chromosome = child.iter().copied().collect();
+            if !constraint_handler.is_valid(&chromosome) {
+                constraint_handler.repair(&mut chromosome);
+                child.copy_from_slice(chromosome.as_slice());
+                statistics.invalid_offspring += 1;
+            }
+        }
 
-                //crossovers
-                let mut child = self.crossover_method.crossover(rng, _parent_a, _parent_b);
+        provenance
+    }
 
-                //mutation
-                self.mutation_method.mutate(rng, &mut child);
+    fn report<I>(&mut self, population: &[I], statistics: &Statistics)
+    where
+        I: Individual,
+    {
+        let champion = population
+            .iter()
+            .max_by(|a, b| a.fitness().total_cmp(&b.fitness()))
+            .expect("got an empty population");
 
-                //create individual
-                I::create(child)
-            })
-            .collect()
+        if self.champion_fitness.is_none_or(|fitness| champion.fitness() > fitness) {
+            self.champion_fitness = Some(champion.fitness());
+            for observer in &mut self.observers {
+                observer.on_new_champion(self.generation, champion.chromosome(), champion.fitness());
+            }
+        }
+
+        for observer in &mut self.observers {
+            observer.on_generation_end(statistics);
+        }
+    }
+}
+
+//a copy of an in place individual, taken before its genes are overwritten
+struct Parent {
+    chromosome: Chromosome,
+    fitness: f32,
+    fitness_stderr: f32,
+}
+
+impl Default for Parent {
+    fn default() -> Self {
+        Self { chromosome: std::iter::empty().collect(), fitness: 0.0, fitness_stderr: 0.0 }
+    }
+}
+
+//copies `population` into `buffer`, whose chromosomes are reused from the generation before
+fn snapshot_parents<V>(mut buffer: Vec<Parent>, population: &[V]) -> Vec<Parent>
+where
+    V: InPlaceIndividual,
+{
+    buffer.resize_with(population.len(), Parent::default);
+    for (parent, individual) in buffer.iter_mut().zip(population) {
+        parent.chromosome.assign(individual.genes());
+        parent.fitness = individual.fitness();
+        parent.fitness_stderr = individual.fitness_stderr();
+    }
+    buffer
+}
+
+impl Individual for Parent {
+    fn create(_chromosome: Chromosome) -> Self {
+        unreachable!("parents are only selected, never created")
+    }
+
+    fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    fn fitness_stderr(&self) -> f32 {
+        self.fitness_stderr
+    }
+
+    fn chromosome(&self) -> &Chromosome {
+        &self.chromosome
+    }
+}
+
+//an individual as seen by the selection when constraints are handled or weights decay
+struct Rated<'a, I> {
+    individual: &'a I,
+    fitness: f32,
+    valid: bool,
+    //the weight decay taken from the fitness
+    regularization: f32,
+}
+
+impl<I> Clone for Rated<'_, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I> Copy for Rated<'_, I> {}
+
+impl<'a, I> Rated<'a, I>
+where
+    I: Individual,
+{
+    fn new(individual: &'a I, constraint_handler: Option<&dyn ConstraintHandler>, weight_decay: f32) -> Self {
+        let chromosome = individual.chromosome();
+        let valid = constraint_handler.is_none_or(|constraint_handler| constraint_handler.is_valid(chromosome));
+        let penalty = match constraint_handler {
+            Some(constraint_handler) if !valid => constraint_handler.penalty(chromosome),
+            _ => 0.0,
+        };
+        let regularization = if weight_decay > 0.0 {
+            weight_decay * chromosome.iter().map(|gene| gene * gene).sum::<f32>()
+        } else {
+            0.0
+        };
+
+        let fitness = if valid && regularization == 0.0 {
+            individual.fitness()
+        } else {
+            (individual.fitness() - penalty - regularization).max(0.0)
+        };
+
+        Self { individual, fitness, valid, regularization }
+    }
+}
+
+impl<I> Individual for Rated<'_, I>
+where
+    I: Individual,
+{
+    fn create(_chromosome: Chromosome) -> Self {
+        unreachable!("rated individuals are only selected, never created")
+    }
+
+    fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    fn fitness_stderr(&self) -> f32 {
+        self.individual.fitness_stderr()
+    }
+
+    fn chromosome(&self) -> &Chromosome {
+        self.individual.chromosome()
+    }
+}
+
+impl<S> Optimizer for GeneticAlgorithm<S>
+where
+    S: SelectionMethod,
+{
+    fn evolve_in_place(&mut self, rng: &mut dyn RngCore, population: &mut [&mut dyn InPlaceIndividual]) {
+        GeneticAlgorithm::evolve_in_place(self, rng, population)
+    }
+
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn statistics(&self) -> Option<&Statistics> {
+        self.statistics.as_ref()
+    }
+
+    fn add_observer(&mut self, observer: Box<dyn Observer>) {
+        self.observers.push(observer);
     }
 }
 
@@ -69,9 +670,9 @@ mod tests {
     #[test]
     fn test() {
         let mut rng = ChaCha8Rng::from_seed(Default::default());
-        let ga = GeneticAlgorithm::new(
+        let mut ga = GeneticAlgorithm::new(
             RouletteWheelSelection::new(),
-            UniformCrossover::default(),
+            UniformCrossover,
             GaussianMutation::new(0.5, 0.5));
 
         let mut population = vec![
@@ -98,4 +699,403 @@ mod tests {
         let final_fitness : f32 = population.iter().map(|i| i.fitness()).sum();
         assert!(final_fitness > initial_fitness);
     }
+
+    mod observers {
+        use super::*;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        #[derive(Default)]
+        struct Recorder {
+            events: Rc<RefCell<Vec<String>>>,
+        }
+
+        impl Observer for Recorder {
+            fn on_generation_start(&mut self, generation: usize) {
+                self.events.borrow_mut().push(format!("start {generation}"));
+            }
+
+            fn on_generation_end(&mut self, statistics: &Statistics) {
+                self.events.borrow_mut().push(format!("end {} max {}", statistics.generation, statistics.max_fitness));
+            }
+
+            fn on_new_champion(&mut self, generation: usize, _chromosome: &Chromosome, fitness: f32) {
+                self.events.borrow_mut().push(format!("champion {generation} {fitness}"));
+            }
+        }
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let recorder = Recorder::default();
+            let events = recorder.events.clone();
+
+            let mut ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover,
+                GaussianMutation::new(0.0, 0.0))
+                .with_observer(recorder);
+
+            ga.evolve(&mut rng, &[individual(&[1.0, 1.0]), individual(&[2.0, 2.0])]);
+            ga.evolve(&mut rng, &[individual(&[1.0, 0.0]), individual(&[0.0, 1.0])]);
+            ga.evolve(&mut rng, &[individual(&[3.0, 3.0]), individual(&[0.0, 1.0])]);
+
+            assert_eq!(ga.generation(), 3);
+            assert_eq!(*events.borrow(), vec![
+                "start 0", "champion 0 4", "end 0 max 4",
+                "start 1", "end 1 max 1",
+                "start 2", "champion 2 6", "end 2 max 6",
+            ]);
+        }
+    }
+
+    //genes that are owned by something other than a chromosome
+    struct Genes(Vec<f32>);
+
+    impl InPlaceIndividual for Genes {
+        fn fitness(&self) -> f32 {
+            self.0.iter().sum()
+        }
+
+        fn genes(&self) -> &[f32] {
+            &self.0
+        }
+
+        fn genes_mut(&mut self) -> &mut [f32] {
+            &mut self.0
+        }
+    }
+
+    fn ga() -> GeneticAlgorithm<RouletteWheelSelection> {
+        GeneticAlgorithm::new(
+            RouletteWheelSelection::new(),
+            UniformCrossover,
+            GaussianMutation::new(0.5, 0.5))
+    }
+
+    fn population() -> Vec<TestIndividual> {
+        vec![
+            individual(&[0.0, 0.0, 0.0]),
+            individual(&[1.0, 1.0, 1.0]),
+            individual(&[1.0, 2.0, 1.0]),
+            individual(&[1.0, 2.0, 4.0]),
+        ]
+    }
+
+    fn genes(population: &[TestIndividual]) -> Vec<Genes> {
+        population.iter().map(|individual| Genes(individual.chromosome().iter().copied().collect())).collect()
+    }
+
+    mod in_place {
+        use super::*;
+
+        #[test]
+        fn test_matches_evolve() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut expected = population();
+            let mut concrete = ga();
+            for _ in 0..10 {
+                expected = concrete.evolve(&mut rng, &expected);
+            }
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut actual = genes(&population());
+            let mut in_place = ga();
+            for _ in 0..10 {
+                in_place.evolve_in_place(&mut rng, &mut actual);
+            }
+
+            for (actual, expected) in actual.iter().zip(&expected) {
+                assert_eq!(actual.0.as_slice(), expected.chromosome().as_slice());
+            }
+            assert_eq!(in_place.generation(), 10);
+            assert_eq!(in_place.statistics(), concrete.statistics());
+        }
+
+        #[test]
+        fn test_keeps_the_buffers() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut population = genes(&population());
+            let buffers: Vec<_> = population.iter().map(|individual| individual.0.as_ptr()).collect();
+
+            ga().evolve_in_place(&mut rng, &mut population);
+
+            assert_eq!(population.iter().map(|individual| individual.0.as_ptr()).collect::<Vec<_>>(), buffers);
+        }
+
+        #[test]
+        fn test_provenance_tracking() {
+            let evolve = |traced: bool| {
+                let mut population = genes(&population());
+                let mut ga = if traced { ga().with_provenance_tracking() } else { ga() };
+                ga.evolve_in_place(&mut ChaCha8Rng::from_seed(Default::default()), &mut population);
+                (population.into_iter().map(|individual| individual.0).collect::<Vec<_>>(), ga.statistics().unwrap().provenance.clone())
+            };
+
+            let (offspring, provenance) = evolve(true);
+            //tracing draws the same numbers
+            assert_eq!(evolve(false), (offspring, None));
+
+            let provenance = provenance.unwrap();
+            assert_eq!(provenance.children, 4);
+            assert!((0.0..=0.5).contains(&provenance.avg_mixing()));
+        }
+
+        #[test]
+        fn test_deterministic_crowding() {
+            let crowd = |children: f32| {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let mut ga = ga().with_deterministic_crowding();
+                let mut population = genes(&population());
+                ga.evolve_in_place(&mut rng, &mut population);
+                assert_eq!(ga.statistics().unwrap().crowded_out, 0);
+
+                //every child competes with a parent of fitness 0.0 to 7.0
+                population.iter_mut().for_each(|individual| individual.0.fill(children));
+                ga.evolve_in_place(&mut rng, &mut population);
+                ga.statistics().unwrap().clone()
+            };
+
+            //roulette never picks the parent without fitness, so every child loses to a fitter one
+            let statistics = crowd(0.1);
+            assert_eq!(statistics.crowded_out, 4);
+            assert!(statistics.min_fitness >= 3.0);
+
+            let statistics = crowd(10.0);
+            assert_eq!(statistics.crowded_out, 0);
+            assert_eq!(statistics.max_fitness, 30.0);
+        }
+    }
+
+    mod surrogate {
+        use super::*;
+
+        fn offspring_fitness(surrogate: Option<Surrogate>) -> (f32, Option<f32>) {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut ga = ga();
+            if let Some(surrogate) = surrogate {
+                ga = ga.with_surrogate(surrogate);
+            }
+
+            let mut population = genes(&population());
+            ga.evolve_in_place(&mut rng, &mut population);
+            assert_eq!(ga.statistics().unwrap().surrogate_error, None);
+            ga.evolve_in_place(&mut rng, &mut population);
+
+            let fitness = population.iter().map(InPlaceIndividual::fitness).sum::<f32>() / population.len() as f32;
+            (fitness, ga.statistics().unwrap().surrogate_error)
+        }
+
+        #[test]
+        fn test_screens_the_offspring() {
+            let (unscreened, error) = offspring_fitness(None);
+            assert_eq!(error, None);
+
+            //the fitness is the sum of the genes, which the surrogate picks up quickly
+            let (screened, error) = offspring_fitness(Some(Surrogate::new(KnnSurrogate::new(2), 16, 8, 0.0)));
+            assert!(error.unwrap() > 0.0);
+            assert!(screened > unscreened, "{screened} <= {unscreened}");
+        }
+    }
+
+    mod speciated {
+        use super::*;
+
+        #[test]
+        fn test_offspring_follow_the_shared_fitness() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover,
+                GaussianMutation::new(0.0, 0.0));
+            let mut speciation = Speciation::new(1.0);
+
+            //five similar individuals share their fitness, the lone fit one gets most children
+            let mut population: Vec<_> = std::iter::repeat_n(vec![1.0, 1.0], 5)
+                .chain([vec![10.0, 10.0]])
+                .map(Genes)
+                .collect();
+            ga.evolve_speciated_in_place(&mut rng, &mut population, &mut speciation);
+
+            let genes: Vec<_> = population.iter().map(|individual| individual.0.clone()).collect();
+            assert_eq!(genes, [vec![1.0, 1.0]].into_iter().chain(std::iter::repeat_n(vec![10.0, 10.0], 5)).collect::<Vec<_>>());
+            assert_eq!(speciation.species().len(), 2);
+            assert_eq!(ga.generation(), 1);
+        }
+    }
+
+    mod optimizer {
+        use super::*;
+
+        #[test]
+        fn test_behaves_like_the_algorithm() {
+            let population = population();
+
+            let mut concrete = ga();
+            let expected = concrete.evolve(&mut ChaCha8Rng::from_seed(Default::default()), &population);
+
+            let mut optimizer: Box<dyn Optimizer> = Box::new(ga());
+            let mut genes = genes(&population);
+            let mut views: Vec<&mut dyn InPlaceIndividual> = genes
+                .iter_mut()
+                .map(|genes| genes as &mut dyn InPlaceIndividual)
+                .collect();
+            optimizer.evolve_in_place(&mut ChaCha8Rng::from_seed(Default::default()), &mut views);
+
+            for (actual, expected) in genes.iter().zip(&expected) {
+                assert_eq!(actual.0.as_slice(), expected.chromosome().as_slice());
+            }
+            assert_eq!(optimizer.generation(), 1);
+        }
+    }
+
+    mod constraints {
+        use super::*;
+        use approx::assert_relative_eq;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        #[derive(Default)]
+        struct LastStatistics(Rc<RefCell<Option<Statistics>>>);
+
+        impl Observer for LastStatistics {
+            fn on_generation_end(&mut self, statistics: &Statistics) {
+                *self.0.borrow_mut() = Some(statistics.clone());
+            }
+        }
+
+        fn in_range(chromosome: &Chromosome) -> bool {
+            chromosome.iter().all(|gene| (0.0..=1.0).contains(gene))
+        }
+
+        #[test]
+        fn test_repair() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let observer = LastStatistics::default();
+            let statistics = observer.0.clone();
+
+            let mut ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover,
+                GaussianMutation::new(1.0, 2.0))
+                .with_constraint_handler(RepairConstraint::new(in_range, |child: &mut Chromosome| {
+                    child.iter_mut().for_each(|gene| *gene = gene.clamp(0.0, 1.0))
+                }))
+                .with_observer(observer);
+
+            let population = vec![
+                individual(&[0.5, 0.5, 0.5]),
+                individual(&[1.0, 1.0, 1.0]),
+            ];
+            let offspring = ga.evolve(&mut rng, &population);
+
+            assert!(offspring.iter().all(|child| in_range(child.chromosome())));
+
+            let statistics = statistics.borrow().clone().unwrap();
+            assert_eq!(statistics.invalid, 0);
+            assert!(statistics.invalid_offspring > 0);
+        }
+
+        #[test]
+        fn test_penalty() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let observer = LastStatistics::default();
+            let statistics = observer.0.clone();
+
+            //the invalid individual is the fittest but loses all its fitness
+            let mut ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover,
+                GaussianMutation::new(0.0, 0.0))
+                .with_constraint_handler(PenaltyConstraint::new(in_range, |_: &Chromosome| 100.0))
+                .with_observer(observer);
+
+            let population = vec![
+                individual(&[0.5, 0.5]),
+                individual(&[5.0, 5.0]),
+            ];
+            let offspring = ga.evolve(&mut rng, &population);
+
+            assert_eq!(offspring, vec![individual(&[0.5, 0.5]), individual(&[0.5, 0.5])]);
+
+            let statistics = statistics.borrow().clone().unwrap();
+            assert_eq!(statistics.invalid, 1);
+            assert_eq!(statistics.invalid_offspring, 0);
+            assert_eq!(statistics.max_fitness, 1.0);
+        }
+
+        #[test]
+        fn test_weight_decay() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let observer = LastStatistics::default();
+            let statistics = observer.0.clone();
+
+            let mut ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover,
+                GaussianMutation::new(0.0, 0.0))
+                .with_weight_decay(0.1)
+                .with_observer(observer);
+
+            ga.evolve(&mut rng, &[individual(&[1.0, 1.0]), individual(&[3.0, 3.0])]);
+
+            //2.0 - 0.1 * 2.0 and 6.0 - 0.1 * 18.0
+            let statistics = statistics.borrow().clone().unwrap();
+            assert_relative_eq!(statistics.max_fitness, 4.2);
+            assert_relative_eq!(statistics.min_fitness, 1.8);
+            assert_relative_eq!(statistics.avg_regularization, 1.0);
+            assert_eq!(statistics.invalid, 0);
+        }
+
+        #[test]
+        fn test_fitness_scaling() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let observer = LastStatistics::default();
+            let statistics = observer.0.clone();
+
+            let mut ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover,
+                GaussianMutation::new(0.0, 0.0))
+                .with_fitness_scaling(FitnessScaling::SigmaTruncation { c: 0.0 })
+                .with_observer(observer);
+
+            //only the individuals above average are left to the selection
+            let population = [individual(&[1.0, 1.0]), individual(&[1.0, 2.0]), individual(&[3.0, 3.0])];
+            let offspring = ga.evolve(&mut rng, &population);
+            assert!(offspring.iter().all(|child| child.chromosome().iter().all(|&gene| gene == 3.0)));
+
+            //the statistics show the fitness unscaled
+            let statistics = statistics.borrow().clone().unwrap();
+            assert_relative_eq!(statistics.max_fitness, 6.0);
+            assert_relative_eq!(statistics.min_fitness, 2.0);
+            //every parent was the only one left, sqrt(2) deviations above the scaled average
+            assert_relative_eq!(statistics.selection_intensity, 2f32.sqrt());
+        }
+
+        #[test]
+        fn test_mutation_logging() {
+            let population = vec![individual(&[0.0, 1.0, 2.0]), individual(&[1.0, 2.0, 3.0])];
+            let evolve = |logged: bool| {
+                let observer = LastStatistics::default();
+                let statistics = observer.0.clone();
+                let ga = GeneticAlgorithm::new(RouletteWheelSelection::new(), UniformCrossover, GaussianMutation::new(1.0, 0.5))
+                    .with_observer(observer);
+                let mut ga = if logged { ga.with_mutation_logging() } else { ga };
+
+                let offspring = ga.evolve(&mut ChaCha8Rng::from_seed(Default::default()), &population);
+                let mutations = statistics.borrow().clone().unwrap().mutations;
+                (offspring, mutations)
+            };
+
+            let (offspring, mutations) = evolve(true);
+            //logging draws the same numbers
+            assert_eq!(evolve(false), (offspring, None));
+
+            let mutations = mutations.unwrap();
+            assert_eq!((mutations.children, mutations.genes, mutations.changed), (2, 6, 6));
+            assert_relative_eq!(mutations.changed_share(), 1.0);
+            assert!(mutations.avg_change() > 0.0 && mutations.avg_change() <= 0.5);
+        }
+    }
 }
@@ -5,4 +5,68 @@ mod gaussian;
 
 pub trait MutationMethod {
     fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome);
+
+    //like mutate(), but on genes that are not in a Chromosome, the default goes through mutate()
+    fn mutate_genes(&self, rng: &mut dyn RngCore, genes: &mut [f32]) {
+        let mut child = genes.iter().copied().collect();
+        self.mutate(rng, &mut child);
+
+        genes.copy_from_slice(child.as_slice());
+    }
+
+    //like mutate_genes(), but tells what it did, the default compares the genes before and after
+    fn mutate_genes_logged(&self, rng: &mut dyn RngCore, genes: &mut [f32]) -> MutationEffect {
+        let before = genes.to_vec();
+        self.mutate_genes(rng, genes);
+
+        let mut effect = MutationEffect { genes: genes.len(), ..MutationEffect::default() };
+        for (before, after) in before.iter().zip(genes.iter()) {
+            if before != after {
+                effect.changed += 1;
+                effect.change += (after - before).abs();
+            }
+        }
+        effect
+    }
+}
+
+//what a mutation did to a child
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MutationEffect {
+    pub genes: usize,
+    pub changed: usize,
+    //the sum of how far every changed gene moved
+    pub change: f32,
+}
+
+//the mutation effects of every child of a generation summed up
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MutationStatistics {
+    pub children: usize,
+    pub genes: usize,
+    pub changed: usize,
+    pub change: f32,
+}
+
+impl MutationStatistics {
+    pub fn record(&mut self, effect: MutationEffect) {
+        self.children += 1;
+        self.genes += effect.genes;
+        self.changed += effect.changed;
+        self.change += effect.change;
+    }
+
+    //the share of the genes that changed, to compare with the configured mutation chance
+    pub fn changed_share(&self) -> f32 {
+        self.changed as f32 / self.genes.max(1) as f32
+    }
+
+    pub fn avg_changed_genes(&self) -> f32 {
+        self.changed as f32 / self.children.max(1) as f32
+    }
+
+    //how far a changed gene moved on average
+    pub fn avg_change(&self) -> f32 {
+        self.change / self.changed.max(1) as f32
+    }
 }
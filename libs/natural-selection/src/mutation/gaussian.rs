@@ -11,20 +11,41 @@ pub struct GaussianMutation {
 
 impl GaussianMutation {
     pub fn new(chance: f32, coefficient: f32) -> Self {
-        assert!(chance >= 0.0 && chance <= 1.0);
+        assert!((0.0..=1.0).contains(&chance));
         Self { chance, coefficient }
     }
 }
 
 impl MutationMethod for GaussianMutation {
     fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
-        for gene in child.iter_mut() {
+        self.mutate_genes(rng, child.as_mut_slice());
+    }
+
+    fn mutate_genes(&self, rng: &mut dyn RngCore, genes: &mut [f32]) {
+        for gene in genes {
             let sign = if rng.gen_bool(0.5) { -1.0 } else { 1.0 };
             if rng.gen_bool(self.chance as _) {
                 *gene += sign * self.coefficient * rng.gen::<f32>();
             }
         }
     }
+
+    fn mutate_genes_logged(&self, rng: &mut dyn RngCore, genes: &mut [f32]) -> MutationEffect {
+        let mut effect = MutationEffect { genes: genes.len(), ..MutationEffect::default() };
+        for gene in genes {
+            let sign = if rng.gen_bool(0.5) { -1.0 } else { 1.0 };
+            if rng.gen_bool(self.chance as _) {
+                let change = self.coefficient * rng.gen::<f32>();
+                *gene += sign * change;
+                //a draw of 0.0 leaves the gene as it was
+                if change != 0.0 {
+                    effect.changed += 1;
+                    effect.change += change;
+                }
+            }
+        }
+        effect
+    }
 }
 
 #[cfg(test)]
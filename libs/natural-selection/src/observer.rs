@@ -0,0 +1,15 @@
+use crate::*;
+
+//gets notified about the progress of a GeneticAlgorithm, so dashboards, metrics or csv
+//files can hook into the library instead of recomputing everything, all methods are no-ops
+//by default
+pub trait Observer {
+    //before a population is evolved, generations count from 0
+    fn on_generation_start(&mut self, _generation: usize) {}
+
+    //after a population was evolved, with the statistics of the parent population
+    fn on_generation_end(&mut self, _statistics: &Statistics) {}
+
+    //when an individual is fitter than every individual seen before
+    fn on_new_champion(&mut self, _generation: usize, _chromosome: &Chromosome, _fitness: f32) {}
+}
@@ -0,0 +1,21 @@
+use crate::*;
+
+//anything that turns a rated population into the next one, so callers can swap the
+//algorithm without depending on how it works
+pub trait Optimizer {
+    //overwrites the genes of every individual with those of its offspring
+    fn evolve_in_place(&mut self, rng: &mut dyn RngCore, population: &mut [&mut dyn InPlaceIndividual]);
+
+    //the number of populations evolved so far
+    fn generation(&self) -> usize;
+
+    //statistics of the last evolved population
+    fn statistics(&self) -> Option<&Statistics>;
+
+    fn add_observer(&mut self, observer: Box<dyn Observer>);
+
+    //the archive of a quality-diversity optimizer, the others have none
+    fn elites(&self) -> Option<&EliteArchive> {
+        None
+    }
+}
@@ -0,0 +1,171 @@
+pub use self::{inversion::*, order::*, partially_mapped::*, swap::*};
+
+use std::ops::Index;
+use rand::seq::SliceRandom;
+use crate::*;
+
+mod inversion;
+mod order;
+mod partially_mapped;
+mod swap;
+
+//an ordering of 0..len, e.g. the order in which to visit cities, every index appears exactly once
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PermutationChromosome {
+    order: Vec<usize>,
+}
+
+impl PermutationChromosome {
+    pub fn new(order: Vec<usize>) -> Self {
+        let mut seen = vec![false; order.len()];
+        for &index in &order {
+            assert!(index < order.len() && !seen[index], "{order:?} is not a permutation");
+            seen[index] = true;
+        }
+
+        Self { order }
+    }
+
+    pub fn identity(len: usize) -> Self {
+        Self { order: (0..len).collect() }
+    }
+
+    pub fn random(rng: &mut dyn RngCore, len: usize) -> Self {
+        let mut order: Vec<usize> = (0..len).collect();
+        order.shuffle(rng);
+        Self { order }
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=&usize> {
+        self.order.iter()
+    }
+
+    pub fn as_slice(&self) -> &[usize] {
+        &self.order
+    }
+}
+
+impl Index<usize> for PermutationChromosome {
+    type Output = usize;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.order[index]
+    }
+}
+
+//crossovers that keep the child a valid permutation
+pub trait PermutationCrossoverMethod {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &PermutationChromosome,
+        parent_b: &PermutationChromosome,
+    ) -> PermutationChromosome;
+}
+
+//mutations that reorder instead of change genes
+pub trait PermutationMutationMethod {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut PermutationChromosome);
+}
+
+//two cut points 0 <= start < end <= len, so the segment between them is never empty
+fn segment(rng: &mut dyn RngCore, len: usize) -> (usize, usize) {
+    let a = rng.gen_range(0..len);
+    let b = rng.gen_range(0..len);
+    (a.min(b), a.max(b) + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn test() {
+            assert_eq!(PermutationChromosome::new(vec![2, 0, 1]).as_slice(), &[2, 0, 1]);
+            assert_eq!(PermutationChromosome::identity(3), PermutationChromosome::new(vec![0, 1, 2]));
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_duplicate() {
+            PermutationChromosome::new(vec![0, 1, 1]);
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_out_of_range() {
+            PermutationChromosome::new(vec![0, 1, 3]);
+        }
+    }
+
+    //orders 8 points on a circle with the operators, the shortest tour visits them in order
+    mod evolve {
+        use super::*;
+
+        fn tour_length(chromosome: &PermutationChromosome) -> f32 {
+            let point = |index: usize| {
+                let angle = index as f32 / 8.0 * std::f32::consts::TAU;
+                (angle.cos(), angle.sin())
+            };
+
+            (0..chromosome.len())
+                .map(|i| {
+                    let (ax, ay) = point(chromosome[i]);
+                    let (bx, by) = point(chromosome[(i + 1) % chromosome.len()]);
+                    ((ax - bx).powi(2) + (ay - by).powi(2)).sqrt()
+                })
+                .sum()
+        }
+
+        fn evolve(crossover: &dyn PermutationCrossoverMethod) -> f32 {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mutations: [&dyn PermutationMutationMethod; 2] = [&SwapMutation::new(0.2), &InversionMutation::new(0.2)];
+
+            let mut population: Vec<_> = (0..40).map(|_| PermutationChromosome::random(&mut rng, 8)).collect();
+            for _ in 0..60 {
+                population.sort_by(|a, b| tour_length(a).total_cmp(&tour_length(b)));
+                population.truncate(20);
+
+                let offspring: Vec<_> = (0..20)
+                    .map(|_| {
+                        let parent_a = population.choose(&mut rng).unwrap();
+                        let parent_b = population.choose(&mut rng).unwrap();
+                        let mut child = crossover.crossover(&mut rng, parent_a, parent_b);
+                        for mutation in mutations {
+                            mutation.mutate(&mut rng, &mut child);
+                        }
+                        child
+                    })
+                    .collect();
+                population.extend(offspring);
+            }
+
+            population.iter().map(tour_length).fold(f32::MAX, f32::min)
+        }
+
+        #[test]
+        fn test_order_crossover() {
+            let optimal = tour_length(&PermutationChromosome::identity(8));
+            approx::assert_relative_eq!(evolve(&OrderCrossover), optimal, epsilon = 1e-4);
+        }
+
+        #[test]
+        fn test_partially_mapped_crossover() {
+            let optimal = tour_length(&PermutationChromosome::identity(8));
+            approx::assert_relative_eq!(evolve(&PartiallyMappedCrossover), optimal, epsilon = 1e-4);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::*;
+
+#[derive(Clone, Debug)]
+pub struct InversionMutation {
+    //the chance that a random segment is reversed 0.0..1.0
+    chance: f32,
+}
+
+impl InversionMutation {
+    pub fn new(chance: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        Self { chance }
+    }
+}
+
+impl PermutationMutationMethod for InversionMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut PermutationChromosome) {
+        if child.len() < 2 || !rng.gen_bool(self.chance as _) {
+            return;
+        }
+
+        let (start, end) = permutation::segment(rng, child.len());
+        child.order[start..end].reverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn mutate(chance: f32) -> PermutationChromosome {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut child = PermutationChromosome::identity(10);
+        InversionMutation::new(chance).mutate(&mut rng, &mut child);
+        child
+    }
+
+    #[test]
+    fn test_zero_chance() {
+        assert_eq!(mutate(0.0), PermutationChromosome::identity(10));
+    }
+
+    #[test]
+    fn test_full_chance() {
+        let child = mutate(1.0);
+
+        //a single reversed run, everything around it stays in place
+        let moved: Vec<_> = child.iter().enumerate().filter(|(i, gene)| i != *gene).map(|(i, _)| i).collect();
+        let (start, end) = (moved[0], *moved.last().unwrap() + 1);
+        let reversed: Vec<_> = (start..end).rev().collect();
+        assert_eq!(&child.as_slice()[start..end], reversed.as_slice());
+    }
+}
@@ -0,0 +1,81 @@
+use crate::*;
+
+//OX: copies a random segment of parent a and fills the rest in the order the remaining
+//genes appear in parent b, starting after the segment
+#[derive(Clone, Debug, Default)]
+pub struct OrderCrossover;
+
+impl OrderCrossover {
+    pub(crate) fn crossover_at(
+        parent_a: &PermutationChromosome,
+        parent_b: &PermutationChromosome,
+        (start, end): (usize, usize),
+    ) -> PermutationChromosome {
+        let len = parent_a.len();
+        let mut taken = vec![false; len];
+        let mut order = vec![0; len];
+
+        for i in start..end {
+            order[i] = parent_a[i];
+            taken[parent_a[i]] = true;
+        }
+
+        let mut remaining = (0..len)
+            .map(|i| parent_b[(end + i) % len])
+            .filter(|gene| !taken[*gene]);
+
+        for i in (0..len - (end - start)).map(|i| (end + i) % len) {
+            order[i] = remaining.next().expect("parents are permutations of the same length");
+        }
+
+        PermutationChromosome::new(order)
+    }
+}
+
+impl PermutationCrossoverMethod for OrderCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &PermutationChromosome,
+        parent_b: &PermutationChromosome,
+    ) -> PermutationChromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        if parent_a.is_empty() {
+            return parent_a.clone();
+        }
+
+        Self::crossover_at(parent_a, parent_b, permutation::segment(rng, parent_a.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_known_segment() {
+        let parent_a = PermutationChromosome::new(vec![0, 1, 2, 3, 4, 5, 6, 7, 8]);
+        let parent_b = PermutationChromosome::new(vec![8, 2, 6, 7, 1, 5, 4, 0, 3]);
+
+        let child = OrderCrossover::crossover_at(&parent_a, &parent_b, (3, 6));
+
+        assert_eq!(child.as_slice(), &[6, 7, 1, 3, 4, 5, 0, 8, 2]);
+    }
+
+    #[test]
+    fn test_children_are_permutations() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        for len in 1..20 {
+            let parent_a = PermutationChromosome::random(&mut rng, len);
+            let parent_b = PermutationChromosome::random(&mut rng, len);
+
+            //new() panics if the child is not a permutation
+            let child = OrderCrossover.crossover(&mut rng, &parent_a, &parent_b);
+            assert_eq!(child.len(), len);
+        }
+    }
+}
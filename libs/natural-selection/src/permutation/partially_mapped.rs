@@ -0,0 +1,95 @@
+use crate::*;
+
+//PMX: copies a random segment of parent a and keeps the positions of parent b elsewhere,
+//genes of b displaced by the segment are moved along the mapping between both segments
+#[derive(Clone, Debug, Default)]
+pub struct PartiallyMappedCrossover;
+
+impl PartiallyMappedCrossover {
+    pub(crate) fn crossover_at(
+        parent_a: &PermutationChromosome,
+        parent_b: &PermutationChromosome,
+        (start, end): (usize, usize),
+    ) -> PermutationChromosome {
+        let len = parent_a.len();
+        let mut position_in_b = vec![0; len];
+        for (position, &gene) in parent_b.iter().enumerate() {
+            position_in_b[gene] = position;
+        }
+
+        let mut order: Vec<Option<usize>> = vec![None; len];
+        for i in start..end {
+            order[i] = Some(parent_a[i]);
+        }
+
+        for i in start..end {
+            let gene = parent_b[i];
+            if parent_a.as_slice()[start..end].contains(&gene) {
+                continue;
+            }
+
+            //follow the mapping until it leaves the segment
+            let mut position = i;
+            while (start..end).contains(&position) {
+                position = position_in_b[parent_a[position]];
+            }
+            order[position] = Some(gene);
+        }
+
+        let order = order
+            .into_iter()
+            .enumerate()
+            .map(|(i, gene)| gene.unwrap_or(parent_b[i]))
+            .collect();
+
+        PermutationChromosome::new(order)
+    }
+}
+
+impl PermutationCrossoverMethod for PartiallyMappedCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &PermutationChromosome,
+        parent_b: &PermutationChromosome,
+    ) -> PermutationChromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        if parent_a.is_empty() {
+            return parent_a.clone();
+        }
+
+        Self::crossover_at(parent_a, parent_b, permutation::segment(rng, parent_a.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_known_segment() {
+        let parent_a = PermutationChromosome::new(vec![0, 1, 2, 3, 4, 5, 6, 7, 8]);
+        let parent_b = PermutationChromosome::new(vec![8, 2, 6, 7, 1, 5, 4, 0, 3]);
+
+        let child = PartiallyMappedCrossover::crossover_at(&parent_a, &parent_b, (3, 6));
+
+        assert_eq!(child.as_slice(), &[8, 2, 6, 3, 4, 5, 1, 0, 7]);
+    }
+
+    #[test]
+    fn test_children_are_permutations() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        for len in 1..20 {
+            let parent_a = PermutationChromosome::random(&mut rng, len);
+            let parent_b = PermutationChromosome::random(&mut rng, len);
+
+            //new() panics if the child is not a permutation
+            let child = PartiallyMappedCrossover.crossover(&mut rng, &parent_a, &parent_b);
+            assert_eq!(child.len(), len);
+        }
+    }
+}
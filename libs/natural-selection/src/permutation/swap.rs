@@ -0,0 +1,52 @@
+use crate::*;
+
+#[derive(Clone, Debug)]
+pub struct SwapMutation {
+    //the chance that two genes swap places 0.0..1.0
+    chance: f32,
+}
+
+impl SwapMutation {
+    pub fn new(chance: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        Self { chance }
+    }
+}
+
+impl PermutationMutationMethod for SwapMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut PermutationChromosome) {
+        if child.len() < 2 || !rng.gen_bool(self.chance as _) {
+            return;
+        }
+
+        let a = rng.gen_range(0..child.len());
+        let b = rng.gen_range(0..child.len());
+        child.order.swap(a, b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn mutate(chance: f32) -> PermutationChromosome {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut child = PermutationChromosome::identity(10);
+        SwapMutation::new(chance).mutate(&mut rng, &mut child);
+        child
+    }
+
+    #[test]
+    fn test_zero_chance() {
+        assert_eq!(mutate(0.0), PermutationChromosome::identity(10));
+    }
+
+    #[test]
+    fn test_full_chance() {
+        let child = mutate(1.0);
+        let moved = child.iter().enumerate().filter(|(i, gene)| i != *gene).count();
+        assert_eq!(moved, 2);
+    }
+}
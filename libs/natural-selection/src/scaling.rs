@@ -0,0 +1,145 @@
+use std::fmt;
+use std::str::FromStr;
+
+//transforms the fitness of a population before selection, so the selection pressure can be
+//tuned without swapping the selection method
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FitnessScaling {
+    #[default]
+    None,
+    //goldberg's linear scaling: the average stays, the best gets `multiple` times the average,
+    //unless that would make the worst negative, then the worst gets 0.0
+    Linear { multiple: f32 },
+    //subtracts the average minus `c` standard deviations, whoever falls below it gets 0.0
+    SigmaTruncation { c: f32 },
+    //raises the fitness to `exponent`, above 1.0 sharpens and below 1.0 flattens the pressure
+    Power { exponent: f32 },
+}
+
+impl FitnessScaling {
+    pub fn scale(&self, fitness: &mut [f32]) {
+        if fitness.is_empty() {
+            return;
+        }
+        let avg = fitness.iter().sum::<f32>() / fitness.len() as f32;
+
+        match *self {
+            Self::None => {}
+            Self::Linear { multiple } => {
+                let max = fitness.iter().copied().fold(f32::MIN, f32::max);
+                let min = fitness.iter().copied().fold(f32::MAX, f32::min);
+                if avg <= 0.0 || max - avg <= f32::EPSILON * max.abs() {
+                    return;
+                }
+
+                let (a, b) = if min > (multiple * avg - max) / (multiple - 1.0) {
+                    let a = (multiple - 1.0) * avg / (max - avg);
+                    (a, avg * (1.0 - a))
+                } else {
+                    let a = avg / (avg - min);
+                    (a, -min * a)
+                };
+                fitness.iter_mut().for_each(|fitness| *fitness = (a * *fitness + b).max(0.0));
+            }
+            Self::SigmaTruncation { c } => {
+                let sigma = (fitness.iter().map(|fitness| (fitness - avg).powi(2)).sum::<f32>() / fitness.len() as f32).sqrt();
+                let floor = avg - c * sigma;
+                fitness.iter_mut().for_each(|fitness| *fitness = (*fitness - floor).max(0.0));
+            }
+            Self::Power { exponent } => {
+                fitness.iter_mut().for_each(|fitness| *fitness = fitness.max(0.0).powf(exponent));
+            }
+        }
+    }
+}
+
+//`none`, or `linear`, `sigma` or `power` with an optional `:parameter`
+impl FromStr for FitnessScaling {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, parameter) = match s.split_once(':') {
+            Some((name, parameter)) => {
+                let parameter = parameter.trim().parse::<f32>().map_err(|e| format!("invalid fitness scaling '{s}': {e}"))?;
+                (name, Some(parameter))
+            }
+            None => (s, None),
+        };
+
+        match (name, parameter) {
+            ("none", None) => Ok(Self::None),
+            ("linear", multiple) => match multiple.unwrap_or(2.0) {
+                multiple if multiple > 1.0 => Ok(Self::Linear { multiple }),
+                _ => Err(format!("invalid fitness scaling '{s}', the multiple needs to be above 1")),
+            },
+            ("sigma", c) => Ok(Self::SigmaTruncation { c: c.unwrap_or(2.0) }),
+            ("power", exponent) => Ok(Self::Power { exponent: exponent.unwrap_or(1.005) }),
+            _ => Err(format!("unknown fitness scaling '{s}', expected none, linear[:multiple], sigma[:c] or power[:exponent]")),
+        }
+    }
+}
+
+impl fmt::Display for FitnessScaling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => f.write_str("none"),
+            Self::Linear { multiple } => write!(f, "linear:{multiple}"),
+            Self::SigmaTruncation { c } => write!(f, "sigma:{c}"),
+            Self::Power { exponent } => write!(f, "power:{exponent}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn scaled(scaling: &str, fitness: &[f32]) -> Vec<f32> {
+        let mut fitness = fitness.to_vec();
+        scaling.parse::<FitnessScaling>().unwrap().scale(&mut fitness);
+        fitness
+    }
+
+    #[test]
+    fn test_linear() {
+        //the average stays 2.0 and the best gets twice that
+        let fitness = scaled("linear", &[1.0, 2.0, 3.0]);
+        assert_relative_eq!(fitness.as_slice(), [0.0, 2.0, 4.0].as_slice());
+
+        let fitness = scaled("linear:1.5", &[1.0, 2.0, 3.0]);
+        assert_relative_eq!(fitness.as_slice(), [1.0, 2.0, 3.0].as_slice());
+
+        //a multiple of 4.0 would make the worst negative
+        let fitness = scaled("linear:4", &[1.0, 2.0, 3.0]);
+        assert_relative_eq!(fitness.as_slice(), [0.0, 2.0, 4.0].as_slice());
+
+        //nothing to spread
+        assert_eq!(scaled("linear", &[2.0, 2.0]), [2.0, 2.0]);
+    }
+
+    #[test]
+    fn test_sigma_truncation() {
+        //average 2.0, standard deviation 1.0
+        assert_eq!(scaled("sigma:1", &[1.0, 1.0, 3.0, 3.0]), [0.0, 0.0, 2.0, 2.0]);
+        assert_eq!(scaled("sigma:0.5", &[1.0, 1.0, 3.0, 3.0]), [0.0, 0.0, 1.5, 1.5]);
+    }
+
+    #[test]
+    fn test_power() {
+        assert_eq!(scaled("power:2", &[-1.0, 2.0, 3.0]), [0.0, 4.0, 9.0]);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("none".parse(), Ok(FitnessScaling::None));
+        assert_eq!("sigma".parse(), Ok(FitnessScaling::SigmaTruncation { c: 2.0 }));
+        assert_eq!("power:1.5".parse(), Ok(FitnessScaling::Power { exponent: 1.5 }));
+        assert!("linear:1".parse::<FitnessScaling>().is_err());
+        assert!("power:x".parse::<FitnessScaling>().is_err());
+        assert!("none:1".parse::<FitnessScaling>().is_err());
+
+        let scaling = FitnessScaling::Linear { multiple: 1.5 };
+        assert_eq!(scaling.to_string().parse(), Ok(scaling));
+    }
+}
@@ -1,7 +1,8 @@
-pub use self::roulette::*;
+pub use self::{confidence::*, roulette::*};
 
 use crate::*;
 
+mod confidence;
 mod roulette;
 
 pub trait SelectionMethod {
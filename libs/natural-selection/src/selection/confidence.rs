@@ -0,0 +1,85 @@
+use crate::*;
+use rand::seq::SliceRandom;
+
+//tournament selection that ranks by the lower bound of each contestant's fitness, so a lucky
+//individual with a noisy estimate does not beat one that is reliably almost as good
+#[derive(Clone, Debug)]
+pub struct ConfidenceTournamentSelection {
+    size: usize,
+    //how many standard errors below the mean the ranked bound is
+    z: f32,
+}
+
+impl ConfidenceTournamentSelection {
+    pub fn new(size: usize, z: f32) -> Self {
+        assert!(size > 0);
+        assert!(z >= 0.0);
+        Self { size, z }
+    }
+}
+
+impl SelectionMethod for ConfidenceTournamentSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        let lower = |individual: &I| individual.fitness() - self.z * individual.fitness_stderr();
+
+        (0..self.size)
+            .map(|_| population.choose(rng).expect("got an empty population"))
+            .max_by(|a, b| lower(a).total_cmp(&lower(b)))
+            .expect("tournaments have at least one contestant")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    struct Noisy {
+        fitness: f32,
+        stderr: f32,
+    }
+
+    impl Individual for Noisy {
+        fn create(_chromosome: Chromosome) -> Self {
+            unimplemented!()
+        }
+
+        fn fitness(&self) -> f32 {
+            self.fitness
+        }
+
+        fn fitness_stderr(&self) -> f32 {
+            self.stderr
+        }
+
+        fn chromosome(&self) -> &Chromosome {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_prefers_reliable_fitness() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let population = [
+            Noisy { fitness: 10.0, stderr: 8.0 },
+            Noisy { fitness: 8.0, stderr: 0.5 },
+        ];
+
+        let method = ConfidenceTournamentSelection::new(population.len() * 4, 1.0);
+        let reliable = (0..100)
+            .filter(|_| method.select(&mut rng, &population).fitness == 8.0)
+            .count();
+        assert!(reliable > 90, "{reliable}");
+
+        //without confidence it is a plain tournament
+        let method = ConfidenceTournamentSelection::new(population.len() * 4, 0.0);
+        let reliable = (0..100)
+            .filter(|_| method.select(&mut rng, &population).fitness == 8.0)
+            .count();
+        assert!(reliable < 10, "{reliable}");
+    }
+}
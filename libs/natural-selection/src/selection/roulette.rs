@@ -1,6 +1,8 @@
 use crate::*;
+use rand::distributions::WeightedError;
 use rand::seq::SliceRandom;
 
+#[derive(Clone, Debug, Default)]
 pub struct RouletteWheelSelection;
 
 impl RouletteWheelSelection {
@@ -14,9 +16,12 @@ impl SelectionMethod for RouletteWheelSelection {
     where
         I: Individual,
     {
-        population
-            .choose_weighted(rng, |individual| individual.fitness())
-            .expect("got an empty population")
+        match population.choose_weighted(&mut *rng, |individual| individual.fitness()) {
+            Ok(individual) => individual,
+            //nobody has any fitness, so everybody has the same chance
+            Err(WeightedError::AllWeightsZero) => population.choose(rng).expect("got an empty population"),
+            Err(err) => panic!("got an invalid population: {err}"),
+        }
     }
 }
 
@@ -57,4 +62,18 @@ mod selection {
 
         assert_eq!(actual_histogram, expected_histogram);
     }
+
+    #[test]
+    fn test_all_zero_fitness() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let method = RouletteWheelSelection::new();
+
+        let population = vec![
+            TestIndividual::new(0.0),
+            TestIndividual::new(0.0),
+        ];
+
+        let selected = method.select(&mut rng, &population);
+        assert_eq!(selected.fitness(), 0.0);
+    }
 }
@@ -0,0 +1,263 @@
+//a group of similar genomes, identified across generations by its id
+#[derive(Clone, Debug, PartialEq)]
+pub struct Species {
+    id: usize,
+    //genomes closer than the threshold to it join the species
+    representative: Vec<f32>,
+    //indices into the population of the last speciate() call
+    members: Vec<usize>,
+    best_fitness: f32,
+    //the best fitness any generation of it reached
+    best_ever: f32,
+    //generations since best_ever last improved
+    stagnant: usize,
+}
+
+impl Species {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn representative(&self) -> &[f32] {
+        &self.representative
+    }
+
+    pub fn members(&self) -> &[usize] {
+        &self.members
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn best_fitness(&self) -> f32 {
+        self.best_fitness
+    }
+
+    pub fn stagnant(&self) -> usize {
+        self.stagnant
+    }
+
+    //the sum of the shared fitness of its members, i.e. their fitness divided by the size of
+    //the species, so a big species does not crowd out the others
+    pub fn adjusted_fitness(&self, fitness: &[f32]) -> f32 {
+        self.members.iter().map(|&member| fitness[member]).sum::<f32>() / self.members.len().max(1) as f32
+    }
+}
+
+//sorts a population into species by the distance of their genes, so niches can be told
+//apart, shown and protected
+#[derive(Clone, Debug)]
+pub struct Speciation {
+    threshold: f32,
+    //species that did not improve for this many generations are pruned
+    stagnation_limit: Option<usize>,
+    species: Vec<Species>,
+    next_id: usize,
+}
+
+impl Speciation {
+    pub fn new(threshold: f32) -> Self {
+        assert!(threshold > 0.0);
+        Self { threshold, stagnation_limit: None, species: Vec::new(), next_id: 0 }
+    }
+
+    pub fn with_stagnation_limit(mut self, generations: usize) -> Self {
+        self.stagnation_limit = Some(generations);
+        self
+    }
+
+    //the mean absolute difference of the genes
+    pub fn distance(a: &[f32], b: &[f32]) -> f32 {
+        assert_eq!(a.len(), b.len());
+        if a.is_empty() {
+            return 0.0;
+        }
+
+        a.iter().zip(b).map(|(a, b)| (a - b).abs()).sum::<f32>() / a.len() as f32
+    }
+
+    pub fn species(&self) -> &[Species] {
+        &self.species
+    }
+
+    //which species the individual at `index` of the last speciate() call belongs to
+    pub fn species_of(&self, index: usize) -> Option<&Species> {
+        self.species.iter().find(|species| species.members.contains(&index))
+    }
+
+    //assigns every genome to the first species whose representative is close enough, or to
+    //a new one, species without members die out and the fittest member of the others
+    //represents them in the next generation
+    pub fn speciate<'a>(&mut self, genomes: impl IntoIterator<Item = &'a [f32]>, fitness: &[f32]) {
+        let genomes: Vec<&[f32]> = genomes.into_iter().collect();
+        assert_eq!(genomes.len(), fitness.len());
+
+        for species in &mut self.species {
+            species.members.clear();
+        }
+
+        for (index, genes) in genomes.iter().enumerate() {
+            let threshold = self.threshold;
+            match self
+                .species
+                .iter_mut()
+                .find(|species| Self::distance(&species.representative, genes) < threshold)
+            {
+                Some(species) => species.members.push(index),
+                None => {
+                    self.species.push(Species {
+                        id: self.next_id,
+                        representative: genes.to_vec(),
+                        members: vec![index],
+                        best_fitness: f32::NEG_INFINITY,
+                        best_ever: f32::NEG_INFINITY,
+                        stagnant: 0,
+                    });
+                    self.next_id += 1;
+                }
+            }
+        }
+
+        self.species.retain(|species| !species.members.is_empty());
+        for species in &mut self.species {
+            let best = species
+                .members
+                .iter()
+                .copied()
+                .max_by(|&a, &b| fitness[a].total_cmp(&fitness[b]))
+                .expect("species have members");
+            species.best_fitness = fitness[best];
+            species.representative.clear();
+            species.representative.extend_from_slice(genomes[best]);
+
+            if species.best_fitness > species.best_ever {
+                species.best_ever = species.best_fitness;
+                species.stagnant = 0;
+            } else {
+                species.stagnant += 1;
+            }
+        }
+    }
+
+    //removes the species that stagnated for longer than the limit, except the one with the
+    //fittest individual so the population never dies out, returns how many were removed
+    pub fn prune_stagnant(&mut self) -> usize {
+        let Some(limit) = self.stagnation_limit else {
+            return 0;
+        };
+        let fittest = self
+            .species
+            .iter()
+            .max_by(|a, b| a.best_fitness.total_cmp(&b.best_fitness))
+            .map(Species::id);
+
+        let len = self.species.len();
+        self.species.retain(|species| species.stagnant < limit || Some(species.id) == fittest);
+        len - self.species.len()
+    }
+
+    //how many of `offspring` children every species gets, in the order of species(),
+    //proportional to their adjusted fitness or to their size if no species has any
+    pub fn offspring_quotas(&self, fitness: &[f32], offspring: usize) -> Vec<usize> {
+        if self.species.is_empty() {
+            return Vec::new();
+        }
+
+        let mut shares: Vec<f32> = self
+            .species
+            .iter()
+            .map(|species| species.adjusted_fitness(fitness).max(0.0))
+            .collect();
+        if shares.iter().sum::<f32>() <= 0.0 {
+            shares = self.species.iter().map(|species| species.len() as f32).collect();
+        }
+        let total: f32 = shares.iter().sum();
+
+        //largest remainder, so the quotas add up to `offspring`
+        let exact: Vec<f32> = shares.iter().map(|share| share / total * offspring as f32).collect();
+        let mut quotas: Vec<usize> = exact.iter().map(|exact| exact.floor() as usize).collect();
+        let mut by_remainder: Vec<usize> = (0..quotas.len()).collect();
+        by_remainder.sort_by(|&a, &b| (exact[b] - exact[b].floor()).total_cmp(&(exact[a] - exact[a].floor())));
+        let missing = offspring.saturating_sub(quotas.iter().sum());
+        for &index in by_remainder.iter().cycle().take(missing) {
+            quotas[index] += 1;
+        }
+
+        quotas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speciate(speciation: &mut Speciation, genomes: &[Vec<f32>], fitness: &[f32]) {
+        speciation.speciate(genomes.iter().map(Vec::as_slice), fitness);
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(Speciation::distance(&[0.0, 1.0], &[1.0, -1.0]), 1.5);
+        assert_eq!(Speciation::distance(&[], &[]), 0.0);
+    }
+
+    #[test]
+    fn test_groups_close_genomes() {
+        let mut speciation = Speciation::new(0.5);
+        let genomes = vec![vec![0.0, 0.0], vec![5.0, 5.0], vec![0.1, 0.0], vec![5.0, 5.2]];
+
+        speciate(&mut speciation, &genomes, &[1.0, 2.0, 3.0, 4.0]);
+
+        let species = speciation.species();
+        assert_eq!(species.len(), 2);
+        assert_eq!(species[0].members(), &[0, 2]);
+        assert_eq!(species[0].best_fitness(), 3.0);
+        assert_eq!(species[0].representative(), &[0.1, 0.0]);
+        assert_eq!(species[1].members(), &[1, 3]);
+        assert_eq!(speciation.species_of(3).map(Species::id), Some(1));
+    }
+
+    #[test]
+    fn test_keeps_ids_and_drops_extinct_species() {
+        let mut speciation = Speciation::new(0.5);
+        speciate(&mut speciation, &[vec![0.0], vec![5.0]], &[1.0, 1.0]);
+        speciate(&mut speciation, &[vec![5.1], vec![9.0]], &[1.0, 1.0]);
+
+        let ids: Vec<_> = speciation.species().iter().map(Species::id).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_offspring_quotas() {
+        let mut speciation = Speciation::new(0.5);
+        let genomes = vec![vec![0.0], vec![0.1], vec![0.2], vec![5.0]];
+        let fitness = [1.0, 1.0, 1.0, 2.0];
+        speciate(&mut speciation, &genomes, &fitness);
+
+        //the three similar genomes share their fitness, so the lone fitter one gets more
+        assert_eq!(speciation.species()[0].adjusted_fitness(&fitness), 1.0);
+        assert_eq!(speciation.offspring_quotas(&fitness, 4), vec![1, 3]);
+        assert_eq!(speciation.offspring_quotas(&fitness, 5), vec![2, 3]);
+        assert_eq!(speciation.offspring_quotas(&[0.0; 4], 4), vec![3, 1]);
+    }
+
+    #[test]
+    fn test_prunes_stagnant_species() {
+        let mut speciation = Speciation::new(0.5).with_stagnation_limit(2);
+        let genomes = vec![vec![0.0], vec![5.0]];
+        for _ in 0..3 {
+            speciate(&mut speciation, &genomes, &[1.0, 2.0]);
+        }
+
+        assert_eq!(speciation.species()[0].stagnant(), 2);
+        //the fitter species is kept even though it stagnated too
+        assert_eq!(speciation.prune_stagnant(), 1);
+        let ids: Vec<_> = speciation.species().iter().map(Species::id).collect();
+        assert_eq!(ids, vec![1]);
+    }
+}
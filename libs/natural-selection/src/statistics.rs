@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use crate::*;
+
+//individuals within this fraction of the best fitness count as taken over by the best
+pub const TAKEOVER_EPSILON: f32 = 0.01;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statistics {
+    pub generation: usize,
+    pub min_fitness: f32,
+    pub max_fitness: f32,
+    pub avg_fitness: f32,
+    //the average standard error of the fitness, 0.0 unless it comes from repeated evaluations
+    pub avg_fitness_stderr: f32,
+    //individuals breaking the constraints, their fitness above is already penalized
+    pub invalid: usize,
+    //offspring that broke the constraints after mutation, repaired if the handler can
+    pub invalid_offspring: usize,
+    //share of the population whose fitness is within TAKEOVER_EPSILON of the best,
+    //approaches 1.0 as the population converges
+    pub takeover: f32,
+    //exp of the shannon entropy of the genotype frequencies, equals the population
+    //size if all genotypes differ and 1.0 if they are all the same
+    pub distinct_genotypes: f32,
+    //how far above average the selected parents were, in standard deviations of the fitness
+    //the selection sees, which is scaled with a fitness scaling
+    pub selection_intensity: f32,
+    //the average weight decay taken from the fitness above, 0.0 without one
+    pub avg_regularization: f32,
+    //what mutation did to the offspring, only with mutation logging
+    pub mutations: Option<MutationStatistics>,
+    //how the offspring mix their parents, only with provenance tracking and a crossover that
+    //knows the provenance
+    pub provenance: Option<ProvenanceStatistics>,
+    //children of the last generation that lost to the parent they are closer to, with
+    //deterministic crowding only
+    pub crowded_out: usize,
+    //the mean absolute difference between the fitness the surrogate predicted and the real one,
+    //with a surrogate that has learned something only
+    pub surrogate_error: Option<f32>,
+}
+
+impl Statistics {
+    pub(crate) fn new<I>(generation: usize, population: &[I]) -> Self
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+
+        let mut min_fitness = population[0].fitness();
+        let mut max_fitness = min_fitness;
+        let mut sum_fitness = 0.0;
+
+        for individual in population {
+            let fitness = individual.fitness();
+
+            min_fitness = min_fitness.min(fitness);
+            max_fitness = max_fitness.max(fitness);
+            sum_fitness += fitness;
+        }
+
+        let epsilon = TAKEOVER_EPSILON * max_fitness.abs().max(f32::EPSILON);
+        let taken_over = population
+            .iter()
+            .filter(|individual| max_fitness - individual.fitness() <= epsilon)
+            .count();
+
+        Self {
+            generation,
+            min_fitness,
+            max_fitness,
+            avg_fitness: sum_fitness / population.len() as f32,
+            avg_fitness_stderr: population.iter().map(Individual::fitness_stderr).sum::<f32>() / population.len() as f32,
+            invalid: 0,
+            invalid_offspring: 0,
+            takeover: taken_over as f32 / population.len() as f32,
+            distinct_genotypes: distinct_genotypes(population),
+            selection_intensity: 0.0,
+            avg_regularization: 0.0,
+            mutations: None,
+            provenance: None,
+            crowded_out: 0,
+            surrogate_error: None,
+        }
+    }
+
+    //needs the fitness of every selected parent, which only the selection knows, `population`
+    //as the selection saw it
+    pub(crate) fn set_selection_intensity<I>(&mut self, population: &[I], selected_fitness: &[f32])
+    where
+        I: Individual,
+    {
+        let avg_fitness = population.iter().map(Individual::fitness).sum::<f32>() / population.len() as f32;
+        let variance = population
+            .iter()
+            .map(|individual| (individual.fitness() - avg_fitness).powi(2))
+            .sum::<f32>() / population.len() as f32;
+
+        self.selection_intensity = if variance > 0.0 && !selected_fitness.is_empty() {
+            let selected_avg = selected_fitness.iter().sum::<f32>() / selected_fitness.len() as f32;
+            (selected_avg - avg_fitness) / variance.sqrt()
+        } else {
+            0.0
+        };
+    }
+}
+
+fn distinct_genotypes<I>(population: &[I]) -> f32
+where
+    I: Individual,
+{
+    let mut counts: HashMap<Vec<u32>, usize> = HashMap::new();
+    for individual in population {
+        let genotype = individual.chromosome().iter().map(|gene| gene.to_bits()).collect();
+        *counts.entry(genotype).or_default() += 1;
+    }
+
+    let entropy: f32 = counts
+        .values()
+        .map(|&count| {
+            let p = count as f32 / population.len() as f32;
+            -p * p.ln()
+        })
+        .sum();
+
+    entropy.exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn individual(genes: &[f32]) -> TestIndividual {
+        TestIndividual::create(genes.iter().cloned().collect())
+    }
+
+    #[test]
+    fn test() {
+        let population = vec![
+            individual(&[10.0, 20.0]),
+            individual(&[10.0]),
+            individual(&[20.0]),
+            individual(&[20.0, 20.0]),
+        ];
+
+        let actual = Statistics::new(3, &population);
+
+        assert_eq!(actual.generation, 3);
+        assert_eq!(actual.min_fitness, 10.0);
+        assert_eq!(actual.max_fitness, 40.0);
+        assert_eq!(actual.avg_fitness, 25.0);
+        assert_eq!(actual.avg_fitness_stderr, 0.0);
+        assert_eq!(actual.invalid, 0);
+        assert_eq!(actual.invalid_offspring, 0);
+        assert_eq!(actual.takeover, 0.25);
+        assert_relative_eq!(actual.distinct_genotypes, 4.0, epsilon = 1e-5);
+    }
+
+    mod takeover {
+        use super::*;
+
+        #[test]
+        fn test_within_epsilon() {
+            let population = vec![
+                individual(&[100.0]),
+                individual(&[99.5]),
+                individual(&[98.0]),
+                individual(&[50.0]),
+            ];
+
+            assert_eq!(Statistics::new(0, &population).takeover, 0.5);
+        }
+
+        #[test]
+        fn test_zero_fitness() {
+            let population = vec![individual(&[0.0]), individual(&[0.0, 0.0])];
+
+            assert_eq!(Statistics::new(0, &population).takeover, 1.0);
+        }
+    }
+
+    mod distinct_genotypes {
+        use super::*;
+
+        #[test]
+        fn test_all_equal() {
+            let population = vec![individual(&[1.0, 2.0]); 5];
+
+            assert_relative_eq!(Statistics::new(0, &population).distinct_genotypes, 1.0);
+        }
+
+        #[test]
+        fn test_uneven() {
+            //two genotypes, 3 to 1
+            let population = vec![
+                individual(&[1.0]),
+                individual(&[1.0]),
+                individual(&[1.0]),
+                individual(&[2.0]),
+            ];
+
+            let entropy = -(0.75f32 * 0.75f32.ln() + 0.25 * 0.25f32.ln());
+            assert_relative_eq!(Statistics::new(0, &population).distinct_genotypes, entropy.exp());
+        }
+    }
+
+    mod selection_intensity {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let population = vec![individual(&[1.0]), individual(&[3.0])];
+            let mut statistics = Statistics::new(0, &population);
+
+            //mean 2, standard deviation 1
+            statistics.set_selection_intensity(&population, &[3.0, 3.0, 1.0, 3.0]);
+            assert_relative_eq!(statistics.selection_intensity, 0.5);
+
+            statistics.set_selection_intensity(&population, &[2.0]);
+            assert_relative_eq!(statistics.selection_intensity, 0.0);
+        }
+
+        #[test]
+        fn test_no_variance() {
+            let population = vec![individual(&[1.0]), individual(&[1.0])];
+            let mut statistics = Statistics::new(0, &population);
+
+            statistics.set_selection_intensity(&population, &[1.0]);
+            assert_eq!(statistics.selection_intensity, 0.0);
+        }
+    }
+}
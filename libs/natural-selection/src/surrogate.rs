@@ -0,0 +1,193 @@
+use crate::*;
+
+//a genome whose fitness is known from a real evaluation
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample {
+    pub genes: Vec<f32>,
+    pub fitness: f32,
+}
+
+//what a surrogate model expects of a genome
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Prediction {
+    pub fitness: f32,
+    //grows with the distance to the samples the prediction is based on
+    pub uncertainty: f32,
+}
+
+//a cheap regression of the fitness over the space of genomes
+pub trait SurrogateModel {
+    //`samples` are never empty
+    fn predict(&self, samples: &[Sample], genes: &[f32]) -> Prediction;
+}
+
+//the mean fitness of the `k` nearest samples, as uncertain as they are far on average
+#[derive(Clone, Debug)]
+pub struct KnnSurrogate {
+    k: usize,
+}
+
+impl KnnSurrogate {
+    pub fn new(k: usize) -> Self {
+        assert!(k > 0);
+        Self { k }
+    }
+}
+
+impl SurrogateModel for KnnSurrogate {
+    fn predict(&self, samples: &[Sample], genes: &[f32]) -> Prediction {
+        let mut nearest: Vec<(f32, f32)> = samples
+            .iter()
+            .map(|sample| (Speciation::distance(&sample.genes, genes), sample.fitness))
+            .collect();
+        nearest.sort_by(|a, b| a.0.total_cmp(&b.0));
+        nearest.truncate(self.k);
+
+        let k = nearest.len() as f32;
+        Prediction {
+            fitness: nearest.iter().map(|&(_, fitness)| fitness).sum::<f32>() / k,
+            uncertainty: nearest.iter().map(|&(distance, _)| distance).sum::<f32>() / k,
+        }
+    }
+}
+
+//the fitness of all samples weighted by a gaussian of their distance with the standard
+//deviation `width`, as uncertain as the nearest sample is far
+#[derive(Clone, Debug)]
+pub struct RbfSurrogate {
+    width: f32,
+}
+
+impl RbfSurrogate {
+    pub fn new(width: f32) -> Self {
+        assert!(width > 0.0);
+        Self { width }
+    }
+}
+
+impl SurrogateModel for RbfSurrogate {
+    fn predict(&self, samples: &[Sample], genes: &[f32]) -> Prediction {
+        let mut nearest = f32::INFINITY;
+        let (mut weights, mut weighted) = (0.0, 0.0);
+        for sample in samples {
+            let distance = Speciation::distance(&sample.genes, genes);
+            let weight = (-0.5 * (distance / self.width).powi(2)).exp();
+            nearest = nearest.min(distance);
+            weights += weight;
+            weighted += weight * sample.fitness;
+        }
+
+        let fitness = if weights > f32::MIN_POSITIVE {
+            weighted / weights
+        } else {
+            //too far from everything, the mean is all there is to go on
+            samples.iter().map(|sample| sample.fitness).sum::<f32>() / samples.len() as f32
+        };
+        Prediction { fitness, uncertainty: nearest }
+    }
+}
+
+//learns the fitness of the genomes evaluated so far, so the genetic algorithm can breed
+//several candidates for every place in the next generation and only the one with the best
+//outlook, the most promising or the most uncertain, costs a real evaluation
+pub struct Surrogate {
+    model: Box<dyn SurrogateModel>,
+    //the latest samples, the oldest is overwritten first once there are `capacity` of them
+    samples: Vec<Sample>,
+    capacity: usize,
+    oldest: usize,
+    candidates: usize,
+    //how much predicted fitness a unit of uncertainty is worth
+    exploration: f32,
+}
+
+impl Surrogate {
+    pub fn new(model: impl SurrogateModel + 'static, capacity: usize, candidates: usize, exploration: f32) -> Self {
+        assert!(capacity > 0 && candidates > 0);
+        Self { model: Box::new(model), samples: Vec::new(), capacity, oldest: 0, candidates, exploration }
+    }
+
+    //offspring bred for every place in the next generation
+    pub fn candidates(&self) -> usize {
+        self.candidates
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn learn(&mut self, genes: &[f32], fitness: f32) {
+        let sample = Sample { genes: genes.to_vec(), fitness };
+        if self.samples.len() < self.capacity {
+            self.samples.push(sample);
+        } else {
+            self.samples[self.oldest] = sample;
+            self.oldest = (self.oldest + 1) % self.capacity;
+        }
+    }
+
+    //nothing before the first sample
+    pub fn predict(&self, genes: &[f32]) -> Option<Prediction> {
+        (!self.samples.is_empty()).then(|| self.model.predict(&self.samples, genes))
+    }
+
+    //the predicted fitness plus the worth of its uncertainty, the candidate with the best one
+    //is evaluated
+    pub fn outlook(&self, genes: &[f32]) -> f32 {
+        self.predict(genes).map_or(0.0, |prediction| prediction.fitness + self.exploration * prediction.uncertainty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn surrogate(model: impl SurrogateModel + 'static) -> Surrogate {
+        let mut surrogate = Surrogate::new(model, 3, 4, 1.0);
+        surrogate.learn(&[0.0, 0.0], 0.0);
+        surrogate.learn(&[1.0, 1.0], 1.0);
+        surrogate.learn(&[2.0, 2.0], 4.0);
+        surrogate
+    }
+
+    #[test]
+    fn test_knn() {
+        let surrogate = surrogate(KnnSurrogate::new(2));
+
+        let prediction = surrogate.predict(&[0.9, 0.9]).unwrap();
+        assert_relative_eq!(prediction.fitness, 0.5);
+        assert_relative_eq!(prediction.uncertainty, (0.9 + 0.1) / 2.0);
+        assert_relative_eq!(surrogate.outlook(&[0.9, 0.9]), 1.0);
+    }
+
+    #[test]
+    fn test_rbf() {
+        let surrogate = surrogate(RbfSurrogate::new(0.1));
+
+        //on a sample the others are too far to count
+        let prediction = surrogate.predict(&[2.0, 2.0]).unwrap();
+        assert_relative_eq!(prediction.fitness, 4.0, epsilon = 1e-4);
+        assert_eq!(prediction.uncertainty, 0.0);
+
+        //halfway between two they count the same
+        assert_relative_eq!(surrogate.predict(&[0.5, 0.5]).unwrap().fitness, 0.5, epsilon = 1e-4);
+        //far away from all of them
+        assert_relative_eq!(surrogate.predict(&[100.0, 100.0]).unwrap().fitness, 5.0 / 3.0);
+    }
+
+    #[test]
+    fn test_forgets_the_oldest() {
+        let mut surrogate = surrogate(KnnSurrogate::new(1));
+        assert_eq!(surrogate.predict(&[0.0, 0.0]).unwrap().fitness, 0.0);
+
+        surrogate.learn(&[0.0, 0.1], 9.0);
+        assert_eq!(surrogate.len(), 3);
+        assert_eq!(surrogate.predict(&[0.0, 0.0]).unwrap().fitness, 9.0);
+        assert_eq!(Surrogate::new(KnnSurrogate::new(1), 1, 1, 0.0).predict(&[0.0]), None);
+    }
+}
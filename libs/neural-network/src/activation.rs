@@ -0,0 +1,109 @@
+use std::fmt;
+use std::sync::Arc;
+
+//a function the neurons of a layer apply to their bias plus weighted inputs, any
+//`Fn(f32) -> f32` is one
+pub trait ActivationFn: Send + Sync {
+    fn activate(&self, x: f32) -> f32;
+
+    //the onnx operator that computes the same, the export needs one
+    fn onnx_op(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl<F: Fn(f32) -> f32 + Send + Sync> ActivationFn for F {
+    fn activate(&self, x: f32) -> f32 {
+        self(x)
+    }
+}
+
+//the activation of the neurons of a layer, part of its topology rather than of the genes so it
+//survives data() and from_data(), custom activations are equal if they are clones of each other
+#[derive(Clone, Default)]
+pub enum Activation {
+    #[default]
+    Relu,
+    //squashes into -1..1, for outputs that are used as they are
+    Tanh,
+    Custom(Arc<dyn ActivationFn>),
+}
+
+impl Activation {
+    pub fn custom(activation: impl ActivationFn + 'static) -> Self {
+        Self::Custom(Arc::new(activation))
+    }
+
+    pub fn apply(&self, x: f32) -> f32 {
+        match self {
+            Self::Relu => x.max(0.0),
+            Self::Tanh => x.tanh(),
+            Self::Custom(activation) => activation.activate(x),
+        }
+    }
+
+    pub fn onnx_op(&self) -> Option<&str> {
+        match self {
+            Self::Relu => Some("Relu"),
+            Self::Tanh => Some("Tanh"),
+            Self::Custom(activation) => activation.onnx_op(),
+        }
+    }
+}
+
+impl PartialEq for Activation {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Relu, Self::Relu) | (Self::Tanh, Self::Tanh) => true,
+            (Self::Custom(a), Self::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for Activation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Relu => f.write_str("Relu"),
+            Self::Tanh => f.write_str("Tanh"),
+            Self::Custom(activation) => write!(f, "Custom({})", activation.onnx_op().unwrap_or("fn")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sin;
+
+    impl ActivationFn for Sin {
+        fn activate(&self, x: f32) -> f32 {
+            x.sin()
+        }
+
+        fn onnx_op(&self) -> Option<&str> {
+            Some("Sin")
+        }
+    }
+
+    #[test]
+    fn test_closures_and_traits() {
+        let tanh = Activation::custom(f32::tanh);
+
+        assert_eq!(tanh.apply(0.5), 0.5f32.tanh());
+        assert_eq!(tanh.onnx_op(), None);
+        assert_eq!(Activation::custom(Sin).onnx_op(), Some("Sin"));
+        assert_eq!(Activation::Relu.apply(-1.0), 0.0);
+    }
+
+    #[test]
+    fn test_equality() {
+        let tanh = Activation::custom(f32::tanh);
+
+        assert_eq!(tanh.clone(), tanh);
+        assert_ne!(Activation::custom(f32::tanh), tanh);
+        assert_ne!(Activation::Relu, tanh);
+        assert_eq!(format!("{:?}", Activation::custom(Sin)), "Custom(Sin)");
+    }
+}
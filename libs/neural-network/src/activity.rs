@@ -0,0 +1,84 @@
+use crate::*;
+
+//how often every neuron produced something other than 0 over a batch of inputs, a neuron that
+//never does is dead: its relu is stuck below zero and its genes are wasted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Activity {
+    pub samples: usize,
+    //per layer after the inputs, per neuron
+    pub firing: Vec<Vec<usize>>,
+}
+
+impl Activity {
+    //(layer, neuron) of every neuron that never fired, the outputs included
+    pub fn dead(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.firing.iter().enumerate().flat_map(|(layer, neurons)| {
+            neurons.iter().enumerate().filter(|(_, &firing)| firing == 0).map(move |(neuron, _)| (layer, neuron))
+        })
+    }
+
+    //like dead(), but only the hidden layers
+    pub fn dead_hidden(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let outputs = self.firing.len().saturating_sub(1);
+        self.dead().filter(move |&(layer, _)| layer < outputs)
+    }
+
+    pub fn hidden_neurons(&self) -> usize {
+        self.firing.iter().rev().skip(1).map(Vec::len).sum()
+    }
+}
+
+impl Network {
+    //runs every input through the network and counts for each neuron how often it fired
+    pub fn activity(&self, inputs: impl IntoIterator<Item = Vec<f32>>) -> Activity {
+        let mut activity = Activity {
+            samples: 0,
+            firing: self.layers.iter().map(|layer| vec![0; layer.outputs]).collect(),
+        };
+
+        for inputs in inputs {
+            activity.samples += 1;
+            let layers = self.layers.iter().zip(self.layer_genes()).zip(&self.state);
+            layers.zip(&mut activity.firing).fold(inputs, |inputs, (((layer, genes), state), firing)| {
+                let outputs = layer.propagate(genes, inputs, state);
+                for (count, output) in firing.iter_mut().zip(&outputs) {
+                    if *output != 0.0 {
+                        *count += 1;
+                    }
+                }
+                outputs
+            });
+        }
+
+        activity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dead_neurons() {
+        //the second hidden neuron has a bias of -10 and can't overcome it, the first passes its input on
+        let network = Network::from_data(
+            &[LayerTopology::new(1), LayerTopology::new(2), LayerTopology::new(1)],
+            [0.0, 1.0, -10.0, 1.0, 0.0, 1.0, 1.0],
+        );
+        let activity = network.activity([vec![0.5], vec![1.0], vec![-1.0]]);
+
+        assert_eq!(activity.samples, 3);
+        assert_eq!(activity.firing, vec![vec![2, 0], vec![2]]);
+        assert_eq!(activity.dead_hidden().collect::<Vec<_>>(), [(0, 1)]);
+        assert_eq!(activity.hidden_neurons(), 2);
+    }
+
+    #[test]
+    fn test_dead_outputs() {
+        let network = Network::from_data(&[LayerTopology::new(1), LayerTopology::new(1)], [-1.0, 0.0]);
+        let activity = network.activity([vec![1.0]]);
+
+        assert_eq!(activity.dead().collect::<Vec<_>>(), [(0, 0)]);
+        assert_eq!(activity.dead_hidden().count(), 0);
+    }
+}
@@ -0,0 +1,60 @@
+use std::f32::consts::TAU;
+
+use crate::*;
+
+impl Network {
+    //feeds the last 2 * `clocks` inputs of the first layer with a sin/cos pair each, pair k
+    //completes 2^k cycles per unit of time, see propagate_at()
+    pub fn with_clocks(mut self, clocks: usize) -> Self {
+        assert!(2 * clocks < self.layers[0].inputs, "the clocks need to leave at least one input");
+        self.clocks = clocks;
+        self
+    }
+
+    pub fn clocks(&self) -> usize {
+        self.clocks
+    }
+
+    //the inputs the caller provides, without those of the clocks
+    pub fn inputs(&self) -> usize {
+        self.layers[0].inputs - 2 * self.clocks
+    }
+
+    //the values of the clock inputs at `time`
+    pub fn clock(&self, time: f32) -> impl Iterator<Item = f32> {
+        (0..self.clocks).flat_map(move |k| {
+            let phase = TAU * (1 << k) as f32 * time;
+            [phase.sin(), phase.cos()]
+        })
+    }
+
+    //like propagate(), with the clock inputs at `time` appended to `inputs`
+    pub fn propagate_at(&self, mut inputs: Vec<f32>, time: f32) -> Vec<f32> {
+        inputs.extend(self.clock(time));
+        self.propagate(inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_clock() {
+        let network = Network::from_data(&[LayerTopology::new(5), LayerTopology::new(1)], vec![0.0; 6]).with_clocks(2);
+        assert_eq!(network.inputs(), 1);
+
+        let clock: Vec<_> = network.clock(0.25).collect();
+        assert_relative_eq!(clock.as_slice(), [1.0, 0.0, 0.0, -1.0].as_ref(), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_propagate_at() {
+        //only listens to the cosine of the slowest clock
+        let network = Network::from_data(&[LayerTopology::new(3), LayerTopology::new(1)], vec![0.0, 0.0, 0.0, 1.0]).with_clocks(1);
+
+        assert_relative_eq!(network.propagate_at(vec![5.0], 0.0)[0], 1.0);
+        assert_relative_eq!(network.propagate_at(vec![5.0], 0.5)[0], 0.0);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::*;
+
+//how the genes of a layer changed from one network to another, the differences are laid out
+//like the genes: a row per output neuron with its bias followed by its weights
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerDiff {
+    pub inputs: usize,
+    pub outputs: usize,
+    pub differences: Vec<f32>,
+}
+
+impl LayerDiff {
+    //the euclidean distance between the genes of the layer in both networks
+    pub fn l2(&self) -> f32 {
+        self.differences.iter().map(|difference| difference * difference).sum::<f32>().sqrt()
+    }
+
+    pub fn mean_abs(&self) -> f32 {
+        self.differences.iter().map(|difference| difference.abs()).sum::<f32>() / self.differences.len() as f32
+    }
+
+    pub fn max_abs(&self) -> f32 {
+        self.differences.iter().fold(0.0, |max, difference| max.max(difference.abs()))
+    }
+
+    //the difference of the gene of neuron `output` for `input`, 0 being the bias
+    pub fn at(&self, output: usize, input: usize) -> f32 {
+        self.differences[output * (1 + self.inputs) + input]
+    }
+}
+
+impl Network {
+    //the differences of `other` to this network per layer, None if their topologies differ
+    pub fn diff(&self, other: &Network) -> Option<Vec<LayerDiff>> {
+        if self.layers != other.layers {
+            return None;
+        }
+
+        Some(
+            self.layers
+                .iter()
+                .zip(self.layer_genes().zip(other.layer_genes()))
+                //a gru layer has a row per gate and its last outputs count as inputs
+                .map(|(layer, (genes, other))| LayerDiff {
+                    inputs: layer.row_len() - 1,
+                    outputs: layer.rows(),
+                    differences: other.iter().zip(genes).map(|(other, gene)| other - gene).collect(),
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_diff() {
+        let topology = [LayerTopology::new(2), LayerTopology::new(1), LayerTopology::new(1)];
+        let a = Network::from_data(&topology, vec![0.0, 1.0, 2.0, 0.5, 0.5]);
+        let b = Network::from_data(&topology, vec![0.0, 4.0, -2.0, 0.5, 0.5]);
+
+        let diff = a.diff(&b).unwrap();
+        assert_eq!(diff[0].differences, [0.0, 3.0, -4.0]);
+        assert_relative_eq!(diff[0].l2(), 5.0);
+        assert_relative_eq!(diff[0].max_abs(), 4.0);
+        assert_eq!(diff[0].at(0, 2), -4.0);
+        assert_eq!(diff[1].l2(), 0.0);
+
+        let other = Network::from_data(&[LayerTopology::new(3), LayerTopology::new(1)], vec![0.0; 4]);
+        assert!(a.diff(&other).is_none());
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Input,
+    Hidden,
+    Output,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeGene {
+    pub id: usize,
+    pub kind: NodeKind,
+    //unused by inputs
+    pub bias: f32,
+}
+
+//a weighted connection between two nodes, disabled connections stay in the genome so a
+//crossover can still line them up by innovation
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionGene {
+    pub innovation: usize,
+    pub from: usize,
+    pub to: usize,
+    pub weight: f32,
+    pub enabled: bool,
+}
+
+//a network as a list of nodes and the connections between them instead of fully connected
+//layers, so evolution can change its topology and networks can be sparse
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConnectionGenome {
+    pub nodes: Vec<NodeGene>,
+    pub connections: Vec<ConnectionGene>,
+}
+
+//a genome that cannot be turned into a feed-forward network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenomeError {
+    UnknownNode(usize),
+    DuplicateNode(usize),
+    //a connection into an input node
+    IntoInput(usize),
+    //the enabled connections loop through these nodes
+    Cycle(Vec<usize>),
+}
+
+impl fmt::Display for GenomeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownNode(id) => write!(f, "a connection uses the unknown node {id}"),
+            Self::DuplicateNode(id) => write!(f, "node {id} is in the genome twice"),
+            Self::IntoInput(id) => write!(f, "a connection leads into the input node {id}"),
+            Self::Cycle(ids) => write!(f, "the connections loop through the nodes {ids:?}"),
+        }
+    }
+}
+
+impl std::error::Error for GenomeError {}
+
+impl ConnectionGenome {
+    //inputs and outputs connected by a fully connected layer with the weights of `weights`,
+    //innovations are numbered in order
+    pub fn fully_connected(inputs: usize, outputs: usize, mut weights: impl FnMut() -> f32) -> Self {
+        let nodes = (0..inputs)
+            .map(|id| NodeGene { id, kind: NodeKind::Input, bias: 0.0 })
+            .chain((inputs..inputs + outputs).map(|id| NodeGene { id, kind: NodeKind::Output, bias: 0.0 }))
+            .collect();
+        let connections = (inputs..inputs + outputs)
+            .flat_map(|to| (0..inputs).map(move |from| (from, to)))
+            .enumerate()
+            .map(|(innovation, (from, to))| ConnectionGene { innovation, from, to, weight: weights(), enabled: true })
+            .collect();
+
+        Self { nodes, connections }
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.nodes.iter().filter(|node| node.kind == NodeKind::Input).count()
+    }
+
+    pub fn outputs(&self) -> usize {
+        self.nodes.iter().filter(|node| node.kind == NodeKind::Output).count()
+    }
+
+    //a copy without the enabled connections whose weight is smaller than `threshold` in
+    //magnitude, and the number of enabled connections left
+    pub fn pruned(&self, threshold: f32) -> (Self, usize) {
+        let connections: Vec<_> = self
+            .connections
+            .iter()
+            .filter(|connection| !connection.enabled || connection.weight.abs() >= threshold)
+            .cloned()
+            .collect();
+        let enabled = connections.iter().filter(|connection| connection.enabled).count();
+
+        (Self { nodes: self.nodes.clone(), connections }, enabled)
+    }
+
+    //sorts the nodes topologically so every node is evaluated after the nodes feeding it
+    pub fn decode(&self) -> Result<FeedForward, GenomeError> {
+        let mut index = HashMap::with_capacity(self.nodes.len());
+        for (i, node) in self.nodes.iter().enumerate() {
+            if index.insert(node.id, i).is_some() {
+                return Err(GenomeError::DuplicateNode(node.id));
+            }
+        }
+
+        let mut incoming = vec![Vec::new(); self.nodes.len()];
+        let mut outgoing = vec![Vec::new(); self.nodes.len()];
+        for connection in self.connections.iter().filter(|connection| connection.enabled) {
+            let from = *index.get(&connection.from).ok_or(GenomeError::UnknownNode(connection.from))?;
+            let to = *index.get(&connection.to).ok_or(GenomeError::UnknownNode(connection.to))?;
+            if self.nodes[to].kind == NodeKind::Input {
+                return Err(GenomeError::IntoInput(connection.to));
+            }
+
+            incoming[to].push((from, connection.weight));
+            outgoing[from].push(to);
+        }
+
+        //kahn's algorithm, in the order of the genome where there is a choice
+        let mut waiting: Vec<usize> = incoming.iter().map(Vec::len).collect();
+        let mut order: Vec<usize> = (0..self.nodes.len()).filter(|&node| waiting[node] == 0).collect();
+        let mut next = 0;
+        while next < order.len() {
+            for &to in &outgoing[order[next]] {
+                waiting[to] -= 1;
+                if waiting[to] == 0 {
+                    order.push(to);
+                }
+            }
+            next += 1;
+        }
+        if order.len() < self.nodes.len() {
+            let cycle = find_cycle(&outgoing, &waiting).into_iter().map(|node| self.nodes[node].id).collect();
+            return Err(GenomeError::Cycle(cycle));
+        }
+
+        let inputs = self.nodes.iter().enumerate().filter(|(_, node)| node.kind == NodeKind::Input).map(|(i, _)| i).collect();
+        let outputs = self.nodes.iter().enumerate().filter(|(_, node)| node.kind == NodeKind::Output).map(|(i, _)| i).collect();
+        let steps = order
+            .into_iter()
+            .filter(|&node| self.nodes[node].kind != NodeKind::Input)
+            .map(|node| Step { node, bias: self.nodes[node].bias, incoming: std::mem::take(&mut incoming[node]) })
+            .collect();
+
+        Ok(FeedForward { nodes: self.nodes.len(), inputs, outputs, steps })
+    }
+}
+
+//one loop among the nodes kahn's algorithm could not sort, in the order it goes through them,
+//the nodes merely downstream of a loop are left out
+fn find_cycle(outgoing: &[Vec<usize>], waiting: &[usize]) -> Vec<usize> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Visit {
+        New,
+        OnPath,
+        Done,
+    }
+
+    let mut visits = vec![Visit::New; outgoing.len()];
+    for start in (0..outgoing.len()).filter(|&node| waiting[node] > 0) {
+        //depth first, with the path and the next edge of every node on it
+        let mut path = vec![(start, 0)];
+        visits[start] = Visit::OnPath;
+        while let Some(&mut (node, ref mut edge)) = path.last_mut() {
+            let Some(&to) = outgoing[node].get(*edge) else {
+                visits[node] = Visit::Done;
+                path.pop();
+                continue;
+            };
+            *edge += 1;
+
+            match visits[to] {
+                Visit::OnPath => {
+                    let start = path.iter().position(|&(node, _)| node == to).expect("on the path");
+                    return path[start..].iter().map(|&(node, _)| node).collect();
+                }
+                Visit::New => {
+                    visits[to] = Visit::OnPath;
+                    path.push((to, 0));
+                }
+                Visit::Done => {}
+            }
+        }
+    }
+
+    unreachable!("kahn's algorithm only leaves nodes unsorted if they are behind a loop")
+}
+
+//a decoded ConnectionGenome, evaluates its nodes one after another in topological order
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedForward {
+    pub(crate) nodes: usize,
+    pub(crate) inputs: Vec<usize>,
+    pub(crate) outputs: Vec<usize>,
+    pub(crate) steps: Vec<Step>,
+}
+
+//the evaluation of a hidden or output node from the nodes feeding it
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Step {
+    pub(crate) node: usize,
+    pub(crate) bias: f32,
+    //the index of a node feeding this one and the weight of the connection
+    pub(crate) incoming: Vec<(usize, f32)>,
+}
+
+impl FeedForward {
+    //takes one value per input node and returns one per output node, both in the order of
+    //the genome's nodes
+    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+        let mut values = vec![0.0; self.nodes];
+        self.propagate_into(&inputs, &mut values);
+        self.outputs.iter().map(|&node| values[node]).collect()
+    }
+
+    //leaves the value of every node in `values`, which needs one slot per node
+    pub(crate) fn propagate_into(&self, inputs: &[f32], values: &mut [f32]) {
+        assert_eq!(inputs.len(), self.inputs.len());
+
+        for (&node, &input) in self.inputs.iter().zip(inputs) {
+            values[node] = input;
+        }
+        for step in &self.steps {
+            let output = step.incoming.iter().map(|&(from, weight)| values[from] * weight).sum::<f32>();
+            values[step.node] = (step.bias + output).max(0.0); //ReLu
+        }
+    }
+
+    //the number of enabled connections
+    pub fn connections(&self) -> usize {
+        self.steps.iter().map(|step| step.incoming.len()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn node(id: usize, kind: NodeKind, bias: f32) -> NodeGene {
+        NodeGene { id, kind, bias }
+    }
+
+    fn connection(innovation: usize, from: usize, to: usize, weight: f32) -> ConnectionGene {
+        ConnectionGene { innovation, from, to, weight, enabled: true }
+    }
+
+    #[test]
+    fn test_matches_a_layer() {
+        let weights = [0.5, -0.3, 0.8];
+        let mut next = weights.into_iter().skip(1);
+        let mut genome = ConnectionGenome::fully_connected(2, 1, || next.next().unwrap());
+        genome.nodes[2].bias = weights[0];
+
+        let network = crate::Network::from_data(&[crate::LayerTopology::new(2), crate::LayerTopology::new(1)], weights);
+        let decoded = genome.decode().unwrap();
+        assert_relative_eq!(decoded.propagate(vec![0.5, 1.0]).as_slice(), network.propagate(vec![0.5, 1.0]).as_slice());
+        assert_eq!(decoded.connections(), 2);
+    }
+
+    #[test]
+    fn test_skip_connections_and_order() {
+        //the hidden node comes after the output in the genome but is evaluated before it
+        let genome = ConnectionGenome {
+            nodes: vec![node(0, NodeKind::Input, 0.0), node(1, NodeKind::Output, 0.0), node(7, NodeKind::Hidden, 1.0)],
+            connections: vec![
+                connection(0, 0, 1, 2.0),
+                connection(1, 0, 7, 1.0),
+                connection(2, 7, 1, 3.0),
+                ConnectionGene { enabled: false, ..connection(3, 0, 1, 100.0) },
+            ],
+        };
+
+        //2 * 1 + 3 * (1 + 1)
+        assert_relative_eq!(genome.decode().unwrap().propagate(vec![1.0])[0], 8.0);
+    }
+
+    #[test]
+    fn test_pruned() {
+        let genome = ConnectionGenome::fully_connected(3, 1, {
+            let mut weights = [0.05, -0.5, 0.2].into_iter();
+            move || weights.next().unwrap()
+        });
+
+        let (pruned, connections) = genome.pruned(0.1);
+        assert_eq!(connections, 2);
+        assert_eq!(pruned.connections.iter().map(|connection| connection.innovation).collect::<Vec<_>>(), vec![1, 2]);
+        assert_relative_eq!(pruned.decode().unwrap().propagate(vec![1.0, 1.0, 1.0])[0], 0.0);
+    }
+
+    #[test]
+    fn test_invalid_genomes() {
+        let nodes = vec![node(0, NodeKind::Input, 0.0), node(1, NodeKind::Hidden, 0.0), node(2, NodeKind::Output, 0.0)];
+        let genome = |connections| ConnectionGenome { nodes: nodes.clone(), connections };
+
+        assert_eq!(genome(vec![connection(0, 0, 5, 1.0)]).decode(), Err(GenomeError::UnknownNode(5)));
+        assert_eq!(genome(vec![connection(0, 2, 0, 1.0)]).decode(), Err(GenomeError::IntoInput(0)));
+        assert_eq!(
+            genome(vec![connection(0, 1, 2, 1.0), connection(1, 2, 1, 1.0)]).decode(),
+            Err(GenomeError::Cycle(vec![1, 2]))
+        );
+    }
+
+    #[test]
+    fn test_cycle_leaves_out_downstream_nodes() {
+        let genome = ConnectionGenome {
+            nodes: vec![
+                node(0, NodeKind::Input, 0.0),
+                node(1, NodeKind::Output, 0.0),
+                node(2, NodeKind::Hidden, 0.0),
+                node(3, NodeKind::Hidden, 0.0),
+                node(4, NodeKind::Hidden, 0.0),
+            ],
+            //1 is only fed by the loop 2 -> 4 -> 3 -> 2
+            connections: vec![
+                connection(0, 0, 2, 1.0),
+                connection(1, 2, 1, 1.0),
+                connection(2, 2, 4, 1.0),
+                connection(3, 4, 3, 1.0),
+                connection(4, 3, 2, 1.0),
+            ],
+        };
+
+        assert_eq!(genome.decode(), Err(GenomeError::Cycle(vec![2, 4, 3])));
+    }
+}
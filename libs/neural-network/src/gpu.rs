@@ -0,0 +1,283 @@
+use std::fmt;
+use std::sync::mpsc;
+use wgpu::util::DeviceExt;
+
+use crate::*;
+
+const WORKGROUP_SIZE: u32 = 64;
+//the shader's params have room for this many layers
+const MAX_LAYERS: usize = 32;
+
+#[derive(Debug)]
+pub enum GpuError {
+    //no graphics adapter that can run compute shaders
+    NoAdapter,
+    RequestDevice(wgpu::RequestDeviceError),
+    Readback(wgpu::BufferAsyncError),
+    //the shader only knows relu
+    Activation,
+    //nor does it remember anything between dispatches
+    Recurrent,
+}
+
+impl fmt::Display for GpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoAdapter => write!(f, "no gpu adapter found"),
+            Self::RequestDevice(err) => write!(f, "could not open the gpu: {err}"),
+            Self::Readback(err) => write!(f, "could not read the results back from the gpu: {err}"),
+            Self::Activation => write!(f, "the gpu only runs networks with relu activations"),
+            Self::Recurrent => write!(f, "the gpu only runs networks of dense layers"),
+        }
+    }
+}
+
+impl std::error::Error for GpuError {}
+
+//buffers sized for one batch of networks
+struct Buffers {
+    networks: usize,
+    weights: wgpu::Buffer,
+    inputs: wgpu::Buffer,
+    outputs: wgpu::Buffer,
+    readback: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+//evaluates many networks of the same topology in a single compute dispatch, the weights
+//are uploaded once with upload() and stay on the gpu until the next upload
+pub struct GpuBatch {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    topology: Vec<usize>,
+    genes: usize,
+    buffers: Option<Buffers>,
+}
+
+impl GpuBatch {
+    pub fn new(layers: &[LayerTopology]) -> Result<Self, GpuError> {
+        assert!(layers.len() > 1); //needs to have more than 1 layer
+        assert!(layers.len() <= MAX_LAYERS, "the gpu supports at most {MAX_LAYERS} layers");
+        if layers[1..].iter().any(|layer| layer.activation != Activation::Relu) {
+            return Err(GpuError::Activation);
+        }
+        if layers[1..].iter().any(|layer| layer.kind != LayerKind::Dense) {
+            return Err(GpuError::Recurrent);
+        }
+
+        let instance = wgpu::Instance::new(wgpu::Backends::all());
+        let adapter = futures_lite::future::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            force_fallback_adapter: false,
+            compatible_surface: None,
+        })).ok_or(GpuError::NoAdapter)?;
+
+        let (device, queue) = futures_lite::future::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("neural network"),
+                features: wgpu::Features::empty(),
+                limits: wgpu::Limits::downlevel_defaults(),
+            },
+            None,
+        )).map_err(GpuError::RequestDevice)?;
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("propagate"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("propagate.wgsl").into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("propagate"),
+            layout: None,
+            module: &module,
+            entry_point: "main",
+        });
+
+        let topology: Vec<usize> = layers.iter().map(|layer| layer.neurons).collect();
+        let genes = topology.windows(2).map(|layers| (layers[0] + 1) * layers[1]).sum();
+
+        Ok(Self { device, queue, pipeline, topology, genes, buffers: None })
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.topology[0]
+    }
+
+    pub fn output_size(&self) -> usize {
+        *self.topology.last().expect("got an empty topology")
+    }
+
+    //replaces the networks that are evaluated, all of them need the batch's topology
+    pub fn upload<'a>(&mut self, networks: impl IntoIterator<Item = &'a Network>) {
+        let mut weights = Vec::new();
+        let mut count = 0;
+
+        for network in networks {
+            let shape: Vec<_> = network.layers.iter().map(|layer| (layer.inputs, layer.outputs)).collect();
+            let expected: Vec<_> = self.topology.windows(2).map(|layers| (layers[0], layers[1])).collect();
+            assert_eq!(shape, expected, "network does not match the batch's topology");
+            assert!(network.layers.iter().all(|layer| layer.activation == Activation::Relu), "the gpu only runs relu activations");
+            assert!(network.layers.iter().all(|layer| layer.kind == LayerKind::Dense), "the gpu only runs dense layers");
+
+            weights.extend_from_slice(network.genes());
+            count += 1;
+        }
+
+        match &self.buffers {
+            Some(buffers) if buffers.networks == count => {
+                self.queue.write_buffer(&buffers.weights, 0, bytemuck::cast_slice(&weights));
+            }
+            _ => self.buffers = Some(self.create_buffers(count, &weights)),
+        }
+    }
+
+    //one set of inputs per uploaded network, in the order they were uploaded
+    pub fn propagate(&mut self, inputs: &[Vec<f32>]) -> Result<Vec<Vec<f32>>, GpuError> {
+        let Some(buffers) = &self.buffers else {
+            assert!(inputs.is_empty(), "no networks uploaded");
+            return Ok(vec![]);
+        };
+        assert_eq!(inputs.len(), buffers.networks);
+        if inputs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let flat: Vec<f32> = inputs
+            .iter()
+            .flat_map(|inputs| {
+                assert_eq!(inputs.len(), self.input_size());
+                inputs.iter().copied()
+            })
+            .collect();
+        self.queue.write_buffer(&buffers.inputs, 0, bytemuck::cast_slice(&flat));
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("propagate") });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: Some("propagate") });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &buffers.bind_group, &[]);
+            pass.dispatch_workgroups((buffers.networks as u32).div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&buffers.outputs, 0, &buffers.readback, 0, buffers.outputs.size());
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = buffers.readback.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .expect("the gpu dropped the readback")
+            .map_err(GpuError::Readback)?;
+
+        let outputs = {
+            let data = slice.get_mapped_range();
+            bytemuck::cast_slice::<u8, f32>(&data)
+                .chunks_exact(self.output_size())
+                .map(<[f32]>::to_vec)
+                .collect()
+        };
+        buffers.readback.unmap();
+
+        Ok(outputs)
+    }
+
+    fn create_buffers(&self, networks: usize, weights: &[f32]) -> Buffers {
+        let width = *self.topology.iter().max().expect("got an empty topology");
+        let params: Vec<u32> = [networks, self.genes, self.topology.len(), width]
+            .into_iter()
+            .chain(self.topology.iter().copied())
+            .chain(std::iter::repeat(0))
+            .take(4 + MAX_LAYERS)
+            .map(|value| value as u32)
+            .collect();
+
+        //empty bindings are not allowed, so there is always room for one network
+        let size = |floats: usize| (floats.max(1) * std::mem::size_of::<f32>()) as wgpu::BufferAddress;
+        let storage = |label, floats, usage| {
+            self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: size(floats),
+                usage: wgpu::BufferUsages::STORAGE | usage,
+                mapped_at_creation: false,
+            })
+        };
+
+        let params = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("params"),
+            contents: bytemuck::cast_slice(&params),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let weights_buffer = storage("weights", weights.len(), wgpu::BufferUsages::COPY_DST);
+        self.queue.write_buffer(&weights_buffer, 0, bytemuck::cast_slice(weights));
+        let inputs = storage("inputs", networks * self.input_size(), wgpu::BufferUsages::COPY_DST);
+        let outputs = storage("outputs", networks * self.output_size(), wgpu::BufferUsages::COPY_SRC);
+        let scratch = storage("scratch", networks * width * 2, wgpu::BufferUsages::empty());
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size: size(networks * self.output_size()),
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("propagate"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[&params, &weights_buffer, &inputs, &outputs, &scratch]
+                .iter()
+                .enumerate()
+                .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                    binding: binding as u32,
+                    resource: buffer.as_entire_binding(),
+                })
+                .collect::<Vec<_>>(),
+        });
+
+        Buffers { networks, weights: weights_buffer, inputs, outputs, readback, bind_group }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand_chacha::ChaCha8Rng;
+
+    //most machines running the tests have no gpu, there is nothing to compare against then
+    fn batch(layers: &[LayerTopology]) -> Option<GpuBatch> {
+        match GpuBatch::new(layers) {
+            Ok(batch) => Some(batch),
+            Err(err) => {
+                eprintln!("skipping gpu test: {err}");
+                None
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_cpu() {
+        let topology = [
+            LayerTopology::new(11),
+            LayerTopology::new(24),
+            LayerTopology::new(5),
+        ];
+        let Some(mut batch) = batch(&topology) else {
+            return;
+        };
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let networks: Vec<_> = (0..100).map(|_| Network::random(&mut rng, &topology)).collect();
+        let inputs: Vec<Vec<f32>> = (0..100)
+            .map(|_| (0..11).map(|_| rng.gen_range(-1.0..=1.0)).collect())
+            .collect();
+
+        batch.upload(&networks);
+        let actual = batch.propagate(&inputs).unwrap();
+
+        for ((network, inputs), actual) in networks.iter().zip(inputs).zip(actual) {
+            assert_relative_eq!(actual.as_slice(), network.propagate(inputs).as_slice(), epsilon = 1e-4);
+        }
+    }
+}
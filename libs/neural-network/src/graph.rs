@@ -0,0 +1,168 @@
+use crate::*;
+
+//propagates ConnectionGenomes of any DAG topology, the evaluation order is worked out once
+//and reused as long as the structure of the genomes stays the same, so genomes that only
+//changed their weights skip the topological sort
+#[derive(Debug, Default)]
+pub struct GraphExecutor {
+    plan: Option<Plan>,
+    //the value of every node of the last propagation
+    values: Vec<f32>,
+}
+
+//a memoized FeedForward and where the weights of the genome go in it
+#[derive(Debug)]
+struct Plan {
+    //the ids and kinds of the nodes and the enabled connections it was made for
+    nodes: Vec<(usize, NodeKind)>,
+    connections: Vec<(usize, usize)>,
+    network: FeedForward,
+    //the step and incoming slot of every enabled connection, in the order of the genome
+    slots: Vec<(usize, usize)>,
+}
+
+impl Plan {
+    fn new(genome: &ConnectionGenome) -> Result<Self, GenomeError> {
+        let network = genome.decode()?;
+
+        let node_index = |id| genome.nodes.iter().position(|node| node.id == id).expect("decoded");
+        let mut step_of = vec![usize::MAX; genome.nodes.len()];
+        for (i, step) in network.steps.iter().enumerate() {
+            step_of[step.node] = i;
+        }
+        //decode() keeps the incoming connections of every node in the order of the genome
+        let mut next_slot = vec![0; network.steps.len()];
+        let slots = enabled(genome)
+            .map(|connection| {
+                let step = step_of[node_index(connection.to)];
+                next_slot[step] += 1;
+                (step, next_slot[step] - 1)
+            })
+            .collect();
+
+        Ok(Self { nodes: structure(genome), connections: enabled(genome).map(|c| (c.from, c.to)).collect(), network, slots })
+    }
+
+    fn fits(&self, genome: &ConnectionGenome) -> bool {
+        self.nodes.len() == genome.nodes.len()
+            && self.nodes.iter().zip(&genome.nodes).all(|(&(id, kind), node)| id == node.id && kind == node.kind)
+            && self.connections.iter().copied().eq(enabled(genome).map(|c| (c.from, c.to)))
+    }
+
+    //copies the weights and biases of a genome that fits()
+    fn refresh(&mut self, genome: &ConnectionGenome) {
+        for (&(step, slot), connection) in self.slots.iter().zip(enabled(genome)) {
+            self.network.steps[step].incoming[slot].1 = connection.weight;
+        }
+        for step in &mut self.network.steps {
+            step.bias = genome.nodes[step.node].bias;
+        }
+    }
+}
+
+fn enabled(genome: &ConnectionGenome) -> impl Iterator<Item = &ConnectionGene> {
+    genome.connections.iter().filter(|connection| connection.enabled)
+}
+
+fn structure(genome: &ConnectionGenome) -> Vec<(usize, NodeKind)> {
+    genome.nodes.iter().map(|node| (node.id, node.kind)).collect()
+}
+
+impl GraphExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //takes one value per input node and returns one per output node, both in the order of
+    //the genome's nodes, fails if the genome has a cycle or other structural error
+    pub fn propagate(&mut self, genome: &ConnectionGenome, inputs: &[f32]) -> Result<Vec<f32>, GenomeError> {
+        let plan = match &mut self.plan {
+            Some(plan) if plan.fits(genome) => {
+                plan.refresh(genome);
+                plan
+            }
+            plan => plan.insert(Plan::new(genome)?),
+        };
+
+        self.values.clear();
+        self.values.resize(plan.network.nodes, 0.0);
+        plan.network.propagate_into(inputs, &mut self.values);
+
+        Ok(plan.network.outputs.iter().map(|&node| self.values[node]).collect())
+    }
+
+    //the value of a node in the last propagation, e.g. to show hidden activations
+    pub fn value(&self, id: usize) -> Option<f32> {
+        let plan = self.plan.as_ref()?;
+        let index = plan.nodes.iter().position(|&(node, _)| node == id)?;
+        self.values.get(index).copied()
+    }
+
+    //whether the next propagation of a genome reuses the evaluation order
+    pub fn is_memoized(&self, genome: &ConnectionGenome) -> bool {
+        self.plan.as_ref().is_some_and(|plan| plan.fits(genome))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn genome() -> ConnectionGenome {
+        let node = |id, kind, bias| NodeGene { id, kind, bias };
+        let connection = |innovation, from, to, weight| ConnectionGene { innovation, from, to, weight, enabled: true };
+
+        //the output reads the input directly and through a chain of two hidden nodes
+        ConnectionGenome {
+            nodes: vec![
+                node(0, NodeKind::Input, 0.0),
+                node(1, NodeKind::Output, 0.5),
+                node(2, NodeKind::Hidden, 0.0),
+                node(3, NodeKind::Hidden, 0.0),
+            ],
+            connections: vec![
+                connection(0, 0, 1, 1.0),
+                connection(1, 3, 1, 2.0),
+                connection(2, 0, 2, 1.0),
+                connection(3, 2, 3, 3.0),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_matches_decode() {
+        let genome = genome();
+        let mut executor = GraphExecutor::new();
+
+        let outputs = executor.propagate(&genome, &[2.0]).unwrap();
+        assert_relative_eq!(outputs.as_slice(), genome.decode().unwrap().propagate(vec![2.0]).as_slice());
+        //0.5 + 2 + 2 * 3 * 2
+        assert_relative_eq!(outputs[0], 14.5);
+        assert_eq!(executor.value(3), Some(6.0));
+    }
+
+    #[test]
+    fn test_reuses_the_order_for_new_weights() {
+        let mut genome = genome();
+        let mut executor = GraphExecutor::new();
+        executor.propagate(&genome, &[2.0]).unwrap();
+
+        genome.connections[3].weight = 1.0;
+        genome.nodes[1].bias = 0.0;
+        assert!(executor.is_memoized(&genome));
+        assert_relative_eq!(executor.propagate(&genome, &[2.0]).unwrap()[0], 6.0);
+
+        genome.connections[0].enabled = false;
+        assert!(!executor.is_memoized(&genome));
+        assert_relative_eq!(executor.propagate(&genome, &[2.0]).unwrap()[0], 4.0);
+    }
+
+    #[test]
+    fn test_rejects_cycles() {
+        let mut genome = genome();
+        genome.connections.push(ConnectionGene { innovation: 4, from: 3, to: 2, weight: 1.0, enabled: true });
+
+        assert_eq!(GraphExecutor::new().propagate(&genome, &[2.0]), Err(GenomeError::Cycle(vec![2, 3])));
+    }
+}
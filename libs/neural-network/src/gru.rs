@@ -0,0 +1,99 @@
+use crate::*;
+
+//the rows of a gru layer are the update gates of all its neurons, then their reset gates, then
+//their candidates, the order onnx keeps its GRU weights in, a row is the bias followed by the
+//weights of the inputs and those of the last outputs
+impl Layer {
+    pub(crate) fn propagate_gru(&self, genes: &[f32], inputs: &[f32], state: &[f32]) -> Vec<f32> {
+        assert_eq!(state.len(), self.outputs);
+
+        let rows: Vec<_> = genes.chunks_exact(self.row_len()).collect();
+        let (update, rows) = rows.split_at(self.outputs);
+        let (reset, candidate) = rows.split_at(self.outputs);
+
+        let reset: Vec<f32> = reset.iter().zip(state).map(|(row, last)| sigmoid(weigh(row, inputs, state)) * last).collect();
+        update
+            .iter()
+            .zip(candidate)
+            .zip(state)
+            .map(|((update, candidate), last)| {
+                let update = sigmoid(weigh(update, inputs, state));
+                let candidate = weigh(candidate, inputs, &reset).tanh();
+                (1.0 - update) * candidate + update * last
+            })
+            .collect()
+    }
+}
+
+//the bias of a row plus its weighted inputs and outputs
+fn weigh(row: &[f32], inputs: &[f32], state: &[f32]) -> f32 {
+    let (bias, weights) = row.split_first().expect("empty row");
+    let (input_weights, state_weights) = weights.split_at(inputs.len());
+    let dot = |values: &[f32], weights: &[f32]| values.iter().zip(weights).map(|(value, weight)| value * weight).sum::<f32>();
+
+    bias + dot(inputs, input_weights) + dot(state, state_weights)
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    //gates that stay half open and a candidate that adds the input to the last output
+    fn half_open() -> Network {
+        Network::from_data(&[LayerTopology::new(1), LayerTopology::gru(1)], [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0])
+    }
+
+    #[test]
+    fn test_step() {
+        let mut network = half_open();
+
+        let first = network.step(vec![1.0])[0];
+        assert_relative_eq!(first, 0.5 * 1.0f32.tanh());
+        assert_eq!(network.state(), [vec![first]]);
+
+        let second = network.step(vec![0.0])[0];
+        assert_relative_eq!(second, 0.5 * (0.5 * first).tanh() + 0.5 * first);
+    }
+
+    #[test]
+    fn test_remembers_only_when_stepping() {
+        let mut network = half_open();
+        network.step(vec![1.0]);
+        let state = network.state().to_vec();
+
+        let remembered = network.propagate(vec![0.0]);
+        assert_eq!(network.state(), state);
+        assert_ne!(remembered, half_open().propagate(vec![0.0]));
+
+        network.reset_state();
+        assert_eq!(network.propagate(vec![0.0]), half_open().propagate(vec![0.0]));
+    }
+
+    #[test]
+    fn test_genes() {
+        let topology = [LayerTopology::new(2), LayerTopology::gru(3), LayerTopology::new(1)];
+        let network = Network::random(&mut rand_chacha::ChaCha8Rng::seed_from_u64(0), &topology);
+
+        //9 rows of a bias, 2 inputs and 3 outputs, then the dense output neuron
+        assert_eq!(network.data_len(), 9 * 6 + 4);
+        assert_eq!(network.segments().count(), 10);
+        assert_eq!(network.topology(), topology);
+        assert_eq!(Network::from_data(&topology, network.data()).propagate(vec![0.5, -0.5]), network.propagate(vec![0.5, -0.5]));
+    }
+
+    #[test]
+    fn test_pool_forgets() {
+        let mut pool = NetworkPool::new();
+        let mut network = half_open();
+        network.step(vec![1.0]);
+        pool.recycle(network);
+
+        let network = pool.from_data(&[LayerTopology::new(1), LayerTopology::gru(1)], half_open().data());
+        assert_eq!(network.state(), [vec![0.0]]);
+    }
+}
@@ -0,0 +1,448 @@
+use crate::*;
+
+//imports dense layers trained elsewhere, every layer is a kernel with a row per input and a
+//column per output, as keras and numpy store them, and a bias per output
+
+//weights that could not be turned into a network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportError {
+    //the file is not what its format says, with what was wrong
+    Malformed(String),
+    //valid, but in a variant that is not read, e.g. a compressed npz
+    Unsupported(String),
+    //the kernels and biases do not chain into a feed forward network
+    Topology(String),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(reason) => write!(f, "malformed weights: {reason}"),
+            Self::Unsupported(reason) => write!(f, "unsupported weights: {reason}"),
+            Self::Topology(reason) => write!(f, "weights do not form a network: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+fn malformed(reason: impl Into<String>) -> ImportError {
+    ImportError::Malformed(reason.into())
+}
+
+//a numpy array or json list flattened in row-major order
+struct Array {
+    shape: Vec<usize>,
+    values: Vec<f32>,
+}
+
+impl Network {
+    //the arrays of an npz written by `np.savez(file, *model.get_weights())`, kernel and bias of
+    //every layer in turns
+    pub fn from_npz(bytes: &[u8]) -> Result<Self, ImportError> {
+        let arrays = zip_entries(bytes)?
+            .into_iter()
+            .map(|(name, data)| {
+                parse_npy(data).map_err(|e| match e {
+                    ImportError::Malformed(reason) => malformed(format!("{name}: {reason}")),
+                    e => e,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        from_arrays(arrays)
+    }
+
+    //`[{"kernel": [[..]], "bias": [..]}, ..]` or the `[[kernel, bias], ..]` of
+    //`json.dumps([[w.tolist() for w in layer.get_weights()] for layer in model.layers])`,
+    //layers without weights are skipped
+    pub fn from_json(json: &str) -> Result<Self, ImportError> {
+        let mut parser = JsonParser { text: json.as_bytes(), position: 0 };
+        let value = parser.value()?;
+        parser.whitespace();
+        if parser.position != parser.text.len() {
+            return Err(malformed("trailing characters after the json"));
+        }
+
+        let JsonValue::Array(layers) = value else {
+            return Err(malformed("expected a list of layers"));
+        };
+        let mut arrays = Vec::new();
+        for layer in layers {
+            match layer {
+                JsonValue::Array(weights) if weights.is_empty() => {}
+                JsonValue::Array(weights) => {
+                    for weights in weights {
+                        arrays.push(weights.array()?);
+                    }
+                }
+                JsonValue::Object(fields) => {
+                    for key in ["kernel", "bias"] {
+                        let (_, value) = fields
+                            .iter()
+                            .find(|(name, _)| name == key)
+                            .ok_or_else(|| malformed(format!("a layer has no {key}")))?;
+                        arrays.push(value.array()?);
+                    }
+                }
+                _ => return Err(malformed("expected a layer to be a list or an object")),
+            }
+        }
+        from_arrays(arrays)
+    }
+}
+
+fn from_arrays(arrays: Vec<Array>) -> Result<Network, ImportError> {
+    if arrays.is_empty() || !arrays.len().is_multiple_of(2) {
+        return Err(ImportError::Topology(format!("expected a kernel and a bias per layer, got {} arrays", arrays.len())));
+    }
+
+    let mut topology = Vec::new();
+    let mut genes = Vec::new();
+    for (layer, pair) in arrays.chunks_exact(2).enumerate() {
+        let (kernel, bias) = (&pair[0], &pair[1]);
+        let &[inputs, outputs] = kernel.shape.as_slice() else {
+            return Err(ImportError::Topology(format!("the kernel of layer {layer} has shape {:?}", kernel.shape)));
+        };
+        if bias.shape != [outputs] {
+            return Err(ImportError::Topology(format!("the bias of layer {layer} has shape {:?}, expected [{outputs}]", bias.shape)));
+        }
+        match topology.last() {
+            None => topology.push(LayerTopology::new(inputs)),
+            Some(previous) if previous.neurons == inputs => {}
+            Some(previous) => {
+                return Err(ImportError::Topology(format!("layer {layer} takes {inputs} inputs, the one before has {} outputs", previous.neurons)));
+            }
+        }
+        if inputs == 0 || outputs == 0 {
+            return Err(ImportError::Topology(format!("layer {layer} is empty")));
+        }
+        topology.push(LayerTopology::new(outputs));
+
+        for output in 0..outputs {
+            genes.push(bias.values[output]);
+            genes.extend((0..inputs).map(|input| kernel.values[input * outputs + output]));
+        }
+    }
+
+    Ok(Network::from_data(&topology, genes))
+}
+
+//the stored entries of a zip archive in order, numpy writes them uncompressed with zip64 sizes
+fn zip_entries(mut bytes: &[u8]) -> Result<Vec<(String, &[u8])>, ImportError> {
+    const LOCAL_HEADER: u32 = 0x04034b50;
+
+    fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], ImportError> {
+        if bytes.len() < len {
+            return Err(malformed("truncated zip archive"));
+        }
+        let (head, tail) = bytes.split_at(len);
+        *bytes = tail;
+        Ok(head)
+    }
+    let u16_at = |bytes: &[u8], at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]);
+    let u32_at = |bytes: &[u8], at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().expect("4 bytes"));
+
+    let mut entries = Vec::new();
+    while bytes.len() >= 4 && u32_at(bytes, 0) == LOCAL_HEADER {
+        let header = take(&mut bytes, 30)?;
+        let (flags, method) = (u16_at(header, 6), u16_at(header, 8));
+        let mut size = u32_at(header, 18) as u64;
+        let name = take(&mut bytes, u16_at(header, 26) as usize)?;
+        let mut extra = take(&mut bytes, u16_at(header, 28) as usize)?;
+
+        let name = String::from_utf8_lossy(name).into_owned();
+        if method != 0 {
+            return Err(ImportError::Unsupported(format!("{name} is compressed, save it with np.savez instead of np.savez_compressed")));
+        }
+        if flags & 0x8 != 0 {
+            return Err(ImportError::Unsupported(format!("{name} was streamed without its size")));
+        }
+        //the zip64 extra field holds the real size if the header has none
+        while extra.len() >= 4 {
+            let (id, len) = (u16_at(extra, 0), u16_at(extra, 2) as usize);
+            let field = &take(&mut extra, 4 + len)?[4..];
+            if id == 1 && size == u32::MAX as u64 && field.len() >= 8 {
+                size = u64::from_le_bytes(field[..8].try_into().expect("8 bytes"));
+            }
+        }
+
+        let data = take(&mut bytes, size as usize)?;
+        entries.push((name, data));
+    }
+
+    if entries.is_empty() {
+        return Err(malformed("not a zip archive"));
+    }
+    Ok(entries)
+}
+
+//a little endian float array in the npy format
+fn parse_npy(bytes: &[u8]) -> Result<Array, ImportError> {
+    const MAGIC: &[u8] = b"\x93NUMPY";
+
+    if bytes.len() < 10 || !bytes.starts_with(MAGIC) {
+        return Err(malformed("not an npy array"));
+    }
+    let (header_len, start) = match bytes[6] {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        2 | 3 if bytes.len() >= 12 => (u32::from_le_bytes(bytes[8..12].try_into().expect("4 bytes")) as usize, 12),
+        version => return Err(ImportError::Unsupported(format!("npy version {version}"))),
+    };
+    let header = bytes
+        .get(start..start + header_len)
+        .ok_or_else(|| malformed("truncated npy header"))
+        .map(String::from_utf8_lossy)?;
+    let data = &bytes[start + header_len..];
+
+    let field = |key: &str| -> Result<&str, ImportError> {
+        let at = header.find(&format!("'{key}':")).ok_or_else(|| malformed(format!("npy header without {key}")))?;
+        Ok(header[at + key.len() + 3..].trim_start())
+    };
+    let descr = field("descr")?;
+    let width = if descr.starts_with("'<f4'") {
+        4
+    } else if descr.starts_with("'<f8'") {
+        8
+    } else {
+        return Err(ImportError::Unsupported(format!("npy arrays of {}", descr.split(',').next().unwrap_or(descr))));
+    };
+    if field("fortran_order")?.starts_with("True") {
+        return Err(ImportError::Unsupported("fortran ordered npy arrays".into()));
+    }
+    let shape = field("shape")?;
+    let shape: Vec<usize> = shape
+        .strip_prefix('(')
+        .and_then(|shape| shape.split(')').next())
+        .ok_or_else(|| malformed("npy shape is not a tuple"))?
+        .split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .map(|dim| dim.parse().map_err(|_| malformed(format!("npy dimension '{dim}'"))))
+        .collect::<Result<_, _>>()?;
+
+    let len: usize = shape.iter().product();
+    if data.len() < len * width {
+        return Err(malformed("truncated npy data"));
+    }
+    let values = data[..len * width]
+        .chunks_exact(width)
+        .map(|bytes| match width {
+            4 => f32::from_le_bytes(bytes.try_into().expect("4 bytes")),
+            _ => f64::from_le_bytes(bytes.try_into().expect("8 bytes")) as f32,
+        })
+        .collect();
+    Ok(Array { shape, values })
+}
+
+//just enough json for lists of numbers in objects
+enum JsonValue {
+    Number(f64),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+    //strings, booleans and null, not part of weights
+    Other,
+}
+
+impl JsonValue {
+    //a nested list of numbers with the same length on every level
+    fn array(&self) -> Result<Array, ImportError> {
+        match self {
+            Self::Number(value) => Ok(Array { shape: Vec::new(), values: vec![*value as f32] }),
+            Self::Array(items) => {
+                let items = items.iter().map(Self::array).collect::<Result<Vec<_>, _>>()?;
+                let inner = items.first().map_or_else(Vec::new, |item| item.shape.clone());
+                if items.iter().any(|item| item.shape != inner) {
+                    return Err(malformed("ragged list of weights"));
+                }
+
+                let mut shape = vec![items.len()];
+                shape.extend(inner);
+                Ok(Array { shape, values: items.into_iter().flat_map(|item| item.values).collect() })
+            }
+            _ => Err(malformed("expected a list of numbers")),
+        }
+    }
+}
+
+struct JsonParser<'a> {
+    text: &'a [u8],
+    position: usize,
+}
+
+impl JsonParser<'_> {
+    fn whitespace(&mut self) {
+        while self.text.get(self.position).is_some_and(u8::is_ascii_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.whitespace();
+        self.text.get(self.position).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), ImportError> {
+        if self.peek() != Some(byte) {
+            return Err(malformed(format!("expected '{}' at byte {} of the json", byte as char, self.position)));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<JsonValue, ImportError> {
+        match self.peek() {
+            Some(b'[') => {
+                self.position += 1;
+                let mut items = Vec::new();
+                if self.peek() == Some(b']') {
+                    self.position += 1;
+                    return Ok(JsonValue::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    if self.peek() == Some(b',') {
+                        self.position += 1;
+                    } else {
+                        self.expect(b']')?;
+                        return Ok(JsonValue::Array(items));
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.position += 1;
+                let mut fields = Vec::new();
+                if self.peek() == Some(b'}') {
+                    self.position += 1;
+                    return Ok(JsonValue::Object(fields));
+                }
+                loop {
+                    let name = self.string()?;
+                    self.expect(b':')?;
+                    fields.push((name, self.value()?));
+                    if self.peek() == Some(b',') {
+                        self.position += 1;
+                    } else {
+                        self.expect(b'}')?;
+                        return Ok(JsonValue::Object(fields));
+                    }
+                }
+            }
+            Some(b'"') => self.string().map(|_| JsonValue::Other),
+            Some(_) => {
+                let start = self.position;
+                while self.text.get(self.position).is_some_and(|byte| !b",]}".contains(byte) && !byte.is_ascii_whitespace()) {
+                    self.position += 1;
+                }
+                let token = std::str::from_utf8(&self.text[start..self.position]).map_err(|_| malformed("invalid utf-8 in the json"))?;
+                match token {
+                    "true" | "false" | "null" => Ok(JsonValue::Other),
+                    //python's json writes these for floats that are not finite
+                    "NaN" => Ok(JsonValue::Number(f64::NAN)),
+                    "Infinity" => Ok(JsonValue::Number(f64::INFINITY)),
+                    "-Infinity" => Ok(JsonValue::Number(f64::NEG_INFINITY)),
+                    _ => token.parse().map(JsonValue::Number).map_err(|_| malformed(format!("unexpected '{token}' in the json"))),
+                }
+            }
+            None => Err(malformed("unexpected end of the json")),
+        }
+    }
+
+    //keys and strings, escapes are kept as they are
+    fn string(&mut self) -> Result<String, ImportError> {
+        self.expect(b'"')?;
+        let start = self.position;
+        while let Some(&byte) = self.text.get(self.position) {
+            match byte {
+                b'"' => {
+                    let string = String::from_utf8_lossy(&self.text[start..self.position]).into_owned();
+                    self.position += 1;
+                    return Ok(string);
+                }
+                b'\\' => self.position += 2,
+                _ => self.position += 1,
+            }
+        }
+        Err(malformed("unterminated string in the json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //a 2-3-1 network whose kernels are numbered through
+    const KERNEL_0: [f32; 6] = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+    const BIAS_0: [f32; 3] = [0.1, 0.2, 0.3];
+    const KERNEL_1: [f32; 3] = [7.0, 8.0, 9.0];
+    const BIAS_1: [f32; 1] = [0.4];
+    const EXPECTED: [f32; 13] = [0.1, 1.0, 4.0, 0.2, 2.0, 5.0, 0.3, 3.0, 6.0, 0.4, 7.0, 8.0, 9.0];
+
+    fn npy(shape: &str, values: &[f32]) -> Vec<u8> {
+        let mut header = format!("{{'descr': '<f4', 'fortran_order': False, 'shape': {shape}, }}");
+        while (10 + header.len() + 1) % 64 != 0 {
+            header.push(' ');
+        }
+        header.push('\n');
+
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        bytes.extend(values.iter().flat_map(|value| value.to_le_bytes()));
+        bytes
+    }
+
+    //stored entries with their size in a zip64 extra field, like np.savez writes them
+    fn npz(arrays: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for (i, array) in arrays.iter().enumerate() {
+            let name = format!("arr_{i}.npy");
+            bytes.extend(0x04034b50u32.to_le_bytes());
+            bytes.extend([20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            bytes.extend(u32::MAX.to_le_bytes());
+            bytes.extend(u32::MAX.to_le_bytes());
+            bytes.extend((name.len() as u16).to_le_bytes());
+            bytes.extend(20u16.to_le_bytes());
+            bytes.extend(name.as_bytes());
+            bytes.extend([1, 0, 16, 0]);
+            bytes.extend((array.len() as u64).to_le_bytes());
+            bytes.extend((array.len() as u64).to_le_bytes());
+            bytes.extend(array);
+        }
+        //the central directory, never read
+        bytes.extend(0x02014b50u32.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_from_npz() {
+        let bytes = npz(&[npy("(2, 3)", &KERNEL_0), npy("(3,)", &BIAS_0), npy("(3, 1)", &KERNEL_1), npy("(1,)", &BIAS_1)]);
+        let network = Network::from_npz(&bytes).unwrap();
+
+        assert_eq!(network.genes(), EXPECTED);
+        assert_eq!(network.propagate(vec![1.0, 0.0]).len(), 1);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let lists = "[[[[1, 2, 3], [4, 5, 6]], [0.1, 0.2, 0.3]], [], [[[7], [8], [9]], [0.4]]]";
+        let objects = r#"[{"name": "dense", "kernel": [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]], "bias": [0.1, 0.2, 0.3]},
+                          {"kernel": [[7e0], [8], [9]], "bias": [4e-1], "trainable": true}]"#;
+
+        assert_eq!(Network::from_json(lists).unwrap().genes(), EXPECTED);
+        assert_eq!(Network::from_json(objects).unwrap().genes(), EXPECTED);
+    }
+
+    #[test]
+    fn test_mismatched_layers() {
+        let json = "[[[[1, 2], [3, 4]], [0, 0]], [[[1], [2], [3]], [0]]]";
+        assert!(matches!(Network::from_json(json), Err(ImportError::Topology(_))));
+        assert!(matches!(Network::from_json("[[[[1, 2], [3]], [0, 0]]]"), Err(ImportError::Malformed(_))));
+    }
+
+    #[test]
+    fn test_compressed_npz() {
+        let mut bytes = npz(&[npy("(1, 1)", &[1.0]), npy("(1,)", &[0.0])]);
+        bytes[8] = 8;
+        assert!(matches!(Network::from_npz(&bytes), Err(ImportError::Unsupported(_))));
+    }
+}
@@ -1,129 +1,409 @@
 extern crate core;
 
-use std::iter::once;
+use std::fmt;
+use std::ops::Range;
 use rand::prelude::*;
 
+pub use self::{activation::*, activity::*, diff::*, genome::*, graph::*, keras::*, onnx::*, softmax::*};
+#[cfg(feature = "gpu")]
+pub use self::gpu::*;
+
+mod activation;
+mod activity;
+mod clock;
+mod diff;
+mod genome;
+mod graph;
+mod gru;
+mod keras;
+mod onnx;
+#[cfg(feature = "serde")]
+mod serialization;
+mod softmax;
+mod weights;
+#[cfg(feature = "gpu")]
+mod gpu;
+
+//all the genes live in one contiguous buffer, in the order data() yields them, so they can be
+//borrowed as a slice without copying
 pub struct Network {
     layers: Vec<Layer>,
+    genes: Vec<f32>,
+    //sin/cos pairs fed into the last inputs of the first layer, see with_clocks()
+    clocks: usize,
+    //the last outputs of every gru layer, empty for the dense ones, see step()
+    state: Vec<Vec<f32>>,
 }
 
+//a fully connected layer, its genes are a row-major matrix with one row per output neuron,
+//holding the neuron's bias followed by its `inputs` weights, see gru.rs for the rows of a
+//gru layer
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Layer {
-    neurons: Vec<Neuron>,
+    inputs: usize,
+    outputs: usize,
+    activation: Activation,
+    kind: LayerKind,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-struct Neuron {
-    bias: f32,
-    weights: Vec<f32>,
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum LayerKind {
+    #[default]
+    Dense,
+    //a gated recurrent unit, it sees its own last outputs next to the inputs and keeps them
+    //from one step() to the next
+    Gru,
 }
 
+//the activation of the first layer is not used, its neurons are the inputs, neither is that of
+//a gru layer, its gates have a sigmoid and its candidates a tanh
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LayerTopology {
     pub neurons: usize,
+    pub activation: Activation,
+    pub kind: LayerKind,
+}
+
+impl LayerTopology {
+    //neurons with a relu
+    pub fn new(neurons: usize) -> Self {
+        Self { neurons, activation: Activation::Relu, kind: LayerKind::Dense }
+    }
+
+    pub fn gru(neurons: usize) -> Self {
+        Self { kind: LayerKind::Gru, ..Self::new(neurons) }
+    }
+
+    pub fn with_activation(self, activation: Activation) -> Self {
+        Self { activation, ..self }
+    }
+}
+
+//the data handed to a network does not fit its topology
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataLengthError {
+    pub expected: usize,
+    pub actual: usize,
 }
 
+impl fmt::Display for DataLengthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {} values for the network but got {}", self.expected, self.actual)
+    }
+}
+
+impl std::error::Error for DataLengthError {}
+
 impl Network {
+    //the outputs for `inputs`, the gru layers start from what they remember without changing it
     pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
         self.layers
             .iter()
-            .fold(inputs, |inputs, layer| layer.propagate(inputs))
+            .zip(self.layer_genes())
+            .zip(&self.state)
+            .fold(inputs, |inputs, ((layer, genes), state)| layer.propagate(genes, inputs, state))
+    }
+
+    //like propagate(), but the gru layers remember their outputs for the next step, the same
+    //as propagate() for a network without them
+    pub fn step(&mut self, inputs: Vec<f32>) -> Vec<f32> {
+        let mut genes = self.genes.as_slice();
+        let mut outputs = inputs;
+        for (layer, state) in self.layers.iter().zip(&mut self.state) {
+            let (head, tail) = genes.split_at(layer.len());
+            genes = tail;
+            outputs = layer.propagate(head, outputs, state);
+            if layer.kind == LayerKind::Gru {
+                state.clone_from(&outputs);
+            }
+        }
+        outputs
+    }
+
+    //forgets what the gru layers remember, like a new network of the same genes
+    pub fn reset_state(&mut self) {
+        for state in &mut self.state {
+            state.fill(0.0);
+        }
+    }
+
+    //what the gru layers remember, layer by layer, empty for the dense ones
+    pub fn state(&self) -> &[Vec<f32>] {
+        &self.state
     }
 
     pub fn random(rng: &mut dyn RngCore, layers: &[LayerTopology]) -> Self {
+        Self::from_data(layers, std::iter::repeat_with(|| rng.gen_range(-1.0..=1.0)).take(Self::len_of(layers)))
+    }
+
+    pub fn from_data(layers: &[LayerTopology], data: impl IntoIterator<Item = f32>) -> Self {
         assert!(layers.len() > 1); //needs to have more than 1 layer
 
-        let layers = layers
+        let layers: Vec<_> = layers
             .windows(2)
-            .map(|layers| Layer::random(rng, layers[0].neurons, layers[1].neurons))
+            .map(|layers| Layer::new(layers[0].neurons, &layers[1]))
             .collect();
 
-        Self { layers }
+        let mut data = data.into_iter();
+        let len = layers.iter().map(Layer::len).sum();
+        let genes: Vec<_> = data.by_ref().take(len).collect();
+
+        if genes.len() < len {
+            panic!("out of data");
+        }
+        if data.next().is_some() {
+            panic!("something is wrong...");
+        }
+
+        let state = layers.iter().map(Layer::initial_state).collect();
+        Self { layers, genes, clocks: 0, state }
     }
 
-    pub fn from_data(layers: &[LayerTopology], data: impl IntoIterator<Item = f32>) -> Self {
-        assert!(layers.len() > 1); //needs to have more than 1 layer
+    //the hidden layers keep their activations, only the output layer uses `activation`, the
+    //same as giving it to the last layer of the topology
+    pub fn with_output_activation(mut self, activation: Activation) -> Self {
+        self.layers.last_mut().expect("got an empty network").activation = activation;
+        self
+    }
+
+    pub fn output_activation(&self) -> &Activation {
+        &self.layers.last().expect("got an empty network").activation
+    }
+
+    //overwrites the weights in place, data has to be laid out like from_data() expects, the
+    //network is left untouched if the length does not match
+    pub fn load_data<I>(&mut self, data: I) -> Result<(), DataLengthError>
+    where
+        I: IntoIterator<Item = f32>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let data = data.into_iter();
+        let expected = self.data_len();
+        if data.len() != expected {
+            return Err(DataLengthError { expected, actual: data.len() });
+        }
 
+        self.fill(data);
+        Ok(())
+    }
+
+    fn fill(&mut self, data: impl IntoIterator<Item = f32>) {
         let mut data = data.into_iter();
 
-        let layers = layers
-            .windows(2)
-            .map(|layers| Layer::from_data(layers[0].neurons, layers[1].neurons, &mut data))
-            .collect();
+        for gene in &mut self.genes {
+            *gene = data.next().expect("out of data");
+        }
 
         if data.next().is_some() {
             panic!("something is wrong...");
         }
+    }
+
+    //the number of values data() yields and load_data() expects
+    pub fn data_len(&self) -> usize {
+        self.genes.len()
+    }
+
+    fn len_of(layers: &[LayerTopology]) -> usize {
+        layers.windows(2).map(|layers| Layer::new(layers[0].neurons, &layers[1]).len()).sum()
+    }
+
+    fn has_topology(&self, layers: &[LayerTopology]) -> bool {
+        self.layers.len() + 1 == layers.len()
+            && self.layers.iter().zip(layers.windows(2)).all(|(layer, layers)| {
+                layer.inputs == layers[0].neurons
+                    && layer.outputs == layers[1].neurons
+                    && layer.activation == layers[1].activation
+                    && layer.kind == layers[1].kind
+            })
+    }
+
+    //what from_data() needs to build the network again from data(), the activations and the
+    //kinds of the layers included
+    pub fn topology(&self) -> Vec<LayerTopology> {
+        std::iter::once(LayerTopology::new(self.layers[0].inputs))
+            .chain(self.layers.iter().map(|layer| LayerTopology {
+                neurons: layer.outputs,
+                activation: layer.activation.clone(),
+                kind: layer.kind,
+            }))
+            .collect()
+    }
+
+    //a copy with every weight smaller than `threshold` in magnitude set to 0.0, the biases are
+    //kept, and the number of connections with a weight left
+    pub fn pruned(&self, threshold: f32) -> (Self, usize) {
+        let mut genes = self.genes.clone();
+        let mut connections = 0;
+        for segment in self.segments() {
+            //the first gene of a segment is the bias
+            for weight in &mut genes[segment.start + 1..segment.end] {
+                if weight.abs() < threshold {
+                    *weight = 0.0;
+                } else {
+                    connections += 1;
+                }
+            }
+        }
 
-        Self { layers }
+        (Self { layers: self.layers.clone(), genes, clocks: self.clocks, state: self.state.clone() }, connections)
     }
 
+    //every neuron's bias followed by its weights
     pub fn data(&self) -> impl Iterator<Item = f32> + '_ {
-        self.layers.iter()
-            .flat_map(|layer| layer.neurons.iter())
-            .flat_map(|neuron| once(&neuron.bias).chain(&neuron.weights))
-            .cloned()
+        self.genes.iter().copied()
+    }
+
+    //the same values as data(), borrowed straight from the network
+    pub fn genes(&self) -> &[f32] {
+        &self.genes
     }
 
+    //lets an optimizer write the next generation's genes without a round trip through data()
+    //and load_data()
+    pub fn genes_mut(&mut self) -> &mut [f32] {
+        &mut self.genes
+    }
 
+    //the genes of each neuron (its bias followed by its weights) as ranges into data(),
+    //so a crossover can keep them together, a gru neuron has a segment per gate
+    pub fn segments(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.layers.iter()
+            .flat_map(|layer| (0..layer.rows()).map(|_| layer.row_len()))
+            .scan(0, |start, len| {
+                let segment = *start..*start + len;
+                *start = segment.end;
+                Some(segment)
+            })
+    }
+
+    fn layer_genes(&self) -> impl Iterator<Item = &[f32]> {
+        self.layers.iter().scan(self.genes.as_slice(), |genes, layer| {
+            let (head, tail) = genes.split_at(layer.len());
+            *genes = tail;
+            Some(head)
+        })
+    }
 }
 
 impl Layer {
-    fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        assert_eq!(inputs.len(), self.neurons[0].weights.len());
+    fn new(inputs: usize, topology: &LayerTopology) -> Self {
+        let layer = Self { inputs, outputs: topology.neurons, activation: topology.activation.clone(), kind: topology.kind };
+        assert_eq!(layer.check(), Ok(()));
 
-        self.neurons
-            .iter()
-            .map(|neuron| neuron.propagate(&inputs))
-            .collect()
+        layer
     }
 
-    fn random(rng: &mut dyn RngCore, input_neurons: usize, output_neurons: usize) -> Self {
-        let neurons = (0..output_neurons)
-            .map(|_| Neuron::random(rng, input_neurons))
-            .collect();
+    //what every layer needs, those of a loaded network are checked the same way
+    fn check(&self) -> Result<(), &'static str> {
+        if self.inputs == 0 || self.outputs == 0 {
+            return Err("every layer needs at least one input and one output");
+        }
+        Ok(())
+    }
 
-        Self { neurons }
+    //the number of genes of the layer
+    fn len(&self) -> usize {
+        self.rows() * self.row_len()
     }
 
-    fn from_data(input_neurons: usize, output_neurons: usize, data: &mut dyn Iterator<Item = f32>) -> Self {
-        let neurons = (0..output_neurons)
-            .map(|_| Neuron::from_data(input_neurons, data))
-            .collect();
+    //rows of genes, one per neuron, or one per gate of a neuron
+    fn rows(&self) -> usize {
+        match self.kind {
+            LayerKind::Dense => self.outputs,
+            LayerKind::Gru => 3 * self.outputs,
+        }
+    }
 
-        Self { neurons }
+    //the genes of a row: the bias, the weights of the inputs and those of the last outputs
+    fn row_len(&self) -> usize {
+        match self.kind {
+            LayerKind::Dense => 1 + self.inputs,
+            LayerKind::Gru => 1 + self.inputs + self.outputs,
+        }
+    }
 
+    fn initial_state(&self) -> Vec<f32> {
+        match self.kind {
+            LayerKind::Dense => Vec::new(),
+            LayerKind::Gru => vec![0.0; self.outputs],
+        }
     }
 
-}
+    //`state` are the last outputs of a gru layer
+    fn propagate(&self, genes: &[f32], inputs: Vec<f32>, state: &[f32]) -> Vec<f32> {
+        assert_eq!(inputs.len(), self.inputs);
 
-impl Neuron {
-    fn propagate(&self, inputs: &[f32]) -> f32 {
-        assert_eq!(inputs.len(), self.weights.len());
+        if self.kind == LayerKind::Gru {
+            return self.propagate_gru(genes, &inputs, state);
+        }
 
-        let output = inputs
-            .iter()
-            .zip(&self.weights) //zip inputs and weights
-            .map(|(input, weight)| input * weight) //calculate weighted inputs
-            .sum::<f32>(); //sum up weighted inputs
+        let mut outputs = self.weigh(genes, &inputs);
+        for output in &mut outputs {
+            *output = self.activation.apply(*output);
+        }
+        outputs
+    }
 
-        (self.bias + output).max(0.0) //ReLu
+    //the bias plus the weighted inputs of every neuron of a dense layer, before the activation
+    fn weigh(&self, genes: &[f32], inputs: &[f32]) -> Vec<f32> {
+        assert_eq!(inputs.len(), self.inputs);
+
+        genes
+            .chunks_exact(1 + self.inputs)
+            .map(|row| {
+                let (bias, weights) = row.split_first().expect("empty row");
+                let output = inputs
+                    .iter()
+                    .zip(weights) //zip inputs and weights
+                    .map(|(input, weight)| input * weight) //calculate weighted inputs
+                    .sum::<f32>(); //sum up weighted inputs
+
+                bias + output
+            })
+            .collect()
     }
+}
 
-    fn random(rng: &mut dyn RngCore, output_size: usize) -> Self {
-        let bias = rng.gen_range(-1.0..=1.0);
-        let weights = (0..output_size)
-            .map(|_| rng.gen_range(-1.0..=1.0))
-            .collect();
+//keeps retired networks around, so the next generation can reuse their allocations
+//instead of allocating every network from scratch
+#[derive(Default)]
+pub struct NetworkPool {
+    networks: Vec<Network>,
+}
 
-        Self { bias, weights }
+impl NetworkPool {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    fn from_data(output_size: usize, data: &mut dyn Iterator<Item = f32>) -> Self {
-        let bias = data.next().expect("out of data");
-        let weights = (0..output_size)
-            .map(|_| data.next().expect("out of data"))
-            .collect();
+    pub fn len(&self) -> usize {
+        self.networks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.networks.is_empty()
+    }
+
+    pub fn recycle(&mut self, network: Network) {
+        self.networks.push(network);
+    }
 
-        Self { bias, weights }
+    //like Network::from_data, but reuses a recycled network of the same topology if there is one,
+    //those of other topologies stay for later
+    pub fn from_data(&mut self, layers: &[LayerTopology], data: impl IntoIterator<Item = f32>) -> Network {
+        let Some(index) = self.networks.iter().position(|network| network.has_topology(layers)) else {
+            return Network::from_data(layers, data);
+        };
+
+        let mut network = self.networks.swap_remove(index);
+        network.fill(data);
+        network.clocks = 0;
+        network.reset_state();
+        network
     }
 }
 
@@ -139,14 +419,21 @@ mod tests {
         #[test]
         fn test_neuron() {
             let mut rng = ChaCha8Rng::from_seed(Default::default());
-            let neuron_a = Neuron::random(&mut rng, 4);
+            let network = Network::random(&mut rng, &[LayerTopology::new(4), LayerTopology::new(1)]);
 
-            assert_relative_eq!(neuron_a.bias, -0.6255188);
             assert_relative_eq!(
-                neuron_a.weights.as_slice(),
-                [0.67383957, 0.8181262, 0.26284897, 0.5238807].as_ref()
+                network.genes(),
+                [-0.6255188, 0.67383957, 0.8181262, 0.26284897, 0.5238807].as_ref()
             );
         }
+
+        #[test]
+        fn test_layer_is_row_major() {
+            let network = Network::from_data(&[LayerTopology::new(2), LayerTopology::new(3)], (0..9).map(|n| n as f32));
+            let rows: Vec<_> = network.layer_genes().flat_map(|genes| genes.chunks_exact(3)).collect();
+
+            assert_eq!(rows, vec![[0.0, 1.0, 2.0], [3.0, 4.0, 5.0], [6.0, 7.0, 8.0]]);
+        }
     }
 
     mod propagate {
@@ -154,15 +441,15 @@ mod tests {
 
         #[test]
         fn test_neuron() {
-            let neuron = Neuron {
-                bias: 0.5,
-                weights: vec![-0.3, 0.8],
-            };
+            let network = Network::from_data(
+                &[LayerTopology::new(2), LayerTopology::new(1)],
+                vec![0.5, -0.3, 0.8],
+            );
 
-            assert_relative_eq!(neuron.propagate(&[-10.0, -10.0]), 0.0,);
+            assert_relative_eq!(network.propagate(vec![-10.0, -10.0])[0], 0.0,);
 
             assert_relative_eq!(
-                neuron.propagate(&[0.5, 1.0]),
+                network.propagate(vec![0.5, 1.0])[0],
                 (-0.3 * 0.5) + (0.8 * 1.0) + 0.5,
             );
         }
@@ -171,8 +458,8 @@ mod tests {
         fn test_layer_propagate() {
             let mut rng = ChaCha8Rng::from_seed(Default::default());
 
-            let layer = Layer::random(&mut rng, 5, 5);
-            let results = layer.propagate((0..5).map(|_| rng.gen_range(-1.0..=1.0)).collect());
+            let network = Network::random(&mut rng, &[LayerTopology::new(5), LayerTopology::new(5)]);
+            let results = network.propagate((0..5).map(|_| rng.gen_range(-1.0..=1.0)).collect());
 
             assert_relative_eq!(
                 results.as_slice(),
@@ -187,9 +474,9 @@ mod tests {
             let network = Network::random(
                 &mut rng,
                 &[
-                    LayerTopology { neurons: 8 },
-                    LayerTopology { neurons: 4 },
-                    LayerTopology { neurons: 3 },
+                    LayerTopology::new(8),
+                    LayerTopology::new(4),
+                    LayerTopology::new(3),
                 ],
             );
 
@@ -200,8 +487,8 @@ mod tests {
         #[test]
         fn test_dna_restore() {
             let topology = &[
-                LayerTopology { neurons: 3 },
-                LayerTopology { neurons: 2 },
+                LayerTopology::new(3),
+                LayerTopology::new(2),
             ];
             let weights = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.7];
 
@@ -213,4 +500,205 @@ mod tests {
             assert_relative_eq!(actual.as_slice(), weights.as_slice());
         }
     }
+
+    mod topology {
+        use super::*;
+
+        #[test]
+        fn test_round_trip() {
+            let sin = Activation::custom(f32::sin);
+            let topology = [LayerTopology::new(2), LayerTopology::new(2).with_activation(sin.clone()), LayerTopology::new(1)];
+            let network = Network::from_data(&topology, (0..9).map(|n| n as f32 / 10.0 - 0.5));
+
+            let restored = Network::from_data(&network.topology(), network.data());
+            let relu = Network::from_data(&[LayerTopology::new(2), LayerTopology::new(2), LayerTopology::new(1)], network.data());
+
+            assert_eq!(network.topology(), topology);
+            assert_relative_eq!(restored.propagate(vec![0.3, -0.7])[0], network.propagate(vec![0.3, -0.7])[0]);
+            assert_ne!(relu.propagate(vec![0.3, -0.7]), network.propagate(vec![0.3, -0.7]));
+        }
+
+        #[test]
+        fn test_pool_keeps_activations_apart() {
+            let topology = |activation| [LayerTopology::new(1), LayerTopology::new(1).with_activation(activation)];
+            let mut pool = NetworkPool::new();
+            pool.recycle(Network::from_data(&topology(Activation::custom(f32::sin)), vec![1.0; 2]));
+
+            let network = pool.from_data(&topology(Activation::Relu), vec![-1.0; 2]);
+
+            assert_eq!(pool.len(), 1);
+            assert_eq!(network.propagate(vec![1.0]), vec![0.0]);
+        }
+    }
+
+    mod segments {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let network = Network::from_data(
+                &[
+                    LayerTopology::new(3),
+                    LayerTopology::new(2),
+                    LayerTopology::new(1),
+                ],
+                (0..11).map(|n| n as f32),
+            );
+
+            let segments: Vec<_> = network.segments().collect();
+
+            assert_eq!(segments, vec![0..4, 4..8, 8..11]);
+            assert_eq!(segments.last().unwrap().end, network.data().count());
+        }
+    }
+
+    mod genes {
+        use super::*;
+
+        #[test]
+        fn test_writes_through() {
+            let topology = [LayerTopology::new(2), LayerTopology::new(1)];
+            let mut network = Network::from_data(&topology, vec![0.0; 3]);
+
+            network.genes_mut().copy_from_slice(&[0.5, -0.3, 0.8]);
+
+            assert_eq!(network.data().collect::<Vec<_>>(), vec![0.5, -0.3, 0.8]);
+            assert_relative_eq!(network.propagate(vec![0.5, 1.0])[0], (-0.3 * 0.5) + (0.8 * 1.0) + 0.5);
+        }
+    }
+
+    mod pruned {
+        use super::*;
+
+        #[test]
+        fn test_keeps_biases() {
+            let network = Network::from_data(
+                &[LayerTopology::new(2), LayerTopology::new(1), LayerTopology::new(1)],
+                vec![0.01, 0.5, -0.05, -0.02, 0.3],
+            );
+
+            let (pruned, connections) = network.pruned(0.1);
+
+            assert_eq!(pruned.genes(), &[0.01, 0.5, 0.0, -0.02, 0.3]);
+            assert_eq!(connections, 2);
+            assert_eq!(network.pruned(0.0).1, 3);
+        }
+    }
+
+    mod load_data {
+        use super::*;
+
+        fn topology() -> [LayerTopology; 3] {
+            [
+                LayerTopology::new(3),
+                LayerTopology::new(2),
+                LayerTopology::new(1),
+            ]
+        }
+
+        #[test]
+        fn test() {
+            let mut network = Network::from_data(&topology(), vec![0.0; 11]);
+            let genes = network.genes().as_ptr();
+
+            network.load_data((0..11).map(|n| n as f32)).unwrap();
+
+            assert_eq!(network.data().collect::<Vec<_>>(), (0..11).map(|n| n as f32).collect::<Vec<_>>());
+            assert_eq!(network.genes(), Network::from_data(&topology(), (0..11).map(|n| n as f32)).genes());
+            assert_eq!(network.genes().as_ptr(), genes);
+        }
+
+        #[test]
+        fn test_too_little_data() {
+            let mut network = Network::from_data(&topology(), vec![0.0; 11]);
+
+            assert_eq!(network.load_data(vec![1.0; 10]), Err(DataLengthError { expected: 11, actual: 10 }));
+            assert_eq!(network.data().collect::<Vec<_>>(), vec![0.0; 11]);
+        }
+
+        #[test]
+        fn test_too_much_data() {
+            let mut network = Network::from_data(&topology(), vec![0.0; 11]);
+
+            assert_eq!(network.load_data(vec![1.0; 12]), Err(DataLengthError { expected: 11, actual: 12 }));
+            assert_eq!(network.data().collect::<Vec<_>>(), vec![0.0; 11]);
+        }
+
+        #[test]
+        fn test_data_len() {
+            assert_eq!(Network::from_data(&topology(), vec![0.0; 11]).data_len(), 11);
+        }
+    }
+
+    mod output_activation {
+        use super::*;
+
+        #[test]
+        fn test_only_the_last_layer() {
+            //the hidden neuron passes -1 on as 0, the output neuron adds its bias of -2
+            let topology = [LayerTopology::new(1), LayerTopology::new(1), LayerTopology::new(1)];
+            let network = Network::from_data(&topology, vec![0.0, 1.0, -2.0, 1.0]).with_output_activation(Activation::Tanh);
+
+            assert_eq!(network.output_activation(), &Activation::Tanh);
+            assert_relative_eq!(network.propagate(vec![-1.0])[0], (-2.0f32).tanh());
+            assert_relative_eq!(network.propagate(vec![1.0])[0], (-1.0f32).tanh());
+        }
+
+        #[test]
+        fn test_is_part_of_the_topology() {
+            let topology = [LayerTopology::new(1), LayerTopology::new(1).with_activation(Activation::Tanh)];
+            let network = Network::from_data(&[LayerTopology::new(1), LayerTopology::new(1)], vec![0.0; 2]).with_output_activation(Activation::Tanh);
+
+            assert_eq!(network.topology(), topology);
+        }
+    }
+
+    mod pool {
+        use super::*;
+
+        #[test]
+        fn test_reuses_networks() {
+            let topology = [LayerTopology::new(2), LayerTopology::new(2)];
+            let mut pool = NetworkPool::new();
+
+            let network = pool.from_data(&topology, vec![1.0; 6]);
+            let genes = network.genes().as_ptr();
+            pool.recycle(network);
+            assert_eq!(pool.len(), 1);
+
+            let network = pool.from_data(&topology, vec![2.0; 6]);
+            assert!(pool.is_empty());
+            assert_eq!(network.genes().as_ptr(), genes);
+            assert_eq!(network.data().collect::<Vec<_>>(), vec![2.0; 6]);
+        }
+
+        #[test]
+        fn test_skips_other_topologies() {
+            let mut pool = NetworkPool::new();
+            pool.recycle(Network::from_data(&[LayerTopology::new(1), LayerTopology::new(1)], vec![1.0; 2]));
+
+            let topology = [LayerTopology::new(2), LayerTopology::new(1)];
+            let network = pool.from_data(&topology, vec![3.0; 3]);
+
+            assert!(network.has_topology(&topology));
+            assert_eq!(pool.len(), 1);
+        }
+
+        #[test]
+        fn test_finds_the_topology_behind_others() {
+            let (small, large) = ([LayerTopology::new(1), LayerTopology::new(1)], [LayerTopology::new(2), LayerTopology::new(2)]);
+            let mut pool = NetworkPool::new();
+            let recycled = Network::from_data(&large, vec![1.0; 6]);
+            let genes = recycled.genes().as_ptr();
+            pool.recycle(recycled);
+            pool.recycle(Network::from_data(&small, vec![1.0; 2]));
+            pool.recycle(Network::from_data(&small, vec![1.0; 2]));
+
+            let network = pool.from_data(&large, vec![2.0; 6]);
+            assert_eq!(network.genes().as_ptr(), genes);
+            assert_eq!(pool.len(), 2);
+            assert!(pool.from_data(&small, vec![3.0; 2]).has_topology(&small));
+            assert_eq!(pool.len(), 1);
+        }
+    }
 }
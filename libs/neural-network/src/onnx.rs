@@ -0,0 +1,241 @@
+use crate::*;
+
+//the onnx ir and operator set the export targets, Gemm, Relu and Tanh have not changed since
+const IR_VERSION: u64 = 8;
+const OPSET_VERSION: u64 = 13;
+//TensorProto.DataType.FLOAT and AttributeProto.AttributeType.INT
+const FLOAT: u64 = 1;
+const INT: u64 = 2;
+
+//what the export can not express, with the index of the layer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OnnxError {
+    //a custom activation without an onnx operator
+    Activation(usize),
+    //the export only knows dense layers
+    Recurrent(usize),
+}
+
+impl fmt::Display for OnnxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Activation(layer) => write!(f, "onnx has no operator for the activation of layer {layer}"),
+            Self::Recurrent(layer) => write!(f, "the onnx export only knows dense layers, layer {layer} is a gru"),
+        }
+    }
+}
+
+impl std::error::Error for OnnxError {}
+
+impl Network {
+    //a minimal onnx model of the network, a Gemm followed by the activation per layer, with an
+    //input named "input" and an output named "output" that both have a batch dimension
+    pub fn to_onnx(&self) -> Result<Vec<u8>, OnnxError> {
+        let mut graph = Message::default();
+        let mut input = "input".to_string();
+
+        for (i, (layer, genes)) in self.layers.iter().zip(self.layer_genes()).enumerate() {
+            if layer.kind == LayerKind::Gru {
+                return Err(OnnxError::Recurrent(i));
+            }
+            let (weights, bias): (Vec<f32>, Vec<f32>) = (
+                genes.chunks_exact(1 + layer.inputs).flat_map(|row| row[1..].iter().copied()).collect(),
+                genes.chunks_exact(1 + layer.inputs).map(|row| row[0]).collect(),
+            );
+            let (weights_name, bias_name) = (format!("layer{i}.weights"), format!("layer{i}.bias"));
+            graph.message(5, &tensor(&weights_name, &[layer.outputs, layer.inputs], &weights));
+            graph.message(5, &tensor(&bias_name, &[layer.outputs], &bias));
+
+            let op = layer.activation.onnx_op().ok_or(OnnxError::Activation(i))?;
+            let gemm = format!("layer{i}.gemm");
+            let output = if i + 1 == self.layers.len() { "output".to_string() } else { format!("layer{i}.{}", op.to_lowercase()) };
+
+            //the weights are stored with a row per output neuron, so B is transposed
+            let mut transposed = Message::default();
+            transposed.string(1, "transB");
+            transposed.varint(3, 1);
+            transposed.varint(20, INT);
+            graph.message(1, &node(&format!("layer{i}.Gemm"), "Gemm", &[&input, &weights_name, &bias_name], &gemm, Some(&transposed)));
+            graph.message(1, &node(&format!("layer{i}.{op}"), op, &[&gemm], &output, None));
+
+            input = output;
+        }
+
+        graph.string(2, "network");
+        graph.message(11, &value_info("input", self.layers[0].inputs));
+        graph.message(12, &value_info("output", self.layers[self.layers.len() - 1].outputs));
+
+        let mut opset = Message::default();
+        opset.string(1, "");
+        opset.varint(2, OPSET_VERSION);
+
+        let mut model = Message::default();
+        model.varint(1, IR_VERSION);
+        model.string(2, "rustism");
+        model.message(7, &graph);
+        model.message(8, &opset);
+        Ok(model.0)
+    }
+}
+
+fn tensor(name: &str, dims: &[usize], values: &[f32]) -> Message {
+    let mut tensor = Message::default();
+    for &dim in dims {
+        tensor.varint(1, dim as u64);
+    }
+    tensor.varint(2, FLOAT);
+    tensor.string(8, name);
+    tensor.bytes(9, &values.iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<_>>());
+    tensor
+}
+
+fn node(name: &str, op_type: &str, inputs: &[&str], output: &str, attribute: Option<&Message>) -> Message {
+    let mut node = Message::default();
+    for input in inputs {
+        node.string(1, input);
+    }
+    node.string(2, output);
+    node.string(3, name);
+    node.string(4, op_type);
+    if let Some(attribute) = attribute {
+        node.message(5, attribute);
+    }
+    node
+}
+
+//a float tensor of shape [batch, `size`]
+fn value_info(name: &str, size: usize) -> Message {
+    let mut batch = Message::default();
+    batch.string(2, "batch");
+    let mut features = Message::default();
+    features.varint(1, size as u64);
+    let mut shape = Message::default();
+    shape.message(1, &batch);
+    shape.message(1, &features);
+
+    let mut tensor_type = Message::default();
+    tensor_type.varint(1, FLOAT);
+    tensor_type.message(2, &shape);
+    let mut type_proto = Message::default();
+    type_proto.message(1, &tensor_type);
+
+    let mut value_info = Message::default();
+    value_info.string(1, name);
+    value_info.message(2, &type_proto);
+    value_info
+}
+
+//just enough of the protobuf wire format for the export
+#[derive(Default)]
+struct Message(Vec<u8>);
+
+impl Message {
+    fn raw_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+
+    fn varint(&mut self, field: u64, value: u64) {
+        self.raw_varint(field << 3);
+        self.raw_varint(value);
+    }
+
+    fn bytes(&mut self, field: u64, bytes: &[u8]) {
+        self.raw_varint(field << 3 | 2);
+        self.raw_varint(bytes.len() as u64);
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn string(&mut self, field: u64, value: &str) {
+        self.bytes(field, value.as_bytes());
+    }
+
+    fn message(&mut self, field: u64, message: &Message) {
+        self.bytes(field, &message.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //the fields of a message as (field, wire type, value or payload)
+    fn fields(mut bytes: &[u8]) -> Vec<(u64, u64, Vec<u8>)> {
+        fn varint(bytes: &mut &[u8]) -> u64 {
+            let mut value = 0;
+            for shift in (0..).step_by(7) {
+                let (byte, rest) = bytes.split_first().expect("truncated varint");
+                *bytes = rest;
+                value |= ((byte & 0x7f) as u64) << shift;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            }
+            value
+        }
+
+        let mut fields = Vec::new();
+        while !bytes.is_empty() {
+            let key = varint(&mut bytes);
+            let value = match key & 7 {
+                0 => varint(&mut bytes).to_le_bytes().to_vec(),
+                2 => {
+                    let len = varint(&mut bytes) as usize;
+                    let (payload, rest) = bytes.split_at(len);
+                    bytes = rest;
+                    payload.to_vec()
+                }
+                wire_type => panic!("unexpected wire type {wire_type}"),
+            };
+            fields.push((key >> 3, key & 7, value));
+        }
+        fields
+    }
+
+    fn field(fields: &[(u64, u64, Vec<u8>)], number: u64) -> Vec<&[u8]> {
+        fields.iter().filter(|(field, ..)| *field == number).map(|(.., value)| value.as_slice()).collect()
+    }
+
+    #[test]
+    fn test_varint() {
+        let mut message = Message::default();
+        message.varint(1, 300);
+        assert_eq!(message.0, [0x08, 0xac, 0x02]);
+    }
+
+    #[test]
+    fn test_graph() {
+        let tanh = LayerTopology::new(1).with_activation(Activation::Tanh);
+        let network = Network::from_data(&[LayerTopology::new(2), LayerTopology::new(3), tanh], (0..13).map(|n| n as f32));
+        let model = fields(&network.to_onnx().unwrap());
+        let graph = fields(field(&model, 7)[0]);
+
+        let ops: Vec<_> = field(&graph, 1)
+            .into_iter()
+            .map(|node| String::from_utf8(field(&fields(node), 4)[0].to_vec()).unwrap())
+            .collect();
+        assert_eq!(ops, ["Gemm", "Relu", "Gemm", "Tanh"]);
+
+        //the first layer's rows are [0, 1, 2], [3, 4, 5] and [6, 7, 8]
+        let initializers: Vec<_> = field(&graph, 5).into_iter().map(fields).collect();
+        let values = |tensor: &[(u64, u64, Vec<u8>)]| -> Vec<f32> {
+            field(tensor, 9)[0].chunks_exact(4).map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap())).collect()
+        };
+        assert_eq!(values(&initializers[0]), [1.0, 2.0, 4.0, 5.0, 7.0, 8.0]);
+        assert_eq!(values(&initializers[1]), [0.0, 3.0, 6.0]);
+        assert_eq!(field(&initializers[0], 1).len(), 2);
+    }
+
+    #[test]
+    fn test_unsupported_layers() {
+        let gru = Network::from_data(&[LayerTopology::new(1), LayerTopology::gru(1), LayerTopology::new(1)], [0.0; 11]);
+        assert_eq!(gru.to_onnx(), Err(OnnxError::Recurrent(0)));
+
+        let sin = LayerTopology::new(1).with_activation(Activation::custom(|x: f32| x.sin()));
+        let custom = Network::from_data(&[LayerTopology::new(1), LayerTopology::new(1), sin], [0.0; 4]);
+        assert_eq!(custom.to_onnx(), Err(OnnxError::Activation(1)));
+    }
+}
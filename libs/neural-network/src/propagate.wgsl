@@ -0,0 +1,69 @@
+// one invocation evaluates one network, layer by layer, ping-ponging between two halves
+// of its scratch space
+//
+// weights: every network's data(), each neuron's bias followed by its weights
+
+struct Params {
+    networks: u32,
+    genes: u32,
+    layers: u32,
+    width: u32,
+    // the neurons of every layer, packed 4 per vector to fit the uniform layout
+    topology: array<vec4<u32>, 8>,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> weights: array<f32>;
+@group(0) @binding(2) var<storage, read> inputs: array<f32>;
+@group(0) @binding(3) var<storage, read_write> outputs: array<f32>;
+@group(0) @binding(4) var<storage, read_write> scratch: array<f32>;
+
+fn neurons(layer: u32) -> u32 {
+    return params.topology[layer / 4u][layer % 4u];
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let network = id.x;
+    if (network >= params.networks) {
+        return;
+    }
+
+    let genes = params.genes;
+    let layers = params.layers;
+    let width = params.width;
+    let base = network * width * 2u;
+
+    let input_size = neurons(0u);
+    for (var i = 0u; i < input_size; i = i + 1u) {
+        scratch[base + i] = inputs[network * input_size + i];
+    }
+
+    var gene = network * genes;
+    var read_half = 0u;
+    for (var layer = 1u; layer < layers; layer = layer + 1u) {
+        let layer_inputs = neurons(layer - 1u);
+        let layer_outputs = neurons(layer);
+        let write_half = width - read_half;
+
+        for (var neuron = 0u; neuron < layer_outputs; neuron = neuron + 1u) {
+            let bias = weights[gene];
+            gene = gene + 1u;
+
+            var sum = 0.0;
+            for (var i = 0u; i < layer_inputs; i = i + 1u) {
+                sum = sum + scratch[base + read_half + i] * weights[gene];
+                gene = gene + 1u;
+            }
+
+            scratch[base + write_half + neuron] = max(bias + sum, 0.0);
+        }
+
+        read_half = write_half;
+    }
+
+    let output_size = neurons(layers - 1u);
+    for (var i = 0u; i < output_size; i = i + 1u) {
+        outputs[network * output_size + i] = scratch[base + read_half + i];
+    }
+}
@@ -0,0 +1,174 @@
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::*;
+
+//a network is saved with its layers next to its genes, so it is read back without being handed
+//its topology, there is no neuron of its own to save, the genes of a neuron are a row of those
+//of its layer, see segments()
+#[derive(Serialize)]
+struct SavedNetwork<'a> {
+    layers: &'a [Layer],
+    genes: &'a [f32],
+    clocks: usize,
+}
+
+#[derive(Deserialize)]
+struct LoadedNetwork {
+    layers: Vec<Layer>,
+    genes: Vec<f32>,
+    #[serde(default)]
+    clocks: usize,
+}
+
+//what the gru layers remember is not saved, a loaded network starts over like from_data()
+impl Serialize for Network {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SavedNetwork { layers: &self.layers, genes: &self.genes, clocks: self.clocks }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Network {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let LoadedNetwork { layers, genes, clocks } = LoadedNetwork::deserialize(deserializer)?;
+
+        let Some(first) = layers.first() else {
+            return Err(D::Error::custom("a network needs at least one layer"));
+        };
+        if first.inputs == 0 || layers.windows(2).any(|layers| layers[0].outputs != layers[1].inputs) {
+            return Err(D::Error::custom("the inputs of every layer have to be the outputs of the one before"));
+        }
+        if 2 * clocks >= first.inputs {
+            return Err(D::Error::custom("the clocks need to leave at least one input"));
+        }
+        for layer in &layers {
+            layer.check().map_err(D::Error::custom)?;
+        }
+        //a neuron has a gene for its bias and each input, the number of genes of layers that can
+        //not fit them is not even counted, it could overflow
+        let fits = |layer: &Layer| layer.inputs < genes.len() && layer.outputs <= genes.len();
+        let expected = layers
+            .iter()
+            .all(fits)
+            .then(|| layers.iter().try_fold(0usize, |sum, layer| layer.rows().checked_mul(layer.row_len())?.checked_add(sum)))
+            .flatten()
+            .ok_or_else(|| D::Error::custom("the layers need more genes than the network holds"))?;
+        if genes.len() != expected {
+            return Err(D::Error::custom(DataLengthError { expected, actual: genes.len() }));
+        }
+
+        let state = layers.iter().map(Layer::initial_state).collect();
+        Ok(Self { layers, genes, clocks, state })
+    }
+}
+
+//the built-in activations by name, a custom one is code and can not be saved
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum NamedActivation {
+    Relu,
+    Tanh,
+}
+
+impl Serialize for Activation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Relu => NamedActivation::Relu.serialize(serializer),
+            Self::Tanh => NamedActivation::Tanh.serialize(serializer),
+            Self::Custom(_) => Err(S::Error::custom(format!("{self:?} can not be saved, only relu and tanh can"))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Activation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match NamedActivation::deserialize(deserializer)? {
+            NamedActivation::Relu => Self::Relu,
+            NamedActivation::Tanh => Self::Tanh,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha8Rng;
+
+    fn network() -> Network {
+        let topology = [LayerTopology::new(3), LayerTopology::gru(2), LayerTopology::new(2).with_activation(Activation::Tanh)];
+        Network::random(&mut ChaCha8Rng::from_seed(Default::default()), &topology).with_clocks(1)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let network = network();
+        let json = serde_json::to_string(&network).unwrap();
+        let loaded: Network = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.topology(), network.topology());
+        assert_eq!(loaded.genes(), network.genes());
+        assert_eq!(loaded.clocks(), 1);
+        assert_eq!(loaded.propagate(vec![0.5, -0.5, 1.0]), network.propagate(vec![0.5, -0.5, 1.0]));
+    }
+
+    #[test]
+    fn test_populations() {
+        let population = vec![network(), network().with_output_activation(Activation::Relu)];
+        let json = serde_json::to_string(&population).unwrap();
+        let loaded: Vec<Network> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[1].output_activation(), &Activation::Relu);
+    }
+
+    #[test]
+    fn test_memory_is_not_saved() {
+        let mut network = network();
+        network.step(vec![1.0, 1.0, 1.0]);
+        let loaded: Network = serde_json::from_str(&serde_json::to_string(&network).unwrap()).unwrap();
+
+        assert!(loaded.state().iter().flatten().all(|&memory| memory == 0.0));
+    }
+
+    #[test]
+    fn test_rejects_what_does_not_fit() {
+        let mut json: serde_json::Value = serde_json::to_value(network()).unwrap();
+        json["genes"].as_array_mut().unwrap().pop();
+        let err = serde_json::from_value::<Network>(json.clone()).err().unwrap();
+        assert!(err.to_string().starts_with("expected "), "{err}");
+
+        json["layers"][1]["inputs"] = 3.into();
+        assert!(serde_json::from_value::<Network>(json).is_err());
+    }
+
+    #[test]
+    fn test_rejects_empty_and_oversized_layers() {
+        let json: serde_json::Value = serde_json::to_value(network()).unwrap();
+        let mut empty = json.clone();
+        empty["layers"][0]["outputs"] = 0.into();
+        empty["layers"][1]["inputs"] = 0.into();
+        let err = serde_json::from_value::<Network>(empty).err().unwrap();
+        assert!(err.to_string().contains("at least one input and one output"), "{err}");
+
+        let mut oversized = json;
+        oversized["layers"][0]["outputs"] = u64::MAX.into();
+        oversized["layers"][1]["inputs"] = u64::MAX.into();
+        let err = serde_json::from_value::<Network>(oversized).err().unwrap();
+        assert!(err.to_string().contains("more genes"), "{err}");
+    }
+
+    #[test]
+    fn test_custom_activations_can_not_be_saved() {
+        let network = network().with_output_activation(Activation::custom(|x: f32| x.sin()));
+        assert!(serde_json::to_string(&network).is_err());
+    }
+
+    #[test]
+    fn test_topologies() {
+        let topology = vec![LayerTopology::new(2), LayerTopology::gru(4), LayerTopology::new(1).with_activation(Activation::Tanh)];
+        let json = serde_json::to_string(&topology).unwrap();
+        assert!(json.contains(r#""kind":"gru""#) && json.contains(r#""activation":"tanh""#), "{json}");
+        assert_eq!(serde_json::from_str::<Vec<LayerTopology>>(&json).unwrap(), topology);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::*;
+
+impl Network {
+    //like propagate(), but the outputs are a probability distribution over them, for brains
+    //that pick one of a few discrete actions: the output layer skips its activation and its
+    //weighted sums go through a softmax, a gru output layer keeps its own outputs
+    pub fn propagate_softmax(&self, inputs: Vec<f32>) -> Vec<f32> {
+        let last = self.layers.len() - 1;
+        let layers = self.layers.iter().zip(self.layer_genes()).zip(&self.state).enumerate();
+        let logits = layers.fold(inputs, |inputs, (i, ((layer, genes), state))| {
+            if i == last && layer.kind == LayerKind::Dense {
+                layer.weigh(genes, &inputs)
+            } else {
+                layer.propagate(genes, inputs, state)
+            }
+        });
+        softmax(&logits)
+    }
+
+    //the output propagate_softmax() gives the highest probability
+    pub fn propagate_argmax(&self, inputs: Vec<f32>) -> usize {
+        argmax(&self.propagate_softmax(inputs)).expect("got a network without outputs")
+    }
+}
+
+//probabilities proportional to the exponentials of `values`, the largest value is taken off
+//first so large ones do not overflow
+pub fn softmax(values: &[f32]) -> Vec<f32> {
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let exponentials: Vec<f32> = values.iter().map(|value| (value - max).exp()).collect();
+    let sum: f32 = exponentials.iter().sum();
+    exponentials.into_iter().map(|exponential| exponential / sum).collect()
+}
+
+//the index of the largest value, the first of equal ones, None for no values
+pub fn argmax(values: &[f32]) -> Option<usize> {
+    values
+        .iter()
+        .enumerate()
+        .fold(None, |best: Option<(usize, f32)>, (i, &value)| match best {
+            Some((_, max)) if max >= value => best,
+            _ => Some((i, value)),
+        })
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_softmax() {
+        let probabilities = softmax(&[1.0, 2.0, 3.0]);
+        let sum = 1.0f32.exp() + 2.0f32.exp() + 3.0f32.exp();
+
+        assert_relative_eq!(probabilities.as_slice(), [1.0f32.exp() / sum, 2.0f32.exp() / sum, 3.0f32.exp() / sum].as_ref());
+        assert_relative_eq!(softmax(&[1000.0, 1000.0]).as_slice(), [0.5, 0.5].as_ref());
+        assert_eq!(argmax(&[0.2, 0.5, 0.5, 0.1]), Some(1));
+        assert_eq!(argmax(&[]), None);
+    }
+
+    #[test]
+    fn test_negative_outputs_count() {
+        //both outputs are negative, a relu would make them equal
+        let network = Network::from_data(&[LayerTopology::new(1), LayerTopology::new(2)], vec![-1.0, 0.0, -3.0, 0.0]);
+
+        assert_eq!(network.propagate(vec![1.0]), [0.0, 0.0]);
+        assert_relative_eq!(network.propagate_softmax(vec![1.0]).as_slice(), softmax(&[-1.0, -3.0]).as_slice());
+        assert_eq!(network.propagate_argmax(vec![1.0]), 0);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::*;
+
+impl Network {
+    //(inputs, outputs) of every layer
+    pub fn layer_shapes(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.layers.iter().map(|layer| (layer.inputs, layer.outputs))
+    }
+
+    pub fn bias(&self, layer: usize, neuron: usize) -> f32 {
+        self.genes[self.gene_index(layer, neuron, 0)]
+    }
+
+    pub fn set_bias(&mut self, layer: usize, neuron: usize, bias: f32) {
+        let index = self.gene_index(layer, neuron, 0);
+        self.genes[index] = bias;
+    }
+
+    //the weight `neuron` of `layer` gives its `input`
+    pub fn weight(&self, layer: usize, neuron: usize, input: usize) -> f32 {
+        self.genes[self.gene_index(layer, neuron, 1 + input)]
+    }
+
+    pub fn set_weight(&mut self, layer: usize, neuron: usize, input: usize, weight: f32) {
+        let index = self.gene_index(layer, neuron, 1 + input);
+        self.genes[index] = weight;
+    }
+
+    //`column` 0 is the bias, the weights follow, the `neuron`s of a gru layer are the rows of
+    //its gates and the weights of its last outputs follow those of the inputs
+    fn gene_index(&self, layer: usize, neuron: usize, column: usize) -> usize {
+        let offset: usize = self.layers[..layer].iter().map(Layer::len).sum();
+        let shape = &self.layers[layer];
+        assert!(neuron < shape.rows() && column < shape.row_len(), "no gene {column} of neuron {neuron} in layer {layer}");
+        offset + neuron * shape.row_len() + column
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accessors() {
+        let mut network = Network::from_data(
+            &[LayerTopology::new(2), LayerTopology::new(2), LayerTopology::new(1)],
+            (0..9).map(|n| n as f32),
+        );
+
+        assert_eq!(network.layer_shapes().collect::<Vec<_>>(), [(2, 2), (2, 1)]);
+        assert_eq!(network.bias(0, 1), 3.0);
+        assert_eq!(network.weight(0, 1, 1), 5.0);
+        assert_eq!(network.weight(1, 0, 0), 7.0);
+
+        network.set_weight(1, 0, 1, -1.0);
+        network.set_bias(0, 0, 0.5);
+        assert_eq!(network.genes(), &[0.5, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, -1.0]);
+    }
+}
@@ -0,0 +1,204 @@
+//the pymethods macros convert every PyResult into itself
+#![allow(clippy::useless_conversion)]
+
+use lib_natural_selection::{Chromosome, GaussianMutation, GeneticAlgorithm, Individual, RouletteWheelSelection, UniformCrossover};
+use lib_neural_network::{LayerTopology, Network};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+//python bindings of the network and the optimizer, so fitness functions can be prototyped in
+//python while propagation and breeding stay in rust
+
+fn topology(layers: &[usize]) -> PyResult<Vec<LayerTopology>> {
+    if layers.len() < 2 || layers.contains(&0) {
+        return Err(PyValueError::new_err("a network needs at least two layers of at least one neuron"));
+    }
+    Ok(layers.iter().map(|&neurons| LayerTopology::new(neurons)).collect())
+}
+
+//a feed forward network, `layers` are the neuron counts from the inputs to the outputs
+#[pyclass(name = "Network")]
+pub struct PyNetwork {
+    layers: Vec<usize>,
+    network: Network,
+}
+
+#[pymethods]
+impl PyNetwork {
+    #[staticmethod]
+    #[pyo3(signature = (layers, seed = 0))]
+    fn random(layers: Vec<usize>, seed: u64) -> PyResult<Self> {
+        let network = Network::random(&mut ChaCha8Rng::seed_from_u64(seed), &topology(&layers)?);
+        Ok(Self { layers, network })
+    }
+
+    //the weights laid out like data() returns them
+    #[staticmethod]
+    fn from_data(layers: Vec<usize>, data: Vec<f32>) -> PyResult<Self> {
+        let mut network = Network::random(&mut ChaCha8Rng::seed_from_u64(0), &topology(&layers)?);
+        network.load_data(data).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { layers, network })
+    }
+
+    fn data(&self) -> Vec<f32> {
+        self.network.data().collect()
+    }
+
+    #[getter]
+    fn layers(&self) -> Vec<usize> {
+        self.layers.clone()
+    }
+
+    fn propagate(&self, inputs: Vec<f32>) -> PyResult<Vec<f32>> {
+        if inputs.len() != self.layers[0] {
+            return Err(PyValueError::new_err(format!("expected {} inputs, got {}", self.layers[0], inputs.len())));
+        }
+        Ok(self.network.propagate(inputs))
+    }
+
+    fn __len__(&self) -> usize {
+        self.network.data_len()
+    }
+}
+
+#[pyclass(name = "Chromosome")]
+#[derive(Clone)]
+pub struct PyChromosome {
+    chromosome: Chromosome,
+}
+
+#[pymethods]
+impl PyChromosome {
+    #[new]
+    fn new(genes: Vec<f32>) -> Self {
+        Self { chromosome: genes.into_iter().collect() }
+    }
+
+    #[staticmethod]
+    fn from_dna(dna: &str) -> PyResult<Self> {
+        let chromosome = dna.parse().map_err(|e: lib_natural_selection::DnaError| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { chromosome })
+    }
+
+    fn genes(&self) -> Vec<f32> {
+        self.chromosome.iter().copied().collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.chromosome.len()
+    }
+
+    //the dna
+    fn __str__(&self) -> String {
+        self.chromosome.to_string()
+    }
+}
+
+//a chromosome with the fitness python gave it
+struct Scored {
+    chromosome: Chromosome,
+    fitness: f32,
+}
+
+impl Individual for Scored {
+    fn create(chromosome: Chromosome) -> Self {
+        Self { chromosome, fitness: 0.0 }
+    }
+
+    fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    fn chromosome(&self) -> &Chromosome {
+        &self.chromosome
+    }
+}
+
+//roulette wheel selection, uniform crossover and gaussian mutation, like the sim breeds with
+//by default
+#[pyclass(name = "GeneticAlgorithm", unsendable)]
+pub struct PyGeneticAlgorithm {
+    algorithm: GeneticAlgorithm<RouletteWheelSelection>,
+    rng: ChaCha8Rng,
+}
+
+#[pymethods]
+impl PyGeneticAlgorithm {
+    #[new]
+    #[pyo3(signature = (mutation_chance = 0.3, mutation_coeff = 0.5, seed = 0))]
+    fn new(mutation_chance: f32, mutation_coeff: f32, seed: u64) -> PyResult<Self> {
+        if !(0.0..=1.0).contains(&mutation_chance) {
+            return Err(PyValueError::new_err("mutation_chance has to be between 0 and 1"));
+        }
+        let algorithm = GeneticAlgorithm::new(
+            RouletteWheelSelection::new(),
+            UniformCrossover,
+            GaussianMutation::new(mutation_chance, mutation_coeff),
+        );
+        Ok(Self { algorithm, rng: ChaCha8Rng::seed_from_u64(seed) })
+    }
+
+    //breeds the next population from `population` scored with `fitness`, the fitness must not
+    //be negative
+    fn evolve(&mut self, population: Vec<PyChromosome>, fitness: Vec<f32>) -> PyResult<Vec<PyChromosome>> {
+        if population.is_empty() || population.len() != fitness.len() {
+            return Err(PyValueError::new_err("need a fitness for every chromosome of a non-empty population"));
+        }
+        if fitness.iter().any(|fitness| fitness.is_nan() || *fitness < 0.0) {
+            return Err(PyValueError::new_err("fitness has to be a non-negative number"));
+        }
+
+        let population: Vec<_> = population
+            .into_iter()
+            .zip(fitness)
+            .map(|(chromosome, fitness)| Scored { chromosome: chromosome.chromosome, fitness })
+            .collect();
+        Ok(self
+            .algorithm
+            .evolve(&mut self.rng, &population)
+            .into_iter()
+            .map(|scored| PyChromosome { chromosome: scored.chromosome })
+            .collect())
+    }
+
+    //the number of populations evolved so far
+    #[getter]
+    fn generation(&self) -> usize {
+        self.algorithm.generation()
+    }
+}
+
+#[pymodule]
+fn rustism(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyNetwork>()?;
+    module.add_class::<PyChromosome>()?;
+    module.add_class::<PyGeneticAlgorithm>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_round_trip() {
+        let network = PyNetwork::random(vec![3, 4, 2], 1).unwrap();
+        let copy = PyNetwork::from_data(vec![3, 4, 2], network.data()).unwrap();
+
+        assert_eq!(copy.data(), network.data());
+        assert_eq!(copy.propagate(vec![0.1, 0.2, 0.3]).unwrap(), network.propagate(vec![0.1, 0.2, 0.3]).unwrap());
+    }
+
+    #[test]
+    fn test_evolve() {
+        let mut algorithm = PyGeneticAlgorithm::new(0.5, 0.1, 7).unwrap();
+        let population: Vec<_> = (0..4).map(|i| PyChromosome::new(vec![i as f32; 3])).collect();
+
+        let offspring = algorithm.evolve(population, vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+        assert_eq!(offspring.len(), 4);
+        assert!(offspring.iter().all(|chromosome| chromosome.__len__() == 3));
+        assert_eq!(algorithm.generation(), 1);
+    }
+}
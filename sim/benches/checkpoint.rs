@@ -0,0 +1,48 @@
+//compares the size and speed of text and zstd compressed checkpoints of a big population,
+//run with `cargo bench -p sim --features zstd`
+use std::time::Instant;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use sim::population::{self, Checkpoint};
+
+const INDIVIDUALS: usize = 5000;
+//the genes of a brain of the simulation
+const GENES: usize = (11 + 1) * 24 + (24 + 1) * 5;
+
+fn main() {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let checkpoint = Checkpoint {
+        population: (0..INDIVIDUALS)
+            .map(|_| (0..GENES).map(|_| rng.gen_range(-1.0..=1.0)).collect())
+            .collect(),
+        curriculum_stage: Some(2),
+        resume: None,
+        robustness: None,
+    };
+
+    let dir = std::env::temp_dir();
+    let text = dir.join("checkpoint_bench.txt");
+    let compressed = dir.join("checkpoint_bench.zst");
+
+    println!("{INDIVIDUALS} individuals with {GENES} genes");
+    for (name, path, level) in [("text", &text, None), ("zstd 1", &compressed, Some(1)), ("zstd 3", &compressed, Some(3)), ("zstd 9", &compressed, Some(9))] {
+        let start = Instant::now();
+        match level {
+            Some(level) => population::save_compressed_checkpoint(path, &checkpoint, level),
+            None => population::save_checkpoint(path, &checkpoint),
+        }
+        .expect("could not save the checkpoint");
+        let saved = start.elapsed();
+
+        let start = Instant::now();
+        let loaded = population::load_checkpoint(path).expect("could not load the checkpoint");
+        let load = start.elapsed();
+        assert_eq!(loaded.population.len(), INDIVIDUALS);
+
+        let size = std::fs::metadata(path).expect("could not read the size").len();
+        println!("{name:>8}: {:>7.2} MiB, save {saved:>10.2?}, load {load:>10.2?}", size as f64 / (1024.0 * 1024.0));
+    }
+
+    let _ = std::fs::remove_file(text);
+    let _ = std::fs::remove_file(compressed);
+}
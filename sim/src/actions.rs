@@ -0,0 +1,119 @@
+use std::fmt;
+use std::str::FromStr;
+use bevy::prelude::*;
+use rand::prelude::*;
+
+//how far a thrust+turn individual can turn per think tick, in radians
+const MAX_TURN: f32 = std::f32::consts::FRAC_PI_4;
+
+//turns the first four outputs of a brain into the direction it moves in until the next
+//think tick, the fifth output is its oscillator frequency
+pub trait ActionDecoder: Send + Sync {
+    //`heading` is the direction the individual faces in radians, kept across think ticks for
+    //decoders that steer
+    fn decode(&self, outputs: &[f32], heading: &mut f32) -> Vec3;
+}
+
+//left/right and up/down push against each other, the result is normalized
+pub struct ContinuousActions;
+
+impl ActionDecoder for ContinuousActions {
+    fn decode(&self, outputs: &[f32], _heading: &mut f32) -> Vec3 {
+        Vec3::new(
+            outputs[0].clamp(0.0, 1.0) - outputs[1].clamp(0.0, 1.0),
+            outputs[2].clamp(0.0, 1.0) - outputs[3].clamp(0.0, 1.0),
+            0.0).normalize_or_zero()
+    }
+}
+
+//the stronger output of each axis wins if it is positive, so individuals move in one of
+//eight directions at full speed or stand still
+pub struct EightDirections;
+
+impl EightDirections {
+    fn axis(positive: f32, negative: f32) -> f32 {
+        if positive.max(negative) <= 0.0 {
+            0.0
+        } else if positive >= negative {
+            1.0
+        } else {
+            -1.0
+        }
+    }
+}
+
+impl ActionDecoder for EightDirections {
+    fn decode(&self, outputs: &[f32], _heading: &mut f32) -> Vec3 {
+        Vec3::new(Self::axis(outputs[0], outputs[1]), Self::axis(outputs[2], outputs[3]), 0.0).normalize_or_zero()
+    }
+}
+
+//the first output is the thrust along the heading, the second and third turn it left and
+//right, the fourth is unused
+pub struct ThrustTurn;
+
+impl ActionDecoder for ThrustTurn {
+    fn decode(&self, outputs: &[f32], heading: &mut f32) -> Vec3 {
+        let turn = (outputs[1] - outputs[2]).clamp(-1.0, 1.0);
+        *heading = (*heading + turn * MAX_TURN).rem_euclid(std::f32::consts::TAU);
+
+        let thrust = outputs[0].clamp(0.0, 1.0);
+        Vec3::new(heading.cos(), heading.sin(), 0.0) * thrust
+    }
+}
+
+//exploration, replaces a decoded action by a random direction with probability `epsilon`
+pub fn perturb(rng: &mut dyn RngCore, action: Vec3, epsilon: f32) -> Vec3 {
+    if epsilon <= 0.0 || !rng.gen_bool(epsilon.min(1.0) as f64) {
+        return action;
+    }
+
+    let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+    Vec3::new(angle.cos(), angle.sin(), 0.0)
+}
+
+//which ActionDecoder a run uses
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ActionEncoding {
+    #[default]
+    Continuous,
+    EightDirections,
+    ThrustTurn,
+}
+
+impl ActionEncoding {
+    pub fn decoder(&self) -> Box<dyn ActionDecoder> {
+        match self {
+            Self::Continuous => Box::new(ContinuousActions),
+            Self::EightDirections => Box::new(EightDirections),
+            Self::ThrustTurn => Box::new(ThrustTurn),
+        }
+    }
+}
+
+impl FromStr for ActionEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "continuous" => Ok(Self::Continuous),
+            "eight-directions" => Ok(Self::EightDirections),
+            "thrust-turn" => Ok(Self::ThrustTurn),
+            _ => Err(format!("unknown action encoding '{s}', expected continuous, eight-directions or thrust-turn")),
+        }
+    }
+}
+
+impl fmt::Display for ActionEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Continuous => "continuous",
+            Self::EightDirections => "eight-directions",
+            Self::ThrustTurn => "thrust-turn",
+        })
+    }
+}
+
+//the decoder of the run
+#[derive(Resource)]
+pub struct Actions(pub Box<dyn ActionDecoder>);
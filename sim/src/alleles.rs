@@ -0,0 +1,167 @@
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy::window::WindowId;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+use lib_natural_selection::AlleleHistogram;
+
+use crate::dashboard::{self, Dashboard};
+use crate::persistence::{IoHandle, SharedFile};
+use crate::{Frozen, Nizm, Statistics};
+
+//the genes are binned over ±RANGE, they start within ±1 and mutation carries a few further
+const BINS: usize = 16;
+const RANGE: f32 = 2.0;
+//the heatmap shows the latest generations at most
+const SHOWN_GENERATIONS: usize = 400;
+
+//the allele histograms of every generation as a binary file, all numbers little endian: the
+//magic `ALLE`, the u32 bins, genes and f32 range, then per generation its u32 number followed by
+//genes × bins u16 counts, gene by gene, until the end of the file
+pub struct AlleleTensor {
+    out: SharedFile,
+    io: IoHandle,
+    header: bool,
+}
+
+impl AlleleTensor {
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self { out: SharedFile::create(path, &[])?, io: IoHandle::default(), header: false })
+    }
+
+    //writes the histograms on the io thread of `io`
+    pub fn with_io(self, io: IoHandle) -> Self {
+        Self { io, ..self }
+    }
+
+    fn write(&mut self, generation: i32, histogram: &AlleleHistogram) {
+        let mut bytes = Vec::new();
+        if !self.header {
+            bytes.extend_from_slice(b"ALLE");
+            bytes.extend_from_slice(&(histogram.bins() as u32).to_le_bytes());
+            bytes.extend_from_slice(&(histogram.genes() as u32).to_le_bytes());
+            bytes.extend_from_slice(&histogram.range().to_le_bytes());
+            self.header = true;
+        }
+        bytes.extend_from_slice(&(generation as u32).to_le_bytes());
+        for count in histogram.as_slice() {
+            bytes.extend_from_slice(&count.to_le_bytes());
+        }
+        self.out.append(&self.io, "the allele frequencies", bytes);
+    }
+}
+
+//the allele frequencies of the population at the start of every generation, with the entropy
+//of every gene kept for the heatmap
+#[derive(Resource, Default)]
+pub struct AlleleTracker {
+    generation: Option<i32>,
+    //the first generation of the entropy
+    first: i32,
+    //per generation, of every gene in bits
+    entropy: Vec<Vec<f32>>,
+    out: Option<AlleleTensor>,
+}
+
+impl AlleleTracker {
+    //a tracker that also writes every histogram to `out`
+    pub fn with_output(out: AlleleTensor) -> Self {
+        Self { out: Some(out), ..default() }
+    }
+
+    pub fn record(&mut self, generation: i32, histogram: &AlleleHistogram) {
+        if self.generation == Some(generation) {
+            return;
+        }
+        if self.generation.is_none() {
+            self.first = generation;
+        }
+        self.generation = Some(generation);
+        self.entropy.push((0..histogram.genes()).map(|gene| histogram.entropy(gene)).collect());
+
+        if let Some(out) = self.out.as_mut() {
+            out.write(generation, histogram);
+        }
+    }
+
+    //the mean entropy of the genes per generation
+    pub fn mean_entropy(&self) -> impl Iterator<Item = f32> + '_ {
+        self.entropy.iter().map(|genes| genes.iter().sum::<f32>() / genes.len().max(1) as f32)
+    }
+}
+
+//runs after the evolution, so the offspring are measured as the population of the new generation
+pub(crate) fn track_alleles(tracker: Option<ResMut<AlleleTracker>>,
+                            statistics: Query<&Statistics>,
+                            nizms: Query<&Nizm, Without<Frozen>>) {
+    let (Some(mut tracker), Ok(statistics)) = (tracker, statistics.get_single()) else {
+        return;
+    };
+    if tracker.generation == Some(statistics.generation) {
+        return;
+    }
+    let histogram = AlleleHistogram::new(nizms.iter().map(|nizm| nizm.network.genes()), BINS, RANGE);
+    tracker.record(statistics.generation, &histogram);
+}
+
+//a heatmap of the entropy of every gene over the generations, bright while a gene stays
+//polymorphic and dark once the population agrees on it
+pub struct AllelePlugin;
+
+impl Plugin for AllelePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+
+        if !app.world.contains_resource::<AlleleTracker>() {
+            app.init_resource::<AlleleTracker>();
+        }
+        app.add_system(alleles_panel);
+    }
+}
+
+fn alleles_panel(mut egui_context: ResMut<EguiContext>,
+                 dashboard: Option<Res<Dashboard>>,
+                 tracker: Res<AlleleTracker>,
+                 mut texture: Local<Option<(WindowId, egui::TextureHandle)>>) {
+    let window = dashboard::panel_window(dashboard.as_deref());
+    let Some(ctx) = egui_context.try_ctx_for_window_mut(window) else {
+        return;
+    };
+    if tracker.is_changed() || texture.as_ref().is_none_or(|(loaded, _)| *loaded != window) {
+        *texture = Some((window, ctx.load_texture("alleles", heatmap(&tracker), egui::TextureOptions::NEAREST)));
+    }
+
+    egui::Window::new("Alleles").show(ctx, |ui| {
+        let shown = tracker.entropy.len().min(SHOWN_GENERATIONS);
+        let genes = tracker.entropy.first().map_or(0, Vec::len);
+        let last = tracker.mean_entropy().last().unwrap_or(0.0);
+        ui.label(format!("{genes} genes, mean entropy {last:.2} of {:.2} bits", (BINS as f32).log2()));
+        ui.label(format!("generations {} to {}, a column each", tracker.first + (tracker.entropy.len() - shown) as i32, tracker.generation.unwrap_or(0)));
+        if let Some((_, texture)) = texture.as_ref() {
+            ui.image(texture.id(), egui::vec2(400.0, 300.0));
+        }
+    });
+}
+
+//a column per generation and a row per gene
+fn heatmap(tracker: &AlleleTracker) -> egui::ColorImage {
+    let generations = &tracker.entropy[tracker.entropy.len().saturating_sub(SHOWN_GENERATIONS)..];
+    let genes = generations.first().map_or(0, Vec::len);
+    if genes == 0 {
+        return egui::ColorImage::new([1, 1], egui::Color32::BLACK);
+    }
+
+    let max = (BINS as f32).log2();
+    let mut image = egui::ColorImage::new([generations.len(), genes], egui::Color32::BLACK);
+    for (x, entropy) in generations.iter().enumerate() {
+        for (y, &entropy) in entropy.iter().enumerate() {
+            let level = (entropy / max * 255.0) as u8;
+            image[(x, y)] = egui::Color32::from_rgb(level, level / 2, 255 - level);
+        }
+    }
+    image
+}
@@ -0,0 +1,296 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use lib_natural_selection::{Aggregation, Chromosome, InPlaceIndividual, MultiTrialEvaluator};
+use lib_neural_network::Network;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::fitness::FitnessTerms;
+use crate::headless::{self, headless_app_with_population};
+use crate::occupancy::KillzoneOccupancy;
+use crate::{check_genomes, genetic_variance, optimizer, Config, Evolution, ExternalSelection, GenerationScores, InitialPopulation, Nizm, NizmIndividual, Statistics};
+
+//how a batched run spreads the population over its worlds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WorldSplit {
+    //every world evaluates a part of the population, all from the same seed
+    #[default]
+    Slice,
+    //every world evaluates the whole population from a seed of its own, the fitness of the
+    //worlds is aggregated like trials
+    Seeds,
+}
+
+impl FromStr for WorldSplit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "slice" => Ok(Self::Slice),
+            "seeds" => Ok(Self::Seeds),
+            _ => Err(format!("unknown world split '{s}', expected slice or seeds")),
+        }
+    }
+}
+
+impl fmt::Display for WorldSplit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Slice => "slice",
+            Self::Seeds => "seeds",
+        })
+    }
+}
+
+//what a world sends back after a generation
+struct WorldResult {
+    scores: GenerationScores,
+    time_in_killzone: f32,
+    fitness_terms: FitnessTerms,
+}
+
+//a headless world on a thread of its own, it waits for the genes of its individuals, runs one
+//generation with them and sends back their scores
+struct WorldThread {
+    //where the genes go, in the order of its individuals
+    range: std::ops::Range<usize>,
+    //dropped to end the thread
+    jobs: Option<Sender<Vec<Vec<f32>>>>,
+    results: Receiver<WorldResult>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WorldThread {
+    fn spawn(config: Config, seed: u64, population: Vec<Chromosome>, range: std::ops::Range<usize>) -> Self {
+        let (jobs, job_receiver) = mpsc::channel::<Vec<Vec<f32>>>();
+        let (result_sender, results) = mpsc::channel();
+
+        //the app is made on the thread, its non-send resources can only be used there
+        let thread = thread::spawn(move || {
+            let mut app = headless_app_with_population(config, seed, Some(InitialPopulation(population)));
+            app.insert_resource(ExternalSelection::default());
+
+            for genes in job_receiver {
+                for (mut nizm, genes) in app.world.query::<&mut Nizm>().iter_mut(&mut app.world).zip(&genes) {
+                    nizm.network.genes_mut().copy_from_slice(genes);
+                }
+                headless::run_generations(&mut app, 1);
+
+                let scores = app.world.resource_mut::<ExternalSelection>().scores.take().expect("the generation was scored");
+                let statistics = headless::statistics(&mut app);
+                let (time_in_killzone, fitness_terms) = (statistics.time_in_killzone, statistics.fitness_terms);
+                if result_sender.send(WorldResult { scores, time_in_killzone, fitness_terms }).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Self { range, jobs: Some(jobs), results, thread: Some(thread) }
+    }
+}
+
+impl Drop for WorldThread {
+    fn drop(&mut self) {
+        //closing the channel ends the loop of the thread once it waits for the next genes
+        self.jobs.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+//a headless run that evaluates every generation in several worlds on their own threads at
+//once and selects over all of them, so evaluation scales with the cores
+pub struct BatchedRun {
+    worlds: Vec<WorldThread>,
+    split: WorldSplit,
+    trial_aggregation: Aggregation,
+    population: Vec<Network>,
+    //of the last generation, in the order of the population it was evaluated with
+    fitness: Vec<f32>,
+    evolution: Evolution,
+    rng: ChaCha8Rng,
+    statistics: Statistics,
+}
+
+impl BatchedRun {
+    //fails for an initial population whose genomes do not fit the brains of `config`
+    pub fn new(config: Config, seed: u64, worlds: usize, split: WorldSplit, initial_population: Option<InitialPopulation>) -> Result<Self, String> {
+        if let Some(population) = &initial_population {
+            check_genomes(&config, &population.0)?;
+        }
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let population: Vec<Network> = match initial_population {
+            Some(population) if !population.0.is_empty() => (0..config.individuals)
+                .map(|i| Nizm::from_chromosome(&population.0[i % population.0.len()], &config).network)
+                .collect(),
+            _ => (0..config.individuals).map(|_| Nizm::random(&mut rng, &config).network).collect(),
+        };
+
+        let worlds = match split {
+            WorldSplit::Slice => worlds.clamp(1, config.individuals.max(1)),
+            WorldSplit::Seeds => worlds.max(1),
+        };
+        let ranges: Vec<_> = match split {
+            WorldSplit::Slice => (0..worlds)
+                .map(|world| world * config.individuals / worlds..(world + 1) * config.individuals / worlds)
+                .collect(),
+            WorldSplit::Seeds => vec![0..config.individuals; worlds],
+        };
+        let worlds = ranges
+            .into_iter()
+            .enumerate()
+            .map(|(world, range)| {
+                let config = Config { individuals: range.len(), ..config.clone() };
+                let seed = match split {
+                    WorldSplit::Slice => seed,
+                    WorldSplit::Seeds => seed.wrapping_add(world as u64),
+                };
+                let population = population[range.clone()].iter().map(|network| network.data().collect()).collect();
+                WorldThread::spawn(config, seed, population, range)
+            })
+            .collect();
+
+        Ok(Self {
+            worlds,
+            split,
+            trial_aggregation: config.trial_aggregation,
+            population,
+            fitness: Vec::new(),
+            evolution: Evolution::new(optimizer(&config)),
+            rng,
+            statistics: Statistics::default(),
+        })
+    }
+
+    pub fn worlds(&self) -> usize {
+        self.worlds.len()
+    }
+
+    pub fn evolution_mut(&mut self) -> &mut Evolution {
+        &mut self.evolution
+    }
+
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    //the fitness of every individual of the generation before population()
+    pub fn fitness(&self) -> &[f32] {
+        &self.fitness
+    }
+
+    pub fn population(&self) -> Vec<Chromosome> {
+        self.population.iter().map(|network| network.data().collect()).collect()
+    }
+
+    //evaluates the population in all worlds at once and breeds the next one
+    pub fn run_generation(&mut self) -> &Statistics {
+        for world in &self.worlds {
+            let genes = self.population[world.range.clone()].iter().map(|network| network.genes().to_vec()).collect();
+            world.jobs.as_ref().expect("running").send(genes).expect("a world stopped");
+        }
+        let results: Vec<WorldResult> = self.worlds.iter().map(|world| world.results.recv().expect("a world stopped")).collect();
+
+        let individuals = self.population.len();
+        let (fitness, stderr, survivors, descriptors): (Vec<f32>, Vec<f32>, f32, Vec<[f32; 2]>) = match self.split {
+            WorldSplit::Slice => (
+                results.iter().flat_map(|result| result.scores.fitness.iter().copied()).collect(),
+                results.iter().flat_map(|result| result.scores.stderr.iter().copied()).collect(),
+                results.iter().map(|result| result.scores.survivors).sum::<usize>() as f32 / individuals as f32,
+                results.iter().flat_map(|result| result.scores.descriptors.iter().copied()).collect(),
+            ),
+            //the worlds are trials of the whole population
+            WorldSplit::Seeds => {
+                let evaluator = MultiTrialEvaluator::new(results.len(), self.trial_aggregation);
+                let (fitness, stderr) = (0..individuals)
+                    .map(|i| {
+                        let mut scores: Vec<f32> = results.iter().map(|result| result.scores.fitness[i]).collect();
+                        let stderr = evaluator.estimate(&scores).stderr();
+                        (evaluator.aggregate(&mut scores), stderr)
+                    })
+                    .unzip();
+                let survivors = results.iter().map(|result| result.scores.survivors).sum::<usize>();
+                //the mean behavior over the worlds
+                let worlds = results.len() as f32;
+                let descriptors = (0..individuals)
+                    .map(|i| {
+                        let [displacement, movement] = results.iter().fold([0.0; 2], |[displacement, movement], result| {
+                            let [d, m] = result.scores.descriptors[i];
+                            [displacement + d, movement + m]
+                        });
+                        [displacement / worlds, movement / worlds]
+                    })
+                    .collect();
+                (fitness, stderr, survivors as f32 / (individuals * results.len()) as f32, descriptors)
+            }
+        };
+
+        //the share of the generation every individual spent inside kill zones, averaged over the
+        //worlds it played in
+        let occupancy: Vec<f32> = match self.split {
+            WorldSplit::Slice => results.iter().flat_map(|result| result.scores.occupancy.iter().copied()).collect(),
+            WorldSplit::Seeds => (0..individuals)
+                .map(|i| results.iter().map(|result| result.scores.occupancy[i]).sum::<f32>() / results.len() as f32)
+                .collect(),
+        };
+
+        let stats = &mut self.statistics;
+        stats.generation += 1;
+        stats.survivors_percentage = survivors;
+        stats.time_in_killzone = results.iter().map(|result| result.time_in_killzone).sum::<f32>() / results.len() as f32;
+        stats.killzone_occupancy = KillzoneOccupancy::of(&occupancy);
+        stats.fitness_terms = FitnessTerms::mean(&results.iter().map(|result| result.fitness_terms).collect::<Vec<_>>());
+        stats.best_fitness = fitness.iter().copied().fold(0.0, f32::max);
+        stats.average_fitness = fitness.iter().sum::<f32>() / fitness.len() as f32;
+        stats.genetic_variance = genetic_variance(self.population.iter().map(Network::genes));
+
+        let (best, &best_fitness) = fitness
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .expect("need individuals");
+        stats.new_champion = stats.champion.is_none() || best_fitness > stats.champion_fitness;
+        if stats.new_champion {
+            stats.champion = Some(self.population[best].data().collect());
+            stats.champion_fitness = best_fitness;
+        }
+
+        let mut individuals: Vec<_> = self
+            .population
+            .iter_mut()
+            .zip(fitness.iter().zip(&stderr).zip(&descriptors))
+            .map(|(network, ((&fitness, &fitness_stderr), &descriptor))| NizmIndividual { network, fitness, fitness_stderr, descriptor })
+            .collect();
+        let mut population: Vec<&mut dyn InPlaceIndividual> = individuals
+            .iter_mut()
+            .map(|individual| individual as &mut dyn InPlaceIndividual)
+            .collect();
+        self.evolution.time_in_killzone.set(stats.time_in_killzone);
+        self.evolution.killzone_occupancy.set(stats.killzone_occupancy);
+        self.evolution.fitness_terms.set(stats.fitness_terms);
+        self.evolution.optimizer.evolve_in_place(&mut self.rng, &mut population);
+        self.fitness = fitness;
+
+        if let Some(ga_statistics) = self.evolution.optimizer.statistics() {
+            stats.takeover = ga_statistics.takeover;
+            stats.distinct_genotypes = ga_statistics.distinct_genotypes;
+            stats.selection_intensity = ga_statistics.selection_intensity;
+            stats.fitness_stderr = ga_statistics.avg_fitness_stderr;
+            stats.regularization = ga_statistics.avg_regularization;
+            if let Some(mutations) = &ga_statistics.mutations {
+                stats.mutated_share = mutations.changed_share();
+                stats.mutation_size = mutations.avg_change();
+            }
+            if let Some(provenance) = &ga_statistics.provenance {
+                stats.parental_mixing = provenance.avg_mixing();
+            }
+            stats.crowded_out = ga_statistics.crowded_out;
+            stats.surrogate_error = ga_statistics.surrogate_error.unwrap_or(0.0);
+        }
+
+        &self.statistics
+    }
+}
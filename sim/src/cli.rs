@@ -0,0 +1,549 @@
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::str::FromStr;
+use clap::{Args, Parser, Subcommand};
+use lib_natural_selection::{Aggregation, Chromosome, FitnessScaling};
+use sim::actions::ActionEncoding;
+use sim::batch::WorldSplit;
+use sim::compare::Variant;
+use sim::environment::{SpawnDistribution, SpawnRegion};
+use sim::fitness::FitnessConfig;
+use sim::hud::{HudConfig, HudCorner, HudField};
+use sim::noise::SensorNoise;
+use sim::normalization::InputNormalization;
+use sim::presets::Preset;
+use sim::render::{IndividualLook, RenderOptions, WorldViewport};
+use sim::sensors::Sensor;
+use sim::surrogate::SurrogateKind;
+use sim::watchdog::WatchdogResponse;
+use sim::wind::Wind;
+use sim::Config;
+
+#[derive(Parser)]
+#[command(name = "sim", about = "Evolves little brains that learn to dodge a kill zone")]
+pub struct Cli {
+    #[command(flatten)]
+    pub sim: SimArgs,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Runs the simulation in a window (the default)
+    Run,
+    /// Runs the simulation without a window as fast as possible
+    Headless {
+        #[arg(long, default_value_t = 100)]
+        generations: i32,
+        /// Writes the final population to this file, zstd compressed if it ends in .zst
+        #[arg(long)]
+        save: Option<PathBuf>,
+        /// Continues from a file written by `--save`, including its curriculum stage
+        #[arg(long)]
+        resume: Option<PathBuf>,
+        /// Shows a live dashboard instead of printing every generation
+        #[arg(long)]
+        tui: bool,
+        /// Evaluates every generation in this many worlds on their own threads
+        #[arg(long, default_value_t = 1)]
+        worlds: usize,
+        /// How the worlds share the work: `slice` gives each a part of the population, `seeds`
+        /// gives each the whole population with a seed of its own
+        #[arg(long, default_value_t = WorldSplit::Slice)]
+        world_split: WorldSplit,
+    },
+    /// Watches a population saved by `headless --save` in a window, it does not evolve
+    Replay {
+        file: PathBuf,
+    },
+    /// Runs every champion file of a directory side by side in split-screen arenas
+    Gallery {
+        dir: PathBuf,
+        /// Shows one arena at a time and moves on after each generation or with the right arrow key
+        #[arg(long)]
+        sequential: bool,
+    },
+    /// Shows how the brain of a champion file differs from another's, per layer and per weight
+    Diff {
+        a: PathBuf,
+        b: PathBuf,
+    },
+    /// Evolves two configs from the same seed side by side with a chart of their fitness
+    Compare {
+        /// A setting of the first config, e.g. `mutation_chance=0.1`, can be repeated
+        #[arg(long)]
+        a: Vec<Setting>,
+        /// A setting of the second config, can be repeated
+        #[arg(long)]
+        b: Vec<Setting>,
+    },
+    /// Scores a single genome over one generation of clones
+    Evaluate {
+        #[arg(long)]
+        dna: Chromosome,
+    },
+    /// Scores genomes alone in a fixed set of scripted worlds and writes a scorecard, so changes
+    /// can be measured against the same benchmark
+    BenchSuite {
+        /// A genome to score, can be repeated
+        #[arg(long)]
+        dna: Vec<Chromosome>,
+        /// Scores every genome of a file written by `headless --save`
+        #[arg(long)]
+        population: Option<PathBuf>,
+        /// Runs per scenario and genome, with the seeds 0 and up
+        #[arg(long, default_value_t = 8)]
+        runs: u64,
+        #[arg(long, default_value = "scorecard.csv")]
+        scorecard: PathBuf,
+    },
+    /// Plays a champion file (one genome, like the champion_<generation>.txt the summary saves)
+    /// in many random worlds, reports how often its clones survive them and stores its
+    /// robustness score with it
+    Robustness {
+        file: PathBuf,
+        /// Where to write the champion with its score, the champion file itself is updated if
+        /// not given
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Worlds to play, with the seeds 0 and up
+        #[arg(long, default_value_t = 200)]
+        seeds: u64,
+        /// Clones of the champion in every world, as many as there are individuals if not given
+        #[arg(long)]
+        clones: Option<usize>,
+    },
+    /// Scores a genome with its small weights pruned away at every threshold
+    Prune {
+        #[arg(long)]
+        dna: Chromosome,
+        /// Comma separated magnitudes below which weights are set to zero
+        #[arg(long, value_delimiter = ',', default_value = "0,0.05,0.1,0.2,0.4,0.8")]
+        thresholds: Vec<f32>,
+    },
+    /// Shows how often each neuron of a genome's brain fires over a generation and which never do
+    Inspect {
+        #[arg(long)]
+        dna: Chromosome,
+    },
+    /// Writes the brain of a genome as an onnx model, for Netron or an inference runtime
+    Export {
+        #[arg(long)]
+        dna: Chromosome,
+        #[arg(long)]
+        onnx: PathBuf,
+    },
+    /// Runs one headless experiment per value, e.g. `mutation_chance=0.1,0.3,0.5`
+    Sweep {
+        spec: SweepSpec,
+        #[arg(long, default_value_t = 20)]
+        generations: i32,
+    },
+}
+
+//options shared by all commands
+#[derive(Args, Clone)]
+pub struct SimArgs {
+    /// Seed of the simulation, random if not given
+    #[arg(long, global = true)]
+    pub seed: Option<u64>,
+    /// Starts from a built-in setup instead of the defaults, the other options change it:
+    /// classic-killzone, maze, exploration or drifting-zones
+    #[arg(long, global = true)]
+    pub preset: Option<Preset>,
+    /// Genome to create the first generation from, can be repeated
+    #[arg(long, global = true)]
+    pub seed_dna: Vec<Chromosome>,
+    /// Weights exported from keras or numpy (.npz or .json) to create the first generation
+    /// from, can be repeated
+    #[arg(long, global = true)]
+    pub seed_weights: Vec<PathBuf>,
+    /// Serves prometheus metrics on this address, e.g. `0.0.0.0:9184`
+    #[arg(long, global = true)]
+    pub metrics: Option<String>,
+    /// Writes the fitness statistics of every generation to this csv file
+    #[arg(long, global = true)]
+    pub stats_csv: Option<PathBuf>,
+    /// The generations a row of the statistics csv covers, a file per value: 1 is the csv itself
+    /// and the others the min, mean and max next to it, like stats.100.csv
+    #[arg(long, global = true, value_delimiter = ',', default_value = "1,10,100")]
+    pub stats_tiers: Vec<usize>,
+    /// Writes the allele frequencies of every generation to this binary file, see AlleleTensor
+    #[arg(long, global = true)]
+    pub alleles: Option<PathBuf>,
+    /// Saves the windowed run to this checkpoint when it ends, by closing the window or Ctrl-C,
+    /// the generation in progress continues from its start with `headless --resume`
+    #[arg(long, global = true)]
+    pub final_checkpoint: Option<PathBuf>,
+    /// Writes the seed, the config, every config change and the summary of the run to this file
+    #[arg(long, global = true)]
+    pub manifest: Option<PathBuf>,
+    /// Adds the run to this sqlite database, with its config, statistics and champions
+    #[cfg(feature = "sqlite")]
+    #[arg(long, global = true)]
+    pub database: Option<PathBuf>,
+    #[arg(long, global = true)]
+    pub individuals: Option<usize>,
+    #[arg(long, global = true)]
+    pub movement_speed: Option<f32>,
+    /// World units an individual may move per generation before it stops, 0 is no limit
+    #[arg(long, global = true)]
+    pub movement_budget: Option<f32>,
+    #[arg(long, global = true)]
+    pub think_rate: Option<f32>,
+    #[arg(long, global = true)]
+    pub generation_time: Option<f32>,
+    #[arg(long, global = true)]
+    pub mutation_chance: Option<f32>,
+    #[arg(long, global = true)]
+    pub mutation_coeff: Option<f32>,
+    /// Measures the share of genes mutation changes and by how much, for the csv and the hud
+    #[arg(long, global = true)]
+    pub log_mutations: bool,
+    /// Measures how evenly the offspring mix the genes of their parents, for the csv and the hud
+    #[arg(long, global = true)]
+    pub log_provenance: bool,
+    /// Deterministic crowding: offspring only take the place of the parent they are closer to
+    /// if they are fitter
+    #[arg(long, global = true)]
+    pub crowding: bool,
+    /// Transforms the fitness before selection to tune the pressure: none, linear[:multiple],
+    /// sigma[:c] or power[:exponent]
+    #[arg(long, global = true)]
+    pub fitness_scaling: Option<FitnessScaling>,
+    /// Splits the population into age layers (ALPS) with random newcomers in the bottom one,
+    /// not with --crowding or --surrogate
+    #[arg(long, global = true)]
+    pub age_layers: Option<usize>,
+    /// Splits the population into fitness bands (hierarchical fair competition) that promote
+    /// whoever passes the threshold of a higher band, not with --crowding or --surrogate
+    #[arg(long, global = true)]
+    pub fitness_bands: Option<usize>,
+    /// Generations between the newcomers of the age layers or fitness bands
+    #[arg(long, global = true)]
+    pub age_gap: Option<usize>,
+    /// Screens the offspring with a model of the fitness (none, knn or rbf), only the most
+    /// promising or most uncertain of several candidates enters the world
+    #[arg(long, global = true)]
+    pub surrogate: Option<SurrogateKind>,
+    /// Candidates the surrogate picks from for every individual
+    #[arg(long, global = true)]
+    pub surrogate_candidates: Option<usize>,
+    /// How much predicted fitness the surrogate's uncertainty is worth
+    #[arg(long, global = true)]
+    pub surrogate_exploration: Option<f32>,
+    /// Breeds an archive of the best brain for every behavior (MAP-Elites) instead of a
+    /// population, with this many bins of displacement and of movement
+    #[arg(long, global = true)]
+    pub map_elites_bins: Option<usize>,
+    /// Keeps the genes of each neuron together during crossover
+    #[arg(long, global = true)]
+    pub linkage: bool,
+    /// Thinks for all individuals on the gpu, needs the gpu feature
+    #[arg(long, global = true)]
+    pub gpu: bool,
+    /// Scores every generation in this many kill zone layouts
+    #[arg(long, global = true)]
+    pub trials: Option<usize>,
+    /// How the scores of the trials are combined: mean, median or min
+    #[arg(long, global = true)]
+    pub trial_aggregation: Option<Aggregation>,
+    /// Selects by the lower confidence bound of the fitness over the trials
+    #[arg(long, global = true)]
+    pub uncertainty_selection: bool,
+    /// Takes this times the sum of the squared genes from the fitness during selection
+    #[arg(long, global = true)]
+    pub weight_decay: Option<f32>,
+    /// Starts with easy kill zones and makes them harder as the population improves
+    #[arg(long, global = true)]
+    pub curriculum: bool,
+    /// Kill zones per generation without a curriculum, at least one
+    #[arg(long, global = true)]
+    pub killzones: Option<NonZeroUsize>,
+    /// Width of every kill zone without a curriculum, the world is 2 wide
+    #[arg(long, global = true)]
+    pub killzone_width: Option<f32>,
+    /// World units per second the kill zones drift at without a curriculum
+    #[arg(long, global = true)]
+    pub killzone_speed: Option<f32>,
+    /// Scales the kill zone width by a random factor within 1 ± this every generation
+    #[arg(long, global = true)]
+    pub killzone_width_jitter: Option<f32>,
+    /// Scales the movement speed by a random factor within 1 ± this every generation
+    #[arg(long, global = true)]
+    pub speed_jitter: Option<f32>,
+    /// Where individuals start a generation: uniform, cluster or edges
+    #[arg(long, global = true)]
+    pub spawn: Option<SpawnDistribution>,
+    /// Randomly placed blocks per generation
+    #[arg(long, global = true)]
+    pub obstacles: Option<usize>,
+    /// Pushes the individuals around: none, constant, rotational or noise
+    #[arg(long, global = true)]
+    pub wind: Option<Wind>,
+    /// World units per second the wind blows at
+    #[arg(long, global = true)]
+    pub wind_strength: Option<f32>,
+    /// Region individuals start in: all, left, right, top, bottom or `min_x,min_y,max_x,max_y`,
+    /// can be repeated to spread the individuals over several regions in turns
+    #[arg(long, global = true)]
+    pub spawn_region: Vec<SpawnRegion>,
+    /// Never places kill zones over the spawn regions
+    #[arg(long, global = true)]
+    pub protect_spawn: bool,
+    /// Health lost per second inside a kill zone out of 1.0, the fitness then reflects how long
+    /// an individual survived instead of where it ended up
+    #[arg(long, global = true)]
+    pub killzone_damage: Option<f32>,
+    /// Fades the fitness out over this distance on each side of a kill zone edge instead of
+    /// scoring everybody inside a kill zone as 0
+    #[arg(long, global = true)]
+    pub near_miss_margin: Option<f32>,
+    /// Fitness per world unit an individual moves, on top of how far it gets
+    #[arg(long, global = true)]
+    pub movement_weight: Option<f32>,
+    /// Counts the movement per second instead of in total
+    #[arg(long, global = true)]
+    pub normalize_movement: bool,
+    /// Share of the fitness lost per generation spent inside kill zones
+    #[arg(long, global = true)]
+    pub killzone_time_penalty: Option<f32>,
+    /// Rewards exploring the cells of the world the population rarely visits, this much fitness
+    /// per second spent in a cell nobody has been to
+    #[arg(long, global = true)]
+    pub curiosity_weight: Option<f32>,
+    /// Fitness lost per second an individual barely moves
+    #[arg(long, global = true)]
+    pub idle_penalty: Option<f32>,
+    /// How the outputs of a brain become movement: continuous, eight-directions or thrust-turn
+    #[arg(long, global = true)]
+    pub action_encoding: Option<ActionEncoding>,
+    /// Chance of an action being replaced by a random direction, for exploration
+    #[arg(long, global = true)]
+    pub action_noise: Option<f32>,
+    /// Multiplies the action noise every generation
+    #[arg(long, global = true)]
+    pub action_noise_decay: Option<f32>,
+    /// How the senses are scaled before a brain sees them: none, ranges or running
+    #[arg(long, global = true)]
+    pub input_normalization: Option<InputNormalization>,
+    /// Leaves a sense out of the inputs of the brains: position, progress, oscillator, movement,
+    /// can_move or killzone, can be repeated
+    #[arg(long, global = true)]
+    pub disable_sensor: Vec<Sensor>,
+    /// Gives the brains a sense that is off by default: time, reward or daylight, can be repeated
+    #[arg(long, global = true)]
+    pub enable_sensor: Vec<Sensor>,
+    /// Seconds after which the time sense wraps around
+    #[arg(long, global = true)]
+    pub time_period: Option<f32>,
+    /// Seconds of a day/night cycle, at night the brains sense less of the world
+    #[arg(long, global = true)]
+    pub day_length: Option<f32>,
+    /// How much of the world the senses lose at midnight, from 0 to 1
+    #[arg(long, global = true)]
+    pub night_blindness: Option<f32>,
+    /// Gaussian noise on the senses: none, global or evolved, which scales the sigma by a gene
+    /// of every individual
+    #[arg(long, global = true)]
+    pub sensor_noise: Option<SensorNoise>,
+    /// Standard deviation of the sensor noise
+    #[arg(long, global = true)]
+    pub sensor_noise_sigma: Option<f32>,
+    /// Sin/cos pairs of the generation's progress the brains get as extra inputs, pair k
+    /// completes 2^k cycles per generation
+    #[arg(long, global = true)]
+    pub clocks: Option<usize>,
+    /// Neurons of the hidden layer of the brains
+    #[arg(long, global = true)]
+    pub hidden_neurons: Option<usize>,
+    /// Makes the hidden layer of the brains a gru that remembers from one think tick to the next
+    #[arg(long, global = true)]
+    pub recurrent: bool,
+    /// Genomes whose genes differ by less than this on average are one species
+    #[arg(long, global = true)]
+    pub species_threshold: Option<f32>,
+    /// Seconds every individual may act the same or stand still before the population counts
+    /// as collapsed
+    #[arg(long, global = true)]
+    pub watchdog_seconds: Option<f32>,
+    /// What happens to a population that collapsed besides a warning: none, reseed or hypermutate
+    #[arg(long, global = true)]
+    pub watchdog_response: Option<WatchdogResponse>,
+    /// Corner of the window the statistics text is in: top-left, top-right, bottom-left or bottom-right
+    #[arg(long, global = true)]
+    pub hud_corner: Option<HudCorner>,
+    /// Multiplies the size of the statistics text
+    #[arg(long, global = true)]
+    pub hud_scale: Option<f32>,
+    /// Comma separated lines of the statistics text, e.g. `generation,best,survivors`
+    #[arg(long, global = true, value_delimiter = ',')]
+    pub hud_fields: Vec<HudField>,
+    /// Draws without the assets directory, individuals become squares and text uses the built-in font
+    #[arg(long, global = true)]
+    pub builtin_assets: bool,
+    /// How individuals are drawn: shapes or ascii
+    #[arg(long, global = true)]
+    pub look: Option<IndividualLook>,
+    /// What a window of another shape than the world shows next to it: expand or letterbox
+    #[arg(long, global = true)]
+    pub viewport: Option<WorldViewport>,
+    /// Opens the charts, the inspector and the experiment panels in a second window, the first
+    /// one only shows the world
+    #[arg(long, global = true)]
+    pub dashboard: bool,
+}
+
+impl SimArgs {
+    pub fn config(&self) -> Config {
+        let default = self.preset.map_or_else(Config::default, Preset::config);
+
+        Config {
+            individuals: self.individuals.unwrap_or(default.individuals),
+            movement_speed: self.movement_speed.unwrap_or(default.movement_speed),
+            movement_budget: self.movement_budget.unwrap_or(default.movement_budget),
+            think_rate: self.think_rate.unwrap_or(default.think_rate),
+            generation_time: self.generation_time.unwrap_or(default.generation_time),
+            mutation_chance: self.mutation_chance.unwrap_or(default.mutation_chance),
+            mutation_coeff: self.mutation_coeff.unwrap_or(default.mutation_coeff),
+            log_mutations: self.log_mutations || default.log_mutations,
+            log_provenance: self.log_provenance || default.log_provenance,
+            crowding: self.crowding || default.crowding,
+            fitness_scaling: self.fitness_scaling.unwrap_or(default.fitness_scaling),
+            age_layers: self.age_layers.unwrap_or(default.age_layers),
+            fitness_bands: self.fitness_bands.unwrap_or(default.fitness_bands),
+            age_gap: self.age_gap.unwrap_or(default.age_gap),
+            surrogate: self.surrogate.unwrap_or(default.surrogate),
+            surrogate_candidates: self.surrogate_candidates.unwrap_or(default.surrogate_candidates),
+            surrogate_exploration: self.surrogate_exploration.unwrap_or(default.surrogate_exploration),
+            map_elites_bins: self.map_elites_bins.unwrap_or(default.map_elites_bins),
+            linkage: self.linkage || default.linkage,
+            gpu: self.gpu || default.gpu,
+            trials: self.trials.unwrap_or(default.trials),
+            trial_aggregation: self.trial_aggregation.unwrap_or(default.trial_aggregation),
+            uncertainty_selection: self.uncertainty_selection || default.uncertainty_selection,
+            weight_decay: self.weight_decay.unwrap_or(default.weight_decay),
+            curriculum: self.curriculum || default.curriculum,
+            killzones: self.killzones.map_or(default.killzones, NonZeroUsize::get),
+            killzone_width: self.killzone_width.unwrap_or(default.killzone_width),
+            killzone_speed: self.killzone_speed.unwrap_or(default.killzone_speed),
+            killzone_width_jitter: self.killzone_width_jitter.unwrap_or(default.killzone_width_jitter),
+            speed_jitter: self.speed_jitter.unwrap_or(default.speed_jitter),
+            spawn: self.spawn.unwrap_or(default.spawn),
+            obstacles: self.obstacles.unwrap_or(default.obstacles),
+            wind: self.wind.unwrap_or(default.wind),
+            wind_strength: self.wind_strength.unwrap_or(default.wind_strength),
+            spawn_regions: if self.spawn_region.is_empty() { default.spawn_regions } else { self.spawn_region.clone() },
+            protect_spawn: self.protect_spawn || default.protect_spawn,
+            killzone_damage: self.killzone_damage.unwrap_or(default.killzone_damage),
+            fitness: FitnessConfig {
+                movement_weight: self.movement_weight.unwrap_or(default.fitness.movement_weight),
+                normalize_movement: self.normalize_movement || default.fitness.normalize_movement,
+                near_miss_margin: self.near_miss_margin.unwrap_or(default.fitness.near_miss_margin),
+                killzone_time_penalty: self.killzone_time_penalty.unwrap_or(default.fitness.killzone_time_penalty),
+                curiosity_weight: self.curiosity_weight.unwrap_or(default.fitness.curiosity_weight),
+                idle_penalty: self.idle_penalty.unwrap_or(default.fitness.idle_penalty),
+            },
+            action_encoding: self.action_encoding.unwrap_or(default.action_encoding),
+            action_noise: self.action_noise.unwrap_or(default.action_noise),
+            action_noise_decay: self.action_noise_decay.unwrap_or(default.action_noise_decay),
+            input_normalization: self.input_normalization.unwrap_or(default.input_normalization),
+            disabled_sensors: if self.disable_sensor.is_empty() { default.disabled_sensors } else { self.disable_sensor.clone() },
+            enabled_sensors: if self.enable_sensor.is_empty() { default.enabled_sensors } else { self.enable_sensor.clone() },
+            time_period: self.time_period.unwrap_or(default.time_period),
+            day_length: self.day_length.unwrap_or(default.day_length),
+            night_blindness: self.night_blindness.unwrap_or(default.night_blindness),
+            sensor_noise: self.sensor_noise.unwrap_or(default.sensor_noise),
+            sensor_noise_sigma: self.sensor_noise_sigma.unwrap_or(default.sensor_noise_sigma),
+            clocks: self.clocks.unwrap_or(default.clocks),
+            hidden_neurons: self.hidden_neurons.unwrap_or(default.hidden_neurons),
+            recurrent: self.recurrent || default.recurrent,
+            species_threshold: self.species_threshold.unwrap_or(default.species_threshold),
+            watchdog_seconds: self.watchdog_seconds.unwrap_or(default.watchdog_seconds),
+            watchdog_response: self.watchdog_response.unwrap_or(default.watchdog_response),
+        }
+    }
+
+    pub fn render_options(&self) -> RenderOptions {
+        RenderOptions {
+            look: self.look.unwrap_or_default(),
+            viewport: self.viewport.unwrap_or_default(),
+            builtin_assets: self.builtin_assets,
+        }
+    }
+
+    pub fn hud(&self) -> HudConfig {
+        let default = HudConfig::default();
+
+        HudConfig {
+            corner: self.hud_corner.unwrap_or(default.corner),
+            scale: self.hud_scale.unwrap_or(default.scale),
+            fields: if self.hud_fields.is_empty() { default.fields } else { self.hud_fields.clone() },
+        }
+    }
+}
+
+//one config value that differs from the command line, as `<name>=<value>`
+#[derive(Clone)]
+pub struct Setting {
+    pub name: String,
+    pub value: String,
+}
+
+impl FromStr for Setting {
+    type Err = String;
+
+    fn from_str(setting: &str) -> Result<Self, Self::Err> {
+        let (name, value) = setting
+            .split_once('=')
+            .ok_or_else(|| format!("expected <name>=<value> but got '{setting}'"))?;
+        Config::default().set(name, value.trim())?;
+
+        Ok(Self { name: name.to_string(), value: value.trim().to_string() })
+    }
+}
+
+//the config with the settings applied and a name that lists them
+pub fn variant(config: &Config, settings: &[Setting]) -> Result<Variant, String> {
+    let mut config = config.clone();
+    for setting in settings {
+        config.set(&setting.name, &setting.value)?;
+    }
+
+    let name = if settings.is_empty() {
+        "unchanged".to_string()
+    } else {
+        settings.iter().map(|setting| format!("{}={}", setting.name, setting.value)).collect::<Vec<_>>().join(", ")
+    };
+    Ok(Variant { name, config })
+}
+
+#[derive(Clone)]
+pub struct SweepSpec {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+impl FromStr for SweepSpec {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (name, values) = spec
+            .split_once('=')
+            .ok_or_else(|| format!("expected <name>=<value>,<value>,... but got '{spec}'"))?;
+
+        let values: Vec<String> = values.split(',').map(|value| value.trim().to_string()).collect();
+        if values.iter().any(|value| value.is_empty()) {
+            return Err(format!("empty value in '{spec}'"));
+        }
+
+        // fail on typos before any experiment has run
+        for value in &values {
+            Config::default().set(name, value)?;
+        }
+
+        Ok(Self { name: name.to_string(), values })
+    }
+}
@@ -0,0 +1,226 @@
+use bevy::prelude::*;
+
+use crate::gallery::{clear_arenas, draw_arena, ArenaSprite};
+use crate::headless::{self, headless_app};
+use crate::hud::{load_font, UiFont};
+use crate::render::{fit_viewport, spawn_camera, CLEAR};
+use crate::{Config, Statistics};
+
+//of the two sides, in their arenas, labels and chart lines
+const COLORS: [Color; 2] = [Color::rgb(1.0, 0.6, 0.2), Color::rgb(0.3, 0.8, 1.0)];
+const FONT_SIZE: f32 = 16.0;
+//the arenas fill the top half of the window, the chart the bottom half
+const ARENA_SCALE: f32 = 0.43;
+const ARENA_CENTERS: [Vec2; 2] = [Vec2::new(-0.5, 0.5), Vec2::new(0.5, 0.5)];
+const CHART_MIN: Vec2 = Vec2::new(-0.9, -0.9);
+const CHART_MAX: Vec2 = Vec2::new(0.9, -0.1);
+const BEST_LINE: f32 = 0.008;
+const AVERAGE_LINE: f32 = 0.004;
+
+//one of the configs that are compared, named after how it differs
+#[derive(Clone, Debug)]
+pub struct Variant {
+    pub name: String,
+    pub config: Config,
+}
+
+struct Side {
+    name: String,
+    app: App,
+    //of every generation so far
+    history: Vec<Statistics>,
+}
+
+//not a Resource, the apps of the sides are not Sync
+struct Comparison {
+    sides: [Side; 2],
+    //the generation both sides run up to before either moves on
+    boundary: i32,
+}
+
+#[derive(Component)]
+struct SideLabel(usize);
+
+#[derive(Component)]
+struct ChartLabel;
+
+#[derive(Component)]
+struct ChartSprite;
+
+//evolves two configs from the same seed side by side, their generations start together, a
+//chart below them shows the best and average fitness of both
+pub struct ComparePlugin {
+    pub variants: [Variant; 2],
+    pub seed: u64,
+}
+
+impl Plugin for ComparePlugin {
+    fn build(&self, app: &mut App) {
+        let side = |variant: &Variant| Side {
+            name: variant.name.clone(),
+            app: headless_app(variant.config.clone(), self.seed),
+            history: Vec::new(),
+        };
+        let comparison = Comparison { sides: [side(&self.variants[0]), side(&self.variants[1])], boundary: 1 };
+
+        app.insert_non_send_resource(comparison)
+            .insert_resource(ClearColor(CLEAR))
+            .add_startup_system_to_stage(StartupStage::PreStartup, load_font)
+            .add_startup_system(spawn_camera)
+            .add_system(fit_viewport)
+            .add_startup_system(add_labels)
+            .add_system(step_sides)
+            .add_system(draw_sides.after(step_sides))
+            .add_system(draw_chart.after(step_sides))
+            .add_system(update_labels.after(step_sides));
+    }
+}
+
+//from world units to percent of the window, which spans -1..1
+fn percent(position: Vec2) -> UiRect {
+    UiRect {
+        left: Val::Percent((position.x + 1.0) * 50.0),
+        top: Val::Percent((1.0 - position.y) * 50.0),
+        ..default()
+    }
+}
+
+fn add_labels(mut commands: Commands, font: Res<UiFont>) {
+    let label = |position: Vec2, color: Color| {
+        TextBundle::from_section("", TextStyle { font: font.0.clone(), font_size: FONT_SIZE, color })
+            .with_style(Style { position_type: PositionType::Absolute, position: percent(position), ..default() })
+    };
+
+    for (i, (center, color)) in ARENA_CENTERS.iter().zip(COLORS).enumerate() {
+        let bundle = label(*center + Vec2::new(-ARENA_SCALE, ARENA_SCALE), color);
+        commands.spawn((bundle, SideLabel(i)));
+    }
+    let bundle = label(Vec2::new(CHART_MIN.x, CHART_MAX.y + 0.06), Color::WHITE);
+    commands.spawn((bundle, ChartLabel));
+}
+
+//moves every side that has not reached the boundary yet, the boundary moves on once both have
+fn step_sides(mut comparison: NonSendMut<Comparison>) {
+    let boundary = comparison.boundary;
+
+    for side in &mut comparison.sides {
+        if headless::generation(&mut side.app) >= boundary {
+            continue;
+        }
+        side.app.update();
+        if headless::generation(&mut side.app) >= boundary {
+            side.history.push(headless::statistics(&mut side.app));
+        }
+    }
+
+    if comparison.sides.iter().all(|side| side.history.len() >= boundary as usize) {
+        let [a, b] = &comparison.sides;
+        let (a, b) = (&a.history[boundary as usize - 1], &b.history[boundary as usize - 1]);
+        info!("generation {boundary}: best {:.3} vs {:.3}, average {:.3} vs {:.3}", a.best_fitness, b.best_fitness, a.average_fitness, b.average_fitness);
+        comparison.boundary += 1;
+    }
+}
+
+fn draw_sides(mut commands: Commands,
+              mut comparison: NonSendMut<Comparison>,
+              sprites: Query<Entity, With<ArenaSprite>>) {
+    clear_arenas(&mut commands, &sprites);
+
+    for (side, center) in comparison.sides.iter_mut().zip(ARENA_CENTERS) {
+        draw_arena(&mut commands, &mut side.app.world, center, ARENA_SCALE);
+    }
+}
+
+//redrawn when a generation boundary has been passed
+fn draw_chart(mut commands: Commands,
+              comparison: NonSend<Comparison>,
+              mut drawn: Local<i32>,
+              sprites: Query<Entity, With<ChartSprite>>) {
+    if *drawn == comparison.boundary {
+        return;
+    }
+    *drawn = comparison.boundary;
+    for entity in sprites.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let size = CHART_MAX - CHART_MIN;
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite { color: Color::rgb(0.14, 0.14, 0.14), custom_size: Some(size), ..default() },
+            transform: Transform::from_translation(((CHART_MIN + CHART_MAX) / 2.0).extend(0.0)),
+            ..default()
+        },
+        ChartSprite,
+    ));
+
+    let generations = comparison.sides.iter().map(|side| side.history.len()).min().unwrap_or(0);
+    if generations < 2 {
+        return;
+    }
+    let max = chart_max(&comparison);
+    let point = |generation: usize, fitness: f32| {
+        CHART_MIN + Vec2::new(generation as f32 / (generations - 1) as f32, fitness / max) * size
+    };
+
+    for (side, color) in comparison.sides.iter().zip(COLORS) {
+        let history = &side.history[..generations];
+        for (width, alpha, fitness) in [
+            (BEST_LINE, 1.0, history.iter().map(|statistics| statistics.best_fitness).collect::<Vec<_>>()),
+            (AVERAGE_LINE, 0.6, history.iter().map(|statistics| statistics.average_fitness).collect()),
+        ] {
+            let mut color = color;
+            color.set_a(alpha);
+            for (generation, pair) in fitness.windows(2).enumerate() {
+                let (from, to) = (point(generation, pair[0]), point(generation + 1, pair[1]));
+                let line = to - from;
+                commands.spawn((
+                    SpriteBundle {
+                        sprite: Sprite { color, custom_size: Some(Vec2::new(line.length(), width)), ..default() },
+                        transform: Transform::from_translation(((from + to) / 2.0).extend(1.0))
+                            .with_rotation(Quat::from_rotation_z(line.y.atan2(line.x))),
+                        ..default()
+                    },
+                    ChartSprite,
+                ));
+            }
+        }
+    }
+}
+
+//the top of the fitness axis
+fn chart_max(comparison: &Comparison) -> f32 {
+    comparison
+        .sides
+        .iter()
+        .flat_map(|side| &side.history)
+        .map(|statistics| statistics.best_fitness)
+        .fold(0.0, f32::max)
+        .max(f32::EPSILON)
+}
+
+fn update_labels(comparison: NonSend<Comparison>,
+                 mut sides: Query<(&SideLabel, &mut Text), Without<ChartLabel>>,
+                 mut chart: Query<&mut Text, With<ChartLabel>>) {
+    for (label, mut text) in sides.iter_mut() {
+        let side = &comparison.sides[label.0];
+        let mut value = side.name.clone();
+        match side.history.last() {
+            Some(last) => value.push_str(&format!(
+                "\ngeneration {}: best {:.3}, average {:.3}",
+                last.generation, last.best_fitness, last.average_fitness,
+            )),
+            None => value.push_str("\nfirst generation running"),
+        }
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+
+    for mut text in chart.iter_mut() {
+        let value = format!("best (thick) and average fitness per generation, up to {:.2}", chart_max(&comparison));
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+use crate::field::ScalarField;
+use crate::{Frozen, Health, Nizm};
+
+//cells per side of the visit counts
+pub const CELLS: usize = 32;
+
+//the marker of the visit counts
+pub enum Visits {}
+
+//the seconds the individuals spent in every cell over the whole run, only with curiosity
+pub type VisitField = ScalarField<Visits>;
+
+//the intrinsic reward per second for being in a cell the population spent `visits` seconds in
+//so far, 1.0 in a cell nobody has been to and fading with the square root of the count, so
+//individuals that find the corners the others never reach stand out even while the fitness
+//itself is sparse
+pub fn novelty(visits: f32) -> f32 {
+    1.0 / (1.0 + visits.max(0.0)).sqrt()
+}
+
+//every individual collects the novelty of its cell before its own visit is counted
+pub(crate) fn explore(time: Res<Time>,
+                      visits: Option<ResMut<VisitField>>,
+                      mut query: Query<(&mut Nizm, &Transform, Option<&Health>), Without<Frozen>>) {
+    let Some(mut visits) = visits else {
+        return;
+    };
+    let delta = time.delta_seconds();
+    for (mut nizm, transform, health) in query.iter_mut() {
+        if health.is_some_and(|health| !health.is_alive()) {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        nizm.curiosity += novelty(visits.values()[visits.index(position)]) * delta;
+        visits.add(position, delta);
+    }
+}
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+
+//how hard the kill zones make it to survive a generation
+#[derive(Clone, Debug, PartialEq)]
+pub struct Difficulty {
+    //horizontal extent of every kill zone, the world is 2.0 wide
+    pub killzone_width: f32,
+    //how fast the kill zones drift towards the other side, in world units per second
+    pub killzone_speed: f32,
+    pub killzones: usize,
+}
+
+//the environment of a run without a curriculum by default, see Config::difficulty
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self {
+            killzone_width: 1.0,
+            killzone_speed: 0.0,
+            killzones: 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stage {
+    pub difficulty: Difficulty,
+    //the average fitness a generation needs to move on to the next stage
+    pub advance_at: f32,
+}
+
+//starts with an easy environment and makes it harder whenever the population masters the
+//current one, so early generations get a useful signal instead of dying out
+#[derive(Resource, Clone, Debug)]
+pub struct Curriculum {
+    stages: Vec<Stage>,
+    stage: usize,
+}
+
+impl Curriculum {
+    pub fn new(stages: Vec<Stage>) -> Self {
+        assert!(!stages.is_empty());
+        Self { stages, stage: 0 }
+    }
+
+    //continues a run that was checkpointed mid curriculum
+    pub fn at_stage(mut self, stage: usize) -> Self {
+        self.stage = stage.min(self.stages.len() - 1);
+        self
+    }
+
+    pub fn stage(&self) -> usize {
+        self.stage
+    }
+
+    pub fn stages(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn difficulty(&self) -> &Difficulty {
+        &self.stages[self.stage].difficulty
+    }
+
+    //moves on to the next stage if the generation did well enough, the last stage is kept
+    //forever, returns whether the stage changed
+    pub fn advance(&mut self, average_fitness: f32) -> bool {
+        let last = self.stage + 1 == self.stages.len();
+        if last || average_fitness < self.stages[self.stage].advance_at {
+            return false;
+        }
+
+        self.stage += 1;
+        true
+    }
+}
+
+//a narrow, still kill zone first, then wider, then moving, then several
+impl Default for Curriculum {
+    fn default() -> Self {
+        let stage = |killzone_width, killzone_speed, killzones, advance_at| Stage {
+            difficulty: Difficulty { killzone_width, killzone_speed, killzones },
+            advance_at,
+        };
+
+        Self::new(vec![
+            stage(0.5, 0.0, 1, 2.0),
+            stage(0.75, 0.0, 1, 2.2),
+            stage(1.0, 0.0, 1, 2.4),
+            stage(1.0, 0.05, 1, 2.2),
+            stage(0.6, 0.1, 2, f32::INFINITY),
+        ])
+    }
+}
+
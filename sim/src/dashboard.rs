@@ -0,0 +1,108 @@
+use bevy::app::AppExit;
+use bevy::core_pipeline::clear_color::ClearColorConfig;
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_graph::RenderGraph;
+use bevy::render::view::RenderLayers;
+use bevy::render::RenderApp;
+use bevy::window::{CreateWindow, WindowClosed, WindowId};
+use bevy_inspector_egui::bevy_egui::{self, EguiContext, EguiPlugin};
+use bevy_inspector_egui::{egui, WorldInspectorParams};
+
+use crate::render::CLEAR;
+
+const DASHBOARD_EGUI_PASS: &str = "dashboard_egui_pass";
+//no entity is drawn on it, the camera of the dashboard only clears the window
+const DASHBOARD_LAYER: u8 = RenderLayers::TOTAL_LAYERS as u8 - 1;
+
+//the window the charts, the inspector and the dashboard of the experiment are drawn in
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Dashboard(pub WindowId);
+
+//the egui context of the panels, the one of the dashboard if it is open, the one of the arena
+//otherwise
+pub fn panels<'a>(egui_context: &'a mut EguiContext, dashboard: Option<&Dashboard>) -> Option<&'a egui::Context> {
+    egui_context.try_ctx_for_window_mut(panel_window(dashboard))
+}
+
+//textures belong to the context they were loaded into, panels with one load it again when
+//this changes
+pub fn panel_window(dashboard: Option<&Dashboard>) -> WindowId {
+    dashboard.map_or(WindowId::primary(), |dashboard| dashboard.0)
+}
+
+//whether `camera` draws the arena and not the background of the dashboard
+pub(crate) fn shows_arena(camera: &Camera) -> bool {
+    camera.target == RenderTarget::Window(WindowId::primary())
+}
+
+//opens a second window for the panels, so the primary one shows nothing but the arena, the
+//panels move back to it when the dashboard is closed
+pub struct DashboardPlugin;
+
+impl Plugin for DashboardPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+        let dashboard = Dashboard(WindowId::new());
+
+        app.insert_resource(dashboard)
+            .add_startup_system(open_dashboard)
+            .add_system(close_dashboard);
+        if let Some(mut inspector) = app.world.get_resource_mut::<WorldInspectorParams>() {
+            inspector.window = dashboard.0;
+        }
+
+        //egui only draws into the windows it has a pass for
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            let mut graph = render_app.world.resource_mut::<RenderGraph>();
+            bevy_egui::setup_pipeline(&mut graph, bevy_egui::RenderGraphConfig { window_id: dashboard.0, egui_pass: DASHBOARD_EGUI_PASS });
+        }
+    }
+}
+
+fn open_dashboard(mut commands: Commands,
+                  dashboard: Res<Dashboard>,
+                  windows: Res<Windows>,
+                  mut create_window: EventWriter<CreateWindow>) {
+    let height = windows.get_primary().map_or(800.0, Window::height);
+    create_window.send(CreateWindow {
+        id: dashboard.0,
+        descriptor: WindowDescriptor {
+            title: "Rustism dashboard".to_string(),
+            width: 0.75 * height,
+            height,
+            ..default()
+        },
+    });
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera { target: RenderTarget::Window(dashboard.0), ..default() },
+            camera_2d: Camera2d { clear_color: ClearColorConfig::Custom(CLEAR) },
+            ..default()
+        },
+        RenderLayers::layer(DASHBOARD_LAYER),
+    ));
+}
+
+//closing the arena ends the run like with a single window, closing the dashboard brings its
+//panels back to the arena
+fn close_dashboard(mut commands: Commands,
+                   dashboard: Option<Res<Dashboard>>,
+                   inspector: Option<ResMut<WorldInspectorParams>>,
+                   mut closed: EventReader<WindowClosed>,
+                   mut exit: EventWriter<AppExit>) {
+    for closed in closed.iter() {
+        if closed.id == WindowId::primary() {
+            exit.send(AppExit);
+        } else if dashboard.as_ref().is_some_and(|dashboard| dashboard.0 == closed.id) {
+            info!("the dashboard was closed, its panels are back in the arena");
+            commands.remove_resource::<Dashboard>();
+            if let Some(mut inspector) = inspector {
+                inspector.window = WindowId::primary();
+            }
+            return;
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::path::Path;
+use bevy::prelude::*;
+use lib_natural_selection::{Chromosome, DnaError, Observer, Statistics};
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::Config;
+
+//the schema is built by running these in order, the user_version of a database is the number
+//that already ran, so databases of older builds are upgraded when they are opened
+const MIGRATIONS: &[&str] = &["
+    CREATE TABLE IF NOT EXISTS runs (
+        id INTEGER PRIMARY KEY,
+        seed INTEGER NOT NULL,
+        config TEXT NOT NULL,
+        started TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE TABLE IF NOT EXISTS generations (
+        run INTEGER NOT NULL REFERENCES runs(id),
+        generation INTEGER NOT NULL,
+        min_fitness REAL NOT NULL,
+        max_fitness REAL NOT NULL,
+        avg_fitness REAL NOT NULL,
+        avg_fitness_stderr REAL NOT NULL,
+        takeover REAL NOT NULL,
+        distinct_genotypes REAL NOT NULL,
+        selection_intensity REAL NOT NULL,
+        PRIMARY KEY (run, generation)
+    );
+    CREATE TABLE IF NOT EXISTS genomes (
+        run INTEGER NOT NULL REFERENCES runs(id),
+        generation INTEGER NOT NULL,
+        fitness REAL NOT NULL,
+        dna TEXT NOT NULL
+    );
+"];
+
+//one row of the fitness curve of a run
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GenerationFitness {
+    pub generation: usize,
+    pub max_fitness: f32,
+    pub avg_fitness: f32,
+}
+
+//stores the config, the statistics of every generation and every new champion of runs in a
+//sqlite file, so they can be analyzed with sql instead of parsing csv files
+pub struct ExperimentDatabase {
+    connection: Connection,
+    //the run the observer writes to
+    run: i64,
+}
+
+impl ExperimentDatabase {
+    //adds a new run to the database at `path`, creating it if needed
+    pub fn create(path: impl AsRef<Path>, config: &Config, seed: u64) -> rusqlite::Result<Self> {
+        let mut connection = Connection::open(path)?;
+        migrate(&mut connection)?;
+        //sqlite integers are signed, the seed keeps its bits
+        connection.execute("INSERT INTO runs (seed, config) VALUES (?1, ?2)", params![seed as i64, format!("{config:#?}")])?;
+        let run = connection.last_insert_rowid();
+
+        Ok(Self { connection, run })
+    }
+
+    //an existing database for analysis, the observer writes to its latest run
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        let mut connection = Connection::open(path)?;
+        migrate(&mut connection)?;
+        let run = connection.query_row("SELECT COALESCE(MAX(id), 0) FROM runs", [], |row| row.get(0))?;
+
+        Ok(Self { connection, run })
+    }
+
+    pub fn run(&self) -> i64 {
+        self.run
+    }
+
+    //the ids of all runs in the database, oldest first
+    pub fn runs(&self) -> rusqlite::Result<Vec<i64>> {
+        let mut statement = self.connection.prepare("SELECT id FROM runs ORDER BY id")?;
+        let runs = statement.query_map([], |row| row.get(0))?.collect();
+        runs
+    }
+
+    //the fittest champion of a run and its fitness
+    pub fn best_genome(&self, run: i64) -> rusqlite::Result<Option<(Chromosome, f32)>> {
+        let best: Option<(String, f64)> = self
+            .connection
+            .query_row(
+                "SELECT dna, fitness FROM genomes WHERE run = ?1 ORDER BY fitness DESC LIMIT 1",
+                [run],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        best.map(|(dna, fitness)| {
+            let chromosome = dna
+                .parse()
+                .map_err(|e: DnaError| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(e)))?;
+            Ok((chromosome, fitness as f32))
+        })
+        .transpose()
+    }
+
+    pub fn fitness_curve(&self, run: i64) -> rusqlite::Result<Vec<GenerationFitness>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT generation, max_fitness, avg_fitness FROM generations WHERE run = ?1 ORDER BY generation")?;
+        let curve = statement
+            .query_map([run], |row| {
+                Ok(GenerationFitness {
+                    generation: row.get::<_, i64>(0)? as usize,
+                    max_fitness: row.get::<_, f64>(1)? as f32,
+                    avg_fitness: row.get::<_, f64>(2)? as f32,
+                })
+            })?
+            .collect();
+        curve
+    }
+}
+
+fn migrate(connection: &mut Connection) -> rusqlite::Result<()> {
+    let version: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > MIGRATIONS.len() {
+        let message = format!("database schema version {version} is newer than {}", MIGRATIONS.len());
+        return Err(rusqlite::Error::FromSqlConversionFailure(0, Type::Integer, message.into()));
+    }
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", i + 1)?;
+        transaction.commit()?;
+    }
+    Ok(())
+}
+
+impl Observer for ExperimentDatabase {
+    fn on_generation_end(&mut self, statistics: &Statistics) {
+        let result = self.connection.execute(
+            "INSERT INTO generations VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                self.run,
+                statistics.generation as i64,
+                statistics.min_fitness,
+                statistics.max_fitness,
+                statistics.avg_fitness,
+                statistics.avg_fitness_stderr,
+                statistics.takeover,
+                statistics.distinct_genotypes,
+                statistics.selection_intensity,
+            ],
+        );
+
+        if let Err(err) = result {
+            warn!("could not write statistics to the database: {err}");
+        }
+    }
+
+    fn on_new_champion(&mut self, generation: usize, chromosome: &Chromosome, fitness: f32) {
+        let result = self.connection.execute(
+            "INSERT INTO genomes VALUES (?1, ?2, ?3, ?4)",
+            params![self.run, generation as i64, fitness, chromosome.to_string()],
+        );
+
+        if let Err(err) = result {
+            warn!("could not write the champion to the database: {err}");
+        }
+    }
+}
@@ -2,7 +2,8 @@ use bevy::prelude::*;
 use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
 use bevy_inspector_egui::{RegisterInspectable, WorldInspectorPlugin};
 
-use crate::{Nizm, Statistics};
+use sim::inspection::InspectionPlugin;
+use sim::{Nizm, Statistics};
 
 pub struct DebugPlugin;
 
@@ -11,9 +12,12 @@ impl Plugin for DebugPlugin {
         if cfg!(debug_assertions) {
             app.add_plugin(WorldInspectorPlugin::new())
                 .add_plugin(LogDiagnosticsPlugin::default())
-                .add_plugin(FrameTimeDiagnosticsPlugin::default())
+                .add_plugin(FrameTimeDiagnosticsPlugin)
                 .register_inspectable::<Statistics>()
                 .register_inspectable::<Nizm>();
+        } else {
+            //release builds go without the world inspector
+            app.add_plugin(InspectionPlugin);
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+use lib_natural_selection::Chromosome;
+use lib_neural_network::{LayerDiff, Network};
+
+use crate::gallery::Champion;
+use crate::render::CLEAR;
+use crate::{check_genomes, Config, Nizm};
+
+//the side of a heatmap cell in points
+const CELL: f32 = 10.0;
+
+//how the brain of genome `b` differs from that of `a` per layer, see Network::diff
+pub fn layer_diffs(config: &Config, a: &Chromosome, b: &Chromosome) -> Result<Vec<LayerDiff>, String> {
+    check_genomes(config, [a, b])?;
+
+    let network = |chromosome: &Chromosome| Network::from_data(&Nizm::topology(config), chromosome.clone());
+    Ok(network(a).diff(&network(b)).expect("same topology"))
+}
+
+#[derive(Resource)]
+struct ChampionDiff {
+    names: [String; 2],
+    layers: Vec<LayerDiff>,
+    //the largest difference of all layers, the heatmaps are colored relative to it
+    max_abs: f32,
+}
+
+//shows how the brain of one champion differs from another, with the distance of every layer
+//and a heatmap of every gene, red where the second one's gene is larger and blue where it is
+//smaller
+pub struct DiffPlugin {
+    pub champions: [Champion; 2],
+    pub layers: Vec<LayerDiff>,
+}
+
+impl Plugin for DiffPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+
+        app.insert_resource(ChampionDiff {
+            names: self.champions.clone().map(|champion| champion.name),
+            layers: self.layers.clone(),
+            max_abs: self.layers.iter().map(LayerDiff::max_abs).fold(0.0, f32::max),
+        })
+        .insert_resource(ClearColor(CLEAR))
+        .add_system(diff_panel);
+    }
+}
+
+fn diff_panel(mut egui_context: ResMut<EguiContext>, diff: Res<ChampionDiff>) {
+    egui::CentralPanel::default().show(egui_context.ctx_mut(), |ui| {
+        ui.heading(format!("{} → {}", diff.names[0], diff.names[1]));
+
+        egui::Grid::new("layers").striped(true).show(ui, |ui| {
+            ui.label("layer");
+            ui.label("genes");
+            ui.label("l2");
+            ui.label("mean |Δ|");
+            ui.label("max |Δ|");
+            ui.end_row();
+
+            for (i, layer) in diff.layers.iter().enumerate() {
+                ui.label(format!("{i}: {} → {}", layer.inputs, layer.outputs));
+                ui.label(layer.differences.len().to_string());
+                ui.label(format!("{:.3}", layer.l2()));
+                ui.label(format!("{:.3}", layer.mean_abs()));
+                ui.label(format!("{:.3}", layer.max_abs()));
+                ui.end_row();
+            }
+        });
+        let total = diff.layers.iter().map(|layer| layer.l2().powi(2)).sum::<f32>().sqrt();
+        ui.label(format!("l2 over all layers {total:.3}"));
+
+        egui::ScrollArea::both().show(ui, |ui| {
+            for (i, layer) in diff.layers.iter().enumerate() {
+                ui.separator();
+                ui.label(format!("layer {i}, a row per neuron, its bias first"));
+                heatmap(ui, layer, diff.max_abs);
+            }
+        });
+    });
+}
+
+fn heatmap(ui: &mut egui::Ui, layer: &LayerDiff, max_abs: f32) {
+    let columns = 1 + layer.inputs;
+    let size = egui::vec2(columns as f32 * CELL, layer.outputs as f32 * CELL);
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+    let origin = response.rect.min;
+
+    for output in 0..layer.outputs {
+        for input in 0..columns {
+            let difference = layer.at(output, input);
+            let strength = if max_abs > 0.0 { (difference.abs() / max_abs * 255.0) as u8 } else { 0 };
+            let color = if difference > 0.0 {
+                egui::Color32::from_rgb(strength, 0, 0)
+            } else {
+                egui::Color32::from_rgb(0, 0, strength)
+            };
+            let min = origin + egui::vec2(input as f32 * CELL, output as f32 * CELL);
+            painter.rect_filled(egui::Rect::from_min_size(min, egui::vec2(CELL - 1.0, CELL - 1.0)), 0.0, color);
+        }
+    }
+
+    if let Some(position) = response.hover_pos() {
+        let cell = (position - origin) / CELL;
+        let (output, input) = (cell.y as usize, cell.x as usize);
+        if output < layer.outputs && input < columns {
+            let gene = if input == 0 { "bias".to_string() } else { format!("input {}", input - 1) };
+            response.on_hover_text(format!("neuron {output}, {gene}: {:+.3}", layer.at(output, input)));
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+
+use crate::render::Selected;
+use crate::{Nizm, Statistics};
+
+//the range of the sliders, genes start within ±1 but mutation carries them further
+const RANGE: f32 = 5.0;
+
+//a gene of the edited brain, `column` 0 is the bias of the neuron and the weights follow
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Gene {
+    layer: usize,
+    neuron: usize,
+    column: usize,
+}
+
+impl Gene {
+    fn get(self, nizm: &Nizm) -> f32 {
+        match self.column {
+            0 => nizm.network.bias(self.layer, self.neuron),
+            column => nizm.network.weight(self.layer, self.neuron, column - 1),
+        }
+    }
+
+    fn set(self, nizm: &mut Nizm, value: f32) {
+        match self.column {
+            0 => nizm.network.set_bias(self.layer, self.neuron, value),
+            column => nizm.network.set_weight(self.layer, self.neuron, column - 1, value),
+        }
+    }
+}
+
+//the neuron shown of the selected individual and the values its edited genes had before, the
+//edits are forgotten when another individual is selected or the genes are bred anew
+#[derive(Resource, Default)]
+struct BrainEditor {
+    entity: Option<Entity>,
+    generation: i32,
+    layer: usize,
+    neuron: usize,
+    undo: Vec<(Gene, f32)>,
+}
+
+//sliders for every gene of a neuron of the selected individual's brain, to try out what a
+//weight does while it runs, brains thinking on the gpu only pick the edits up next generation
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+
+        app.init_resource::<BrainEditor>()
+            .add_system(editor_panel);
+    }
+}
+
+fn editor_panel(mut egui_context: ResMut<EguiContext>,
+                mut editor: ResMut<BrainEditor>,
+                mut selected: Query<(Entity, &mut Nizm), With<Selected>>,
+                statistics: Query<&Statistics>) {
+    let Ok((entity, mut nizm)) = selected.get_single_mut() else {
+        return;
+    };
+    let generation = statistics.get_single().map(|statistics| statistics.generation).unwrap_or(0);
+    if editor.entity != Some(entity) || editor.generation != generation {
+        *editor = BrainEditor { entity: Some(entity), generation, ..default() };
+    }
+
+    let shapes: Vec<_> = nizm.network.layer_shapes().collect();
+    egui::Window::new("Brain editor").show(egui_context.ctx_mut(), |ui| {
+        ui.horizontal(|ui| {
+            ui.label("layer");
+            for layer in 0..shapes.len() {
+                if ui.selectable_label(editor.layer == layer, layer.to_string()).clicked() {
+                    editor.layer = layer;
+                    editor.neuron = 0;
+                }
+            }
+        });
+        let (inputs, outputs) = shapes[editor.layer];
+        ui.add(egui::Slider::new(&mut editor.neuron, 0..=outputs - 1).text("neuron"));
+
+        egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+            for column in 0..=inputs {
+                let gene = Gene { layer: editor.layer, neuron: editor.neuron, column };
+                let before = gene.get(&nizm);
+                let mut value = before;
+                let label = if column == 0 { "bias".to_string() } else { format!("input {}", column - 1) };
+                let slider = egui::Slider::new(&mut value, -RANGE..=RANGE).clamp_to_range(false).text(label);
+                if ui.add(slider).changed() {
+                    //a drag is one edit
+                    if editor.undo.last().map(|(last, _)| *last) != Some(gene) {
+                        editor.undo.push((gene, before));
+                    }
+                    gene.set(&mut nizm, value);
+                }
+            }
+        });
+
+        ui.separator();
+        if ui.add_enabled(!editor.undo.is_empty(), egui::Button::new(format!("undo ({})", editor.undo.len()))).clicked() {
+            if let Some((gene, before)) = editor.undo.pop() {
+                gene.set(&mut nizm, before);
+                (editor.layer, editor.neuron) = (gene.layer, gene.neuron);
+            }
+        }
+    });
+}
@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+use bevy::window::WindowId;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+use lib_natural_selection::EliteArchive;
+
+use crate::dashboard::{self, Dashboard};
+use crate::{Evolution, Statistics};
+
+//the illumination of the MAP-Elites archive: a cell per combination of displacement (left to
+//right) and movement (bottom to top), colored by the fitness of its elite from dark blue to
+//yellow, black while nobody has shown that behavior, only while MAP-Elites breeds the population
+pub struct ElitesPlugin;
+
+impl Plugin for ElitesPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+        app.add_system(elites_panel);
+    }
+}
+
+fn elites_panel(mut egui_context: ResMut<EguiContext>,
+                dashboard: Option<Res<Dashboard>>,
+                evolution: Option<NonSend<Evolution>>,
+                statistics: Query<&Statistics>,
+                mut texture: Local<Option<(i32, WindowId, egui::TextureHandle)>>) {
+    let Some(archive) = evolution.as_ref().and_then(|evolution| evolution.elites()) else {
+        return;
+    };
+    let generation = statistics.get_single().map_or(0, |statistics| statistics.generation);
+
+    let window = dashboard::panel_window(dashboard.as_deref());
+    let Some(ctx) = egui_context.try_ctx_for_window_mut(window) else {
+        return;
+    };
+    if texture.as_ref().is_none_or(|(drawn, loaded, _)| *drawn != generation || *loaded != window) {
+        *texture = Some((generation, window, ctx.load_texture("elites", illumination(archive), egui::TextureOptions::NEAREST)));
+    }
+
+    egui::Window::new("Elites").show(ctx, |ui| {
+        let best = archive.best().map_or(0.0, |elite| elite.fitness);
+        ui.label(format!("{} elites, {:.0}% coverage", archive.len(), archive.coverage() * 100.0));
+        ui.label(format!("best fitness {best:.2}, qd score {:.1}", archive.qd_score()));
+        ui.label("displacement → movement ↑");
+        if let Some((_, _, texture)) = texture.as_ref() {
+            ui.image(texture.id(), egui::vec2(300.0, 300.0));
+        }
+    });
+}
+
+fn illumination(archive: &EliteArchive) -> egui::ColorImage {
+    let &[columns, rows] = archive.bins() else {
+        unreachable!("the archive of the sim has two descriptors");
+    };
+    let best = archive.best().map_or(0.0, |elite| elite.fitness).max(f32::EPSILON);
+
+    let mut image = egui::ColorImage::new([columns, rows], egui::Color32::BLACK);
+    for (cell, elite) in archive.cells().iter().enumerate() {
+        let Some(elite) = elite else {
+            continue;
+        };
+        let level = (elite.fitness / best).clamp(0.0, 1.0);
+        let (x, y) = (cell % columns, rows - 1 - cell / columns);
+        image[(x, y)] = egui::Color32::from_rgb((level * 255.0) as u8, (level * 220.0) as u8, ((1.0 - level) * 160.0) as u8 + 40);
+    }
+    image
+}
@@ -0,0 +1,243 @@
+use std::fmt;
+use std::str::FromStr;
+use bevy::prelude::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::curriculum::Difficulty;
+use crate::{Config, KillZone};
+
+//how far a cluster's center may be from the middle of the world and how far its
+//individuals are spread around it
+const CLUSTER_RANGE: f32 = 0.6;
+const CLUSTER_SPREAD: f32 = 0.3;
+//individuals spawned at the edges keep this much distance to the middle
+const EDGE: f32 = 0.8;
+
+//where individuals appear at the start of a generation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpawnDistribution {
+    #[default]
+    Uniform,
+    //around a random point
+    Cluster,
+    //close to the left and right edge of the world
+    Edges,
+}
+
+impl FromStr for SpawnDistribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "uniform" => Ok(Self::Uniform),
+            "cluster" => Ok(Self::Cluster),
+            "edges" => Ok(Self::Edges),
+            _ => Err(format!("unknown spawn distribution '{s}', expected uniform, cluster or edges")),
+        }
+    }
+}
+
+impl fmt::Display for SpawnDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Uniform => "uniform",
+            Self::Cluster => "cluster",
+            Self::Edges => "edges",
+        })
+    }
+}
+
+//a box individuals start in, in world coordinates
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpawnRegion {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl SpawnRegion {
+    pub const WORLD: Self = Self { min: Vec2::splat(-1.0), max: Vec2::splat(1.0) };
+
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        assert!(min.x < max.x && min.y < max.y, "empty spawn region");
+        Self { min, max }
+    }
+
+    //maps a position of the whole world into the region
+    fn map(&self, position: Vec2) -> Vec2 {
+        let center = (self.min + self.max) / 2.0;
+        let half_size = (self.max - self.min) / 2.0;
+        center + position * half_size
+    }
+}
+
+impl Default for SpawnRegion {
+    fn default() -> Self {
+        Self::WORLD
+    }
+}
+
+//either a half of the world or `min_x,min_y,max_x,max_y`
+impl FromStr for SpawnRegion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Self::WORLD),
+            "left" => Ok(Self::new(Vec2::new(-1.0, -1.0), Vec2::new(0.0, 1.0))),
+            "right" => Ok(Self::new(Vec2::new(0.0, -1.0), Vec2::new(1.0, 1.0))),
+            "top" => Ok(Self::new(Vec2::new(-1.0, 0.0), Vec2::new(1.0, 1.0))),
+            "bottom" => Ok(Self::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 0.0))),
+            _ => {
+                let bounds = s
+                    .split(',')
+                    .map(|bound| bound.trim().parse::<f32>().map_err(|e| format!("invalid spawn region '{s}': {e}")))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match bounds[..] {
+                    [min_x, min_y, max_x, max_y] if min_x < max_x && min_y < max_y => {
+                        Ok(Self::new(Vec2::new(min_x, min_y), Vec2::new(max_x, max_y)))
+                    }
+                    _ => Err(format!("invalid spawn region '{s}', expected all, left, right, top, bottom or min_x,min_y,max_x,max_y")),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for SpawnRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{},{}", self.min.x, self.min.y, self.max.x, self.max.y)
+    }
+}
+
+//randomness of the environment, a separate stream of the simulation's seed so the layouts
+//of a run do not depend on how the population evolves
+#[derive(Resource)]
+pub struct EnvironmentRng(pub ChaCha8Rng);
+
+impl EnvironmentRng {
+    pub fn from_seed(seed: <ChaCha8Rng as SeedableRng>::Seed) -> Self {
+        let mut rng = ChaCha8Rng::from_seed(seed);
+        rng.set_stream(1);
+        Self(rng)
+    }
+}
+
+//the randomized parts of the world for the current generation, varying them keeps the
+//population from overfitting a single layout
+#[derive(Resource, Clone, Debug)]
+pub struct Environment {
+    //multiplies the kill zone width of the difficulty
+    pub killzone_scale: f32,
+    //multiplies the movement speed of everybody
+    pub speed_factor: f32,
+    pub spawn: SpawnDistribution,
+    pub spawn_center: Vec2,
+    //individual i starts in region i % len, the whole world if there are none
+    pub spawn_regions: Vec<SpawnRegion>,
+    //keeps the kill zones out of the columns of the spawn regions
+    pub protect_spawn: bool,
+    pub obstacles: Vec<Vec2>,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self {
+            killzone_scale: 1.0,
+            speed_factor: 1.0,
+            spawn: SpawnDistribution::Uniform,
+            spawn_center: Vec2::ZERO,
+            spawn_regions: Vec::new(),
+            protect_spawn: false,
+            obstacles: Vec::new(),
+        }
+    }
+}
+
+impl Environment {
+    //draws the environment of the next generation
+    pub fn random(rng: &mut dyn RngCore, config: &Config) -> Self {
+        let mut jitter = |amount: f32| if amount > 0.0 { 1.0 + rng.gen_range(-amount..=amount) } else { 1.0 };
+        let killzone_scale = jitter(config.killzone_width_jitter);
+        let speed_factor = jitter(config.speed_jitter);
+
+        let spawn_center = match config.spawn {
+            SpawnDistribution::Cluster => Vec2::new(rng.gen_range(-CLUSTER_RANGE..=CLUSTER_RANGE), rng.gen_range(-CLUSTER_RANGE..=CLUSTER_RANGE)),
+            _ => Vec2::ZERO,
+        };
+        let obstacles = (0..config.obstacles)
+            .map(|_| Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)))
+            .collect();
+
+        Self {
+            killzone_scale,
+            speed_factor,
+            spawn: config.spawn,
+            spawn_center,
+            spawn_regions: config.spawn_regions.clone(),
+            protect_spawn: config.protect_spawn,
+            obstacles,
+        }
+    }
+
+    //the difficulty with this generation's kill zone width
+    pub fn difficulty(&self, difficulty: &Difficulty) -> Difficulty {
+        Difficulty {
+            killzone_width: (difficulty.killzone_width * self.killzone_scale).clamp(0.0, 2.0),
+            ..difficulty.clone()
+        }
+    }
+
+    //where the `individual`th individual starts, draws from the simulation's rng like a
+    //uniform spawn does
+    pub fn spawn_position(&self, rng: &mut dyn RngCore, individual: usize) -> Vec3 {
+        let (u, v) = (rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+
+        let position = match self.spawn {
+            SpawnDistribution::Uniform => Vec2::new(u, v),
+            SpawnDistribution::Cluster => (self.spawn_center + Vec2::new(u, v) * CLUSTER_SPREAD).clamp(Vec2::splat(-1.0), Vec2::splat(1.0)),
+            SpawnDistribution::Edges => Vec2::new(f32::signum(u) * (EDGE + (1.0 - EDGE) * u.abs()), v),
+        };
+
+        let region = match self.spawn_regions.len() {
+            0 => SpawnRegion::WORLD,
+            len => self.spawn_regions[individual % len],
+        };
+
+        region.map(position).extend(900.0)
+    }
+
+    //the horizontal extents kill zones have to stay out of
+    fn protected(&self) -> impl Iterator<Item = (f32, f32)> + '_ {
+        self.spawn_regions
+            .iter()
+            .filter(|_| self.protect_spawn)
+            .map(|region| (region.min.x, region.max.x))
+    }
+
+    //whether a kill zone spanning `min..max` would cover a protected start area
+    pub fn is_protected(&self, min: f32, max: f32) -> bool {
+        self.protected().any(|(start, end)| min < end && max > start)
+    }
+
+    //cuts a kill zone back to the side of the protected start areas it was placed on, it
+    //may end up empty if a start area reaches the edge of the world
+    pub fn protect(&self, mut killzone: KillZone) -> KillZone {
+        for (start, end) in self.protected() {
+            if !(killzone.min < end && killzone.max > start) {
+                continue;
+            }
+
+            if killzone.center() < (start + end) / 2.0 {
+                killzone.max = start.max(killzone.min);
+            } else {
+                killzone.min = end.min(killzone.max);
+            }
+        }
+        killzone
+    }
+}
+
+//a block that nobody can walk through
+#[derive(Component)]
+pub struct Obstacle;
@@ -0,0 +1,207 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+//a square grid of values over the world from -1.0 to 1.0, the reward painting, pheromones or a
+//temperature are each one with their own marker `T`, so every field is its own resource
+#[derive(Resource)]
+pub struct ScalarField<T> {
+    cells: usize,
+    values: Vec<f32>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> ScalarField<T> {
+    //a field of `cells` by `cells` zeros
+    pub fn new(cells: usize) -> Self {
+        assert!(cells > 0, "a field needs cells");
+        Self { cells, values: vec![0.0; cells * cells], marker: PhantomData }
+    }
+
+    //cells per side of the world
+    pub fn cells(&self) -> usize {
+        self.cells
+    }
+
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    //the side of a cell in the world
+    pub fn cell_size(&self) -> f32 {
+        2.0 / self.cells as f32
+    }
+
+    //the center of cell `index` in the world, the cells are laid out row by row from the bottom
+    pub fn center(&self, index: usize) -> Vec2 {
+        let (x, y) = (index % self.cells, index / self.cells);
+        (Vec2::new(x as f32, y as f32) + 0.5) * self.cell_size() - 1.0
+    }
+
+    //the cell `position` lies in, outside of the world the closest one on the edge
+    pub fn index(&self, position: Vec2) -> usize {
+        let grid = ((position + 1.0) / self.cell_size()).floor().clamp(Vec2::ZERO, Vec2::splat((self.cells - 1) as f32));
+        grid.y as usize * self.cells + grid.x as usize
+    }
+
+    fn at(&self, x: usize, y: usize) -> f32 {
+        self.values[y * self.cells + x]
+    }
+
+    //the value at `position` interpolated bilinearly between the centers of the four closest
+    //cells, outside of the world that of the closest edge
+    pub fn sample(&self, position: Vec2) -> f32 {
+        let last = (self.cells - 1) as f32;
+        let grid = ((position + 1.0) / self.cell_size() - 0.5).clamp(Vec2::ZERO, Vec2::splat(last));
+        let (x, y) = (grid.x.floor() as usize, grid.y.floor() as usize);
+        let (x1, y1) = ((x + 1).min(self.cells - 1), (y + 1).min(self.cells - 1));
+        let (tx, ty) = (grid.x.fract(), grid.y.fract());
+
+        let bottom = self.at(x, y) * (1.0 - tx) + self.at(x1, y) * tx;
+        let top = self.at(x, y1) * (1.0 - tx) + self.at(x1, y1) * tx;
+        bottom * (1.0 - ty) + top * ty
+    }
+
+    //which way and how steeply the field rises at `position`, per unit of the world
+    pub fn gradient(&self, position: Vec2) -> Vec2 {
+        let step = self.cell_size() / 2.0;
+        let (dx, dy) = (Vec2::X * step, Vec2::Y * step);
+        Vec2::new(
+            self.sample(position + dx) - self.sample(position - dx),
+            self.sample(position + dy) - self.sample(position - dy),
+        ) / (2.0 * step)
+    }
+
+    //adds `amount` to the cell `position` lies in
+    pub fn add(&mut self, position: Vec2, amount: f32) {
+        let index = self.index(position);
+        self.values[index] += amount;
+    }
+
+    //adds `amount` to every cell whose center is within `radius` of `position`, clamped to `range`
+    pub fn paint(&mut self, position: Vec2, radius: f32, amount: f32, range: (f32, f32)) {
+        for index in 0..self.values.len() {
+            if self.center(index).distance(position) <= radius {
+                self.values[index] = (self.values[index] + amount).clamp(range.0, range.1);
+            }
+        }
+    }
+
+    //shrinks every value by `rate` per second over `delta` seconds
+    pub fn decay(&mut self, rate: f32, delta: f32) {
+        let factor = (-rate * delta).exp();
+        self.values.iter_mut().for_each(|value| *value *= factor);
+    }
+
+    //lets every cell exchange `rate` per second of its difference to each of its neighbours over
+    //`delta` seconds, nothing flows over the edges of the world so the sum stays the same
+    pub fn diffuse(&mut self, rate: f32, delta: f32) {
+        let amount = (rate * delta).min(1.0);
+        let n = self.cells;
+        let values: Vec<f32> = (0..self.values.len())
+            .map(|index| {
+                let (x, y) = (index % n, index / n);
+                let neighbours = [
+                    (x > 0).then(|| self.at(x - 1, y)),
+                    (x + 1 < n).then(|| self.at(x + 1, y)),
+                    (y > 0).then(|| self.at(x, y - 1)),
+                    (y + 1 < n).then(|| self.at(x, y + 1)),
+                ];
+                let value = self.values[index];
+                let flow: f32 = neighbours.into_iter().flatten().map(|neighbour| neighbour - value).sum();
+                value + flow * amount / 4.0
+            })
+            .collect();
+        self.values = values;
+    }
+
+    pub fn clear(&mut self) {
+        self.values.fill(0.0);
+    }
+}
+
+impl<T> Clone for ScalarField<T> {
+    fn clone(&self) -> Self {
+        Self { cells: self.cells, values: self.values.clone(), marker: PhantomData }
+    }
+}
+
+impl<T> std::fmt::Debug for ScalarField<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScalarField").field("cells", &self.cells).field("values", &self.values).finish()
+    }
+}
+
+impl<T> PartialEq for ScalarField<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells && self.values == other.values
+    }
+}
+
+//a sprite of the overlay of field `T`
+#[derive(Component)]
+struct OverlayCell<T> {
+    index: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+//keeps field `T` as a resource, decays and diffuses it every frame and draws it over the world
+//in `colors` for negative and positive values, fully opaque at `max`
+pub struct FieldPlugin<T> {
+    pub cells: usize,
+    //per second, 0.0 keeps the values as they are
+    pub decay: f32,
+    pub diffusion: f32,
+    pub colors: [Color; 2],
+    pub max: f32,
+    //how deep the overlay lies, the individuals are at 0.0
+    pub z: f32,
+    pub marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Send + Sync + 'static> Plugin for FieldPlugin<T> {
+    fn build(&self, app: &mut App) {
+        let (decay, diffusion, colors, max, z) = (self.decay, self.diffusion, self.colors, self.max, self.z);
+
+        app.insert_resource(ScalarField::<T>::new(self.cells))
+            .add_startup_system(move |commands: Commands, field: Res<ScalarField<T>>| add_overlay(commands, &field, z))
+            .add_system(move |time: Res<Time>, mut field: ResMut<ScalarField<T>>| {
+                if decay > 0.0 {
+                    field.decay(decay, time.delta_seconds());
+                }
+                if diffusion > 0.0 {
+                    field.diffuse(diffusion, time.delta_seconds());
+                }
+            })
+            .add_system_to_stage(CoreStage::PostUpdate, move |field: Res<ScalarField<T>>, cells: Query<(&OverlayCell<T>, &mut Sprite)>| {
+                color_overlay(&field, cells, colors, max)
+            });
+    }
+}
+
+fn add_overlay<T: Send + Sync + 'static>(mut commands: Commands, field: &ScalarField<T>, z: f32) {
+    for index in 0..field.values.len() {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite { color: Color::NONE, custom_size: Some(Vec2::splat(field.cell_size())), ..default() },
+                transform: Transform::from_translation(field.center(index).extend(z)),
+                ..default()
+            },
+            OverlayCell::<T> { index, marker: PhantomData },
+        ));
+    }
+}
+
+fn color_overlay<T: Send + Sync + 'static>(field: &Res<ScalarField<T>>,
+                                           mut cells: Query<(&OverlayCell<T>, &mut Sprite)>,
+                                           [negative, positive]: [Color; 2],
+                                           max: f32) {
+    if !field.is_changed() {
+        return;
+    }
+    for (cell, mut sprite) in cells.iter_mut() {
+        let value = field.values[cell.index] / max;
+        let mut color = if value > 0.0 { positive } else { negative };
+        sprite.color = *color.set_a(value.abs().min(1.0) * color.a());
+    }
+}
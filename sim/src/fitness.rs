@@ -0,0 +1,105 @@
+use crate::KillZone;
+
+//how a generation of an individual is scored
+#[derive(Clone, Debug)]
+pub struct FitnessConfig {
+    //fitness per world unit an individual moved, on top of how far it got, jitter earns it as
+    //well as purposeful movement
+    pub movement_weight: f32,
+    //counts the movement per second of the generation instead of in total, so the weight means
+    //the same whatever the generation_time
+    pub normalize_movement: bool,
+    //width of the ramp on each side of a kill zone edge the fitness fades out over, so a near
+    //miss is worth more than dying in the middle of a zone, 0.0 scores everything inside a
+    //kill zone as 0 and everything outside in full
+    pub near_miss_margin: f32,
+    //share of the fitness lost per generation spent inside kill zones, so an individual that
+    //only dodged out at the last second scores less than one that never entered
+    pub killzone_time_penalty: f32,
+    //fitness per unit of the novelty collected exploring rarely visited cells, see
+    //curiosity::novelty, 0.0 turns it off
+    pub curiosity_weight: f32,
+    //fitness lost per second spent idle, see rules::IDLE_SPEED
+    pub idle_penalty: f32,
+}
+
+impl Default for FitnessConfig {
+    fn default() -> Self {
+        Self {
+            movement_weight: 1.0,
+            normalize_movement: false,
+            near_miss_margin: 0.0,
+            killzone_time_penalty: 0.0,
+            curiosity_weight: 0.0,
+            idle_penalty: 0.0,
+        }
+    }
+}
+
+impl FitnessConfig {
+    //the movement term of `total_movement` world units over a generation of `generation_time`
+    pub fn movement(&self, total_movement: f32, generation_time: f32) -> f32 {
+        let movement = if self.normalize_movement { total_movement / generation_time } else { total_movement };
+        self.movement_weight * movement
+    }
+
+    //the share of the fitness kept at `x`, 0.0 deep inside a kill zone and 1.0 well outside
+    //of all of them
+    pub fn survival<'a>(&self, x: f32, killzones: impl IntoIterator<Item = &'a KillZone>) -> f32 {
+        let mut killzones = killzones.into_iter();
+        if self.near_miss_margin <= 0.0 {
+            return if killzones.any(|killzone| killzone.contains(x)) { 0.0 } else { 1.0 };
+        }
+
+        let distance = killzones
+            .map(|killzone| killzone.signed_distance(x))
+            .fold(f32::INFINITY, f32::min);
+        ((distance + self.near_miss_margin) / (2.0 * self.near_miss_margin)).clamp(0.0, 1.0)
+    }
+
+    //the share of the fitness kept after `time_in_killzone` seconds of a generation
+    pub fn exposure(&self, time_in_killzone: f32, generation_time: f32) -> f32 {
+        (1.0 - self.killzone_time_penalty * time_in_killzone / generation_time).max(0.0)
+    }
+}
+
+//the parts the fitness of an individual is made of, or their means over a population
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FitnessTerms {
+    //how far from the center it ended up, plus one
+    pub distance: f32,
+    //see FitnessConfig::movement
+    pub movement: f32,
+    //the share of distance and movement it keeps for where it ended up or how long it lived
+    pub survival: f32,
+    //the share it keeps after the time spent in kill zones, see FitnessConfig::exposure
+    pub exposure: f32,
+    //added on top, see painting::RewardField
+    pub painted: f32,
+    //added on top, see FitnessConfig::curiosity_weight
+    pub curiosity: f32,
+    //taken off, see FitnessConfig::idle_penalty
+    pub idle: f32,
+}
+
+impl FitnessTerms {
+    //penalties take the fitness down to 0 at most, selection needs it positive
+    pub fn total(&self) -> f32 {
+        let shaping = self.painted + self.curiosity - self.idle;
+        ((self.distance + self.movement) * self.survival * self.exposure + shaping).max(0.0)
+    }
+
+    pub fn mean(terms: &[FitnessTerms]) -> Self {
+        let n = terms.len().max(1) as f32;
+        let mean = |term: fn(&FitnessTerms) -> f32| terms.iter().map(term).sum::<f32>() / n;
+        Self {
+            distance: mean(|terms| terms.distance),
+            movement: mean(|terms| terms.movement),
+            survival: mean(|terms| terms.survival),
+            exposure: mean(|terms| terms.exposure),
+            painted: mean(|terms| terms.painted),
+            curiosity: mean(|terms| terms.curiosity),
+            idle: mean(|terms| terms.idle),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+use lib_natural_selection::Chromosome;
+
+use crate::render::Selected;
+use crate::{Blocking, Config, Frozen, Nizm, Tint};
+
+//frozen references are drawn pale, clones stand out until the next generation recolors them
+const FROZEN: Color = Color::rgba(1.0, 1.0, 1.0, 0.5);
+const CLONE: Color = Color::CYAN;
+
+//a frozen copy of an individual at `transform`
+pub fn frozen_copy(config: &Config, nizm: &Nizm, transform: Transform) -> impl Bundle {
+    let chromosome: Chromosome = nizm.network.data().collect();
+    (
+        TransformBundle::from_transform(transform),
+        Name::new("frozen"),
+        Tint(FROZEN),
+        Nizm::from_chromosome(&chromosome, config),
+        Blocking,
+        Frozen,
+    )
+}
+
+//how many clones the next click makes
+#[derive(Resource)]
+struct CloneCount(usize);
+
+//buttons to freeze a copy of the selected individual as a reference that outlives the
+//generations, or to clone it over live individuals, for comparisons inside a running simulation
+pub struct FreezePlugin;
+
+impl Plugin for FreezePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+
+        app.insert_resource(CloneCount(8))
+            .add_system(individual_panel);
+    }
+}
+
+fn individual_panel(mut egui_context: ResMut<EguiContext>,
+                    config: Res<Config>,
+                    mut count: ResMut<CloneCount>,
+                    mut commands: Commands,
+                    selected: Query<(Entity, &Nizm, &Transform, Option<&Frozen>), With<Selected>>,
+                    mut live: Query<(&mut Nizm, &mut Tint), (Without<Selected>, Without<Frozen>)>) {
+    let Ok((entity, nizm, transform, frozen)) = selected.get_single() else {
+        return;
+    };
+
+    egui::Window::new("Individual").show(egui_context.ctx_mut(), |ui| {
+        if frozen.is_some() {
+            ui.label("a frozen reference");
+            if ui.button("Remove").clicked() {
+                commands.entity(entity).despawn_recursive();
+            }
+        } else if ui.button("Freeze a copy").clicked() {
+            commands.spawn(frozen_copy(&config, nizm, *transform));
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut count.0).clamp_range(1..=config.individuals));
+            if ui.button("Clone into the population").clicked() {
+                for (mut clone, mut tint) in live.iter_mut().take(count.0) {
+                    clone.network.genes_mut().copy_from_slice(nizm.network.genes());
+                    tint.0 = CLONE;
+                }
+            }
+        });
+    });
+}
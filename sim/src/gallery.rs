@@ -0,0 +1,273 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use bevy::prelude::*;
+use lib_natural_selection::Chromosome;
+
+use crate::environment::Environment;
+use crate::headless::{self, headless_app_with_population};
+use crate::hud::{load_font, UiFont};
+use crate::population;
+use crate::render::{fit_viewport, spawn_camera, CLEAR};
+use crate::{Config, Health, InitialPopulation, KillZone, Nizm, Statistics, Tint};
+
+//the part of a tile left empty around its arena
+const GAP: f32 = 0.05;
+const FONT_SIZE: f32 = 16.0;
+
+//a champion file loaded for the gallery
+#[derive(Clone, Debug)]
+pub struct Champion {
+    pub name: String,
+    pub chromosome: Chromosome,
+    //stored by the robustness command, see robustness::Robustness::score
+    pub robustness: Option<f32>,
+}
+
+//the first chromosome of every population file (.txt or .zst) in a directory, by file name
+pub fn load_champions(dir: impl AsRef<Path>) -> io::Result<Vec<Champion>> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.retain(|path| path.is_file() && path.extension().is_some_and(|extension| extension == "txt" || extension == "zst"));
+    paths.sort();
+
+    let mut champions = Vec::new();
+    for path in paths {
+        champions.extend(load_champion(&path)?);
+    }
+
+    Ok(champions)
+}
+
+//the first chromosome of a population file named after the file, None if it is empty
+pub fn load_champion(path: impl AsRef<Path>) -> io::Result<Option<Champion>> {
+    let path = path.as_ref();
+    let checkpoint = population::load_checkpoint(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+    Ok(checkpoint.population.into_iter().next().map(|chromosome| {
+        let name = path.file_stem().map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        Champion { name, chromosome, robustness: checkpoint.robustness }
+    }))
+}
+
+//a champion cloned into a headless simulation of its own, every arena starts from the same
+//seed so they all face the same environment and kill zones
+struct Arena {
+    champion: Champion,
+    app: App,
+    //of the last whole generation
+    last: Option<Statistics>,
+}
+
+impl Arena {
+    fn new(champion: Champion, config: &Config, seed: u64) -> Self {
+        let app = arena_app(&champion, config, seed);
+        Self { champion, app, last: None }
+    }
+}
+
+fn arena_app(champion: &Champion, config: &Config, seed: u64) -> App {
+    headless_app_with_population(config.clone(), seed, Some(InitialPopulation(vec![champion.chromosome.clone()])))
+}
+
+//not a Resource, the apps of the arenas are not Sync
+struct Gallery {
+    arenas: Vec<Arena>,
+    config: Config,
+    seed: u64,
+    sequential: bool,
+    //the arena shown in sequential mode
+    current: usize,
+}
+
+impl Gallery {
+    //the arena of each tile
+    fn shown(&self) -> std::ops::Range<usize> {
+        if self.sequential {
+            self.current..self.current + 1
+        } else {
+            0..self.arenas.len()
+        }
+    }
+}
+
+//the label above the arena of a tile
+#[derive(Component)]
+struct ArenaLabel(usize);
+
+//drawn for one frame, the arenas are mirrored anew every frame
+#[derive(Component)]
+pub(crate) struct ArenaSprite;
+
+//runs champion files side by side in split-screen arenas, or one after the other, against the
+//same environment seed, every arena plays its first generation over and over so the champions
+//are compared and not what evolution makes of them
+pub struct GalleryPlugin {
+    pub champions: Vec<Champion>,
+    pub config: Config,
+    pub seed: u64,
+    pub sequential: bool,
+}
+
+impl Plugin for GalleryPlugin {
+    fn build(&self, app: &mut App) {
+        let gallery = Gallery {
+            arenas: self.champions.iter().map(|champion| Arena::new(champion.clone(), &self.config, self.seed)).collect(),
+            config: self.config.clone(),
+            seed: self.seed,
+            sequential: self.sequential,
+            current: 0,
+        };
+
+        app.insert_non_send_resource(gallery)
+            .insert_resource(ClearColor(CLEAR))
+            .add_startup_system_to_stage(StartupStage::PreStartup, load_font)
+            .add_startup_system(spawn_camera)
+            .add_system(fit_viewport)
+            .add_startup_system(add_labels)
+            .add_system(step_arenas)
+            .add_system(next_arena.before(step_arenas))
+            .add_system(draw_arenas.after(step_arenas))
+            .add_system(update_labels.after(step_arenas));
+    }
+}
+
+//the center and half size of the square tile `i` of `n`, the window spans -1..1
+fn tile(i: usize, n: usize) -> (Vec2, f32) {
+    let columns = (n as f32).sqrt().ceil().max(1.0) as usize;
+    let rows = n.div_ceil(columns).max(1);
+    let size = 2.0 / columns.max(rows) as f32;
+    let (column, row) = (i % columns, i / columns);
+
+    let center = Vec2::new(-1.0 + size * (column as f32 + 0.5), 1.0 - size * (row as f32 + 0.5));
+    (center, size / 2.0)
+}
+
+fn add_labels(mut commands: Commands, font: Res<UiFont>, gallery: NonSend<Gallery>) {
+    let tiles = gallery.shown().len();
+    for i in 0..tiles {
+        let (center, half) = tile(i, tiles);
+        //from world units to percent of the window
+        let (left, top) = ((center.x - half + 1.0) * 50.0, (1.0 - center.y - half) * 50.0);
+
+        commands.spawn((
+            TextBundle::from_section("", TextStyle { font: font.0.clone(), font_size: FONT_SIZE, color: Color::WHITE })
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect { left: Val::Percent(left + 0.5), top: Val::Percent(top + 0.5), ..default() },
+                    ..default()
+                }),
+            ArenaLabel(i),
+        ));
+    }
+}
+
+//every shown arena moves one step, an arena that finished its generation starts it again
+fn step_arenas(mut gallery: NonSendMut<Gallery>) {
+    let gallery = &mut *gallery;
+
+    for i in gallery.shown() {
+        let arena = &mut gallery.arenas[i];
+        arena.app.update();
+        if headless::generation(&mut arena.app) < 1 {
+            continue;
+        }
+
+        arena.last = Some(headless::statistics(&mut arena.app));
+        arena.app = arena_app(&arena.champion, &gallery.config, gallery.seed);
+        if gallery.sequential {
+            gallery.current = (gallery.current + 1) % gallery.arenas.len();
+        }
+    }
+}
+
+//skips to the next champion in sequential mode
+fn next_arena(keys: Res<Input<KeyCode>>, mut gallery: NonSendMut<Gallery>) {
+    if gallery.sequential && keys.just_pressed(KeyCode::Right) {
+        let (current, seed) = (gallery.current, gallery.seed);
+        let app = arena_app(&gallery.arenas[current].champion, &gallery.config, seed);
+        gallery.arenas[current].app = app;
+        gallery.current = (current + 1) % gallery.arenas.len();
+    }
+}
+
+fn draw_arenas(mut commands: Commands,
+               mut gallery: NonSendMut<Gallery>,
+               sprites: Query<Entity, With<ArenaSprite>>) {
+    clear_arenas(&mut commands, &sprites);
+
+    let shown = gallery.shown();
+    let tiles = shown.len();
+    for (slot, i) in shown.enumerate() {
+        let (center, half) = tile(slot, tiles);
+        draw_arena(&mut commands, &mut gallery.arenas[i].app.world, center, half * (1.0 - GAP));
+    }
+}
+
+pub(crate) fn clear_arenas(commands: &mut Commands, sprites: &Query<Entity, With<ArenaSprite>>) {
+    for entity in sprites.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+//mirrors the kill zones, obstacles and individuals of the world of a headless app into sprites
+//for one frame, its -1..1 square drawn `scale` big around `center`
+pub(crate) fn draw_arena(commands: &mut Commands, world: &mut World, center: Vec2, scale: f32) {
+    let mut sprite = |position: Vec2, size: Vec2, color: Color, z: f32| {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite { color, custom_size: Some(size * scale), ..default() },
+                transform: Transform::from_translation((center + position * scale).extend(z)),
+                ..default()
+            },
+            ArenaSprite,
+        ));
+    };
+
+    sprite(Vec2::ZERO, Vec2::splat(2.0), Color::rgb(0.14, 0.14, 0.14), 0.0);
+    for killzone in world.query::<&KillZone>().iter(world) {
+        sprite(Vec2::new(killzone.center(), 0.0), Vec2::new(killzone.max - killzone.min, 2.0), Color::rgb(0.2, 0.0, 0.0), 1.0);
+    }
+    if let Some(environment) = world.get_resource::<Environment>() {
+        for &obstacle in &environment.obstacles {
+            sprite(obstacle, Vec2::splat(0.03), Color::GRAY, 2.0);
+        }
+    }
+    for (transform, tint, health) in world.query_filtered::<(&Transform, &Tint, Option<&Health>), With<Nizm>>().iter(world) {
+        let mut color = tint.0;
+        if health.is_some_and(|health| !health.is_alive()) {
+            color.set_a(0.3);
+        }
+        sprite(transform.translation.truncate(), Vec2::splat(0.03), color, 3.0);
+    }
+}
+
+fn update_labels(gallery: NonSend<Gallery>, mut labels: Query<(&ArenaLabel, &mut Text)>) {
+    let shown: Vec<_> = gallery.shown().collect();
+
+    for (label, mut text) in labels.iter_mut() {
+        let Some(arena) = shown.get(label.0).map(|&i| &gallery.arenas[i]) else {
+            continue;
+        };
+        let mut value = arena.champion.name.clone();
+        if gallery.sequential {
+            value.push_str(&format!(" ({}/{})", gallery.current + 1, gallery.arenas.len()));
+        }
+        if let Some(robustness) = arena.champion.robustness {
+            value.push_str(&format!(", robustness {robustness:.2}"));
+        }
+        match &arena.last {
+            Some(last) => value.push_str(&format!(
+                "\nbest {:.3}, average {:.3}, survivors {:.1}%",
+                last.best_fitness,
+                last.average_fitness,
+                last.survivors_percentage * 100.0,
+            )),
+            None => value.push_str("\nfirst generation running"),
+        }
+
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+use lib_neural_network::{GpuBatch, GpuError, Network};
+
+use crate::{Config, Nizm};
+
+//thinks for all individuals in one gpu dispatch, the brains are uploaded once per generation
+#[derive(Resource)]
+pub struct GpuBrains {
+    batch: GpuBatch,
+    //the generation and the number of brains uploaded, frozen individuals come and go within one
+    uploaded: Option<(i32, usize)>,
+}
+
+impl GpuBrains {
+    pub fn new(config: &Config) -> Result<Self, GpuError> {
+        Ok(Self {
+            batch: GpuBatch::new(&Nizm::topology(config))?,
+            uploaded: None,
+        })
+    }
+
+    pub(crate) fn propagate<'a>(&mut self,
+                                generation: i32,
+                                networks: impl IntoIterator<Item = &'a Network>,
+                                inputs: &[Vec<f32>]) -> Result<Vec<Vec<f32>>, GpuError> {
+        if self.uploaded != Some((generation, inputs.len())) {
+            self.batch.upload(networks);
+            self.uploaded = Some((generation, inputs.len()));
+        }
+
+        self.batch.propagate(inputs)
+    }
+}
@@ -0,0 +1,80 @@
+use lib_natural_selection::Chromosome;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::curriculum::Difficulty;
+use crate::rules::{SimCore, FRAME};
+use crate::{Config, Nizm};
+
+//what a step of a GymEnv hands back, one entry per agent
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    pub observations: Vec<Vec<f32>>,
+    pub rewards: Vec<f32>,
+    //the generation is over, reset() starts the next episode
+    pub done: bool,
+}
+
+//the world the GA evolves in behind a reset()/step(actions) interface, so reinforcement
+//learning frameworks can drive the agents instead of evolved brains, an episode is one
+//generation and a step one think tick
+pub struct GymEnv {
+    config: Config,
+    difficulty: Difficulty,
+    agents: usize,
+    rng: ChaCha8Rng,
+    core: SimCore,
+}
+
+impl GymEnv {
+    //the length of an observation and of an action, laid out like the inputs and outputs of a brain,
+    //observations differ by the sensors that are disabled or enabled
+    pub const OBSERVATIONS: usize = 11;
+    pub const ACTIONS: usize = 5;
+
+    pub fn new(config: Config, difficulty: Difficulty, agents: usize, seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let core = Self::episode(&config, &difficulty, agents, &mut rng);
+        Self { config, difficulty, agents, rng, core }
+    }
+
+    fn episode(config: &Config, difficulty: &Difficulty, agents: usize, rng: &mut ChaCha8Rng) -> SimCore {
+        //the brains of the agents never think, their actions come from step()
+        let unused: Chromosome = Nizm::random(&mut ChaCha8Rng::seed_from_u64(0), config).network.data().collect();
+        SimCore::new(config.clone(), rng, difficulty, &vec![unused; agents])
+    }
+
+    //lays out a fresh world, the environment draws go on from the seed of the last one
+    pub fn reset(&mut self) -> Vec<Vec<f32>> {
+        self.core = Self::episode(&self.config, &self.difficulty, self.agents, &mut self.rng);
+        self.core.observations()
+    }
+
+    //gives every agent its action and runs the world until the next think tick, the rewards
+    //are 0.0 until the episode is done and then the fitness the GA would score the agents with
+    pub fn step(&mut self, actions: &[Vec<f32>]) -> Step {
+        assert_eq!(actions.len(), self.agents, "need an action per agent");
+        assert!(actions.iter().all(|action| action.len() == Self::ACTIONS), "actions have {} values", Self::ACTIONS);
+
+        let noise = self.config.action_noise_at(0);
+        self.core.act(actions, noise, &mut self.rng);
+
+        let mut left = 1.0 / self.config.think_rate;
+        while left > 0.0 && !self.core.finished() {
+            let delta = left.min(FRAME);
+            self.core.hold(delta);
+            left -= delta;
+        }
+
+        let done = self.core.finished();
+        Step {
+            observations: self.core.observations(),
+            rewards: if done { self.core.fitness() } else { vec![0.0; self.agents] },
+            done,
+        }
+    }
+
+    pub fn core(&self) -> &SimCore {
+        &self.core
+    }
+}
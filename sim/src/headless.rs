@@ -0,0 +1,120 @@
+use std::time::Duration;
+use bevy::core::CorePlugin;
+use bevy::prelude::*;
+
+use lib_natural_selection::Chromosome;
+
+use crate::curriculum::Curriculum;
+use crate::population::Checkpoint;
+use crate::{Config, Frozen, InitialPopulation, KillZone, Nizm, Resume, ResumePoint, SimPlugin, SimRng, Statistics};
+
+//fixed simulation step of a headless run, independent of how fast the machine is
+pub const HEADLESS_STEP: f32 = 1.0 / 60.0;
+
+//advances the clock by exactly one step per update instead of following the wall clock
+fn fixed_time_step(mut time: ResMut<Time>) {
+    let last_update = time.last_update().unwrap_or_else(|| time.startup());
+    time.update_with_instant(last_update + Duration::from_secs_f32(HEADLESS_STEP));
+}
+
+pub fn headless_app(config: Config, seed: u64) -> App {
+    headless_app_with_population(config, seed, None)
+}
+
+pub fn headless_app_with_population(config: Config, seed: u64, population: Option<InitialPopulation>) -> App {
+    let mut app = App::new();
+
+    if let Some(population) = population {
+        app.insert_resource(population);
+    }
+
+    app.add_plugin(CorePlugin::default())
+        .init_resource::<Time>()
+        .add_system_to_stage(CoreStage::First, fixed_time_step.at_start())
+        .insert_resource(config)
+        .insert_resource(SimRng::from_seed(seed))
+        .add_plugin(SimPlugin);
+
+    app
+}
+
+//the current generation, 0 until the startup systems have run
+pub fn generation(app: &mut App) -> i32 {
+    app.world
+        .query::<&Statistics>()
+        .get_single(&app.world)
+        .map(|statistics| statistics.generation)
+        .unwrap_or(0)
+}
+
+pub fn run_generations(app: &mut App, generations: i32) {
+    //the startup systems run with the first update, a resumed run only knows its generation after them
+    if app.world.query::<&Statistics>().iter(&app.world).next().is_none() {
+        app.update();
+    }
+    let target = generation(app) + generations;
+
+    while generation(app) < target {
+        app.update();
+    }
+}
+
+pub fn statistics(app: &mut App) -> Statistics {
+    app.world
+        .query::<&Statistics>()
+        .single(&app.world)
+        .clone()
+}
+
+pub fn population(app: &mut App) -> Vec<Chromosome> {
+    app.world
+        .query_filtered::<&Nizm, Without<Frozen>>()
+        .iter(&app.world)
+        .map(|nizm| nizm.network.data().collect())
+        .collect()
+}
+
+pub fn checkpoint(app: &mut App) -> Checkpoint {
+    world_checkpoint(&mut app.world)
+}
+
+//the checkpoint of a world, for the systems that save the run they are part of
+pub fn world_checkpoint(world: &mut World) -> Checkpoint {
+    Checkpoint {
+        population: world.query_filtered::<&Nizm, Without<Frozen>>().iter(world).map(|nizm| nizm.network.data().collect()).collect(),
+        curriculum_stage: world.get_resource::<Curriculum>().map(Curriculum::stage),
+        resume: world.get_resource::<ResumePoint>().cloned(),
+        robustness: None,
+    }
+}
+
+//an app that continues the run of a checkpoint where it stopped, the random sequence included
+//if the checkpoint has a resume point
+pub fn resumed_app(config: Config, seed: u64, checkpoint: &Checkpoint) -> App {
+    let mut app = headless_app_with_population(config, seed, Some(InitialPopulation(checkpoint.population.clone())));
+    if let Some(resume) = &checkpoint.resume {
+        app.insert_resource(Resume(resume.clone()));
+    }
+    if let Some(stage) = checkpoint.curriculum_stage {
+        app.insert_resource(Curriculum::default().at_stage(stage));
+    }
+
+    app
+}
+
+pub fn positions(app: &mut App) -> Vec<Vec2> {
+    app.world
+        .query_filtered::<&Transform, With<Nizm>>()
+        .iter(&app.world)
+        .map(|transform| transform.translation.truncate())
+        .collect()
+}
+
+//the horizontal extent of every kill zone
+pub fn killzones(app: &mut App) -> Vec<(f32, f32)> {
+    app.world
+        .query::<&KillZone>()
+        .iter(&app.world)
+        .map(|killzone| (killzone.min, killzone.max))
+        .collect()
+}
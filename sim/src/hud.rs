@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use bevy::prelude::*;
+
+use crate::render::RenderOptions;
+use crate::{EvolutionTimer, Statistics};
+
+pub const FONT: &str = "fonts/FiraMono-Medium.ttf";
+//used when the assets are not next to the binary
+const BUILTIN_FONT: &[u8] = include_bytes!("../assets/fonts/FiraMono-Medium.ttf");
+const FONT_SIZE: f32 = 20.0;
+
+//the font of all text, from the assets if they are there
+#[derive(Resource)]
+pub struct UiFont(pub Handle<Font>);
+
+pub(crate) fn load_font(mut commands: Commands,
+             options: Option<Res<RenderOptions>>,
+             assets: Res<AssetServer>,
+             mut fonts: ResMut<Assets<Font>>) {
+    let options = options.map(|options| *options).unwrap_or_default();
+    let font = if options.has_asset(&assets, FONT) {
+        assets.load(FONT)
+    } else {
+        info!("using the built-in font instead of {FONT}");
+        fonts.add(Font::try_from_bytes(BUILTIN_FONT.to_vec()).expect("the built-in font is valid"))
+    };
+    commands.insert_resource(UiFont(font));
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HudCorner {
+    TopLeft,
+    TopRight,
+    #[default]
+    BottomLeft,
+    BottomRight,
+}
+
+impl FromStr for HudCorner {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top-left" => Ok(Self::TopLeft),
+            "top-right" => Ok(Self::TopRight),
+            "bottom-left" => Ok(Self::BottomLeft),
+            "bottom-right" => Ok(Self::BottomRight),
+            _ => Err(format!("unknown corner '{s}', expected top-left, top-right, bottom-left or bottom-right")),
+        }
+    }
+}
+
+impl fmt::Display for HudCorner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::TopLeft => "top-left",
+            Self::TopRight => "top-right",
+            Self::BottomLeft => "bottom-left",
+            Self::BottomRight => "bottom-right",
+        })
+    }
+}
+
+//a line of the hud
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HudField {
+    Time,
+    Generation,
+    Survivors,
+    Best,
+    Average,
+    Stderr,
+    Takeover,
+    Genotypes,
+    Diversity,
+    Curriculum,
+    Regularization,
+    Mutation,
+    Mixing,
+}
+
+impl HudField {
+    const ALL: [Self; 13] = [
+        Self::Time,
+        Self::Generation,
+        Self::Survivors,
+        Self::Best,
+        Self::Average,
+        Self::Stderr,
+        Self::Takeover,
+        Self::Genotypes,
+        Self::Diversity,
+        Self::Curriculum,
+        Self::Regularization,
+        Self::Mutation,
+        Self::Mixing,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Time => "time",
+            Self::Generation => "generation",
+            Self::Survivors => "survivors",
+            Self::Best => "best",
+            Self::Average => "average",
+            Self::Stderr => "stderr",
+            Self::Takeover => "takeover",
+            Self::Genotypes => "genotypes",
+            Self::Diversity => "diversity",
+            Self::Curriculum => "curriculum",
+            Self::Regularization => "regularization",
+            Self::Mutation => "mutation",
+            Self::Mixing => "mixing",
+        }
+    }
+
+    fn line(&self, statistics: &Statistics, timer: &EvolutionTimer) -> String {
+        match self {
+            Self::Time => format!("Time: {:.1}s", timer.0.remaining().as_secs_f32()),
+            Self::Generation => format!("Generation: {}", statistics.generation),
+            Self::Survivors => format!("Percentage: {:.2}", statistics.survivors_percentage),
+            Self::Best => format!("Best: {:.3}", statistics.best_fitness),
+            Self::Average => format!("Average: {:.3}", statistics.average_fitness),
+            Self::Stderr => format!("Stderr: {:.3}", statistics.fitness_stderr),
+            Self::Takeover => format!("Takeover: {:.2}", statistics.takeover),
+            Self::Genotypes => format!("Genotypes: {:.1}", statistics.distinct_genotypes),
+            Self::Diversity => format!("Diversity: {:.4}", statistics.genetic_variance),
+            Self::Curriculum => format!("Curriculum: {}", statistics.curriculum_stage + 1),
+            Self::Regularization => format!("Regularization: {:.3}", statistics.regularization),
+            Self::Mutation => format!("Mutated: {:.1}% by {:.3}", statistics.mutated_share * 100.0, statistics.mutation_size),
+            Self::Mixing => format!("Parental mixing: {:.2}, crowded out: {}", statistics.parental_mixing, statistics.crowded_out),
+        }
+    }
+}
+
+impl FromStr for HudField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|field| field.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(HudField::name).collect();
+                format!("unknown hud field '{s}', expected one of {}", names.join(", "))
+            })
+    }
+}
+
+impl fmt::Display for HudField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct HudConfig {
+    pub corner: HudCorner,
+    //multiplies the font size
+    pub scale: f32,
+    pub fields: Vec<HudField>,
+}
+
+impl Default for HudConfig {
+    fn default() -> Self {
+        Self {
+            corner: HudCorner::BottomLeft,
+            scale: 1.0,
+            fields: vec![HudField::Time, HudField::Generation, HudField::Survivors, HudField::Takeover, HudField::Genotypes],
+        }
+    }
+}
+
+//lines other plugins add below the fields, by name so they can be updated every frame
+#[derive(Resource, Default)]
+pub struct HudExtras(BTreeMap<&'static str, String>);
+
+impl HudExtras {
+    pub fn set(&mut self, name: &'static str, value: impl fmt::Display) {
+        self.0.insert(name, value.to_string());
+    }
+
+    pub fn remove(&mut self, name: &'static str) {
+        self.0.remove(name);
+    }
+}
+
+#[derive(Component)]
+struct HudText;
+
+//the always visible statistics text, expects a HudConfig resource or uses the default one
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HudConfig>()
+            .init_resource::<HudExtras>()
+            .add_startup_system_to_stage(StartupStage::PreStartup, load_font)
+            .add_startup_system(add_hud)
+            .add_system(update_hud);
+    }
+}
+
+fn add_hud(mut commands: Commands, config: Res<HudConfig>, font: Res<UiFont>) {
+    let margin = |px: f32| Val::Px(px * config.scale);
+    let (vertical, horizontal) = (margin(5.0), margin(15.0));
+    let position = match config.corner {
+        HudCorner::TopLeft => UiRect { top: vertical, left: horizontal, ..default() },
+        HudCorner::TopRight => UiRect { top: vertical, right: horizontal, ..default() },
+        HudCorner::BottomLeft => UiRect { bottom: vertical, left: horizontal, ..default() },
+        HudCorner::BottomRight => UiRect { bottom: vertical, right: horizontal, ..default() },
+    };
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: font.0.clone(),
+                font_size: FONT_SIZE * config.scale,
+                color: Color::WHITE,
+            },
+        )
+            .with_text_alignment(TextAlignment::TOP_LEFT)
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position,
+                ..default()
+            }),
+        HudText,
+    ));
+}
+
+fn update_hud(timer: Res<EvolutionTimer>,
+              config: Res<HudConfig>,
+              extras: Res<HudExtras>,
+              statistics: Query<&Statistics>,
+              mut query: Query<&mut Text, With<HudText>>) {
+    let statistics = statistics.get_single().expect("Stats");
+
+    let lines: Vec<String> = config
+        .fields
+        .iter()
+        .map(|field| field.line(statistics, &timer))
+        .chain(extras.0.iter().map(|(name, value)| format!("{name}: {value}")))
+        .collect();
+    for mut text in query.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+use lib_neural_network::LayerKind;
+
+use crate::dashboard::{self, Dashboard};
+use crate::render::Selected;
+use crate::{Frozen, Health, Nizm, Statistics};
+
+//what the world inspector shows of the selected individual and the statistics, for release
+//builds that go without it
+pub struct InspectionPlugin;
+
+impl Plugin for InspectionPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+
+        app.add_system(inspection_panel);
+    }
+}
+
+fn inspection_panel(mut egui_context: ResMut<EguiContext>,
+                    dashboard: Option<Res<Dashboard>>,
+                    selected: Query<(&Nizm, &Transform, Option<&Health>, Option<&Frozen>), With<Selected>>,
+                    statistics: Query<&Statistics>) {
+    let Some(ctx) = dashboard::panels(&mut egui_context, dashboard.as_deref()) else {
+        return;
+    };
+    egui::Window::new("Inspection").show(ctx, |ui| {
+        if let Ok(statistics) = statistics.get_single() {
+            egui::CollapsingHeader::new("Statistics").default_open(true).show(ui, |ui| statistics_grid(ui, statistics));
+        }
+        match selected.get_single() {
+            Ok((nizm, transform, health, frozen)) => {
+                let title = if frozen.is_some() { "Selected (frozen)" } else { "Selected" };
+                egui::CollapsingHeader::new(title).default_open(true).show(ui, |ui| nizm_grid(ui, nizm, transform, health));
+            }
+            Err(_) => {
+                ui.label("click an individual to inspect it");
+            }
+        }
+    });
+}
+
+fn statistics_grid(ui: &mut egui::Ui, statistics: &Statistics) {
+    egui::Grid::new("statistics").striped(true).show(ui, |ui| {
+        row(ui, "generation", statistics.generation);
+        row(ui, "survivors", format!("{:.1}%", statistics.survivors_percentage));
+        row(ui, "best fitness", format!("{:.3}", statistics.best_fitness));
+        row(ui, "average fitness", format!("{:.3} ± {:.3}", statistics.average_fitness, statistics.fitness_stderr));
+        row(ui, "champion fitness", format!("{:.3}{}", statistics.champion_fitness, if statistics.new_champion { " (new)" } else { "" }));
+        row(ui, "genetic variance", format!("{:.4}", statistics.genetic_variance));
+        row(ui, "distinct genotypes", format!("{:.2}", statistics.distinct_genotypes));
+        row(ui, "takeover", format!("{:.2}", statistics.takeover));
+        row(ui, "selection intensity", format!("{:.3}", statistics.selection_intensity));
+        row(ui, "time in kill zones", format!("{:.2}s", statistics.time_in_killzone));
+        row(ui, "curriculum stage", statistics.curriculum_stage);
+        row(ui, "species", statistics.species);
+    });
+}
+
+fn nizm_grid(ui: &mut egui::Ui, nizm: &Nizm, transform: &Transform, health: Option<&Health>) {
+    egui::Grid::new("selected").striped(true).show(ui, |ui| {
+        row(ui, "position", format!("{:.2}, {:.2}", transform.translation.x, transform.translation.y));
+        row(ui, "heading", format!("{:.0}°", nizm.heading.to_degrees()));
+        row(ui, "action", format!("{:.2}, {:.2}", nizm.action.x, nizm.action.y));
+        row(ui, "moved", format!("{:.2}", nizm.total_movement));
+        row(ui, "displacement", format!("{:.2}", nizm.displacement.length()));
+        row(ui, "time in kill zones", format!("{:.2}s", nizm.time_in_killzone));
+        row(ui, "time idle", format!("{:.2}s", nizm.time_idle));
+        row(ui, "painted reward", format!("{:.3}", nizm.painted_reward));
+        row(ui, "curiosity", format!("{:.3}", nizm.curiosity));
+        row(ui, "oscillator", format!("{:.2}", nizm.osc_freq));
+        if let Some(health) = health {
+            let died = health.died_at.map_or_else(String::new, |died_at| format!(", died at {died_at:.1}s"));
+            row(ui, "health", format!("{:.2}{died}", health.health));
+        }
+        let scores: Vec<_> = nizm.scores.iter().map(|score| format!("{score:.3}")).collect();
+        row(ui, "trial scores", if scores.is_empty() { "-".to_string() } else { scores.join(" ") });
+        row(ui, "brain", brain(nizm));
+        row(ui, "genes", nizm.network.data_len());
+    });
+}
+
+//the neurons of every layer, like 16 → 24 gru → 6
+fn brain(nizm: &Nizm) -> String {
+    let layers = nizm.network.topology();
+    layers
+        .iter()
+        .map(|layer| match layer.kind {
+            LayerKind::Dense => layer.neurons.to_string(),
+            LayerKind::Gru => format!("{} gru", layer.neurons),
+        })
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
+fn row(ui: &mut egui::Ui, name: &str, value: impl ToString) {
+    ui.label(name);
+    ui.label(value.to_string());
+    ui.end_row();
+}
@@ -0,0 +1,1519 @@
+// bevy systems take their world access as arguments
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+pub mod actions;
+pub mod alleles;
+pub mod batch;
+pub mod compare;
+pub mod curiosity;
+pub mod curriculum;
+pub mod dashboard;
+#[cfg(feature = "sqlite")]
+pub mod database;
+pub mod diff;
+pub mod editor;
+pub mod elites;
+pub mod environment;
+pub mod field;
+pub mod fitness;
+pub mod freeze;
+pub mod gallery;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod gym;
+pub mod headless;
+pub mod hud;
+pub mod inspection;
+pub mod manifest;
+pub mod metrics;
+pub mod noise;
+pub mod normalization;
+pub mod observers;
+pub mod occupancy;
+pub mod painting;
+pub mod persistence;
+pub mod population;
+pub mod presets;
+pub mod profiler;
+pub mod render;
+pub mod restart;
+pub mod robustness;
+pub mod rules;
+pub mod sensors;
+pub mod shutdown;
+pub mod species;
+pub mod states;
+pub mod suite;
+pub mod surrogate;
+pub mod summary;
+pub mod tiers;
+pub mod timeline;
+pub mod watchdog;
+pub mod wind;
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_inspector_egui::{Inspectable};
+use lib_neural_network::{Activity, LayerTopology, Network};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use crate::actions::{ActionEncoding, Actions};
+use crate::curiosity::VisitField;
+use crate::curriculum::{Curriculum, Difficulty};
+use crate::environment::{Environment, EnvironmentRng, Obstacle, SpawnDistribution, SpawnRegion};
+use crate::fitness::{FitnessConfig, FitnessTerms};
+use crate::manifest::ConfigChanged;
+use crate::noise::{SensorNoise, SensorRng};
+use crate::normalization::{InputNormalization, InputNormalizer};
+use crate::observers::{ChampionLog, CsvObserver};
+use crate::occupancy::KillzoneOccupancy;
+use crate::painting::RewardField;
+use crate::restart::{CurrentRun, RestartRun, RunRestarted};
+use crate::sensors::Sensor;
+use crate::species::{SpeciesCommand, SpeciesRegistry};
+use crate::states::SimState;
+use crate::surrogate::SurrogateKind;
+use crate::watchdog::{PopulationCollapsed, Watchdog, WatchdogResponse};
+use crate::wind::Wind;
+use lib_natural_selection::{AgeLayers, Aggregation, Chromosome, ConfidenceTournamentSelection, EliteArchive, FitnessBands, FitnessScaling, GaussianMutation, GeneticAlgorithm, InPlaceIndividual, LinkedUniformCrossover, MapElites, MultiTrialEvaluator, Observer, Optimizer, RouletteWheelSelection, SelectionMethod, UniformCrossover};
+
+#[derive(Component, Inspectable, Clone, Debug, Default)]
+pub struct Statistics {
+    pub generation: i32,
+    pub survivors_percentage: f32,
+    pub genetic_variance: f32,
+    pub best_fitness: f32,
+    pub average_fitness: f32,
+    //convergence metrics of the genetic algorithm, see lib_natural_selection::Statistics
+    pub takeover: f32,
+    pub distinct_genotypes: f32,
+    pub selection_intensity: f32,
+    //average standard error of the fitness over the trials
+    pub fitness_stderr: f32,
+    //average weight decay taken from the fitness during selection
+    pub regularization: f32,
+    //the share of the offspring's genes mutation changed and how far on average, with
+    //log_mutations only
+    pub mutated_share: f32,
+    pub mutation_size: f32,
+    //how evenly the offspring mix their parents, 0.0 for copies and 0.5 for even mixes, with
+    //log_provenance only
+    pub parental_mixing: f32,
+    //offspring that lost their place to a fitter parent, with crowding only
+    pub crowded_out: usize,
+    //how far the surrogate's predictions of the fitness were off on average, with a surrogate
+    //only
+    pub surrogate_error: f32,
+    pub curriculum_stage: usize,
+    //average seconds an individual spent inside kill zones during the last trial
+    pub time_in_killzone: f32,
+    //how the shares of the last trial the individuals spent inside kill zones are spread
+    #[inspectable(ignore)]
+    pub killzone_occupancy: KillzoneOccupancy,
+    //the means of the parts of the fitness of the last trial
+    #[inspectable(ignore)]
+    pub fitness_terms: FitnessTerms,
+    //the fittest individual of the run so far, new_champion if it is from the last generation
+    #[inspectable(ignore)]
+    pub champion: Option<Chromosome>,
+    pub champion_fitness: f32,
+    pub new_champion: bool,
+    //0 without a species_threshold
+    pub species: usize,
+}
+
+#[derive(Component, Inspectable)]
+pub struct KillZone {
+    pub min: f32,
+    pub max: f32,
+    //horizontal drift in world units per second
+    pub velocity: f32,
+}
+
+impl KillZone {
+    //a kill zone on a random side of the world
+    fn random(rng: &mut dyn RngCore, difficulty: &Difficulty) -> Self {
+        let width = difficulty.killzone_width;
+
+        if rng.gen_bool(0.5) {
+            Self { min: -1.0, max: -1.0 + width, velocity: difficulty.killzone_speed }
+        } else {
+            Self { min: 1.0 - width, max: 1.0, velocity: -difficulty.killzone_speed }
+        }
+    }
+
+    pub fn center(&self) -> f32 {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn contains(&self, x: f32) -> bool {
+        x < self.max && x > self.min
+    }
+
+    //how far `x` is outside of the kill zone, negative inside it
+    pub fn signed_distance(&self, x: f32) -> f32 {
+        (self.min - x).max(x - self.max)
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct Config {
+    pub individuals: usize,
+    pub movement_speed: f32,
+    //world units an individual may move per generation before it stops, 0.0 is no limit
+    pub movement_budget: f32,
+    //how often per second the brains are evaluated
+    pub think_rate: f32,
+    //length of a generation in seconds
+    pub generation_time: f32,
+    pub mutation_chance: f32,
+    pub mutation_coeff: f32,
+    //measures what mutation does to the offspring, see Statistics::mutated_share
+    pub log_mutations: bool,
+    //measures which parent the genes of the offspring come from, see Statistics::parental_mixing
+    pub log_provenance: bool,
+    //deterministic crowding, offspring only replace the parent they are closer to if they are
+    //fitter, which keeps different solutions around longer
+    pub crowding: bool,
+    //tunes the selection pressure by transforming the fitness the selection sees
+    pub fitness_scaling: FitnessScaling,
+    //splits the population into this many age layers (ALPS) that only breed from themselves
+    //and the layer below, with random newcomers in the bottom one, 0 turns it off
+    pub age_layers: usize,
+    //splits the population into this many fitness bands (HFC) that only breed among
+    //themselves, whoever passes the threshold of a higher band is promoted to it, 0 turns it
+    //off, age_layers win if both are on
+    pub fitness_bands: usize,
+    //generations between the newcomers of the age layers or the fitness bands, the age limits
+    //of the layers are multiples of it
+    pub age_gap: usize,
+    //breeds several candidates for every place in the next generation and lets a model of the
+    //fitness learned from the generations so far pick the most promising or most uncertain
+    pub surrogate: SurrogateKind,
+    pub surrogate_candidates: usize,
+    //how much predicted fitness the surrogate's uncertainty about a candidate is worth
+    pub surrogate_exploration: f32,
+    //replaces the genetic algorithm with MAP-Elites, an archive of the best brain for every
+    //combination of how far an individual got and how much it moved, split into this many bins
+    //each, 0 turns it off
+    pub map_elites_bins: usize,
+    //keeps the genes of each neuron together during crossover
+    pub linkage: bool,
+    //thinks on the gpu, needs the gpu feature
+    pub gpu: bool,
+    //how many kill zone layouts every generation is scored in
+    pub trials: usize,
+    //how the scores of the trials are combined into the fitness
+    pub trial_aggregation: Aggregation,
+    //selects by the lower confidence bound of the fitness over the trials instead of by
+    //roulette wheel, only makes a difference with more than one trial
+    pub uncertainty_selection: bool,
+    //lambda of the l2 penalty on the genes that selection takes from the fitness, 0.0 turns
+    //it off
+    pub weight_decay: f32,
+    //starts with easy kill zones and makes them harder as the population improves
+    pub curriculum: bool,
+    //the kill zones of a run without a curriculum, see Difficulty
+    pub killzones: usize,
+    pub killzone_width: f32,
+    pub killzone_speed: f32,
+    //every generation the kill zone width and the movement speed are scaled by a random
+    //factor within 1.0 ± the jitter
+    pub killzone_width_jitter: f32,
+    pub speed_jitter: f32,
+    pub spawn: SpawnDistribution,
+    //randomly placed blocks per generation
+    pub obstacles: usize,
+    //pushes the individuals around, at `wind_strength` world units per second
+    pub wind: Wind,
+    pub wind_strength: f32,
+    //individuals start in these regions instead of anywhere, in turns
+    pub spawn_regions: Vec<SpawnRegion>,
+    //kill zones never cover the spawn regions
+    pub protect_spawn: bool,
+    //health lost per second inside a kill zone, individuals start with 1.0 and the fitness
+    //is scaled by how long they lived, with 0.0 ending inside one at the end of a generation
+    //is fatal instead
+    pub killzone_damage: f32,
+    pub fitness: FitnessConfig,
+    //how the outputs of a brain become movement
+    pub action_encoding: ActionEncoding,
+    //chance of an action being replaced by a random direction, multiplied by the decay every
+    //generation so the exploration fades out
+    pub action_noise: f32,
+    pub action_noise_decay: f32,
+    //how the senses are scaled before the brains see them
+    pub input_normalization: InputNormalization,
+    //senses left out of the inputs of the brains, for ablation runs, and senses that are off
+    //by default given to them
+    pub disabled_sensors: Vec<Sensor>,
+    pub enabled_sensors: Vec<Sensor>,
+    //seconds after which the time sense wraps around
+    pub time_period: f32,
+    //seconds of a day/night cycle over the run, 0.0 is always day, at night the senses of the
+    //world fade out by up to the night blindness
+    pub day_length: f32,
+    pub night_blindness: f32,
+    //gaussian noise on the senses, with the sigma for everybody or scaled by a gene per
+    //individual
+    pub sensor_noise: SensorNoise,
+    pub sensor_noise_sigma: f32,
+    //sin/cos pairs of the generation's progress appended to the inputs of the brains, pair k
+    //completes 2^k cycles per generation
+    pub clocks: usize,
+    //neurons of the hidden layer of the brains
+    pub hidden_neurons: usize,
+    //makes the hidden layer a gru that remembers its outputs from one think tick to the next
+    //until the trial ends, the gpu only thinks without it
+    pub recurrent: bool,
+    //genomes whose genes differ by less than this on average are one species, 0.0 turns
+    //speciation off
+    pub species_threshold: f32,
+    //how long every individual may act the same or stand still before the watchdog reports a
+    //collapsed population
+    pub watchdog_seconds: f32,
+    //what the watchdog does to the offspring of a generation the population collapsed in
+    pub watchdog_response: WatchdogResponse,
+}
+
+impl Config {
+    //the action noise of a generation
+    pub fn action_noise_at(&self, generation: i32) -> f32 {
+        self.action_noise * self.action_noise_decay.powi(generation)
+    }
+
+    //the kill zones of a run without a curriculum
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty { killzone_width: self.killzone_width, killzone_speed: self.killzone_speed, killzones: self.killzones }
+    }
+
+    //values a run can not start with and settings that can not be honoured together, the age
+    //layers and fitness bands breed their own structure and neither screen offspring with a
+    //surrogate nor crowd
+    pub fn check(&self) -> Result<(), String> {
+        if self.individuals == 0 {
+            return Err("individuals needs to be at least 1".to_string());
+        }
+        for (name, value) in [("think_rate", self.think_rate), ("generation_time", self.generation_time)] {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(format!("{name} needs to be above 0, not {value}"));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.mutation_chance) {
+            return Err(format!("mutation_chance needs to be between 0 and 1, not {}", self.mutation_chance));
+        }
+
+        let structure = if self.age_layers > 0 {
+            "age_layers"
+        } else if self.fitness_bands > 0 {
+            "fitness_bands"
+        } else {
+            return Ok(());
+        };
+        if self.crowding {
+            return Err(format!("crowding can not be combined with {structure}"));
+        }
+        if self.surrogate != SurrogateKind::None {
+            return Err(format!("a surrogate can not be combined with {structure}"));
+        }
+        Ok(())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            individuals: 128,
+            movement_speed: 0.5,
+            movement_budget: 0.0,
+            think_rate: 10.0,
+            generation_time: 8.0,
+            mutation_chance: 0.3,
+            mutation_coeff: 0.5,
+            log_mutations: false,
+            log_provenance: false,
+            crowding: false,
+            fitness_scaling: FitnessScaling::None,
+            age_layers: 0,
+            fitness_bands: 0,
+            age_gap: 10,
+            surrogate: SurrogateKind::None,
+            surrogate_candidates: 4,
+            surrogate_exploration: 0.5,
+            map_elites_bins: 0,
+            linkage: false,
+            gpu: false,
+            trials: 1,
+            trial_aggregation: Aggregation::Mean,
+            uncertainty_selection: false,
+            weight_decay: 0.0,
+            curriculum: false,
+            killzones: 1,
+            killzone_width: 1.0,
+            killzone_speed: 0.0,
+            killzone_width_jitter: 0.0,
+            speed_jitter: 0.0,
+            spawn: SpawnDistribution::Uniform,
+            obstacles: 0,
+            wind: Wind::None,
+            wind_strength: 0.25,
+            spawn_regions: Vec::new(),
+            protect_spawn: false,
+            killzone_damage: 0.0,
+            fitness: FitnessConfig::default(),
+            action_encoding: ActionEncoding::Continuous,
+            action_noise: 0.0,
+            action_noise_decay: 1.0,
+            input_normalization: InputNormalization::None,
+            disabled_sensors: Vec::new(),
+            enabled_sensors: Vec::new(),
+            time_period: 10.0,
+            day_length: 0.0,
+            night_blindness: 1.0,
+            sensor_noise: SensorNoise::None,
+            sensor_noise_sigma: 0.05,
+            clocks: 0,
+            hidden_neurons: 24,
+            recurrent: false,
+            species_threshold: 0.0,
+            watchdog_seconds: 3.0,
+            watchdog_response: WatchdogResponse::None,
+        }
+    }
+}
+
+impl Config {
+    //sets a value by its field name, used for parameter sweeps, a value the config can not run
+    //with is refused and leaves it as it was, see check()
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let before = self.clone();
+        self.set_unchecked(name, value)?;
+        self.check().inspect_err(|_| *self = before)
+    }
+
+    fn set_unchecked(&mut self, name: &str, value: &str) -> Result<(), String> {
+        fn parse<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+            value.parse().map_err(|_| format!("invalid value '{value}' for {name}"))
+        }
+        //a comma separated list, all of it replaces the old one, an empty value clears it
+        fn parse_list<T: std::str::FromStr>(name: &str, value: &str) -> Result<Vec<T>, String> {
+            value.split(',').map(str::trim).filter(|item| !item.is_empty()).map(|item| parse(name, item)).collect()
+        }
+
+        match name {
+            "individuals" => self.individuals = parse(name, value)?,
+            "movement_speed" => self.movement_speed = parse(name, value)?,
+            "movement_budget" => self.movement_budget = parse(name, value)?,
+            "think_rate" => self.think_rate = parse(name, value)?,
+            "generation_time" => self.generation_time = parse(name, value)?,
+            "mutation_chance" => self.mutation_chance = parse(name, value)?,
+            "mutation_coeff" => self.mutation_coeff = parse(name, value)?,
+            "log_mutations" => self.log_mutations = parse(name, value)?,
+            "log_provenance" => self.log_provenance = parse(name, value)?,
+            "crowding" => self.crowding = parse(name, value)?,
+            "fitness_scaling" => self.fitness_scaling = parse(name, value)?,
+            "age_layers" => self.age_layers = parse(name, value)?,
+            "fitness_bands" => self.fitness_bands = parse(name, value)?,
+            "age_gap" => self.age_gap = parse(name, value)?,
+            "surrogate" => self.surrogate = parse(name, value)?,
+            "surrogate_candidates" => self.surrogate_candidates = parse(name, value)?,
+            "surrogate_exploration" => self.surrogate_exploration = parse(name, value)?,
+            "map_elites_bins" => self.map_elites_bins = parse(name, value)?,
+            "linkage" => self.linkage = parse(name, value)?,
+            "gpu" => self.gpu = parse(name, value)?,
+            "trials" => self.trials = parse(name, value)?,
+            "trial_aggregation" => self.trial_aggregation = parse(name, value)?,
+            "uncertainty_selection" => self.uncertainty_selection = parse(name, value)?,
+            "weight_decay" => self.weight_decay = parse(name, value)?,
+            "curriculum" => self.curriculum = parse(name, value)?,
+            //the brains sense the nearest kill zone, there has to be one
+            "killzones" => self.killzones = parse::<std::num::NonZeroUsize>(name, value)?.get(),
+            "killzone_width" => self.killzone_width = parse(name, value)?,
+            "killzone_speed" => self.killzone_speed = parse(name, value)?,
+            "killzone_width_jitter" => self.killzone_width_jitter = parse(name, value)?,
+            "speed_jitter" => self.speed_jitter = parse(name, value)?,
+            "spawn" => self.spawn = parse(name, value)?,
+            "obstacles" => self.obstacles = parse(name, value)?,
+            "wind" => self.wind = parse(name, value)?,
+            "wind_strength" => self.wind_strength = parse(name, value)?,
+            "spawn_region" => self.spawn_regions = vec![parse(name, value)?],
+            "protect_spawn" => self.protect_spawn = parse(name, value)?,
+            "killzone_damage" => self.killzone_damage = parse(name, value)?,
+            "movement_weight" => self.fitness.movement_weight = parse(name, value)?,
+            "normalize_movement" => self.fitness.normalize_movement = parse(name, value)?,
+            "near_miss_margin" => self.fitness.near_miss_margin = parse(name, value)?,
+            "killzone_time_penalty" => self.fitness.killzone_time_penalty = parse(name, value)?,
+            "curiosity_weight" => self.fitness.curiosity_weight = parse(name, value)?,
+            "idle_penalty" => self.fitness.idle_penalty = parse(name, value)?,
+            "action_encoding" => self.action_encoding = parse(name, value)?,
+            "action_noise" => self.action_noise = parse(name, value)?,
+            "action_noise_decay" => self.action_noise_decay = parse(name, value)?,
+            "input_normalization" => self.input_normalization = parse(name, value)?,
+            "disabled_sensor" => self.disabled_sensors = parse_list(name, value)?,
+            "enabled_sensor" => self.enabled_sensors = parse_list(name, value)?,
+            "time_period" => self.time_period = parse(name, value)?,
+            "day_length" => self.day_length = parse(name, value)?,
+            "night_blindness" => self.night_blindness = parse(name, value)?,
+            "sensor_noise" => self.sensor_noise = parse(name, value)?,
+            "sensor_noise_sigma" => self.sensor_noise_sigma = parse(name, value)?,
+            "clocks" => self.clocks = parse(name, value)?,
+            "hidden_neurons" => self.hidden_neurons = parse(name, value)?,
+            "recurrent" => self.recurrent = parse(name, value)?,
+            "species_threshold" => self.species_threshold = parse(name, value)?,
+            "watchdog_seconds" => self.watchdog_seconds = parse(name, value)?,
+            "watchdog_response" => self.watchdog_response = parse(name, value)?,
+            _ => return Err(format!("unknown config value '{name}'")),
+        }
+        Ok(())
+    }
+}
+
+//genomes the first generation is created from instead of random brains, repeated
+//until the population is full
+#[derive(Resource, Clone)]
+pub struct InitialPopulation(pub Vec<Chromosome>);
+
+//the single source of randomness of a simulation, so runs can be reproduced from a seed
+#[derive(Resource)]
+pub struct SimRng(pub ChaCha8Rng);
+
+impl SimRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self(ChaCha8Rng::seed_from_u64(seed))
+    }
+}
+
+//the state a generation started from, taken after the offspring were bred and before the
+//world was laid out for them, a checkpoint with it continues the run as if it never stopped
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct ResumePoint {
+    pub rng: ChaCha8Rng,
+    pub environment_rng: ChaCha8Rng,
+    pub generation: i32,
+    pub evolution_timer: Duration,
+    pub think_timer: Duration,
+}
+
+//makes a world hand out the scores of every generation instead of evolving its individuals,
+//for a caller that selects over several worlds and writes the offspring back itself
+#[derive(Resource, Default)]
+pub struct ExternalSelection {
+    pub scores: Option<GenerationScores>,
+}
+
+//of the individuals of a world in the order they were spawned in
+#[derive(Clone, Debug, Default)]
+pub struct GenerationScores {
+    pub fitness: Vec<f32>,
+    pub stderr: Vec<f32>,
+    //of the last trial
+    pub survivors: usize,
+    //of the last trial, see Nizm::descriptor
+    pub descriptors: Vec<[f32; 2]>,
+    //of the last trial, the share of it every individual spent inside kill zones
+    pub occupancy: Vec<f32>,
+}
+
+//a ResumePoint for the startup of a new app to continue from
+#[derive(Resource, Clone, Debug)]
+pub struct Resume(pub ResumePoint);
+
+#[derive(Component, Inspectable)]
+pub struct Nizm {
+    #[inspectable(ignore)]
+    pub network: Network,
+    //the frequency of the oscillator sense, set by the brain itself, the clocks of the network
+    //are the fixed alternative
+    osc_freq: f32,
+    //the last decided direction, held until the next think tick
+    action: Vec3,
+    //the direction it faces in radians, for action encodings that steer
+    heading: f32,
+    movement: Vec3,
+    can_move_left: f32,
+    can_move_right: f32,
+    can_move_up: f32,
+    can_move_down: f32,
+    total_movement: f32,
+    //from where it started
+    displacement: Vec3,
+    time_in_killzone: f32,
+    //fitness collected in the painted reward regions, see painting::RewardField
+    painted_reward: f32,
+    //novelty collected in rarely visited cells, see curiosity::novelty
+    curiosity: f32,
+    //seconds spent barely moving, see rules::IDLE_SPEED
+    time_idle: f32,
+    //the fitness of each finished trial of the current generation
+    #[inspectable(ignore)]
+    scores: Vec<f32>,
+}
+
+#[derive(Component)]
+pub struct Blocking;
+
+//a copy of an individual kept as a reference, it lives through every generation unchanged and
+//takes no part in the evolution or damage
+#[derive(Component)]
+pub struct Frozen;
+
+//the color an individual is drawn in, from its genes so relatives look alike
+#[derive(Component)]
+pub struct Tint(pub Color);
+
+//how much longer an individual lasts inside a kill zone, only with killzone_damage
+#[derive(Component, Inspectable)]
+pub struct Health {
+    pub health: f32,
+    //seconds into the generation it ran out of health at
+    pub died_at: Option<f32>,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self { health: 1.0, died_at: None }
+    }
+}
+
+impl Health {
+    pub fn is_alive(&self) -> bool {
+        self.died_at.is_none()
+    }
+}
+
+//sent when an individual runs out of health, it stays where it died until the next generation
+pub struct Died {
+    pub entity: Entity,
+    pub at: f32,
+}
+
+impl Nizm {
+    fn random(rng: &mut dyn RngCore, config: &Config) -> Self {
+        Self::new(Network::random(
+            rng,
+            &Self::topology(config),
+        ).with_clocks(config.clocks))
+    }
+
+    fn from_chromosome(chromosome: &Chromosome, config: &Config) -> Self {
+        Self::new(Network::from_data(&Self::topology(config), chromosome.clone()).with_clocks(config.clocks))
+    }
+
+    fn new(network: Network) -> Self {
+        Self {
+            network,
+            osc_freq: 1.0,
+            action: Vec3::ZERO,
+            heading: 0.0,
+            movement: Vec3::ZERO,
+            can_move_left: 1.0,
+            can_move_right: 1.0,
+            can_move_up: 1.0,
+            can_move_down: 1.0,
+            total_movement: 0.0,
+            displacement: Vec3::ZERO,
+            time_in_killzone: 0.0,
+            painted_reward: 0.0,
+            curiosity: 0.0,
+            time_idle: 0.0,
+            scores: Vec::new(),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.network.reset_state();
+        self.osc_freq = 1.0;
+        self.action = Vec3::ZERO;
+        self.heading = 0.0;
+        self.movement = Vec3::ZERO;
+        self.total_movement = 0.0;
+        self.displacement = Vec3::ZERO;
+        self.time_in_killzone = 0.0;
+        self.painted_reward = 0.0;
+        self.curiosity = 0.0;
+        self.time_idle = 0.0;
+    }
+
+    //the behavior of the trial so far for MAP-Elites: how far it got from where it started and
+    //how far it moved on the way
+    fn descriptor(&self) -> [f32; 2] {
+        [self.displacement.length(), self.total_movement]
+    }
+
+    //the genes of each neuron, they only depend on the topology
+    fn segments(config: &Config) -> Vec<std::ops::Range<usize>> {
+        Network::random(&mut ChaCha8Rng::seed_from_u64(0), &Self::topology(config))
+            .segments()
+            .collect()
+    }
+
+    //an input per sense that is on, followed by the clock inputs, and the outputs of the action
+    //and the oscillator, followed by one for evolved sensor noise
+    fn topology(config: &Config) -> Vec<LayerTopology> {
+        let hidden = if config.recurrent { LayerTopology::gru } else { LayerTopology::new };
+        vec![
+            LayerTopology::new(sensors::inputs(config) + 2 * config.clocks),
+            hidden(config.hidden_neurons),
+            LayerTopology::new(5 + config.sensor_noise.outputs()),
+        ]
+    }
+}
+
+//contestants per tournament and how many standard errors below its mean fitness an
+//individual is ranked with uncertainty_selection
+const TOURNAMENT_SIZE: usize = 4;
+const CONFIDENCE_Z: f32 = 1.0;
+
+//the optimizer lives across generations so its observers see the whole run
+pub struct Evolution {
+    optimizer: Box<dyn Optimizer>,
+    //shared with the csv observers, written before every generation is evolved
+    time_in_killzone: Rc<Cell<f32>>,
+    killzone_occupancy: Rc<Cell<KillzoneOccupancy>>,
+    fitness_terms: Rc<Cell<FitnessTerms>>,
+}
+
+impl Evolution {
+    fn new(optimizer: Box<dyn Optimizer>) -> Self {
+        Self { optimizer, time_in_killzone: Rc::default(), killzone_occupancy: Rc::default(), fitness_terms: Rc::default() }
+    }
+
+    pub fn add_observer(&mut self, observer: impl Observer + 'static) {
+        self.optimizer.add_observer(Box::new(observer));
+    }
+
+    //the archive of MAP-Elites, if it breeds the population
+    pub fn elites(&self) -> Option<&EliteArchive> {
+        self.optimizer.elites()
+    }
+
+    pub fn add_csv_observer(&mut self, mut observer: CsvObserver) {
+        observer.time_in_killzone = self.time_in_killzone.clone();
+        observer.killzone_occupancy = self.killzone_occupancy.clone();
+        observer.fitness_terms = self.fitness_terms.clone();
+        self.add_observer(observer);
+    }
+}
+
+//the algorithm that breeds the next generation, the only place that knows which one it is
+fn optimizer(config: &Config) -> Box<dyn Optimizer> {
+    if config.map_elites_bins > 0 {
+        map_elites(config)
+    } else if config.uncertainty_selection {
+        genetic_algorithm(config, ConfidenceTournamentSelection::new(TOURNAMENT_SIZE, CONFIDENCE_Z))
+    } else {
+        genetic_algorithm(config, RouletteWheelSelection::new())
+    }
+}
+
+//the descriptors range from standing still to crossing the arena diagonally and moving at full
+//speed the whole generation, anything beyond ends up in the outer bins
+fn map_elites(config: &Config) -> Box<dyn Optimizer> {
+    let bins = config.map_elites_bins;
+    let max_movement = (config.movement_speed * config.generation_time).max(f32::EPSILON);
+    let archive = EliteArchive::new([(0.0..2.0 * std::f32::consts::SQRT_2, bins), (0.0..max_movement, bins)]);
+
+    let mut map_elites = MapElites::new(archive, GaussianMutation::new(config.mutation_chance, config.mutation_coeff));
+    map_elites.add_observer(Box::new(ChampionLog));
+    Box::new(map_elites)
+}
+
+fn genetic_algorithm<S>(config: &Config, selection: S) -> Box<dyn Optimizer>
+where
+    S: SelectionMethod + 'static,
+{
+    let mutation = GaussianMutation::new(config.mutation_chance, config.mutation_coeff);
+    let ga = if config.linkage {
+        GeneticAlgorithm::new(selection, LinkedUniformCrossover::new(Nizm::segments(config)), mutation)
+    } else {
+        GeneticAlgorithm::new(selection, UniformCrossover, mutation)
+    };
+
+    debug_assert_eq!(config.check(), Ok(()));
+    let mut ga = ga
+        .with_weight_decay(config.weight_decay)
+        .with_fitness_scaling(config.fitness_scaling)
+        .with_observer(ChampionLog);
+    if config.log_mutations {
+        ga = ga.with_mutation_logging();
+    }
+    if config.log_provenance {
+        ga = ga.with_provenance_tracking();
+    }
+    if config.crowding {
+        ga = ga.with_deterministic_crowding();
+    }
+    if let Some(surrogate) = surrogate::surrogate(config) {
+        ga = ga.with_surrogate(surrogate);
+    }
+
+    //newcomers are drawn like the brains of the first generation
+    let newcomer = |rng: &mut dyn RngCore, genes: &mut [f32]| genes.iter_mut().for_each(|gene| *gene = rng.gen_range(-1.0..=1.0));
+    if config.age_layers > 0 {
+        Box::new(AgeLayers::new(ga, config.age_layers, config.age_gap.max(1), newcomer))
+    } else if config.fitness_bands > 0 {
+        Box::new(FitnessBands::new(ga, config.fitness_bands, config.age_gap.max(1), newcomer))
+    } else {
+        Box::new(ga)
+    }
+}
+
+#[derive(Resource)]
+pub struct EvolutionTimer(pub Timer);
+
+#[derive(Resource)]
+pub struct ThinkTimer(pub Timer);
+
+//how long each simulation stage took
+#[derive(Resource, Default)]
+pub struct SystemTimings {
+    //the last time the stage did its work
+    pub last: BTreeMap<&'static str, Duration>,
+    //summed over the current frame
+    pub frame: BTreeMap<&'static str, Duration>,
+    //summed over the last completed generation
+    pub generation: BTreeMap<&'static str, Duration>,
+    running_generation: BTreeMap<&'static str, Duration>,
+}
+
+impl SystemTimings {
+    fn record(&mut self, stage: &'static str, start: Instant) {
+        self.add(stage, start.elapsed());
+    }
+
+    fn add(&mut self, stage: &'static str, duration: Duration) {
+        self.last.insert(stage, duration);
+        *self.frame.entry(stage).or_default() += duration;
+        *self.running_generation.entry(stage).or_default() += duration;
+    }
+
+    fn finish_generation(&mut self) {
+        self.generation = std::mem::take(&mut self.running_generation);
+    }
+}
+
+fn clear_frame_timings(mut timings: ResMut<SystemTimings>) {
+    timings.frame.clear();
+}
+
+//a nizm as the optimizer sees it, its genes are the weights of its brain so the offspring
+//is written straight into the network
+struct NizmIndividual<'a> {
+    network: &'a mut Network,
+    fitness: f32,
+    fitness_stderr: f32,
+    descriptor: [f32; 2],
+}
+
+impl InPlaceIndividual for NizmIndividual<'_> {
+    fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    fn fitness_stderr(&self) -> f32 {
+        self.fitness_stderr
+    }
+
+    fn descriptor(&self) -> &[f32] {
+        &self.descriptor
+    }
+
+    fn genes(&self) -> &[f32] {
+        self.network.genes()
+    }
+
+    fn genes_mut(&mut self) -> &mut [f32] {
+        self.network.genes_mut()
+    }
+}
+
+//a genome saved with other layers or senses than those of `config` does not fit its brains,
+//loaded genomes are checked before they are turned into one
+pub fn check_genome(config: &Config, chromosome: &Chromosome) -> Result<(), String> {
+    let len = Nizm::segments(config).last().map_or(0, |segment| segment.end);
+    if chromosome.len() != len {
+        return Err(format!("a brain has {len} genes, not {}", chromosome.len()));
+    }
+    Ok(())
+}
+
+pub fn check_genomes<'a>(config: &Config, chromosomes: impl IntoIterator<Item = &'a Chromosome>) -> Result<(), String> {
+    chromosomes.into_iter().try_for_each(|chromosome| check_genome(config, chromosome))
+}
+
+//the genes of a brain with every weight smaller than `threshold` zeroed, and how many of its
+//connections are left out of how many
+pub fn prune(config: &Config, chromosome: &Chromosome, threshold: f32) -> Result<(Chromosome, usize, usize), String> {
+    check_genome(config, chromosome)?;
+    let network = Network::from_data(&Nizm::topology(config), chromosome.clone());
+    let (_, total) = network.pruned(0.0);
+    let (pruned, connections) = network.pruned(threshold);
+    Ok((pruned.data().collect(), connections, total))
+}
+
+//the genes of a brain trained outside of the sim, from a keras/numpy .npz or .json, see
+//Network::from_npz and Network::from_json, its layers have to match those of a brain
+pub fn import_weights(config: &Config, path: impl AsRef<std::path::Path>) -> Result<Chromosome, String> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let network = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("npz") => Network::from_npz(&bytes),
+        Some("json") => Network::from_json(&String::from_utf8_lossy(&bytes)),
+        _ => return Err(format!("{}: expected a .npz or .json file", path.display())),
+    }
+    .map_err(|e| format!("{}: {e}", path.display()))?;
+
+    if network.segments().ne(Nizm::segments(config)) {
+        let layers: Vec<_> = Nizm::topology(config).iter().map(|layer| layer.neurons.to_string()).collect();
+        return Err(format!("{}: the layers do not match those of a brain, {}", path.display(), layers.join("-")));
+    }
+    Ok(network.data().collect())
+}
+
+//how often every neuron of the brain of a genome fires over a generation alone in a world
+//from `seed`, see Network::activity
+pub fn activity(config: &Config, seed: u64, chromosome: &Chromosome) -> Result<Activity, String> {
+    check_genome(config, chromosome)?;
+    let network = Network::from_data(&Nizm::topology(config), chromosome.clone());
+    Ok(network.activity(rules::SimCore::observe(config, seed, chromosome)))
+}
+
+//the brain of a genome as an onnx model, see Network::to_onnx
+pub fn to_onnx(config: &Config, chromosome: &Chromosome) -> Result<Vec<u8>, String> {
+    check_genome(config, chromosome)?;
+    Network::from_data(&Nizm::topology(config), chromosome.clone()).to_onnx().map_err(|e| e.to_string())
+}
+
+pub fn chromosome_to_color(genes: &[f32]) -> Color {
+    let hash: i32 = genes.iter().fold(0, |acc, v| acc.wrapping_mul(23).wrapping_add((v * 100.0) as i32));
+
+    let float1 = (hash as f64 * 23.32).sin() as f32;
+    let float2 = (hash as f64 * 73261.2).sin() as f32;
+    let float3 = (hash as f64 * 32132.21).sin() as f32;
+    Color::rgb(float1, float2, float3)
+}
+
+//what lay_out_generation() needs besides the individuals and kill zones
+#[derive(SystemParam)]
+struct WorldLayout<'w, 's> {
+    environment: ResMut<'w, Environment>,
+    environment_rng: ResMut<'w, EnvironmentRng>,
+    obstacles: Query<'w, 's, Entity, With<Obstacle>>,
+}
+
+fn evolution(time: Res<Time>,
+             config: Res<Config>,
+             mut rng: ResMut<SimRng>,
+             mut evolution: NonSendMut<Evolution>,
+             mut timer: ResMut<EvolutionTimer>,
+             mut timings: ResMut<SystemTimings>,
+             mut commands: Commands,
+             mut curriculum: Option<ResMut<Curriculum>>,
+             mut layout: WorldLayout,
+             mut external: Option<ResMut<ExternalSelection>>,
+             (mut species, mut species_commands): (Option<ResMut<SpeciesRegistry>>, EventReader<SpeciesCommand>),
+             state: Option<Res<State<SimState>>>,
+             mut query: Query<(&mut Nizm, &mut Transform, &mut Tint, Option<&mut Health>), (Without<KillZone>, Without<Frozen>)>,
+             mut frozen: Query<(&mut Nizm, &mut Transform), (With<Frozen>, Without<KillZone>)>,
+             mut statistics: Query<&mut Statistics>,
+             mut killzones: Query<(Entity, &mut KillZone, &mut Transform)>) {
+    for command in species_commands.iter() {
+        if let Some(species) = species.as_mut() {
+            species.apply(command);
+        }
+    }
+
+    if (timer.0.tick(time.delta())).just_finished() {
+        let start = Instant::now();
+        let rng = &mut rng.0;
+
+        let mut nizms: Vec<_> = query.iter_mut().collect();
+        let mut survivors = 0;
+        let mut time_in_killzone = 0.0;
+        let mut occupancy = Vec::with_capacity(nizms.len());
+        let mut terms = Vec::with_capacity(nizms.len());
+        for (brain, transform, _, health) in &mut nizms {
+            let inside = killzones.iter().any(|(_, killzone, _)| killzone.contains(transform.translation.x));
+            let fitness_terms = rules::fitness_terms(&config, brain, transform.translation.x, health.as_deref(), killzones.iter().map(|(_, killzone, _)| killzone));
+            let fitness = fitness_terms.total();
+            terms.push(fitness_terms);
+            time_in_killzone += brain.time_in_killzone;
+            occupancy.push(occupancy::share(brain.time_in_killzone, config.generation_time));
+            if health.as_ref().map_or(!inside, |health| health.is_alive()) {
+                survivors += 1;
+            }
+            brain.scores.push(fitness);
+        }
+
+        //every trial but the last only moves on to a fresh kill zone layout
+        let evaluator = MultiTrialEvaluator::new(config.trials, config.trial_aggregation);
+        let scored = nizms.iter().all(|(brain, ..)| brain.scores.len() >= evaluator.trials());
+
+        if scored {
+            let (mut fitness, stderr): (Vec<f32>, Vec<f32>) = nizms
+                .iter_mut()
+                .map(|(brain, ..)| {
+                    let stderr = evaluator.estimate(&brain.scores).stderr();
+                    let fitness = evaluator.aggregate(&mut brain.scores);
+                    brain.scores.clear();
+                    (fitness, stderr)
+                })
+                .unzip();
+
+            //the genes are overwritten by the offspring, so the survivors are measured first
+            let mut stats = statistics.get_single_mut().expect("Stats");
+            stats.generation += 1;
+            //of the last trial
+            stats.survivors_percentage = survivors as f32 / config.individuals as f32;
+            stats.time_in_killzone = time_in_killzone / config.individuals as f32;
+            stats.killzone_occupancy = KillzoneOccupancy::of(&occupancy);
+            stats.fitness_terms = FitnessTerms::mean(&terms);
+            stats.best_fitness = fitness.iter().copied().fold(0.0, f32::max);
+            stats.average_fitness = fitness.iter().sum::<f32>() / fitness.len() as f32;
+            stats.genetic_variance = genetic_variance(nizms.iter().map(|(brain, ..)| brain.network.genes()));
+
+            let (best, &best_fitness) = fitness
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .expect("need individuals");
+            stats.new_champion = stats.champion.is_none() || best_fitness > stats.champion_fitness;
+            if stats.new_champion {
+                stats.champion = Some(nizms[best].0.network.genes().iter().copied().collect());
+                stats.champion_fitness = best_fitness;
+            }
+
+            if let Some(external) = external.as_mut() {
+                let descriptors = nizms.iter().map(|(brain, ..)| brain.descriptor()).collect();
+                external.scores = Some(GenerationScores { fitness, stderr, survivors, descriptors, occupancy });
+            } else if !states::replaying(state.as_deref()) {
+                let protected = match species.as_mut() {
+                    Some(species) => {
+                        let protected = species.prepare(nizms.iter().map(|(brain, ..)| brain.network.genes()), &mut fitness);
+                        stats.species = species.speciation.species().len();
+                        protected
+                    }
+                    None => Vec::new(),
+                };
+
+                let mut individuals: Vec<_> = nizms
+                    .iter_mut()
+                    .zip(fitness.iter().zip(&stderr))
+                    .map(|((brain, ..), (&fitness, &fitness_stderr))| {
+                        let descriptor = brain.descriptor();
+                        NizmIndividual { network: &mut brain.network, fitness, fitness_stderr, descriptor }
+                    })
+                    .collect();
+                let mut population: Vec<&mut dyn InPlaceIndividual> = individuals
+                    .iter_mut()
+                    .map(|individual| individual as &mut dyn InPlaceIndividual)
+                    .collect();
+                evolution.time_in_killzone.set(stats.time_in_killzone);
+                evolution.killzone_occupancy.set(stats.killzone_occupancy);
+                evolution.fitness_terms.set(stats.fitness_terms);
+                evolution.optimizer.evolve_in_place(rng, &mut population);
+                for (index, genes) in protected {
+                    nizms[index].0.network.genes_mut().copy_from_slice(&genes);
+                }
+
+                if let Some(ga_statistics) = evolution.optimizer.statistics() {
+                    stats.takeover = ga_statistics.takeover;
+                    stats.distinct_genotypes = ga_statistics.distinct_genotypes;
+                    stats.selection_intensity = ga_statistics.selection_intensity;
+                    stats.fitness_stderr = ga_statistics.avg_fitness_stderr;
+                    stats.regularization = ga_statistics.avg_regularization;
+                    if let Some(mutations) = &ga_statistics.mutations {
+                        stats.mutated_share = mutations.changed_share();
+                        stats.mutation_size = mutations.avg_change();
+                    }
+                    if let Some(provenance) = &ga_statistics.provenance {
+                        stats.parental_mixing = provenance.avg_mixing();
+                    }
+                    stats.crowded_out = ga_statistics.crowded_out;
+                    stats.surrogate_error = ga_statistics.surrogate_error.unwrap_or(0.0);
+                }
+            }
+
+            if let Some(curriculum) = curriculum.as_mut() {
+                if curriculum.advance(stats.average_fitness) {
+                    info!("curriculum stage {} of {}", curriculum.stage() + 1, curriculum.stages());
+                }
+                stats.curriculum_stage = curriculum.stage();
+            }
+        }
+
+        if scored {
+            let resume = ResumePoint {
+                rng: rng.clone(),
+                environment_rng: layout.environment_rng.0.clone(),
+                generation: statistics.get_single().expect("Stats").generation,
+                evolution_timer: timer.0.elapsed(),
+                think_timer: Duration::ZERO,
+            };
+            //the think timer is read when the commands are applied, it does not tick in between
+            commands.add(move |world: &mut World| {
+                let think_timer = world.resource::<ThinkTimer>().0.elapsed();
+                world.insert_resource(ResumePoint { think_timer, ..resume });
+            });
+        }
+
+        for (brain, _, tint, health) in nizms.iter_mut() {
+            //offspring get the color of their genes, the dead come back to life
+            if scored {
+                tint.0 = chromosome_to_color(brain.network.genes());
+            }
+            tint.0.set_a(1.0);
+            if let Some(health) = health.as_mut() {
+                **health = Health::default();
+            }
+            brain.reset();
+        }
+        for (mut brain, _) in frozen.iter_mut() {
+            brain.reset();
+        }
+
+        lay_out_generation(
+            &mut commands,
+            &config,
+            curriculum.as_deref(),
+            &mut layout.environment,
+            &mut layout.environment_rng.0,
+            rng,
+            layout.obstacles.iter(),
+            nizms.iter_mut().map(|(_, transform, ..)| transform.as_mut()).chain(frozen.iter_mut().map(|(_, transform)| transform.into_inner())),
+            &mut killzones,
+        );
+
+        timings.record("evolution", start);
+        if scored {
+            timings.finish_generation();
+        }
+    }
+}
+
+//the world of a new generation, the environment, where the individuals start and the kill
+//zones, resuming a checkpoint lays it out again with the same draws in the same order
+#[allow(clippy::too_many_arguments)]
+fn lay_out_generation<'a>(commands: &mut Commands,
+                          config: &Config,
+                          curriculum: Option<&Curriculum>,
+                          environment: &mut Environment,
+                          environment_rng: &mut ChaCha8Rng,
+                          rng: &mut ChaCha8Rng,
+                          obstacles: impl Iterator<Item = Entity>,
+                          individuals: impl Iterator<Item = &'a mut Transform>,
+                          killzones: &mut Query<(Entity, &mut KillZone, &mut Transform)>) {
+    *environment = Environment::random(environment_rng, config);
+    for entity in obstacles {
+        commands.entity(entity).despawn();
+    }
+    spawn_obstacles(commands, environment);
+
+    for (i, transform) in individuals.enumerate() {
+        transform.translation = environment.spawn_position(rng, i);
+    }
+
+    let difficulty = environment.difficulty(&curriculum.map_or_else(|| config.difficulty(), |curriculum| curriculum.difficulty().clone()));
+    let mut existing = killzones.iter_mut();
+    for _ in 0..difficulty.killzones {
+        let new = environment.protect(KillZone::random(rng, &difficulty));
+        match existing.next() {
+            Some((_, mut killzone, mut transform)) => {
+                transform.translation.x = new.center();
+                *killzone = new;
+            }
+            None => spawn_killzone(commands, new),
+        }
+    }
+    for (entity, _, _) in existing {
+        commands.entity(entity).despawn();
+    }
+}
+
+//variance of each gene across the population, averaged over all genes
+fn genetic_variance<'a>(chromosomes: impl Iterator<Item = &'a [f32]> + Clone) -> f32 {
+    let count = chromosomes.clone().count() as f32;
+    let Some(genes) = chromosomes.clone().next().map(|chromosome| chromosome.len()) else {
+        return 0.0;
+    };
+
+    let variance_sum: f32 = (0..genes)
+        .map(|gene| {
+            let mean = chromosomes.clone().map(|c| c[gene]).sum::<f32>() / count;
+            chromosomes.clone().map(|c| (c[gene] - mean).powi(2)).sum::<f32>() / count
+        })
+        .sum();
+
+    variance_sum / genes as f32
+}
+
+fn check_if_can_move(time: Res<Time>,
+                     config: Res<Config>,
+                     environment: Res<Environment>,
+                     mut timings: ResMut<SystemTimings>,
+                     mut query: Query<(&mut Nizm, &Transform)>) {
+    let start = Instant::now();
+    let speed = config.movement_speed * environment.speed_factor;
+
+    let mut combinations = query.iter_combinations_mut();
+    while let Some([(mut nizm, transform), (_, other)]) = combinations.fetch_next() {
+        let [left, right, up, down] = rules::blocked_directions(transform.translation, other.translation, time.delta().as_secs_f32(), speed);
+        (nizm.can_move_left, nizm.can_move_right, nizm.can_move_up, nizm.can_move_down) = (left, right, up, down);
+    }
+
+    timings.record("collision", start);
+}
+
+fn move_individuals(time: Res<Time>,
+                    config: Res<Config>,
+                    environment: Res<Environment>,
+                    timer: Res<EvolutionTimer>,
+                    mut timings: ResMut<SystemTimings>,
+                    mut query: Query<(Entity, &mut Nizm, Option<&Health>)>,
+                    mut transforms: Query<&mut Transform, With<Blocking>>) {
+    let start = Instant::now();
+
+    let speed = config.movement_speed * environment.speed_factor;
+    for (entity, mut nizm, health) in query.iter_mut() {
+        if health.is_some_and(|health| !health.is_alive()) {
+            continue;
+        }
+        let translation = transforms.get_mut(entity).expect("WTF").translation;
+
+        let wind = wind::wind_at(&config, translation.truncate(), timer.0.elapsed_secs());
+        let movement = rules::movement(translation, nizm.action, time.delta().as_secs_f32(), speed, wind);
+        let movement = rules::within_budget(movement, nizm.total_movement, config.movement_budget);
+
+        if !transforms.iter().any(|t| translation != t.translation && rules::collides(translation + movement, t.translation)) {
+            transforms.get_mut(entity).expect("WTF").translation = translation + movement;
+            nizm.total_movement += movement.length();
+            nizm.displacement += movement;
+            nizm.movement = movement;
+        } else {
+            nizm.movement = Vec3::ZERO;
+        }
+        rules::track_idle(&mut nizm, time.delta().as_secs_f32());
+    }
+
+    timings.record("movement", start);
+}
+
+//the kill zones are only checked at the end of a generation, this remembers how long
+//everybody spent in them before
+fn track_killzone_time(time: Res<Time>,
+                       killzones: Query<&KillZone>,
+                       mut query: Query<(&mut Nizm, &Transform, Option<&Health>)>) {
+    for (mut nizm, transform, health) in query.iter_mut() {
+        let alive = health.is_none_or(Health::is_alive);
+        if alive && killzones.iter().any(|killzone| killzone.contains(transform.translation.x)) {
+            nizm.time_in_killzone += time.delta_seconds();
+        }
+    }
+}
+
+fn damage_individuals(time: Res<Time>,
+                      config: Res<Config>,
+                      timer: Res<EvolutionTimer>,
+                      mut timings: ResMut<SystemTimings>,
+                      killzones: Query<&KillZone>,
+                      mut query: Query<(Entity, &Transform, &mut Health), Without<Frozen>>,
+                      mut deaths: EventWriter<Died>) {
+    let start = Instant::now();
+
+    for (entity, transform, mut health) in query.iter_mut() {
+        if !health.is_alive() || !killzones.iter().any(|killzone| killzone.contains(transform.translation.x)) {
+            continue;
+        }
+
+        let at = timer.0.elapsed_secs();
+        if rules::damage(&mut health, config.killzone_damage * time.delta_seconds(), at) {
+            deaths.send(Died { entity, at });
+        }
+    }
+
+    timings.record("damage", start);
+}
+
+fn make_individuals_think(time: Res<Time>,
+                          config: Res<Config>,
+                          timer: Res<EvolutionTimer>,
+                          actions: Res<Actions>,
+                          mut normalizer: ResMut<InputNormalizer>,
+                          mut rng: ResMut<SimRng>,
+                          mut sensor_rng: ResMut<SensorRng>,
+                          mut think_timer: ResMut<ThinkTimer>,
+                          mut timings: ResMut<SystemTimings>,
+                          transforms: Query<&Transform>,
+                          mut nizms: Query<(Entity, &mut Nizm)>,
+                          killzones: Query<&KillZone>,
+                          rewards: Option<Res<RewardField>>,
+                          #[cfg(feature = "gpu")] mut gpu: Option<ResMut<gpu::GpuBrains>>,
+                          statistics: Query<&Statistics>) {
+    //brains tick at a fixed rate, in between the last action is held
+    if !think_timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+
+    let start = Instant::now();
+    let generation = statistics.get_single().map(|statistics| statistics.generation).unwrap_or(0);
+    let remaining = timer.0.elapsed_secs() / timer.0.duration().as_secs_f32();
+    //counted from the generations, so it does not depend on how fast the frames were
+    let time = generation as f32 * config.generation_time + timer.0.elapsed_secs();
+    let mut inputs: Vec<Vec<f32>> = nizms
+        .iter()
+        .map(|(entity, nizm)| {
+            let position = transforms.get(entity).expect("WTF").translation;
+            let reward = rewards.as_ref().map_or(0.0, |rewards| rewards.sample(position.truncate()));
+            rules::senses(&config, nizm, position, remaining, time, reward, &killzones)
+        })
+        .collect();
+    noise::add_noise(&config, nizms.iter().map(|(_, nizm)| nizm), &mut inputs, &mut sensor_rng.0);
+    normalizer.normalize(&mut inputs);
+    for ((_, nizm), inputs) in nizms.iter().zip(&mut inputs) {
+        inputs.extend(nizm.network.clock(remaining));
+    }
+    timings.record("sensing", start);
+
+    let start = Instant::now();
+    //stepping lets recurrent brains remember what they saw
+    let think_on_cpu = |nizms: &mut Query<(Entity, &mut Nizm)>, inputs: Vec<Vec<f32>>| -> Vec<Vec<f32>> {
+        nizms
+            .iter_mut()
+            .zip(inputs)
+            .map(|((_, mut nizm), inputs)| nizm.network.step(inputs))
+            .collect()
+    };
+
+    #[cfg(feature = "gpu")]
+    let outputs = gpu
+        .as_mut()
+        .and_then(|gpu| {
+            gpu.propagate(generation, nizms.iter().map(|(_, nizm)| &nizm.network), &inputs)
+                .map_err(|err| error!("thinking on the cpu, the gpu failed: {err}"))
+                .ok()
+        })
+        .unwrap_or_else(|| think_on_cpu(&mut nizms, inputs));
+    #[cfg(not(feature = "gpu"))]
+    let outputs = think_on_cpu(&mut nizms, inputs);
+
+    let noise = config.action_noise_at(generation);
+    for ((_, mut nizm), result) in nizms.iter_mut().zip(outputs) {
+        rules::act(&mut nizm, &result, &*actions.0, noise, &mut rng.0);
+    }
+
+    timings.record("think", start);
+}
+
+fn init_statistics(mut commands: Commands) {
+    commands.spawn((
+        Name::new("statistics"),
+        Statistics::default(),
+    ));
+}
+
+fn init_environment(config: Res<Config>,
+                    mut environment_rng: ResMut<EnvironmentRng>,
+                    mut environment: ResMut<Environment>,
+                    mut commands: Commands) {
+    *environment = Environment::random(&mut environment_rng.0, &config);
+    spawn_obstacles(&mut commands, &environment);
+}
+
+fn spawn_obstacles(commands: &mut Commands, environment: &Environment) {
+    for position in &environment.obstacles {
+        commands.spawn((
+            TransformBundle::from_transform(Transform::from_translation(position.extend(900.0))),
+            Obstacle,
+            Blocking,
+        ));
+    }
+}
+
+fn init_killzones(mut commands: Commands,
+                  config: Res<Config>,
+                  mut rng: ResMut<SimRng>,
+                  environment: Res<Environment>,
+                  curriculum: Option<Res<Curriculum>>) {
+    let difficulty = environment.difficulty(&curriculum.map_or_else(|| config.difficulty(), |curriculum| curriculum.difficulty().clone()));
+
+    for _ in 0..difficulty.killzones {
+        spawn_killzone(&mut commands, environment.protect(KillZone::random(&mut rng.0, &difficulty)));
+    }
+}
+
+fn spawn_killzone(commands: &mut Commands, killzone: KillZone) {
+    commands.spawn((
+        TransformBundle::from_transform(Transform::from_translation(Vec3::new(killzone.center(), 0.0, 10.0))),
+        killzone,
+    ));
+}
+
+//drifts the moving kill zones, they turn around at the edges of the world
+fn move_killzones(time: Res<Time>,
+                  environment: Res<Environment>,
+                  mut killzones: Query<(&mut KillZone, &mut Transform)>) {
+    for (mut killzone, mut transform) in killzones.iter_mut() {
+        if killzone.velocity == 0.0 {
+            continue;
+        }
+
+        rules::drift(&mut killzone, time.delta_seconds(), &environment);
+        transform.translation.x = killzone.center();
+    }
+}
+
+fn add_individuals(config: Res<Config>,
+                   environment: Res<Environment>,
+                   initial_population: Option<Res<InitialPopulation>>,
+                   mut rng: ResMut<SimRng>,
+                   mut commands: Commands) {
+    let rng = &mut rng.0;
+
+    for i in 0..config.individuals {
+        let translation = environment.spawn_position(rng, i);
+        let nizm = match &initial_population {
+            Some(population) if !population.0.is_empty() => {
+                Nizm::from_chromosome(&population.0[i % population.0.len()], &config)
+            }
+            _ => Nizm::random(rng, &config),
+        };
+
+        spawn_individual(&mut commands, &config, i, translation, nizm);
+    }
+}
+
+//individual `i` of the population, it can be hurt with killzone_damage
+fn spawn_individual(commands: &mut Commands, config: &Config, i: usize, translation: Vec3, nizm: Nizm) {
+    let mut individual = commands.spawn((
+        TransformBundle::from_transform(Transform::from_translation(translation)),
+        Name::new(format!("nizm_{i}")),
+        Tint(chromosome_to_color(nizm.network.genes())),
+        nizm,
+        Blocking
+    ));
+    if config.killzone_damage > 0.0 {
+        individual.insert(Health::default());
+    }
+}
+
+//lays out the world again from a ResumePoint, replacing the one the startup systems made
+#[allow(clippy::too_many_arguments)]
+fn resume_run(resume: Option<Res<Resume>>,
+              config: Res<Config>,
+              curriculum: Option<Res<Curriculum>>,
+              mut rng: ResMut<SimRng>,
+              mut environment: ResMut<Environment>,
+              mut environment_rng: ResMut<EnvironmentRng>,
+              mut evolution_timer: ResMut<EvolutionTimer>,
+              mut think_timer: ResMut<ThinkTimer>,
+              mut commands: Commands,
+              obstacles: Query<Entity, With<Obstacle>>,
+              mut individuals: Query<&mut Transform, (With<Nizm>, Without<KillZone>)>,
+              mut statistics: Query<&mut Statistics>,
+              mut killzones: Query<(Entity, &mut KillZone, &mut Transform)>) {
+    let Some(resume) = resume else {
+        return;
+    };
+    let resume = resume.0.clone();
+
+    rng.0 = resume.rng.clone();
+    environment_rng.0 = resume.environment_rng.clone();
+    evolution_timer.0.set_elapsed(resume.evolution_timer);
+    think_timer.0.set_elapsed(resume.think_timer);
+    statistics.get_single_mut().expect("Stats").generation = resume.generation;
+
+    lay_out_generation(
+        &mut commands,
+        &config,
+        curriculum.as_deref(),
+        &mut environment,
+        &mut environment_rng.0,
+        &mut rng.0,
+        obstacles.iter(),
+        individuals.iter_mut().map(Mut::into_inner),
+        &mut killzones,
+    );
+    commands.remove_resource::<Resume>();
+    commands.insert_resource(resume);
+}
+
+//the simulation itself, without any rendering, expects a Config and SimRng resource
+pub struct SimPlugin;
+
+impl Plugin for SimPlugin {
+    fn build(&self, app: &mut App) {
+        let config = app.world.get_resource::<Config>().expect("need config").clone();
+
+        if config.gpu {
+            #[cfg(feature = "gpu")]
+            match gpu::GpuBrains::new(&config) {
+                Ok(brains) => {
+                    app.insert_resource(brains);
+                }
+                Err(err) => error!("thinking on the cpu, could not use the gpu: {err}"),
+            }
+            #[cfg(not(feature = "gpu"))]
+            error!("thinking on the cpu, built without the gpu feature");
+        }
+
+        if config.species_threshold > 0.0 {
+            app.insert_resource(SpeciesRegistry::new(config.species_threshold));
+        }
+
+        if config.fitness.curiosity_weight > 0.0 {
+            app.insert_resource(VisitField::new(curiosity::CELLS));
+        }
+
+        if config.curriculum && !app.world.contains_resource::<Curriculum>() {
+            app.insert_resource(Curriculum::default());
+        }
+
+        let seed = app.world.get_resource::<SimRng>().expect("need rng").0.get_seed();
+
+        app.insert_non_send_resource(Evolution::new(optimizer(&config)))
+            .insert_resource(EnvironmentRng::from_seed(seed))
+            .insert_resource(SensorRng::from_seed(seed))
+            .init_resource::<Environment>()
+            .insert_resource(Actions(config.action_encoding.decoder()))
+            .insert_resource(InputNormalizer::new(&config))
+            .insert_resource(EvolutionTimer(Timer::from_seconds(config.generation_time, TimerMode::Repeating)))
+            .insert_resource(ThinkTimer(Timer::from_seconds(1.0 / config.think_rate, TimerMode::Repeating)))
+            .init_resource::<SystemTimings>()
+            .init_resource::<Watchdog>()
+            .add_event::<Died>()
+            .add_event::<SpeciesCommand>()
+            .add_event::<PopulationCollapsed>()
+            .add_event::<ConfigChanged>()
+            .add_event::<RestartRun>()
+            .add_event::<RunRestarted>()
+            .init_resource::<CurrentRun>()
+            .add_system_to_stage(CoreStage::First, clear_frame_timings)
+            .add_system_to_stage(CoreStage::PreUpdate, restart::restart_run)
+            .add_startup_system(add_individuals)
+            .add_startup_system(init_statistics)
+            .add_startup_system(init_environment.before(init_killzones).before(add_individuals))
+            .add_startup_system(init_killzones.before(add_individuals))
+            .add_startup_system_to_stage(StartupStage::PostStartup, resume_run)
+            .add_system_set(
+                SystemSet::new()
+                    .with_run_criteria(states::simulating)
+                    .with_system(move_killzones.before(make_individuals_think))
+                    .with_system(check_if_can_move.before(make_individuals_think))
+                    .with_system(make_individuals_think.before(move_individuals))
+                    .with_system(move_individuals)
+                    .with_system(track_killzone_time.after(move_individuals).before(evolution))
+                    .with_system(damage_individuals.after(move_individuals).before(evolution))
+                    .with_system(curiosity::explore.after(move_individuals).before(evolution))
+                    .with_system(evolution.after(move_individuals))
+                    .with_system(alleles::track_alleles.after(evolution))
+                    .with_system(watchdog::watch_behavior.after(move_individuals).before(evolution))
+                    .with_system(watchdog::watch_generations.after(evolution)),
+            )
+            .add_system(manifest::track_config);
+    }
+}
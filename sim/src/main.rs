@@ -1,432 +1,431 @@
+mod cli;
 mod debug;
+mod tui;
 
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use bevy::app::PluginGroupBuilder;
 use bevy::prelude::*;
-use bevy::render::camera::ScalingMode;
-use bevy::sprite::collide_aabb::collide;
 use bevy::window::PresentMode;
-use bevy_inspector_egui::{Inspectable};
-use lib_neural_network::{LayerTopology, Network};
+use clap::Parser;
+use lib_neural_network::LayerDiff;
 use rand::prelude::*;
-use lib_natural_selection::{Chromosome, GaussianMutation, GeneticAlgorithm, Individual, RouletteWheelSelection, UniformCrossover};
+use sim::metrics::MetricsPlugin;
+use sim::alleles::{AllelePlugin, AlleleTensor, AlleleTracker};
+use sim::curriculum::Curriculum;
+use sim::dashboard::DashboardPlugin;
+use sim::batch::BatchedRun;
+use sim::compare::{ComparePlugin, Variant};
+use sim::diff::DiffPlugin;
+use sim::editor::EditorPlugin;
+use sim::elites::ElitesPlugin;
+use sim::freeze::FreezePlugin;
+use sim::gallery::{load_champion, load_champions, Champion, GalleryPlugin};
+use sim::manifest::{ConfigConsolePlugin, RunManifest};
+use sim::headless::{checkpoint, headless_app_with_population, resumed_app, run_generations, statistics};
+use sim::render::{SimRenderPlugin, ASPECT_RATIO};
+#[cfg(feature = "sqlite")]
+use sim::database::ExperimentDatabase;
+use sim::observers::CsvObserver;
+use sim::painting::PaintingPlugin;
+use sim::persistence::{IoHandle, IoWorker, PersistencePlugin};
+use sim::population::Checkpoint;
+use sim::profiler::ProfilerPlugin;
+use sim::restart::{self, RestartHook, RestartPlugin};
+use sim::shutdown::{self, ShutdownPlugin};
+use sim::species::SpeciesPlugin;
+use sim::states::{SimState, StatesPlugin};
+use sim::summary::SummaryPlugin;
+use sim::timeline::TimelinePlugin;
+use sim::watchdog::WatchdogPlugin;
+use sim::wind::WindPlugin;
+use sim::{Config, Evolution, InitialPopulation, SimPlugin, SimRng, Statistics};
+use crate::cli::{Cli, Command, SimArgs};
 use crate::debug::DebugPlugin;
 
-pub const CLEAR: Color = Color::rgb(0.1, 0.1, 0.1);
-pub const ASPECT_RATIO: f32 = 1.0;
-
-#[derive(Resource)]
-struct AsciiSheet(Handle<TextureAtlas>);
-
-fn load_ascii(mut commands: Commands,
-              assets: Res<AssetServer>,
-              mut texture_atlases: ResMut<Assets<TextureAtlas>>) {
-    let image = assets.load("Ascii.png");
-    let atlas = TextureAtlas::from_grid(
-        image,
-        Vec2::splat(9.0),
-        16, 16,
-        Some(Vec2::splat(2.0)), None);
-
-    let atlas_handle = texture_atlases.add(atlas);
-    commands.insert_resource(AsciiSheet(atlas_handle));
-}
-
-#[derive(Component, Inspectable)]
-struct Statistics {
-    generation: i32,
-    survivors_percentage: f32,
-    genetic_variance: f32,
-}
-
-impl Statistics {
-    fn new() -> Self {
-        Self {
-            generation: 0,
-            survivors_percentage: 0.0,
-            genetic_variance: 0.0,
-        }
-    }
-}
-
-#[derive(Component, Inspectable)]
-struct KillZone {
-    min: f32,
-    max: f32,
-}
-
-#[derive(Resource)]
-struct Config {
-    individuals: usize,
-    movement_speed: f32,
-}
+fn window_plugins(title: &str) -> PluginGroupBuilder {
+    let height: f32 = 800.0;
 
-#[derive(Component, Inspectable)]
-struct Nizm {
-    #[inspectable(ignore)]
-    network: Network,
-    osc_freq: f32,
-    movement: Vec3,
-    can_move_left: f32,
-    can_move_right: f32,
-    can_move_up: f32,
-    can_move_down: f32,
-    total_movement: f32,
+    DefaultPlugins.set(WindowPlugin {
+        window: WindowDescriptor {
+            title: title.to_string(),
+            width: height * ASPECT_RATIO,
+            height,
+            present_mode: PresentMode::AutoNoVsync,
+            ..default()
+        },
+        ..default()
+    })
 }
 
-#[derive(Component)]
-struct Blocking;
+//`replay` watches the initial population without evolving it
+#[allow(clippy::too_many_arguments)]
+fn run(config: Config,
+       seed: u64,
+       initial_population: Option<InitialPopulation>,
+       curriculum: Option<Curriculum>,
+       metrics: Option<MetricsPlugin>,
+       recorders: Recorders,
+       window: &SimArgs,
+       replay: bool) {
+    let mut app = App::new();
+    //the start menu is for runs that start from scratch
+    let initial = if replay {
+        SimState::Replay
+    } else if window.preset.is_none() && initial_population.is_none() {
+        SimState::Menu
+    } else {
+        SimState::Running
+    };
 
-impl Nizm {
-    fn random(rng: &mut dyn RngCore) -> Self {
-        let network = Network::random(
-            rng,
-            Self::topology(),
-        );
-        Self {
-            network,
-            osc_freq: 1.0,
-            movement: Vec3::ZERO,
-            can_move_left: 1.0,
-            can_move_right: 1.0,
-            can_move_up: 1.0,
-            can_move_down: 1.0,
-            total_movement: 0.0,
-        }
+    if let Some(population) = initial_population {
+        app.insert_resource(population);
     }
-
-    fn reset(&mut self) {
-        self.osc_freq = 1.0;
-        self.movement = Vec3::ZERO;
-        self.total_movement = 0.0;
+    if let Some(metrics) = metrics {
+        app.add_plugin(metrics);
     }
 
-    fn topology() -> &'static [LayerTopology] {
-        &[
-            LayerTopology { neurons: 11 },
-            LayerTopology { neurons: 24 },
-            LayerTopology { neurons: 5 },
-        ]
+    app.insert_resource(config)
+        .insert_resource(window.hud())
+        .insert_resource(window.render_options())
+        .insert_resource(SimRng::from_seed(seed))
+        .add_plugins(window_plugins("Rustism"))
+        .add_plugin(SimPlugin)
+        .add_plugin(SimRenderPlugin)
+        .add_plugin(PersistencePlugin)
+        .add_plugin(ShutdownPlugin { checkpoint: window.final_checkpoint.clone() })
+        .add_plugin(SummaryPlugin)
+        .add_plugin(SpeciesPlugin)
+        .add_plugin(TimelinePlugin)
+        .add_plugin(AllelePlugin)
+        .add_plugin(ElitesPlugin)
+        .add_plugin(EditorPlugin)
+        .add_plugin(FreezePlugin)
+        .add_plugin(PaintingPlugin)
+        .add_plugin(WindPlugin)
+        .add_plugin(WatchdogPlugin)
+        .add_plugin(ConfigConsolePlugin)
+        .add_plugin(RestartPlugin)
+        .add_plugin(StatesPlugin { initial, seed })
+        .add_plugin(ProfilerPlugin)
+        .add_plugin(DebugPlugin);
+    //after the inspector of the debug builds, so it moves to the dashboard as well
+    if window.dashboard {
+        app.add_plugin(DashboardPlugin);
     }
-}
-
-#[derive(Component)]
-struct Position(Transform);
 
-#[derive(Resource)]
-struct EvolutionTimer(Timer);
-
-struct NizmIndividual {
-    chromosome: Chromosome,
-    fitness: f32,
-}
-
-impl Individual for NizmIndividual {
-    fn create(chromosome: Chromosome) -> Self {
-        Self {
-            chromosome,
-            fitness: 0.0,
+    recorders.add_to(&mut app.world);
+    //a restarted run is a new entry of every output, next to the files of the first run
+    let args = window.clone();
+    app.insert_resource(RestartHook(Box::new(move |world, restarted| {
+        let config = world.resource::<Config>().clone();
+        match Recorders::new(&config, restarted.seed, &args, restarted.run) {
+            Ok(recorders) => recorders.add_to(world),
+            Err(err) => error!("run {} is not recorded: {err}", restarted.run),
         }
+    })));
+    if let Some(curriculum) = curriculum {
+        app.insert_resource(curriculum);
     }
 
-    fn fitness(&self) -> f32 {
-        self.fitness
-    }
-
-    fn chromosome(&self) -> &Chromosome {
-        &self.chromosome
-    }
+    app.run();
 }
 
-fn chromosome_to_color(chromosome: &Chromosome) -> Color {
-    let hash: i32 = chromosome.iter().fold(0, |acc, v| (acc.wrapping_mul(23).wrapping_add((v * 100.0) as i32)));
-
-    let float1 = (hash as f64 * 23.32).sin() as f32;
-    let float2 = (hash as f64 * 73261.2).sin() as f32;
-    let float3 = (hash as f64 * 32132.21).sin() as f32;
-    return Color::rgb(float1, float2, float3);
+fn gallery(config: Config, seed: u64, champions: Vec<Champion>, sequential: bool, window: &SimArgs) {
+    App::new()
+        .insert_resource(window.render_options())
+        .add_plugins(window_plugins("Rustism gallery"))
+        .add_plugin(GalleryPlugin { champions, config, seed, sequential })
+        .run();
 }
 
-fn update_statistics(timer: Res<EvolutionTimer>,
-                     mut query: Query<(&mut Text, &Statistics)>) {
-    for (mut text, statistics) in query.iter_mut() {
-        let generation = statistics.generation;
-        let survivor_percentage = statistics.survivors_percentage;
-        let time_left_in_generation = timer.0.remaining().as_secs_f32();
-        text.sections[0].value = format!("Time: {time_left_in_generation:.1}s\nGeneration: {generation}\nPercentage: {survivor_percentage:.2}");
-    }
+fn diff(champions: [Champion; 2], layers: Vec<LayerDiff>) {
+    App::new()
+        .add_plugins(window_plugins("Rustism diff"))
+        .add_plugin(DiffPlugin { champions, layers })
+        .run();
 }
 
-
-fn evolution(time: Res<Time>,
-             config: Res<Config>,
-             mut timer: ResMut<EvolutionTimer>,
-             mut query: Query<(Entity, &mut Nizm, &mut Transform, &mut TextureAtlasSprite), Without<KillZone>>,
-             mut statistics: Query<&mut Statistics>,
-             mut killzone: Query<(&mut KillZone, &mut Transform)>) {
-    if (timer.0.tick(time.delta())).just_finished() {
-        let (mut killzone, mut killzonetransform) = killzone.get_single_mut().expect("need killzone");
-
-        let mut survivors = Vec::new();
-
-        for (_entity, brain, transform, _sprite) in query.iter_mut() {
-            survivors.push(NizmIndividual {
-                chromosome: brain.network.data().collect(),
-                fitness: if transform.translation.x < killzone.max && transform.translation.x > killzone.min { 0.0 } else { transform.translation.x.abs() + 1.0 + brain.total_movement },
-            });
-        }
-
-        let ga = GeneticAlgorithm::new(
-            RouletteWheelSelection::new(),
-            UniformCrossover::default(),
-            GaussianMutation::new(0.3, 0.5));
-
-        let mut rng = thread_rng();
-        let offspring = ga.evolve(&mut rng, &survivors);
-
-        for ((_entity, mut brain, mut transform, mut sprite), child) in query.iter_mut().zip(offspring) {
-            brain.network = Network::from_data(Nizm::topology(), child.chromosome.clone());
-            brain.reset();
-            transform.translation = Vec3::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), 900.0);
-            sprite.color = chromosome_to_color(child.chromosome());
-        }
-
-        let mut stats = statistics.get_single_mut().expect("Stats");
-        stats.generation = stats.generation + 1;
-        stats.survivors_percentage = survivors.iter().filter(|s| s.fitness > 0.0).count() as f32 / config.individuals as f32;
-
-        let x = killzone_pos();
-        killzone.min = x;
-        killzone.max = x + 1.0;
-        killzonetransform.translation.x = x + 0.5;
-    }
+fn compare(variants: [Variant; 2], seed: u64, window: &SimArgs) {
+    App::new()
+        .insert_resource(window.render_options())
+        .add_plugins(window_plugins("Rustism comparison"))
+        .add_plugin(ComparePlugin { variants, seed })
+        .run();
 }
 
-fn check_collision(target: Vec3, other: Vec3) -> bool {
-    let collision = collide(
-        target,
-        Vec2::splat(0.03) * 0.8,
-        other,
-        Vec2::splat(0.03),
-    );
-    collision.is_some()
+//where the statistics of a run are written to besides the console
+struct Recorders {
+    stats_csv: Option<CsvObserver>,
+    alleles: Option<AlleleTensor>,
+    manifest: Option<RunManifest>,
+    #[cfg(feature = "sqlite")]
+    database: Option<ExperimentDatabase>,
 }
 
-fn check_if_can_move(time: Res<Time>,
-                     config: Res<Config>,
-                     mut query: Query<(&mut Nizm, &Transform)>) {
-    let left = Vec3::new(-1.0, 0.0, 0.0) * 0.8 * time.delta().as_secs_f32() * config.movement_speed;
-    let right = Vec3::new(1.0, 0.0, 0.0) * 0.8 * time.delta().as_secs_f32() * config.movement_speed;
-    let up = Vec3::new(0.0, -1.0, 0.0) * 0.8 * time.delta().as_secs_f32() * config.movement_speed;
-    let down = Vec3::new(0.0, 1.0, 0.0) * 0.8 * time.delta().as_secs_f32() * config.movement_speed;
-
-    let mut combinations = query.iter_combinations_mut();
-    while let Some([(mut nizm, transform), (_, other)]) = combinations.fetch_next() {
-        nizm.can_move_left = if check_collision(transform.translation + left, other.translation.clone()) { 1.0 } else { 0.0 };
-        nizm.can_move_right = if check_collision(transform.translation + right, other.translation.clone()) { 1.0 } else { 0.0 };
-        nizm.can_move_up = if check_collision(transform.translation + up, other.translation.clone()) { 1.0 } else { 0.0 };
-        nizm.can_move_down = if check_collision(transform.translation + down, other.translation.clone()) { 1.0 } else { 0.0 };
+impl Recorders {
+    //the files of the `run`th run of the app, see restart::run_path, the database gets a row per run
+    fn new(config: &Config, seed: u64, args: &SimArgs, run: usize) -> Result<Self, Box<dyn Error>> {
+        let run_path = |path: &PathBuf| restart::run_path(path, run);
+        Ok(Self {
+            stats_csv: args.stats_csv.as_ref().map(|path| CsvObserver::with_tiers(run_path(path), &args.stats_tiers)).transpose()?,
+            alleles: args.alleles.as_ref().map(|path| AlleleTensor::create(run_path(path))).transpose()?,
+            manifest: args.manifest.as_ref().map(|path| RunManifest::create(run_path(path), config, seed)).transpose()?,
+            #[cfg(feature = "sqlite")]
+            database: args.database.as_ref().map(|path| ExperimentDatabase::create(path, config, seed)).transpose()?,
+        })
     }
-}
-
-fn move_individuals(time: Res<Time>,
-                    config: Res<Config>,
-                    mut query: Query<(Entity, &mut Nizm)>,
-                    mut transforms: Query<&mut Transform, With<Blocking>>) {
-    for (entity, mut nizm) in query.iter_mut() {
-        let translation = transforms.get_mut(entity).expect("WTF").translation;
-
-        let mut movement = nizm.movement * time.delta().as_secs_f32() * config.movement_speed;
-        let target = translation + movement;
 
-        // if (target.x < -1.0) { translation.x += 2.0; }
-        // if (target.x > 1.0) { translation.x -= 2.0; }
-        // if (target.y < -1.0) { translation.y += 2.0; }
-        // if (target.y > 1.0) { translation.y -= 2.0; }
-
-        if target.x.abs() > 1.0 { movement.x = 0.0 }
-        if target.y.abs() > 1.0 { movement.y = 0.0 }
-
-        if !transforms.iter().any(|t| {
-            if translation != t.translation {
-                check_collision(translation + movement, t.translation.clone())
-            } else {
-                false
-            }
-        }) {
-            transforms.get_mut(entity).expect("WTF").translation = translation + movement;
-            nizm.total_movement += movement.length();
-            nizm.movement = movement;
-        } else {
-            nizm.movement = Vec3::ZERO;
+    //the files are written on the io thread of a windowed app, and right away by a headless one
+    fn add_to(mut self, world: &mut World) {
+        let io = world.get_resource::<IoWorker>().map_or_else(IoHandle::default, IoWorker::handle);
+        if let Some(out) = self.alleles.take() {
+            world.insert_resource(AlleleTracker::with_output(out.with_io(io.clone())));
+        }
+        if let Some(manifest) = self.manifest.take() {
+            world.insert_resource(manifest.with_io(io.clone()));
         }
+        self.stats_csv = self.stats_csv.take().map(|observer| observer.with_io(io));
+        self.add_to_evolution(&mut world.non_send_resource_mut::<Evolution>());
     }
-}
-
-fn make_individuals_think(timer: Res<EvolutionTimer>,
-                          mut transforms: Query<&mut Transform>,
-                          mut nizms: Query<(Entity, &mut Nizm)>,
-                          killzone: Query<&KillZone>, ) {
-    let killzone = killzone.get_single().expect("need killzone");
 
-    for (entity, mut nizm) in nizms.iter_mut() {
-        let translation = transforms.get_mut(entity).expect("WTF").translation;
-        let remaining = timer.0.elapsed_secs() / timer.0.duration().as_secs_f32();
-        let osc = (nizm.osc_freq * remaining * 3.14 * 2.0).sin();
-        let result = nizm.network.propagate(vec![
-            translation.x,
-            translation.y,
-            remaining,
-            osc,
-            nizm.movement.x,
-            nizm.movement.y,
-            nizm.can_move_left,
-            nizm.can_move_right,
-            nizm.can_move_up,
-            nizm.can_move_down,
-            if killzone.min < 0.0 { -1.0 } else { 1.0 },
-        ]);
-
-        let movement = Vec3::new(
-            result[0].clamp(0.0, 1.0) - result[1].clamp(0.0, 1.0),
-            result[2].clamp(0.0, 1.0) - result[3].clamp(0.0, 1.0),
-            0.0).normalize_or_zero();
-        nizm.movement = movement;
-        nizm.osc_freq = result[4];
-
-
-        //transforms.get_mut(entity).expect("WTF").translation = target;
+    //the allele frequencies are only tracked inside an app
+    fn add_to_evolution(self, evolution: &mut Evolution) {
+        if let Some(observer) = self.stats_csv {
+            evolution.add_csv_observer(observer);
+        }
+        #[cfg(feature = "sqlite")]
+        if let Some(database) = self.database {
+            evolution.add_observer(database);
+        }
     }
 }
 
-fn add_statistics_text(mut commands: Commands, assets: Res<AssetServer>) {
-    commands.spawn((
-        // Create a TextBundle that has a Text with a list of sections.
-        TextBundle::from_sections([
-            TextSection::new(
-                "XXX",
-                TextStyle {
-                    font: assets.load("fonts/FiraMono-Medium.ttf"),
-                    font_size: 20.0,
-                    color: Color::WHITE,
-                },
-            )
-        ])
-            .with_text_alignment(TextAlignment::TOP_LEFT)
-            // Set the style of the TextBundle itself.
-            .with_style(Style {
-                position_type: PositionType::Absolute,
-                position: UiRect {
-                    bottom: Val::Px(5.0),
-                    left: Val::Px(15.0),
-                    ..default()
-                },
-                ..default()
-            }),
-        Statistics::new(),
-    ));
+fn print_statistics(statistics: &Statistics) {
+    println!(
+        "generation {}: best {:.3}, average {:.3}, survivors {:.1}%",
+        statistics.generation,
+        statistics.best_fitness,
+        statistics.average_fitness,
+        statistics.survivors_percentage * 100.0,
+    );
 }
 
-fn init_killzone(mut commands: Commands) {
-    let x = killzone_pos();
-
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color: Color::rgb(0.2, 0.0, 0.0),
-                custom_size: Some(Vec2::new(1.0, 2.0)),
-                ..default()
-            },
-            transform: Transform::from_translation(Vec3::new(x + 0.5, 0.0, 10.0)),
-            ..default()
-        },
-        KillZone { min: x, max: x + 1.0 }
-    ));
+//a checkpoint whose brains fit `config`, one saved with other layers or senses is an error
+fn load_checkpoint(config: &Config, path: &Path) -> Result<Checkpoint, Box<dyn Error>> {
+    let checkpoint = sim::population::load_checkpoint(path)?;
+    sim::check_genomes(config, &checkpoint.population).map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(checkpoint)
 }
 
-fn killzone_pos() -> f32 {
-    let mut rng = thread_rng();
+//a run saved mid curriculum continues at the stage it reached
+fn resumed_curriculum(checkpoint: &Checkpoint) -> Option<Curriculum> {
+    checkpoint.curriculum_stage.map(|stage| Curriculum::default().at_stage(stage))
+}
 
-    let x = if rng.gen_bool(0.5) {
-        -1.0
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    let config = cli.sim.config();
+    config.check()?;
+    let seed = cli.sim.seed.unwrap_or_else(|| thread_rng().gen());
+    let mut seed_dna = cli.sim.seed_dna.clone();
+    for path in &cli.sim.seed_weights {
+        seed_dna.push(sim::import_weights(&config, path)?);
+    }
+    sim::check_genomes(&config, &seed_dna).map_err(|e| format!("--seed-dna: {e}"))?;
+    let seed_population = if seed_dna.is_empty() {
+        None
     } else {
-        0.0
+        Some(InitialPopulation(seed_dna))
     };
-    x
-}
 
-fn add_individuals(config: Res<Config>, ascii: Res<AsciiSheet>, mut commands: Commands) {
-    let mut rng = thread_rng();
+    let metrics = cli.sim.metrics.as_ref().map(MetricsPlugin::bind).transpose()?;
+    let recorders = Recorders::new(&config, seed, &cli.sim, 1)?;
 
-    for i in 0..config.individuals {
-        let mut sprite = TextureAtlasSprite::new(1);
-        sprite.custom_size = Some(Vec2::splat(0.03));
+    println!("seed: {seed}");
 
-        let network = Network::random(
-            &mut rng,
-            Nizm::topology(),
-        );
+    match cli.command.unwrap_or(Command::Run) {
+        Command::Run => run(config, seed, seed_population, None, metrics, recorders, &cli.sim, false),
+        Command::Headless { generations, save, resume, tui, worlds, world_split } if worlds > 1 => {
+            if tui || metrics.is_some() || cli.sim.alleles.is_some() {
+                return Err("--tui, --metrics and --alleles need a single world".into());
+            }
+            let initial_population = match resume {
+                Some(path) => {
+                    let population = sim::population::load(&path)?;
+                    sim::check_genomes(&config, &population).map_err(|e| format!("{}: {e}", path.display()))?;
+                    Some(InitialPopulation(population))
+                }
+                None => seed_population,
+            };
+
+            let mut run = BatchedRun::new(config, seed, worlds, world_split, initial_population)?;
+            recorders.add_to_evolution(run.evolution_mut());
+            println!("{} worlds, split by {world_split}", run.worlds());
+            let interrupted = shutdown::interrupted();
+            for _ in 0..generations {
+                if interrupted.load(Ordering::SeqCst) {
+                    println!("interrupted, saving the run");
+                    break;
+                }
+                print_statistics(run.run_generation());
+            }
+            if let Some(path) = save {
+                sim::population::save(path, &run.population())?;
+            }
+        }
+        Command::Headless { generations, save, resume, tui, .. } => {
+            let resumed = resume.map(|path| load_checkpoint(&config, &path)).transpose()?;
+            let mut app = match &resumed {
+                Some(checkpoint) => resumed_app(config, seed, checkpoint),
+                None => headless_app_with_population(config, seed, seed_population),
+            };
+            if let Some(metrics) = metrics {
+                app.add_plugin(metrics);
+            }
+            recorders.add_to(&mut app.world);
+            if tui {
+                tui::run(&mut app, generations)?;
+            } else {
+                //Ctrl-C stops after the generation in progress, so --save still has the run
+                let interrupted = shutdown::interrupted();
+                for _ in 0..generations {
+                    if interrupted.load(Ordering::SeqCst) {
+                        println!("interrupted, saving the run");
+                        break;
+                    }
+                    run_generations(&mut app, 1);
+                    print_statistics(&statistics(&mut app));
+                }
+            }
+            if let Some(path) = save {
+                sim::population::save_checkpoint(path, &checkpoint(&mut app))?;
+            }
+        }
+        Command::Replay { file } => {
+            let checkpoint = load_checkpoint(&config, &file)?;
+            let curriculum = resumed_curriculum(&checkpoint);
+            run(config, seed, Some(InitialPopulation(checkpoint.population)), curriculum, metrics, recorders, &cli.sim, true);
+        }
+        Command::Gallery { dir, sequential } => {
+            let champions = load_champions(&dir)?;
+            if champions.is_empty() {
+                return Err(format!("no champion files in {}", dir.display()).into());
+            }
+            for champion in &champions {
+                sim::check_genome(&config, &champion.chromosome).map_err(|e| format!("{}: {e}", champion.name))?;
+            }
+            gallery(config, seed, champions, sequential, &cli.sim);
+        }
+        Command::Diff { a, b } => {
+            let champion = |path: &PathBuf| -> Result<Champion, Box<dyn Error>> {
+                Ok(load_champion(path)?.ok_or_else(|| format!("no champion in {}", path.display()))?)
+            };
+            let champions = [champion(&a)?, champion(&b)?];
+            let layers = sim::diff::layer_diffs(&config, &champions[0].chromosome, &champions[1].chromosome)?;
+            diff(champions, layers);
+        }
+        Command::Compare { a, b } => {
+            let variants = [cli::variant(&config, &a)?, cli::variant(&config, &b)?];
+            compare(variants, seed, &cli.sim);
+        }
+        Command::Evaluate { dna } => {
+            sim::check_genome(&config, &dna).map_err(|e| format!("--dna: {e}"))?;
+            let mut app = headless_app_with_population(config, seed, Some(InitialPopulation(vec![dna])));
+            run_generations(&mut app, 1);
+            print_statistics(&statistics(&mut app));
+        }
+        Command::BenchSuite { mut dna, population, runs, scorecard } => {
+            if let Some(path) = population {
+                dna.extend(sim::population::load(path)?);
+            }
+            if dna.is_empty() {
+                return Err("need --dna or --population to score".into());
+            }
+            sim::check_genomes(&config, &dna)?;
+
+            let scores = sim::suite::run_suite(&config, &dna, runs);
+            for score in &scores {
+                println!(
+                    "{}: mean {:.3}, best {:.3}, survival {:.1}%",
+                    score.scenario,
+                    score.mean_fitness,
+                    score.best_fitness,
+                    score.survival * 100.0,
+                );
+            }
+            std::fs::write(&scorecard, sim::suite::scorecard(&scores))?;
+            println!("wrote {}", scorecard.display());
+        }
+        Command::Robustness { file, output, seeds, clones } => {
+            let mut checkpoint = load_checkpoint(&config, &file)?;
+            //a population is saved in no particular order, only a champion file says who the champion is
+            let [champion] = checkpoint.population.as_slice() else {
+                let genomes = checkpoint.population.len();
+                return Err(format!("{} holds {genomes} genomes, not a single champion", file.display()).into());
+            };
+            let robustness = sim::robustness::evaluate(&config, champion, 0..seeds, clones.unwrap_or(config.individuals));
+
+            println!(
+                "survival over {seeds} worlds: mean {:.1}%, median {:.1}%, 5th percentile {:.1}%, worst {:.1}%",
+                robustness.mean_survival() * 100.0,
+                robustness.quantile(0.5) * 100.0,
+                robustness.quantile(0.05) * 100.0,
+                robustness.worst_survival() * 100.0,
+            );
+            for (bin, count) in robustness.histogram(10).into_iter().enumerate() {
+                println!("{:>3}-{:>3}% {count:>5} {}", bin * 10, bin * 10 + 10, "#".repeat(count * 50 / seeds.max(1) as usize));
+            }
+            println!("worst mean fitness {:.3}, robustness score {:.3}", robustness.worst_fitness(), robustness.score());
 
-        sprite.color = chromosome_to_color(&network.data().collect());
+            checkpoint.robustness = Some(robustness.score());
+            let output = output.unwrap_or(file);
+            sim::population::save_checkpoint(&output, &checkpoint)?;
+            println!("wrote {}", output.display());
+        }
+        Command::Prune { dna, thresholds } => {
+            for threshold in thresholds {
+                let (pruned, connections, total) = sim::prune(&config, &dna, threshold).map_err(|e| format!("--dna: {e}"))?;
+
+                let mut app = headless_app_with_population(config.clone(), seed, Some(InitialPopulation(vec![pruned])));
+                run_generations(&mut app, 1);
+                print!("threshold {threshold}: {connections}/{total} connections, ");
+                print_statistics(&statistics(&mut app));
+            }
+        }
+        Command::Inspect { dna } => {
+            let activity = sim::activity(&config, seed, &dna).map_err(|e| format!("--dna: {e}"))?;
+            println!("{} observations", activity.samples);
+            for (layer, firing) in activity.firing.iter().enumerate() {
+                let kind = if layer + 1 == activity.firing.len() { "output" } else { "hidden" };
+                let rates: Vec<_> = firing.iter().map(|&count| format!("{:.0}%", count as f32 / activity.samples.max(1) as f32 * 100.0)).collect();
+                println!("{kind} layer {layer} fires: {}", rates.join(" "));
+            }
 
-        commands.spawn((
-            SpriteSheetBundle {
-                sprite: sprite,
-                texture_atlas: ascii.0.clone(),
-                transform: Transform {
-                    translation: Vec3::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), 900.0),
-                    ..default()
-                },
-                ..default()
-            },
-            Name::new(format!("nizm_{i}")),
-            Nizm::random(&mut rng),
-            Blocking
-        ));
+            let dead: Vec<_> = activity.dead_hidden().map(|(layer, neuron)| format!("{layer}:{neuron}")).collect();
+            println!("{}/{} hidden neurons are dead: {}", dead.len(), activity.hidden_neurons(), dead.join(" "));
+        }
+        Command::Export { dna, onnx } => {
+            std::fs::write(&onnx, sim::to_onnx(&config, &dna).map_err(|e| format!("--dna: {e}"))?)?;
+            println!("wrote {}", onnx.display());
+        }
+        Command::Sweep { spec, generations } => {
+            for value in &spec.values {
+                let mut config = config.clone();
+                config.set(&spec.name, value)?;
+                config.check().map_err(|e| format!("{}={value}: {e}", spec.name))?;
+                if let Some(population) = &seed_population {
+                    sim::check_genomes(&config, &population.0).map_err(|e| format!("{}={value}: {e}", spec.name))?;
+                }
+
+                let mut app = headless_app_with_population(config, seed, seed_population.clone());
+                run_generations(&mut app, generations);
+                print!("{}={}: ", spec.name, value);
+                print_statistics(&statistics(&mut app));
+            }
+        }
     }
-}
-
-fn spawn_camera(mut commands: Commands) {
-    commands.spawn(Camera2dBundle {
-        projection: OrthographicProjection {
-            top: 1.0,
-            bottom: -1.0,
-            right: 1.0 * ASPECT_RATIO,
-            left: -1.0 * ASPECT_RATIO,
-            scaling_mode: ScalingMode::None,
-            scale: 1.,
-            ..default()
-        },
-        ..default()
-    });
-}
-
-fn main() {
-    let height: f32 = 800.0;
-
-    App::new()
-        .insert_resource(ClearColor(CLEAR))
-        .insert_resource(Config { individuals: 128, movement_speed: 0.5 })
-        .insert_resource(EvolutionTimer(Timer::from_seconds(8.0, TimerMode::Repeating)))
-        .add_startup_system(spawn_camera)
-        .add_startup_system(add_individuals)
-        .add_startup_system(add_statistics_text)
-        .add_startup_system(init_killzone)
-        .add_startup_system_to_stage(StartupStage::PreStartup, load_ascii)
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            window: WindowDescriptor {
-                title: "Rustism".to_string(),
-                width: height * ASPECT_RATIO,
-                height: height,
-                present_mode: PresentMode::AutoNoVsync,
-                ..default()
-            },
-            ..default()
-        }))
-        .add_system(evolution)
-        .add_system(check_if_can_move.before(make_individuals_think))
-        .add_system(make_individuals_think.before(move_individuals))
-        .add_system(move_individuals)
-        .add_system(update_statistics)
-        .add_plugin(DebugPlugin)
-        .run();
 
+    Ok(())
 }
@@ -0,0 +1,230 @@
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+
+use crate::dashboard::{self, Dashboard};
+use crate::persistence::{IoHandle, SharedFile};
+use crate::restart::RestartRun;
+use crate::{Config, Nizm, Statistics};
+
+//a config value that changed while the run was going, as Debug prints it
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigChange {
+    //nested values by their path, like fitness.movement_weight
+    pub name: String,
+    pub old: String,
+    pub new: String,
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} -> {}", self.name, self.old, self.new)
+    }
+}
+
+//sent whenever the config changes mid-run, once for all values changed at the same time
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigChanged {
+    //the generation that was running
+    pub generation: i32,
+    pub changes: Vec<ConfigChange>,
+}
+
+//the values that differ between two configs
+pub fn config_changes(old: &Config, new: &Config) -> Vec<ConfigChange> {
+    let old = config_values(old);
+    config_values(new)
+        .into_iter()
+        .filter_map(|(name, new)| {
+            let old = old.iter().find(|(old, _)| *old == name).map_or_else(String::new, |(_, value)| value.clone());
+            (old != new).then_some(ConfigChange { name, old, new })
+        })
+        .collect()
+}
+
+//every value of the config by its name, read from its Debug output so no value is forgotten,
+//nested structs are split into their values, lists and options are kept whole
+fn config_values(config: &Config) -> Vec<(String, String)> {
+    let debug = format!("{config:#?}");
+    let mut values: Vec<(String, String)> = Vec::new();
+    //the nested structs the line is in, with their indentation
+    let mut structs: Vec<(usize, &str)> = Vec::new();
+    //the indentation of the list or option being collected into the last value
+    let mut collecting = None;
+
+    for line in debug.lines().skip(1) {
+        let indent = line.len() - line.trim_start().len();
+        let line = line.trim();
+        if let Some(opened) = collecting {
+            let (_, value) = values.last_mut().expect("collecting a value");
+            value.push_str(line.trim_end_matches(if indent == opened { "," } else { "" }));
+            value.push(' ');
+            if indent == opened {
+                //back to how Debug prints it on one line
+                *value = [("( ", "("), ("[ ", "["), (", )", ")"), (", ]", "]"), (", }", " }")]
+                    .iter()
+                    .fold(value.trim_end().to_string(), |value, (from, to)| value.replace(from, to));
+                collecting = None;
+            }
+            continue;
+        }
+
+        let Some((name, value)) = line.split_once(": ") else {
+            //the end of a nested struct
+            structs.pop_if(|(opened, _)| *opened == indent);
+            continue;
+        };
+        if value.ends_with('{') {
+            structs.push((indent, name));
+            continue;
+        }
+
+        let path = structs.iter().map(|(_, name)| *name).chain([name]).collect::<Vec<_>>().join(".");
+        if value.ends_with(['[', '(']) {
+            collecting = Some(indent);
+            values.push((path, value.to_string()));
+        } else {
+            values.push((path, value.trim_end_matches(',').to_string()));
+        }
+    }
+    values
+}
+
+//what a run was started with and every config change during it, so later analysis can tell
+//which fitness jumps followed a change of the parameters
+#[derive(Resource)]
+pub struct RunManifest {
+    out: SharedFile,
+    io: IoHandle,
+    pub changes: Vec<ConfigChanged>,
+}
+
+impl RunManifest {
+    pub fn create(path: impl AsRef<Path>, config: &Config, seed: u64) -> io::Result<Self> {
+        let header = format!("seed: {seed}\nconfig: {config:#?}\nchanges:\n");
+        Ok(Self { out: SharedFile::create(path, header.as_bytes())?, io: IoHandle::default(), changes: Vec::new() })
+    }
+
+    //writes the changes on the io thread of `io`
+    pub fn with_io(self, io: IoHandle) -> Self {
+        Self { io, ..self }
+    }
+
+    pub fn record(&mut self, changed: &ConfigChanged) {
+        let lines: String = changed.changes.iter().map(|change| format!("generation {}: {change}\n", changed.generation)).collect();
+        self.out.append(&self.io, "the run manifest", lines.into_bytes());
+        self.changes.push(changed.clone());
+    }
+
+    //the end of the run, see shutdown::run_summary
+    pub fn summarize(&mut self, summary: &str) {
+        self.out.append(&self.io, "the run manifest", format!("summary: {summary}\n").into_bytes());
+    }
+}
+
+//compares the config to the last one seen whenever it changed, announces the values that
+//differ and records them in the manifest if there is one
+pub(crate) fn track_config(config: Res<Config>,
+                           statistics: Query<&Statistics>,
+                           manifest: Option<ResMut<RunManifest>>,
+                           mut last: Local<Option<Config>>,
+                           mut changed: EventWriter<ConfigChanged>) {
+    if !config.is_changed() {
+        return;
+    }
+    let Some(old) = last.replace(config.clone()) else {
+        return;
+    };
+    let changes = config_changes(&old, &config);
+    if changes.is_empty() {
+        return;
+    }
+
+    //the generation that was running, the one after the last that ended
+    let generation = statistics.get_single().map_or(0, |statistics| statistics.generation) + 1;
+    let config_changed = ConfigChanged { generation, changes };
+    info!("generation {generation}: config changed, {}", config_changed.changes.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "));
+    if let Some(mut manifest) = manifest {
+        manifest.record(&config_changed);
+    }
+    changed.send(config_changed);
+}
+
+//a console to change config values mid-run, as `<name>=<value>` like the settings of the
+//command line, `restart` or `restart <seed>` starts the run over
+pub struct ConfigConsolePlugin;
+
+impl Plugin for ConfigConsolePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+        app.add_system(config_console);
+    }
+}
+
+fn config_console(mut egui_context: ResMut<EguiContext>,
+                  dashboard: Option<Res<Dashboard>>,
+                  mut config: ResMut<Config>,
+                  mut restarts: EventWriter<RestartRun>,
+                  mut input: Local<String>,
+                  mut error: Local<Option<String>>) {
+    let Some(ctx) = dashboard::panels(&mut egui_context, dashboard.as_deref()) else {
+        return;
+    };
+    egui::Window::new("Config").show(ctx, |ui| {
+        let response = ui.text_edit_singleline(&mut *input);
+        if response.lost_focus() && ui.input().key_pressed(egui::Key::Enter) {
+            let result = match restart_command(&input) {
+                Some(restart) => restart.map(|restart| restarts.send(restart)),
+                None => apply(&mut config, &input),
+            };
+            *error = result.err();
+            if error.is_none() {
+                input.clear();
+            }
+        }
+        if let Some(error) = error.as_ref() {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    });
+}
+
+//`restart` with an optional seed, None for anything else
+fn restart_command(input: &str) -> Option<Result<RestartRun, String>> {
+    let mut words = input.split_whitespace();
+    if words.next() != Some("restart") {
+        return None;
+    }
+    Some(match (words.next(), words.next()) {
+        (None, _) => Ok(RestartRun { seed: None }),
+        (Some(seed), None) => seed.parse().map(|seed| RestartRun { seed: Some(seed) }).map_err(|_| format!("expected a seed but got '{seed}'")),
+        _ => Err(format!("expected restart [<seed>] but got '{input}'")),
+    })
+}
+
+//only touches the config if the setting is valid, so a typo is not reported as a change,
+//settings that change the inputs or layers of the brains are refused, the brains alive and
+//frozen could not think with them
+fn apply(config: &mut ResMut<Config>, setting: &str) -> Result<(), String> {
+    let (name, value) = setting
+        .split_once('=')
+        .ok_or_else(|| format!("expected <name>=<value> but got '{setting}'"))?;
+    let changed = console_change(config, name.trim(), value.trim())?;
+    **config = changed;
+    Ok(())
+}
+
+pub fn console_change(config: &Config, name: &str, value: &str) -> Result<Config, String> {
+    let mut changed = config.clone();
+    changed.set(name, value)?;
+    changed.check()?;
+    if Nizm::topology(&changed) != Nizm::topology(config) {
+        return Err(format!("{name} changes the brains, it can only be set on the command line"));
+    }
+    Ok(changed)
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use bevy::prelude::*;
+
+use crate::{Config, Statistics, SystemTimings};
+
+#[derive(Default)]
+struct Snapshot {
+    generation: i32,
+    best_fitness: f32,
+    average_fitness: f32,
+    evaluations: u64,
+    evaluations_per_second: f64,
+    timings: BTreeMap<&'static str, Duration>,
+}
+
+#[derive(Resource, Clone, Default)]
+struct SharedSnapshot(Arc<Mutex<Snapshot>>);
+
+//serves the simulation's state in the prometheus text format on /metrics, so long
+//headless runs can be watched from grafana
+pub struct MetricsPlugin {
+    listener: TcpListener,
+}
+
+impl MetricsPlugin {
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Ok(Self { listener: TcpListener::bind(addr)? })
+    }
+}
+
+impl Plugin for MetricsPlugin {
+    fn build(&self, app: &mut App) {
+        let listener = self.listener.try_clone().expect("could not share the metrics socket");
+        let snapshot = SharedSnapshot::default();
+
+        let shared = snapshot.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Err(err) = respond(stream, &shared) {
+                    warn!("metrics request failed: {err}");
+                }
+            }
+        });
+
+        app.insert_resource(snapshot)
+            .add_system_to_stage(CoreStage::Last, update_snapshot);
+    }
+}
+
+fn update_snapshot(config: Res<Config>,
+                   timings: Res<SystemTimings>,
+                   shared: Res<SharedSnapshot>,
+                   statistics: Query<&Statistics, Changed<Statistics>>,
+                   mut last_generation: Local<Option<Instant>>) {
+    let mut snapshot = shared.0.lock().expect("metrics lock poisoned");
+    snapshot.timings = timings.last.clone();
+
+    let Ok(statistics) = statistics.get_single() else {
+        return;
+    };
+
+    let now = Instant::now();
+    if statistics.generation > 0 {
+        snapshot.evaluations += config.individuals as u64;
+        if let Some(last_generation) = *last_generation {
+            snapshot.evaluations_per_second = config.individuals as f64 / (now - last_generation).as_secs_f64();
+        }
+    }
+    *last_generation = Some(now);
+
+    snapshot.generation = statistics.generation;
+    snapshot.best_fitness = statistics.best_fitness;
+    snapshot.average_fitness = statistics.average_fitness;
+}
+
+fn render(snapshot: &Snapshot) -> String {
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, value: f64| {
+        let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}");
+    };
+
+    metric("rustism_generation", "counter", "Completed generations.", snapshot.generation as f64);
+    metric("rustism_best_fitness", "gauge", "Best fitness of the last generation.", snapshot.best_fitness as f64);
+    metric("rustism_average_fitness", "gauge", "Average fitness of the last generation.", snapshot.average_fitness as f64);
+    metric("rustism_evaluations_total", "counter", "Evaluated individuals.", snapshot.evaluations as f64);
+    metric("rustism_evaluations_per_second", "gauge", "Evaluated individuals per second during the last generation.", snapshot.evaluations_per_second);
+
+    let _ = writeln!(out, "# HELP rustism_system_seconds Duration of the last run of a simulation system.");
+    let _ = writeln!(out, "# TYPE rustism_system_seconds gauge");
+    for (system, duration) in &snapshot.timings {
+        let _ = writeln!(out, "rustism_system_seconds{{system=\"{system}\"}} {}", duration.as_secs_f64());
+    }
+
+    out
+}
+
+fn respond(mut stream: TcpStream, shared: &SharedSnapshot) -> io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let (status, body) = match request_line.split_whitespace().nth(1) {
+        Some("/metrics") | Some("/") => {
+            let snapshot = shared.0.lock().expect("metrics lock poisoned");
+            ("200 OK", render(&snapshot))
+        }
+        _ => ("404 Not Found", String::new()),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len(),
+    )
+}
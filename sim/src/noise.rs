@@ -0,0 +1,97 @@
+use std::f32::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::{Config, Nizm};
+
+//gaussian noise on the senses, so the brains cannot rely on perfect information
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SensorNoise {
+    #[default]
+    None,
+    //the same sigma for everybody
+    Global,
+    //every brain has an extra output neuron whose bias scales the sigma, its output is never
+    //used, so how noisy an individual's senses are is inherited and mutated like its weights
+    Evolved,
+}
+
+impl SensorNoise {
+    //output neurons the brains need for it
+    pub fn outputs(self) -> usize {
+        match self {
+            Self::Evolved => 1,
+            Self::None | Self::Global => 0,
+        }
+    }
+}
+
+impl FromStr for SensorNoise {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "global" => Ok(Self::Global),
+            "evolved" => Ok(Self::Evolved),
+            _ => Err(format!("unknown sensor noise '{s}', expected none, global or evolved")),
+        }
+    }
+}
+
+impl fmt::Display for SensorNoise {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Global => "global",
+            Self::Evolved => "evolved",
+        })
+    }
+}
+
+//randomness of the sensor noise, a separate stream of the simulation's seed so turning the
+//noise on leaves the rest of a run as it was
+#[derive(Resource)]
+pub struct SensorRng(pub ChaCha8Rng);
+
+impl SensorRng {
+    pub fn from_seed(seed: <ChaCha8Rng as SeedableRng>::Seed) -> Self {
+        let mut rng = ChaCha8Rng::from_seed(seed);
+        rng.set_stream(2);
+        Self(rng)
+    }
+}
+
+//the standard deviation of the noise on the senses of `nizm`
+pub fn sigma(config: &Config, nizm: &Nizm) -> f32 {
+    match config.sensor_noise {
+        SensorNoise::None => 0.0,
+        SensorNoise::Global => config.sensor_noise_sigma,
+        SensorNoise::Evolved => {
+            let (layers, (_, outputs)) = (nizm.network.layer_shapes().count(), nizm.network.layer_shapes().last().expect("need layers"));
+            config.sensor_noise_sigma * nizm.network.bias(layers - 1, outputs - 1).abs()
+        }
+    }
+}
+
+//a sample of the standard normal distribution
+fn gaussian(rng: &mut dyn RngCore) -> f32 {
+    //box-muller, 1.0 - u keeps the logarithm finite
+    let (u, v): (f32, f32) = (rng.gen(), rng.gen());
+    (-2.0 * (1.0 - u).ln()).sqrt() * (2.0 * PI * v).cos()
+}
+
+//adds noise to `senses` of the individuals `nizms`, in the same order
+pub fn add_noise<'a>(config: &Config, nizms: impl IntoIterator<Item = &'a Nizm>, senses: &mut [Vec<f32>], rng: &mut dyn RngCore) {
+    if config.sensor_noise == SensorNoise::None {
+        return;
+    }
+    for (nizm, senses) in nizms.into_iter().zip(senses) {
+        let sigma = sigma(config, nizm);
+        senses.iter_mut().for_each(|sense| *sense += sigma * gaussian(rng));
+    }
+}
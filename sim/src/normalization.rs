@@ -0,0 +1,134 @@
+use std::fmt;
+use std::str::FromStr;
+use bevy::prelude::*;
+
+use crate::painting::MAX_REWARD;
+use crate::rules::FRAME;
+use crate::sensors;
+use crate::wind;
+use crate::Config;
+
+//keeps the running standard deviation from dividing by almost nothing for senses that hardly vary
+const MIN_STD: f32 = 1e-3;
+
+//how the senses are scaled before a brain sees them, the movement of a frame is a hundred
+//times smaller than a position, so without scaling the first layer mostly listens to the big ones
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InputNormalization {
+    //the brains see the raw senses
+    #[default]
+    None,
+    //every sense is mapped from its declared range to -1..1
+    Ranges,
+    //every sense is standardized by its mean and standard deviation over the run so far
+    Running,
+}
+
+impl FromStr for InputNormalization {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "ranges" => Ok(Self::Ranges),
+            "running" => Ok(Self::Running),
+            _ => Err(format!("unknown input normalization '{s}', expected none, ranges or running")),
+        }
+    }
+}
+
+impl fmt::Display for InputNormalization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Ranges => "ranges",
+            Self::Running => "running",
+        })
+    }
+}
+
+//the range every enabled sense can take, in the order rules::senses() returns them
+pub fn sensor_ranges(config: &Config) -> Vec<(f32, f32)> {
+    //the movement senses are what an individual moved during the last frame
+    let movement = (config.movement_speed * (1.0 + config.speed_jitter) + wind::max_wind(config)) * FRAME;
+    let ranges = vec![
+        //position
+        (-1.0, 1.0),
+        (-1.0, 1.0),
+        //how far the generation has come
+        (0.0, 1.0),
+        //oscillator
+        (-1.0, 1.0),
+        //movement of the last frame
+        (-movement, movement),
+        (-movement, movement),
+        //blocked directions
+        (0.0, 1.0),
+        (0.0, 1.0),
+        (0.0, 1.0),
+        (0.0, 1.0),
+        //side of the nearest kill zone
+        (-1.0, 1.0),
+        //time
+        (0.0, 1.0),
+        //painted reward
+        (-MAX_REWARD, MAX_REWARD),
+        //day phase
+        (0.0, 1.0),
+    ];
+    sensors::ablate(config, ranges)
+}
+
+//scales the senses of every think tick, the running statistics last the whole run
+#[derive(Resource, Clone, Debug)]
+pub struct InputNormalizer {
+    mode: InputNormalization,
+    ranges: Vec<(f32, f32)>,
+    //welford's online mean and sum of squared deviations per sense
+    count: f32,
+    mean: Vec<f32>,
+    m2: Vec<f32>,
+}
+
+impl InputNormalizer {
+    pub fn new(config: &Config) -> Self {
+        let ranges = sensor_ranges(config);
+        Self {
+            mode: config.input_normalization,
+            count: 0.0,
+            mean: vec![0.0; ranges.len()],
+            m2: vec![0.0; ranges.len()],
+            ranges,
+        }
+    }
+
+    //scales the senses of all individuals of one think tick in place
+    pub fn normalize(&mut self, inputs: &mut [Vec<f32>]) {
+        match self.mode {
+            InputNormalization::None => {}
+            InputNormalization::Ranges => {
+                for inputs in inputs {
+                    for (input, &(min, max)) in inputs.iter_mut().zip(&self.ranges) {
+                        *input = (*input - min) / (max - min) * 2.0 - 1.0;
+                    }
+                }
+            }
+            InputNormalization::Running => {
+                for inputs in inputs.iter() {
+                    self.count += 1.0;
+                    for ((input, mean), m2) in inputs.iter().zip(&mut self.mean).zip(&mut self.m2) {
+                        let delta = input - *mean;
+                        *mean += delta / self.count;
+                        *m2 += delta * (input - *mean);
+                    }
+                }
+                for inputs in inputs {
+                    for ((input, mean), m2) in inputs.iter_mut().zip(&self.mean).zip(&self.m2) {
+                        let std = (m2 / self.count).sqrt().max(MIN_STD);
+                        *input = (*input - mean) / std;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::cell::Cell;
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+use bevy::prelude::*;
+use lib_natural_selection::{Chromosome, Observer, Statistics};
+use crate::fitness::FitnessTerms;
+use crate::occupancy::KillzoneOccupancy;
+use crate::persistence::{IoHandle, SharedFile};
+use crate::tiers::Aggregate;
+
+//logs every genome that beats the best fitness seen so far
+pub struct ChampionLog;
+
+impl Observer for ChampionLog {
+    fn on_new_champion(&mut self, generation: usize, chromosome: &Chromosome, fitness: f32) {
+        info!("new champion in generation {generation} with fitness {fitness:.3}: {chromosome}");
+    }
+}
+
+//the columns of the csv after the generation
+const COLUMNS: [&str; 28] = [
+    "min_fitness", "max_fitness", "avg_fitness", "avg_fitness_stderr", "invalid", "invalid_offspring", "takeover",
+    "distinct_genotypes", "selection_intensity", "avg_regularization", "avg_time_in_killzone", "mutated_share",
+    "avg_mutation_size", "parental_mixing", "crowded_out", "surrogate_error", "distance_term", "movement_term",
+    "survival_term", "exposure_term", "painted_term", "curiosity_term", "idle_term", "killzone_occupancy_mean",
+    "killzone_occupancy_min", "killzone_occupancy_median", "killzone_occupancy_max", "never_in_killzone",
+];
+
+//writes the fitness statistics of every generation as csv, or their min, mean and max over
+//every few generations for long runs
+pub struct CsvObserver {
+    tiers: Vec<CsvTier>,
+    io: IoHandle,
+    //filled in by the simulation, the genetic algorithm does not know about kill zones
+    pub(crate) time_in_killzone: Rc<Cell<f32>>,
+    pub(crate) killzone_occupancy: Rc<Cell<KillzoneOccupancy>>,
+    pub(crate) fitness_terms: Rc<Cell<FitnessTerms>>,
+}
+
+//a file of the statistics aggregated over `every` generations
+struct CsvTier {
+    every: usize,
+    out: SharedFile,
+    //per column, of the generations since the last row
+    aggregates: Vec<Aggregate>,
+}
+
+impl CsvTier {
+    fn create(path: &Path, every: usize) -> io::Result<Self> {
+        let header = if every == 1 {
+            format!("generation,{}\n", COLUMNS.join(","))
+        } else {
+            let columns: Vec<String> = COLUMNS.iter().map(|column| format!("{column}_min,{column}_mean,{column}_max")).collect();
+            format!("first_generation,last_generation,{}\n", columns.join(","))
+        };
+        Ok(Self { every, out: SharedFile::create(path, header.as_bytes())?, aggregates: Vec::new() })
+    }
+
+    fn write(&mut self, io: &IoHandle, generation: usize, values: &[f32]) {
+        if self.every == 1 {
+            let values: Vec<String> = values.iter().map(ToString::to_string).collect();
+            self.out.append(io, "the statistics csv", format!("{generation},{}\n", values.join(",")).into_bytes());
+            return;
+        }
+
+        if self.aggregates.is_empty() {
+            self.aggregates = values.iter().map(|&value| Aggregate::new(generation, value)).collect();
+        } else {
+            self.aggregates.iter_mut().zip(values).for_each(|(aggregate, &value)| aggregate.add(value));
+        }
+        let aggregate = self.aggregates[0];
+        if aggregate.generations < self.every {
+            return;
+        }
+
+        let values: Vec<String> = self.aggregates.drain(..).map(|aggregate| format!("{},{},{}", aggregate.min, aggregate.mean, aggregate.max)).collect();
+        let row = format!("{},{},{}\n", aggregate.first, aggregate.last(), values.join(","));
+        self.out.append(io, "the statistics csv", row.into_bytes());
+    }
+}
+
+impl CsvObserver {
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::with_tiers(path, &[1])
+    }
+
+    //a file per number of generations a row covers, the one of every generation at `path` and
+    //the others with their number before the extension, like stats.100.csv
+    pub fn with_tiers(path: impl AsRef<Path>, tiers: &[usize]) -> io::Result<Self> {
+        let path = path.as_ref();
+        let tiers = tiers
+            .iter()
+            .map(|&every| match (every, path.extension()) {
+                (1, _) => CsvTier::create(path, every),
+                (_, Some(extension)) => CsvTier::create(&path.with_extension(format!("{every}.{}", extension.to_string_lossy())), every),
+                (_, None) => CsvTier::create(&path.with_extension(every.to_string()), every),
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self { tiers, io: IoHandle::default(), time_in_killzone: Rc::default(), killzone_occupancy: Rc::default(), fitness_terms: Rc::default() })
+    }
+
+    //writes the rows on the io thread of `io`
+    pub fn with_io(self, io: IoHandle) -> Self {
+        Self { io, ..self }
+    }
+}
+
+impl Observer for CsvObserver {
+    fn on_generation_end(&mut self, statistics: &Statistics) {
+        let terms = self.fitness_terms.get();
+        let occupancy = self.killzone_occupancy.get();
+        let values: [f32; COLUMNS.len()] = [
+            statistics.min_fitness,
+            statistics.max_fitness,
+            statistics.avg_fitness,
+            statistics.avg_fitness_stderr,
+            statistics.invalid as f32,
+            statistics.invalid_offspring as f32,
+            statistics.takeover,
+            statistics.distinct_genotypes,
+            statistics.selection_intensity,
+            statistics.avg_regularization,
+            self.time_in_killzone.get(),
+            statistics.mutations.as_ref().map_or(0.0, |mutations| mutations.changed_share()),
+            statistics.mutations.as_ref().map_or(0.0, |mutations| mutations.avg_change()),
+            statistics.provenance.as_ref().map_or(0.0, |provenance| provenance.avg_mixing()),
+            statistics.crowded_out as f32,
+            statistics.surrogate_error.unwrap_or(0.0),
+            terms.distance,
+            terms.movement,
+            terms.survival,
+            terms.exposure,
+            terms.painted,
+            terms.curiosity,
+            terms.idle,
+            occupancy.mean,
+            occupancy.min,
+            occupancy.median,
+            occupancy.max,
+            occupancy.never,
+        ];
+
+        for tier in &mut self.tiers {
+            tier.write(&self.io, statistics.generation, &values);
+        }
+    }
+}
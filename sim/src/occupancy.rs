@@ -0,0 +1,37 @@
+//how the time the individuals of a generation spent inside kill zones is spread over the
+//population, as shares of the generation time so runs with different generation times compare
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct KillzoneOccupancy {
+    pub mean: f32,
+    pub min: f32,
+    pub median: f32,
+    pub max: f32,
+    //the share of the individuals that were never inside
+    pub never: f32,
+}
+
+impl KillzoneOccupancy {
+    //of the shares of the generation every individual spent inside
+    pub fn of(shares: &[f32]) -> Self {
+        if shares.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = shares.to_vec();
+        sorted.sort_by(f32::total_cmp);
+
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) { (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0 } else { sorted[n / 2] };
+        Self {
+            mean: sorted.iter().sum::<f32>() / n as f32,
+            min: sorted[0],
+            median,
+            max: sorted[n - 1],
+            never: sorted.iter().filter(|&&share| share <= 0.0).count() as f32 / n as f32,
+        }
+    }
+}
+
+//the share of a generation of `generation_time` seconds spent `time_in_killzone` seconds inside
+pub fn share(time_in_killzone: f32, generation_time: f32) -> f32 {
+    (time_in_killzone / generation_time).clamp(0.0, 1.0)
+}
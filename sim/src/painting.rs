@@ -0,0 +1,91 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+
+use crate::field::{FieldPlugin, ScalarField};
+use crate::render::cursor_in_world;
+use crate::{Frozen, Health, Nizm};
+
+//the most fitness a cell can give or take per second spent in it
+pub const MAX_REWARD: f32 = 1.0;
+
+//the marker of the reward field
+pub enum Reward {}
+
+//fitness per second an individual gets for being somewhere, painted by hand over the world
+pub type RewardField = ScalarField<Reward>;
+
+//the brush of the painting panel
+#[derive(Resource)]
+struct Brush {
+    painting: bool,
+    radius: f32,
+    //added per second of holding the mouse button, negative paints penalties
+    strength: f32,
+}
+
+//paints reward and penalty regions with the right mouse button while painting is on, the time
+//individuals spend in them is added to their fitness
+pub struct PaintingPlugin;
+
+impl Plugin for PaintingPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+
+        app.add_plugin(FieldPlugin::<Reward> {
+            cells: 32,
+            decay: 0.0,
+            diffusion: 0.0,
+            colors: [Color::rgba(0.6, 0.0, 0.6, 0.5), Color::rgba(0.0, 0.6, 0.0, 0.5)],
+            max: MAX_REWARD,
+            z: -0.5,
+            marker: PhantomData,
+        })
+        .insert_resource(Brush { painting: false, radius: 0.1, strength: 2.0 })
+        .add_system(painting_panel)
+        .add_system(paint.after(painting_panel))
+        .add_system(collect_rewards);
+    }
+}
+
+fn painting_panel(mut egui_context: ResMut<EguiContext>,
+                  mut brush: ResMut<Brush>,
+                  mut field: ResMut<RewardField>) {
+    egui::Window::new("Reward painting").show(egui_context.ctx_mut(), |ui| {
+        ui.checkbox(&mut brush.painting, "paint with the right mouse button");
+        ui.add(egui::Slider::new(&mut brush.radius, 0.02..=0.5).text("radius"));
+        ui.add(egui::Slider::new(&mut brush.strength, -5.0..=5.0).text("reward per second"));
+        if ui.button("Clear").clicked() {
+            field.clear();
+        }
+    });
+}
+
+fn paint(time: Res<Time>,
+         brush: Res<Brush>,
+         windows: Res<Windows>,
+         buttons: Res<Input<MouseButton>>,
+         mut egui_context: ResMut<EguiContext>,
+         cameras: Query<(&Camera, &GlobalTransform)>,
+         mut field: ResMut<RewardField>) {
+    if !brush.painting || !buttons.pressed(MouseButton::Right) || egui_context.ctx_mut().wants_pointer_input() {
+        return;
+    }
+    if let Some(position) = cursor_in_world(&windows, &cameras) {
+        field.paint(position, brush.radius, brush.strength * time.delta_seconds(), (-MAX_REWARD, MAX_REWARD));
+    }
+}
+
+fn collect_rewards(time: Res<Time>,
+                   field: Res<RewardField>,
+                   mut query: Query<(&mut Nizm, &Transform, Option<&Health>), Without<Frozen>>) {
+    for (mut nizm, transform, health) in query.iter_mut() {
+        if health.is_none_or(Health::is_alive) {
+            nizm.painted_reward += field.sample(transform.translation.truncate()) * time.delta_seconds();
+        }
+    }
+}
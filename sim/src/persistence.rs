@@ -0,0 +1,220 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+
+//seconds a failed write stays on screen
+const ERROR_SECONDS: f32 = 10.0;
+
+//a write to run on the io thread, named for the reports
+struct IoJob {
+    label: String,
+    write: Box<dyn FnOnce() -> io::Result<()> + Send>,
+}
+
+//sent once a job of the io thread finished
+#[derive(Clone, Debug, PartialEq)]
+pub struct IoCompleted {
+    pub label: String,
+    pub error: Option<String>,
+}
+
+//the jobs submitted to the io thread but not finished yet, flushing waits for them to drop to 0
+#[derive(Default)]
+struct Pending {
+    count: Mutex<usize>,
+    finished: Condvar,
+}
+
+impl Pending {
+    fn count(&self) -> std::sync::MutexGuard<'_, usize> {
+        //the lock is only held to count, a poisoned one still counts right
+        self.count.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn add(&self) {
+        *self.count() += 1;
+    }
+
+    fn finish(&self) {
+        *self.count() -= 1;
+        self.finished.notify_all();
+    }
+
+    fn wait(&self) {
+        let count = self.count();
+        drop(self.finished.wait_while(count, |count| *count > 0).unwrap_or_else(|poisoned| poisoned.into_inner()));
+    }
+}
+
+//where the subsystems that persist something submit their writes, cheap to clone, the default
+//one writes right away on the calling thread for headless runs and tests
+#[derive(Clone, Default)]
+pub struct IoHandle {
+    worker: Option<(Sender<IoJob>, Arc<Pending>)>,
+}
+
+impl IoHandle {
+    pub fn submit(&self, label: impl Into<String>, write: impl FnOnce() -> io::Result<()> + Send + 'static) {
+        let label = label.into();
+        match &self.worker {
+            Some((jobs, pending)) => {
+                pending.add();
+                if jobs.send(IoJob { label: label.clone(), write: Box::new(write) }).is_err() {
+                    pending.finish();
+                    error!("could not write {label}, the io thread stopped");
+                }
+            }
+            None => {
+                if let Err(err) = write() {
+                    warn!("could not write {label}: {err}");
+                }
+            }
+        }
+    }
+}
+
+//a thread that runs the writes of the windowed app in the order they were submitted, so
+//flushing files and saving genomes never stall a frame
+#[derive(Resource)]
+pub struct IoWorker {
+    handle: IoHandle,
+    pending: Arc<Pending>,
+    completed: Mutex<Receiver<IoCompleted>>,
+}
+
+impl IoWorker {
+    pub fn spawn() -> Self {
+        let (jobs, job_receiver) = mpsc::channel::<IoJob>();
+        let (completed_sender, completed) = mpsc::channel();
+        let pending = Arc::new(Pending::default());
+
+        let done = pending.clone();
+        thread::spawn(move || {
+            for job in job_receiver {
+                //a panicking job is reported like a failed one, the thread goes on with the rest
+                let error = match panic::catch_unwind(AssertUnwindSafe(job.write)) {
+                    Ok(result) => result.err().map(|err| err.to_string()),
+                    Err(_) => Some("the write panicked".to_string()),
+                };
+                //reported before it stops being pending, so a flush also waits for the report
+                let _ = completed_sender.send(IoCompleted { label: job.label, error });
+                done.finish();
+            }
+        });
+
+        let handle = IoHandle { worker: Some((jobs, pending.clone())) };
+        Self { handle, pending, completed: Mutex::new(completed) }
+    }
+
+    pub fn handle(&self) -> IoHandle {
+        self.handle.clone()
+    }
+
+    //the jobs submitted but not finished yet
+    pub fn pending(&self) -> usize {
+        *self.pending.count()
+    }
+
+    //the jobs finished since the last call
+    pub fn completed(&self) -> Vec<IoCompleted> {
+        self.completed.lock().expect("io reports").try_iter().collect()
+    }
+
+    //blocks until every job submitted so far is written
+    pub fn flush(&self) {
+        self.pending.wait();
+    }
+}
+
+//a file appended to by io jobs, shared between the subsystem that fills it and the io thread
+#[derive(Clone)]
+pub struct SharedFile(Arc<Mutex<BufWriter<File>>>);
+
+impl SharedFile {
+    //a new file at `path` that starts with `header`, written right away so the errors of the
+    //path show up at once
+    pub fn create(path: impl AsRef<Path>, header: &[u8]) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(header)?;
+        out.flush()?;
+        Ok(Self(Arc::new(Mutex::new(out))))
+    }
+
+    pub fn append(&self, io: &IoHandle, label: &str, bytes: Vec<u8>) {
+        let file = self.0.clone();
+        io.submit(label, move || {
+            let mut out = file.lock().map_err(|_| io::Error::other("a write to the file panicked"))?;
+            out.write_all(&bytes)?;
+            out.flush()
+        });
+    }
+}
+
+//runs the writes of the app on an io thread, reports the ones that failed on screen and waits
+//for the rest when the app exits
+pub struct PersistencePlugin;
+
+impl Plugin for PersistencePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+
+        app.insert_resource(IoWorker::spawn())
+            .add_event::<IoCompleted>()
+            .add_system(report_io)
+            .add_system(io_status.after(report_io))
+            .add_system_to_stage(CoreStage::Last, flush_on_exit);
+    }
+}
+
+fn report_io(worker: Res<IoWorker>, mut completed: EventWriter<IoCompleted>) {
+    for job in worker.completed() {
+        if let Some(err) = &job.error {
+            error!("could not write {}: {err}", job.label);
+        }
+        completed.send(job);
+    }
+}
+
+fn io_status(time: Res<Time>,
+             worker: Res<IoWorker>,
+             mut egui_context: ResMut<EguiContext>,
+             mut completed: EventReader<IoCompleted>,
+             mut errors: Local<Vec<(String, f32)>>) {
+    let now = time.elapsed_seconds();
+    for job in completed.iter() {
+        if let Some(err) = &job.error {
+            errors.push((format!("could not write {}: {err}", job.label), now));
+        }
+    }
+    errors.retain(|(_, since)| now - since < ERROR_SECONDS);
+    let pending = worker.pending();
+    if pending == 0 && errors.is_empty() {
+        return;
+    }
+
+    egui::Area::new("io_status").anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0)).show(egui_context.ctx_mut(), |ui| {
+        if pending > 0 {
+            ui.label(format!("{pending} writes pending…"));
+        }
+        for (error, _) in errors.iter() {
+            ui.colored_label(egui::Color32::from_rgb(220, 60, 60), error);
+        }
+    });
+}
+
+fn flush_on_exit(worker: Res<IoWorker>, mut exits: EventReader<AppExit>) {
+    if exits.iter().next().is_some() {
+        worker.flush();
+    }
+}
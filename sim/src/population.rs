@@ -0,0 +1,367 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+use lib_natural_selection::Chromosome;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use crate::ResumePoint;
+
+//a population file holds one chromosome per line as dna, lines starting with # are comments,
+//except for `# key: value` lines with a known key that hold the rest of a checkpoint, big
+//checkpoints can also be zstd compressed binary files with the zstd feature, both formats are
+//versioned and older versions are migrated step by step to the current one when loaded
+
+//the version save_checkpoint writes, files from before the formats were versioned are version 1
+pub const CHECKPOINT_VERSION: u32 = 3;
+const VERSION: &str = "version";
+const CURRICULUM_STAGE: &str = "curriculum_stage";
+//the word state of the rngs as seed, stream and word position, the generation and the elapsed
+//nanoseconds of the evolution and think timers
+const RNG: &str = "rng";
+const ENVIRONMENT_RNG: &str = "environment_rng";
+const GENERATION: &str = "generation";
+const TIMERS: &str = "timers";
+const ROBUSTNESS: &str = "robustness";
+//the first bytes of every zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+//followed by the version as an ascii digit
+#[cfg(feature = "zstd")]
+const BINARY_MAGIC: &[u8; 3] = b"NZC";
+#[cfg(feature = "zstd")]
+pub const COMPRESSION_LEVEL: i32 = 3;
+
+//everything needed to continue a run
+#[derive(Clone, Debug, Default)]
+pub struct Checkpoint {
+    pub population: Vec<Chromosome>,
+    pub curriculum_stage: Option<usize>,
+    //continues the random sequence of the run, without it a resumed run starts a fresh one
+    pub resume: Option<ResumePoint>,
+    //of the single chromosome of a champion file, see robustness::Robustness::score
+    pub robustness: Option<f32>,
+}
+
+pub fn load(path: impl AsRef<Path>) -> io::Result<Vec<Chromosome>> {
+    Ok(load_checkpoint(path)?.population)
+}
+
+pub fn save(path: impl AsRef<Path>, population: &[Chromosome]) -> io::Result<()> {
+    save_checkpoint(path, &Checkpoint { population: population.to_vec(), ..Checkpoint::default() })
+}
+
+//reads text and compressed checkpoints alike
+pub fn load_checkpoint(path: impl AsRef<Path>) -> io::Result<Checkpoint> {
+    let contents = fs::read(path)?;
+    if contents.starts_with(&ZSTD_MAGIC) {
+        return decompress(&contents);
+    }
+
+    let contents = String::from_utf8(contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    parse_checkpoint(&contents)
+}
+
+fn parse_checkpoint(contents: &str) -> io::Result<Checkpoint> {
+    let mut checkpoint = Checkpoint::default();
+    let mut entries = Vec::new();
+    let mut version = 1;
+
+    for line in contents.lines().map(str::trim) {
+        match line.strip_prefix('#') {
+            Some(comment) => {
+                let Some((key, value)) = comment.split_once(':') else {
+                    continue;
+                };
+                match key.trim() {
+                    VERSION => version = parse(value.trim())?,
+                    key => entries.push((key.to_string(), value.trim().to_string())),
+                }
+            }
+            None if line.is_empty() => {}
+            None => checkpoint.population.push(line.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?),
+        }
+    }
+
+    check_version(version)?;
+    for version in version..CHECKPOINT_VERSION {
+        migrate_text(version, &mut entries);
+    }
+
+    let (mut rng, mut environment_rng, mut generation, mut timers) = (None, None, None, None);
+    for (key, value) in &entries {
+        match key.as_str() {
+            CURRICULUM_STAGE => checkpoint.curriculum_stage = Some(parse(value)?),
+            ROBUSTNESS => checkpoint.robustness = Some(parse(value)?),
+            RNG => rng = Some(parse_rng(value)?),
+            ENVIRONMENT_RNG => environment_rng = Some(parse_rng(value)?),
+            GENERATION => generation = Some(parse(value)?),
+            TIMERS => {
+                let (evolution, think) = value.split_once(' ').ok_or_else(|| invalid("timers need two values"))?;
+                timers = Some((Duration::from_nanos(parse(evolution)?), Duration::from_nanos(parse(think.trim())?)));
+            }
+            _ => {}
+        }
+    }
+
+    if let (Some(rng), Some(environment_rng), Some(generation), Some((evolution_timer, think_timer))) = (rng, environment_rng, generation, timers) {
+        checkpoint.resume = Some(ResumePoint { rng, environment_rng, generation, evolution_timer, think_timer });
+    }
+
+    Ok(checkpoint)
+}
+
+fn check_version(version: u32) -> io::Result<()> {
+    if version == 0 || version > CHECKPOINT_VERSION {
+        return Err(invalid(&format!("checkpoint version {version} is not supported, this build reads 1 to {CHECKPOINT_VERSION}")));
+    }
+    Ok(())
+}
+
+//upgrades the `# key: value` lines of a text checkpoint of `version` to the next version, the
+//dna of the population migrates itself
+fn migrate_text(version: u32, _entries: &mut Vec<(String, String)>) {
+    match version {
+        //version 2 only added the version line, version 3 the optional robustness line
+        1 | 2 => {}
+        _ => unreachable!("no migration from checkpoint version {version}"),
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn parse<T>(value: &str) -> io::Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    value.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+//the hex seed, the stream and the word position
+fn parse_rng(value: &str) -> io::Result<ChaCha8Rng> {
+    let mut parts = value.split_whitespace();
+    let (Some(seed), Some(stream), Some(word_pos), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid("an rng needs a seed, a stream and a word position"));
+    };
+    if seed.len() != 64 || !seed.is_ascii() {
+        return Err(invalid("the seed of an rng needs 64 hex digits"));
+    }
+
+    let mut bytes = [0; 32];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&seed[i * 2..i * 2 + 2], 16).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    }
+    Ok(restore_rng(bytes, parse(stream)?, parse(word_pos)?))
+}
+
+fn restore_rng(seed: [u8; 32], stream: u64, word_pos: u128) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::from_seed(seed);
+    rng.set_stream(stream);
+    rng.set_word_pos(word_pos);
+    rng
+}
+
+fn format_rng(rng: &ChaCha8Rng) -> String {
+    let seed: String = rng.get_seed().iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("{seed} {} {}", rng.get_stream(), rng.get_word_pos())
+}
+
+//compressed if the file name ends in .zst
+pub fn save_checkpoint(path: impl AsRef<Path>, checkpoint: &Checkpoint) -> io::Result<()> {
+    if path.as_ref().extension().is_some_and(|extension| extension == "zst") {
+        return compress(path, checkpoint);
+    }
+
+    let mut contents = format!("# {VERSION}: {CHECKPOINT_VERSION}\n");
+    if let Some(stage) = checkpoint.curriculum_stage {
+        contents.push_str(&format!("# {CURRICULUM_STAGE}: {stage}\n"));
+    }
+    if let Some(robustness) = checkpoint.robustness {
+        contents.push_str(&format!("# {ROBUSTNESS}: {robustness}\n"));
+    }
+    if let Some(resume) = &checkpoint.resume {
+        contents.push_str(&format!("# {RNG}: {}\n", format_rng(&resume.rng)));
+        contents.push_str(&format!("# {ENVIRONMENT_RNG}: {}\n", format_rng(&resume.environment_rng)));
+        contents.push_str(&format!("# {GENERATION}: {}\n", resume.generation));
+        contents.push_str(&format!("# {TIMERS}: {} {}\n", resume.evolution_timer.as_nanos(), resume.think_timer.as_nanos()));
+    }
+    for chromosome in &checkpoint.population {
+        contents.push_str(&format!("{chromosome}\n"));
+    }
+    fs::write(path, contents)
+}
+
+//a zstd compressed binary checkpoint, a fraction of the size of the text for big populations
+#[cfg(feature = "zstd")]
+pub fn save_compressed_checkpoint(path: impl AsRef<Path>, checkpoint: &Checkpoint, level: i32) -> io::Result<()> {
+    fs::write(path, zstd::encode_all(encode(checkpoint).as_slice(), level)?)
+}
+
+#[cfg(feature = "zstd")]
+fn compress(path: impl AsRef<Path>, checkpoint: &Checkpoint) -> io::Result<()> {
+    save_compressed_checkpoint(path, checkpoint, COMPRESSION_LEVEL)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn compress(_path: impl AsRef<Path>, _checkpoint: &Checkpoint) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "writing compressed checkpoints needs the zstd feature"))
+}
+
+#[cfg(feature = "zstd")]
+fn decompress(contents: &[u8]) -> io::Result<Checkpoint> {
+    decode(&zstd::decode_all(contents)?)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn decompress(_contents: &[u8]) -> io::Result<Checkpoint> {
+    Err(io::Error::new(io::ErrorKind::InvalidData, "the checkpoint is compressed, reading it needs the zstd feature"))
+}
+
+//the magic and version, the curriculum stage or u64::MAX, the number of chromosomes and their length, a
+//byte telling whether the resume point follows, the f32 robustness or NaN, then all genes,
+//everything little endian
+#[cfg(feature = "zstd")]
+fn encode(checkpoint: &Checkpoint) -> Vec<u8> {
+    let len = checkpoint.population.first().map_or(0, Chromosome::len);
+    let mut bytes = Vec::with_capacity(BINARY_MAGIC.len() + 30 + RESUME_BYTES + checkpoint.population.len() * len * 4);
+
+    bytes.extend_from_slice(BINARY_MAGIC);
+    bytes.push(b'0' + CHECKPOINT_VERSION as u8);
+    bytes.extend_from_slice(&checkpoint.curriculum_stage.map_or(u64::MAX, |stage| stage as u64).to_le_bytes());
+    bytes.extend_from_slice(&(checkpoint.population.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&(len as u64).to_le_bytes());
+    match &checkpoint.resume {
+        Some(resume) => {
+            bytes.push(1);
+            for rng in [&resume.rng, &resume.environment_rng] {
+                bytes.extend_from_slice(&rng.get_seed());
+                bytes.extend_from_slice(&rng.get_stream().to_le_bytes());
+                bytes.extend_from_slice(&rng.get_word_pos().to_le_bytes());
+            }
+            bytes.extend_from_slice(&resume.generation.to_le_bytes());
+            bytes.extend_from_slice(&(resume.evolution_timer.as_nanos() as u64).to_le_bytes());
+            bytes.extend_from_slice(&(resume.think_timer.as_nanos() as u64).to_le_bytes());
+        }
+        None => bytes.push(0),
+    }
+    bytes.extend_from_slice(&checkpoint.robustness.unwrap_or(f32::NAN).to_le_bytes());
+    for chromosome in &checkpoint.population {
+        assert_eq!(chromosome.len(), len, "all chromosomes of a checkpoint need the same length");
+        for gene in chromosome.iter() {
+            bytes.extend_from_slice(&gene.to_le_bytes());
+        }
+    }
+
+    bytes
+}
+
+//two rngs, the generation and two timers
+#[cfg(feature = "zstd")]
+const RESUME_BYTES: usize = 2 * (32 + 8 + 16) + 4 + 2 * 8;
+
+#[cfg(feature = "zstd")]
+fn decode(bytes: &[u8]) -> io::Result<Checkpoint> {
+    let rest = bytes.strip_prefix(BINARY_MAGIC).ok_or_else(|| invalid("not a binary checkpoint"))?;
+    let (&version, rest) = rest.split_first().ok_or_else(|| invalid("truncated checkpoint header"))?;
+    let version = version.wrapping_sub(b'0') as u32;
+    check_version(version)?;
+    //only copied if it needs a migration
+    let mut rest = std::borrow::Cow::Borrowed(rest);
+    for version in version..CHECKPOINT_VERSION {
+        migrate_binary(version, rest.to_mut());
+    }
+
+    if rest.len() < 24 {
+        return Err(invalid("truncated checkpoint header"));
+    }
+    let (header, genes) = rest.split_at(24);
+    let field = |i: usize| u64::from_le_bytes(header[i * 8..(i + 1) * 8].try_into().expect("8 bytes"));
+    let (stage, count, len) = (field(0), field(1) as usize, field(2) as usize);
+
+    let (&flag, mut genes) = genes.split_first().ok_or_else(|| invalid("truncated checkpoint header"))?;
+    let mut resume = None;
+    if flag == 1 {
+        if genes.len() < RESUME_BYTES {
+            return Err(invalid("truncated resume point"));
+        }
+        let (point, rest) = genes.split_at(RESUME_BYTES);
+        genes = rest;
+        resume = Some(decode_resume(point));
+    }
+    if genes.len() < 4 {
+        return Err(invalid("truncated checkpoint header"));
+    }
+    let (robustness, genes) = genes.split_at(4);
+    let robustness = f32::from_le_bytes(robustness.try_into().expect("4 bytes"));
+
+    if genes.len() != count.saturating_mul(len).saturating_mul(4) {
+        return Err(invalid("the checkpoint does not hold as many genes as its header says"));
+    }
+    //without genes the payload says nothing about the count, which could be anything
+    if len == 0 && count != 0 {
+        return Err(invalid("the checkpoint holds chromosomes without genes"));
+    }
+    let population = if len == 0 {
+        Vec::new()
+    } else {
+        genes
+            .chunks_exact(len * 4)
+            .map(|chromosome| {
+                chromosome
+                    .chunks_exact(4)
+                    .map(|gene| f32::from_le_bytes(gene.try_into().expect("4 bytes")))
+                    .collect()
+            })
+            .collect()
+    };
+
+    Ok(Checkpoint {
+        population,
+        curriculum_stage: (stage != u64::MAX).then_some(stage as usize),
+        resume,
+        robustness: (!robustness.is_nan()).then_some(robustness),
+    })
+}
+
+//upgrades the bytes after the magic and version of a binary checkpoint of `version` to the
+//next version
+#[cfg(feature = "zstd")]
+fn migrate_binary(version: u32, bytes: &mut Vec<u8>) {
+    match version {
+        //version 2 added the resume point after the header, version 1 checkpoints have none
+        1 => {
+            if bytes.len() >= 24 {
+                bytes.insert(24, 0);
+            }
+        }
+        //version 3 added the robustness after the resume point
+        2 => {
+            if bytes.len() > 24 {
+                let at = 25 + if bytes[24] == 1 { RESUME_BYTES } else { 0 };
+                bytes.splice(at.min(bytes.len())..at.min(bytes.len()), f32::NAN.to_le_bytes());
+            }
+        }
+        _ => unreachable!("no migration from checkpoint version {version}"),
+    }
+}
+
+#[cfg(feature = "zstd")]
+fn decode_resume(mut bytes: &[u8]) -> ResumePoint {
+    let mut take = |n: usize| {
+        let (taken, rest) = bytes.split_at(n);
+        bytes = rest;
+        taken
+    };
+    let mut rng = || {
+        let seed = take(32).try_into().expect("32 bytes");
+        let stream = u64::from_le_bytes(take(8).try_into().expect("8 bytes"));
+        restore_rng(seed, stream, u128::from_le_bytes(take(16).try_into().expect("16 bytes")))
+    };
+    let (rng, environment_rng) = (rng(), rng());
+    let generation = i32::from_le_bytes(take(4).try_into().expect("4 bytes"));
+    let mut timer = || Duration::from_nanos(u64::from_le_bytes(take(8).try_into().expect("8 bytes")));
+
+    ResumePoint { rng, environment_rng, generation, evolution_timer: timer(), think_timer: timer() }
+}
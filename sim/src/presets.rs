@@ -0,0 +1,86 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::fitness::FitnessConfig;
+use crate::sensors::Sensor;
+use crate::Config;
+
+//a built-in setup of the world, the brains and the fitness to start a run from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preset {
+    ClassicKillzone,
+    Maze,
+    Exploration,
+    DriftingZones,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [Self::ClassicKillzone, Self::Maze, Self::Exploration, Self::DriftingZones];
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::ClassicKillzone => "a still kill zone over half the world, whoever is outside when the generation ends survives",
+            Self::Maze => "walls of blocks all over the world, the way out of the kill zone leads around them",
+            Self::Exploration => "a narrow kill zone and a day/night cycle, new ground pays and standing around costs",
+            Self::DriftingZones => "two kill zones drift over the world and wear down whoever is inside, the longer alive the better",
+        }
+    }
+
+    //the config of the preset, the defaults for everything it leaves alone
+    pub fn config(self) -> Config {
+        let default = Config::default();
+        match self {
+            Self::ClassicKillzone => default,
+            Self::Maze => Config {
+                obstacles: 60,
+                killzone_width: 0.8,
+                generation_time: 10.0,
+                hidden_neurons: 32,
+                fitness: FitnessConfig { movement_weight: 0.5, near_miss_margin: 0.1, ..default.fitness },
+                ..default
+            },
+            Self::Exploration => Config {
+                killzone_width: 0.4,
+                day_length: 40.0,
+                night_blindness: 0.5,
+                enabled_sensors: vec![Sensor::Daylight],
+                hidden_neurons: 32,
+                fitness: FitnessConfig { movement_weight: 0.25, curiosity_weight: 2.0, idle_penalty: 0.1, ..default.fitness },
+                ..default
+            },
+            Self::DriftingZones => Config {
+                killzones: 2,
+                killzone_width: 0.4,
+                killzone_speed: 0.15,
+                killzone_damage: 0.5,
+                generation_time: 12.0,
+                clocks: 1,
+                hidden_neurons: 32,
+                fitness: FitnessConfig { near_miss_margin: 0.1, killzone_time_penalty: 0.5, ..default.fitness },
+                ..default
+            },
+        }
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.to_string() == s)
+            .ok_or_else(|| format!("unknown preset '{s}', expected classic-killzone, maze, exploration or drifting-zones"))
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::ClassicKillzone => "classic-killzone",
+            Self::Maze => "maze",
+            Self::Exploration => "exploration",
+            Self::DriftingZones => "drifting-zones",
+        })
+    }
+}
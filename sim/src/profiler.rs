@@ -0,0 +1,93 @@
+use bevy::diagnostic::{Diagnostic, DiagnosticId, Diagnostics, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+
+use crate::dashboard::{self, Dashboard};
+use crate::hud::HudExtras;
+use crate::SystemTimings;
+
+//the simulation stages in the order they run
+pub const STAGES: [&str; 5] = ["collision", "sensing", "think", "movement", "evolution"];
+
+const DIAGNOSTIC_IDS: [DiagnosticId; 5] = [
+    DiagnosticId::from_u128(0x0c5b_7a2e_61f4_4d59_9a0e_3f1b_2c6d_0001),
+    DiagnosticId::from_u128(0x0c5b_7a2e_61f4_4d59_9a0e_3f1b_2c6d_0002),
+    DiagnosticId::from_u128(0x0c5b_7a2e_61f4_4d59_9a0e_3f1b_2c6d_0003),
+    DiagnosticId::from_u128(0x0c5b_7a2e_61f4_4d59_9a0e_3f1b_2c6d_0004),
+    DiagnosticId::from_u128(0x0c5b_7a2e_61f4_4d59_9a0e_3f1b_2c6d_0005),
+];
+
+//feeds the per frame time of every simulation stage into bevy's diagnostics and shows
+//them next to the totals of the last generation, so optimizations target the real bottleneck
+pub struct ProfilerPlugin;
+
+impl Plugin for ProfilerPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+
+        app.add_startup_system(register_diagnostics)
+            .add_system_to_stage(CoreStage::Last, measure_stages)
+            .add_system(profiler_panel);
+    }
+}
+
+fn register_diagnostics(mut diagnostics: ResMut<Diagnostics>) {
+    for (stage, id) in STAGES.iter().zip(DIAGNOSTIC_IDS) {
+        diagnostics.add(Diagnostic::new(id, format!("sim_{stage}"), 120).with_suffix("ms"));
+    }
+}
+
+//stages that did not run this frame count as 0ms
+fn measure_stages(timings: Res<SystemTimings>,
+                  mut diagnostics: ResMut<Diagnostics>,
+                  hud: Option<ResMut<HudExtras>>) {
+    for (stage, id) in STAGES.iter().zip(DIAGNOSTIC_IDS) {
+        let duration = timings.frame.get(stage).copied().unwrap_or_default();
+        diagnostics.add_measurement(id, || duration.as_secs_f64() * 1000.0);
+    }
+
+    if let Some(mut hud) = hud {
+        let total: f64 = timings.frame.values().map(|duration| duration.as_secs_f64()).sum();
+        hud.set("Sim", format!("{:.2}ms", total * 1000.0));
+    }
+}
+
+fn profiler_panel(mut egui_context: ResMut<EguiContext>,
+                  dashboard: Option<Res<Dashboard>>,
+                  timings: Res<SystemTimings>,
+                  diagnostics: Res<Diagnostics>) {
+    let generation_total: f64 = timings.generation.values().map(|duration| duration.as_secs_f64()).sum();
+
+    let Some(ctx) = dashboard::panels(&mut egui_context, dashboard.as_deref()) else {
+        return;
+    };
+    egui::Window::new("Profiler").show(ctx, |ui| {
+        egui::Grid::new("stages").striped(true).show(ui, |ui| {
+            ui.label("stage");
+            ui.label("frame ms");
+            ui.label("avg ms");
+            ui.label("generation ms");
+            ui.label("share");
+            ui.end_row();
+
+            for (stage, id) in STAGES.iter().zip(DIAGNOSTIC_IDS) {
+                let diagnostic = diagnostics.get(id);
+                let generation = timings.generation.get(stage).map(|duration| duration.as_secs_f64()).unwrap_or_default();
+
+                ui.label(*stage);
+                ui.label(format!("{:.3}", diagnostic.and_then(Diagnostic::value).unwrap_or_default()));
+                ui.label(format!("{:.3}", diagnostic.and_then(Diagnostic::average).unwrap_or_default()));
+                ui.label(format!("{:.1}", generation * 1000.0));
+                ui.label(if generation_total > 0.0 { format!("{:.0}%", generation / generation_total * 100.0) } else { "-".to_string() });
+                ui.end_row();
+            }
+        });
+
+        if let Some(frame_time) = diagnostics.get(FrameTimeDiagnosticsPlugin::FRAME_TIME).and_then(Diagnostic::average) {
+            ui.label(format!("frame time {frame_time:.2}ms"));
+        }
+    });
+}
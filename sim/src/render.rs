@@ -0,0 +1,494 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use bevy::prelude::*;
+use bevy::render::camera::{ScalingMode, Viewport};
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::sprite::MaterialMesh2dBundle;
+use bevy::window::WindowResized;
+
+use crate::dashboard;
+use crate::environment::Obstacle;
+use crate::hud::HudPlugin;
+use crate::sensors;
+use crate::{Config, Died, EvolutionTimer, Health, KillZone, Nizm, Statistics, Tint};
+
+pub const CLEAR: Color = Color::rgb(0.1, 0.1, 0.1);
+pub const ASPECT_RATIO: f32 = 1.0;
+
+const ASCII: &str = "Ascii.png";
+//how close to an individual a click has to be to select it
+const SELECT_DISTANCE: f32 = 0.05;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IndividualLook {
+    //a triangle pointing where it goes, with a ring for its health and an outline when selected
+    #[default]
+    Shapes,
+    //a character of Ascii.png, a plain square without it
+    Ascii,
+}
+
+impl FromStr for IndividualLook {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "shapes" => Ok(Self::Shapes),
+            "ascii" => Ok(Self::Ascii),
+            _ => Err(format!("unknown look '{s}', expected shapes or ascii")),
+        }
+    }
+}
+
+impl fmt::Display for IndividualLook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Shapes => "shapes",
+            Self::Ascii => "ascii",
+        })
+    }
+}
+
+//what a window of another shape than the world shows next to it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WorldViewport {
+    //more of the empty space around the world
+    #[default]
+    Expand,
+    //bars of the clear color
+    Letterbox,
+}
+
+impl FromStr for WorldViewport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "expand" => Ok(Self::Expand),
+            "letterbox" => Ok(Self::Letterbox),
+            _ => Err(format!("unknown viewport '{s}', expected expand or letterbox")),
+        }
+    }
+}
+
+impl fmt::Display for WorldViewport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Expand => "expand",
+            Self::Letterbox => "letterbox",
+        })
+    }
+}
+
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct RenderOptions {
+    pub look: IndividualLook,
+    pub viewport: WorldViewport,
+    //draws everything without the assets directory, text uses the built-in font, also used
+    //when the assets are missing
+    pub builtin_assets: bool,
+}
+
+impl RenderOptions {
+    //whether an asset can be loaded
+    pub fn has_asset(&self, assets: &AssetServer, path: &str) -> bool {
+        !self.builtin_assets && assets.asset_io().is_file(Path::new(path))
+    }
+}
+
+#[derive(Resource)]
+struct AsciiSheet(Handle<TextureAtlas>);
+
+fn load_ascii(mut commands: Commands,
+              options: Res<RenderOptions>,
+              assets: Res<AssetServer>,
+              mut texture_atlases: ResMut<Assets<TextureAtlas>>) {
+    if options.look != IndividualLook::Ascii {
+        return;
+    }
+    if !options.has_asset(&assets, ASCII) {
+        info!("drawing individuals without {ASCII}");
+        return;
+    }
+
+    let image = assets.load(ASCII);
+    let atlas = TextureAtlas::from_grid(
+        image,
+        Vec2::splat(9.0),
+        16, 16,
+        Some(Vec2::splat(2.0)), None);
+
+    let atlas_handle = texture_atlases.add(atlas);
+    commands.insert_resource(AsciiSheet(atlas_handle));
+}
+
+//individuals are spawned by the simulation, here they only get their looks
+fn add_individual_looks(options: Res<RenderOptions>,
+                        ascii: Option<Res<AsciiSheet>>,
+                        shapes: Res<ShapeMeshes>,
+                        mut materials: ResMut<Assets<ColorMaterial>>,
+                        mut commands: Commands,
+                        query: Query<(Entity, &Tint, Option<&Health>), Added<Nizm>>) {
+    for (entity, tint, health) in query.iter() {
+        let size = Some(Vec2::splat(0.03));
+
+        let mut individual = commands.entity(entity);
+        individual.insert((Visibility::default(), ComputedVisibility::default()));
+        match (options.look, &ascii) {
+            (IndividualLook::Shapes, _) => {
+                individual.with_children(|parts| {
+                    let mut part = |mesh: &Handle<Mesh>, color: Color, z: f32, visible: bool, marker| {
+                        let mut part = parts.spawn(MaterialMesh2dBundle {
+                            mesh: mesh.clone().into(),
+                            material: materials.add(color.into()),
+                            transform: Transform::from_xyz(0.0, 0.0, z),
+                            visibility: Visibility { is_visible: visible },
+                            ..default()
+                        });
+                        match marker {
+                            Part::Body => part.insert(Body),
+                            Part::Outline => part.insert(Outline),
+                            Part::Energy => part.insert(Energy),
+                        };
+                    };
+
+                    part(&shapes.triangle, tint.0, 0.2, true, Part::Body);
+                    part(&shapes.outline, Color::YELLOW, 0.1, false, Part::Outline);
+                    if let Some(health) = health {
+                        part(&shapes.ring, Color::rgba(1.0, 1.0, 1.0, health.health), 0.3, true, Part::Energy);
+                    }
+                });
+            }
+            (IndividualLook::Ascii, Some(ascii)) => {
+                let mut sprite = TextureAtlasSprite::new(1);
+                sprite.custom_size = size;
+                sprite.color = tint.0;
+                individual.insert((sprite, ascii.0.clone()));
+            }
+            (IndividualLook::Ascii, None) => {
+                individual.insert((Sprite { color: tint.0, custom_size: size, ..default() }, Handle::<Image>::default()));
+            }
+        }
+    }
+}
+
+enum Part {
+    Body,
+    Outline,
+    Energy,
+}
+
+//the triangle of a shape, pointing where the individual is going
+#[derive(Component)]
+struct Body;
+
+//around the body of the selected individual
+#[derive(Component)]
+struct Outline;
+
+//a ring that fades out with the health of an individual
+#[derive(Component)]
+struct Energy;
+
+#[derive(Resource)]
+struct ShapeMeshes {
+    triangle: Handle<Mesh>,
+    outline: Handle<Mesh>,
+    ring: Handle<Mesh>,
+}
+
+fn triangle_mesh(scale: f32) -> Mesh {
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    let positions = [[0.02, 0.0, 0.0], [-0.015, 0.012, 0.0], [-0.015, -0.012, 0.0]]
+        .map(|[x, y, z]| [x * scale, y * scale, z]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions.to_vec());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; 3]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0]; 3]);
+    mesh.set_indices(Some(Indices::U32(vec![0, 1, 2])));
+    mesh
+}
+
+fn ring_mesh(inner: f32, outer: f32, segments: u32) -> Mesh {
+    let mut positions = Vec::new();
+    for segment in 0..segments {
+        let angle = segment as f32 / segments as f32 * std::f32::consts::TAU;
+        let direction = Vec2::new(angle.cos(), angle.sin());
+        positions.push((direction * inner).extend(0.0).to_array());
+        positions.push((direction * outer).extend(0.0).to_array());
+    }
+    //two triangles between every segment and the next
+    let indices = (0..segments)
+        .flat_map(|segment| {
+            let (a, b) = (segment * 2, (segment + 1) % segments * 2);
+            [a, a + 1, b + 1, a, b + 1, b]
+        })
+        .collect();
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    let vertices = positions.len();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; vertices]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0]; vertices]);
+    mesh.set_indices(Some(Indices::U32(indices)));
+    mesh
+}
+
+fn load_shapes(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.insert_resource(ShapeMeshes {
+        triangle: meshes.add(triangle_mesh(1.0)),
+        outline: meshes.add(triangle_mesh(1.5)),
+        ring: meshes.add(ring_mesh(0.022, 0.026, 24)),
+    });
+}
+
+//the simulation changes the tint when the genes or the health of an individual change
+fn apply_tints(mut materials: ResMut<Assets<ColorMaterial>>,
+               mut individuals: Query<(&Tint, Option<&mut TextureAtlasSprite>, Option<&mut Sprite>, Option<&Children>), Changed<Tint>>,
+               bodies: Query<&Handle<ColorMaterial>, With<Body>>) {
+    for (tint, sprite, square, children) in individuals.iter_mut() {
+        if let Some(mut sprite) = sprite {
+            sprite.color = tint.0;
+        }
+        if let Some(mut square) = square {
+            square.color = tint.0;
+        }
+        for body in children.into_iter().flatten().filter_map(|&child| bodies.get(child).ok()) {
+            if let Some(material) = materials.get_mut(body) {
+                material.color = tint.0;
+            }
+        }
+    }
+}
+
+fn orient_shapes(individuals: Query<(&Nizm, &Children)>,
+                 mut bodies: Query<&mut Transform, With<Body>>) {
+    for (nizm, children) in individuals.iter() {
+        if nizm.action == Vec3::ZERO {
+            continue;
+        }
+
+        let rotation = Quat::from_rotation_z(nizm.action.y.atan2(nizm.action.x));
+        for &child in children.iter() {
+            if let Ok(mut transform) = bodies.get_mut(child) {
+                transform.rotation = rotation;
+            }
+        }
+    }
+}
+
+fn show_energy(mut materials: ResMut<Assets<ColorMaterial>>,
+               individuals: Query<(&Health, &Children), Changed<Health>>,
+               rings: Query<&Handle<ColorMaterial>, With<Energy>>) {
+    for (health, children) in individuals.iter() {
+        for ring in children.iter().filter_map(|&child| rings.get(child).ok()) {
+            if let Some(material) = materials.get_mut(ring) {
+                material.color.set_a(health.health);
+            }
+        }
+    }
+}
+
+//the background gets as dark as the senses at night
+fn darken_at_night(config: Res<Config>,
+                   timer: Res<EvolutionTimer>,
+                   statistics: Query<&Statistics>,
+                   mut clear: ResMut<ClearColor>) {
+    if config.day_length <= 0.0 {
+        return;
+    }
+    let generation = statistics.get_single().map(|statistics| statistics.generation).unwrap_or(0);
+    let time = generation as f32 * config.generation_time + timer.0.elapsed_secs();
+    let visibility = sensors::visibility(&config, sensors::day_phase(&config, time));
+    clear.0 = CLEAR * (0.25 + 0.75 * visibility);
+}
+
+//the individual that was clicked on, outlined when drawn as shapes
+#[derive(Component)]
+pub struct Selected;
+
+fn select_individual(mut commands: Commands,
+                     windows: Res<Windows>,
+                     buttons: Res<Input<MouseButton>>,
+                     cameras: Query<(&Camera, &GlobalTransform)>,
+                     individuals: Query<(Entity, &Transform), With<Nizm>>,
+                     selected: Query<Entity, With<Selected>>) {
+    if !buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(position) = cursor_in_world(&windows, &cameras) else {
+        return;
+    };
+
+    for entity in selected.iter() {
+        commands.entity(entity).remove::<Selected>();
+    }
+    let nearest = individuals
+        .iter()
+        .map(|(entity, transform)| (entity, transform.translation.truncate().distance(position)))
+        .filter(|(_, distance)| *distance < SELECT_DISTANCE)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b));
+    if let Some((entity, _)) = nearest {
+        commands.entity(entity).insert(Selected);
+    }
+}
+
+//where the mouse points in the world, None outside of the window or on the letterbox bars
+pub(crate) fn cursor_in_world(windows: &Windows, cameras: &Query<(&Camera, &GlobalTransform)>) -> Option<Vec2> {
+    let window = windows.get_primary()?;
+    let cursor = window.cursor_position()?;
+    cameras
+        .iter()
+        .filter(|(camera, _)| dashboard::shows_arena(camera))
+        .find_map(|(camera, transform)| {
+            //the cursor counts from the bottom left of the window, viewports from its top left
+            let (min, max) = camera.logical_viewport_rect()?;
+            let position = Vec2::new(cursor.x - min.x, cursor.y - (window.height() - max.y));
+            let inside = position.cmpge(Vec2::ZERO).all() && position.cmple(max - min).all();
+            inside.then(|| camera.viewport_to_world(transform, position)).flatten()
+        })
+        .map(|ray| ray.origin.truncate())
+}
+
+fn outline_selected(individuals: Query<(&Children, Option<&Selected>), With<Nizm>>,
+                    mut outlines: Query<&mut Visibility, With<Outline>>) {
+    for (children, selected) in individuals.iter() {
+        for &child in children.iter() {
+            if let Ok(mut visibility) = outlines.get_mut(child) {
+                if visibility.is_visible != selected.is_some() {
+                    visibility.is_visible = selected.is_some();
+                }
+            }
+        }
+    }
+}
+
+//the dead fade until the next generation brings them back
+fn fade_dead_individuals(mut deaths: EventReader<Died>,
+                         mut tints: Query<&mut Tint>) {
+    for death in deaths.iter() {
+        if let Ok(mut tint) = tints.get_mut(death.entity) {
+            tint.0.set_a(0.3);
+        }
+    }
+}
+
+fn add_killzone_sprite(mut commands: Commands,
+                       query: Query<(Entity, &KillZone), Added<KillZone>>) {
+    for (entity, killzone) in query.iter() {
+        commands.entity(entity).insert((
+            Sprite {
+                color: Color::rgb(0.2, 0.0, 0.0),
+                custom_size: Some(Vec2::new(killzone.max - killzone.min, 2.0)),
+                ..default()
+            },
+            Handle::<Image>::default(),
+            Visibility::default(),
+            ComputedVisibility::default(),
+        ));
+    }
+}
+
+fn add_obstacle_sprites(mut commands: Commands,
+                        query: Query<Entity, Added<Obstacle>>) {
+    for entity in query.iter() {
+        commands.entity(entity).insert((
+            Sprite {
+                color: Color::GRAY,
+                custom_size: Some(Vec2::splat(0.03)),
+                ..default()
+            },
+            Handle::<Image>::default(),
+            Visibility::default(),
+            ComputedVisibility::default(),
+        ));
+    }
+}
+
+//the curriculum changes the width of the kill zones between generations
+fn resize_killzone_sprites(mut query: Query<(&KillZone, &mut Sprite), Changed<KillZone>>) {
+    for (killzone, mut sprite) in query.iter_mut() {
+        sprite.custom_size = Some(Vec2::new(killzone.max - killzone.min, 2.0));
+    }
+}
+
+pub(crate) fn spawn_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            top: 1.0,
+            bottom: -1.0,
+            right: 1.0 * ASPECT_RATIO,
+            left: -ASPECT_RATIO,
+            //shows at least the whole world whatever the shape of the window, the resize
+            //handler takes care of the rest
+            scaling_mode: ScalingMode::Auto { min_width: 2.0 * ASPECT_RATIO, min_height: 2.0 },
+            scale: 1.,
+            ..default()
+        },
+        ..default()
+    });
+}
+
+//the largest area of a window of `width` by `height` pixels with the shape of the world,
+//centered, as its top left corner and its size
+pub fn letterbox(width: u32, height: u32) -> (UVec2, UVec2) {
+    let size = if width as f32 > height as f32 * ASPECT_RATIO {
+        UVec2::new((height as f32 * ASPECT_RATIO).round() as u32, height)
+    } else {
+        UVec2::new(width, (width as f32 / ASPECT_RATIO).round() as u32)
+    };
+    ((UVec2::new(width, height) - size) / 2, size)
+}
+
+//keeps the world undistorted when the window is resized, the projection already shows all of
+//it, letterboxing also hides what is around it
+pub(crate) fn fit_viewport(options: Option<Res<RenderOptions>>,
+                           windows: Res<Windows>,
+                           mut resized: EventReader<WindowResized>,
+                           mut cameras: Query<(&mut Camera, ChangeTrackers<Camera>)>) {
+    let resized = resized.iter().count() > 0;
+    if !resized && !cameras.iter().any(|(_, trackers)| trackers.is_added()) {
+        return;
+    }
+    let Some(window) = windows.get_primary() else {
+        return;
+    };
+    let (width, height) = (window.physical_width(), window.physical_height());
+    //a minimized window has no size to fit into
+    if width == 0 || height == 0 {
+        return;
+    }
+    let letterboxed = options.is_some_and(|options| options.viewport == WorldViewport::Letterbox);
+    let viewport = letterboxed.then(|| {
+        let (physical_position, physical_size) = letterbox(width, height);
+        Viewport { physical_position, physical_size, ..default() }
+    });
+    for (mut camera, _) in cameras.iter_mut().filter(|(camera, _)| dashboard::shows_arena(camera)) {
+        camera.viewport = viewport.clone();
+    }
+}
+
+pub struct SimRenderPlugin;
+
+impl Plugin for SimRenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RenderOptions>()
+            .insert_resource(ClearColor(CLEAR))
+            .add_startup_system(spawn_camera)
+            .add_system(fit_viewport)
+            .add_startup_system_to_stage(StartupStage::PreStartup, load_ascii)
+            .add_startup_system_to_stage(StartupStage::PreStartup, load_shapes)
+            .add_system(add_individual_looks)
+            .add_system(fade_dead_individuals.before(apply_tints))
+            .add_system(apply_tints)
+            .add_system(orient_shapes)
+            .add_system(show_energy)
+            .add_system(select_individual)
+            .add_system(outline_selected)
+            .add_system(add_killzone_sprite)
+            .add_system(resize_killzone_sprites)
+            .add_system(add_obstacle_sprites)
+            .add_system(darken_at_night)
+            .add_plugin(HudPlugin);
+    }
+}
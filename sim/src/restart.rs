@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use bevy::ecs::system::{CommandQueue, SystemState};
+use bevy::prelude::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::actions::Actions;
+use crate::alleles::AlleleTracker;
+use crate::curiosity::{self, VisitField};
+use crate::curriculum::Curriculum;
+use crate::environment::EnvironmentRng;
+use crate::manifest::RunManifest;
+use crate::noise::SensorRng;
+use crate::normalization::InputNormalizer;
+use crate::shutdown::{self, ShutdownReason};
+use crate::species::SpeciesRegistry;
+use crate::watchdog::Watchdog;
+use crate::{chromosome_to_color, lay_out_generation, optimizer, spawn_individual, Config, Evolution, EvolutionTimer, Frozen, Health, KillZone, Nizm, ResumePoint, SimRng, Statistics, ThinkTimer, Tint, WorldLayout};
+
+//asks to start the run over from generation 0, from `seed` or one drawn from the current run
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestartRun {
+    pub seed: Option<u64>,
+}
+
+//sent once the run started over, the first run of the app is 1
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunRestarted {
+    pub run: usize,
+    pub seed: u64,
+}
+
+//the run the app is on
+#[derive(Resource)]
+pub struct CurrentRun {
+    pub number: usize,
+    pub started: Instant,
+}
+
+impl Default for CurrentRun {
+    fn default() -> Self {
+        Self { number: 1, started: Instant::now() }
+    }
+}
+
+//called with the world of a run that just started over, for the outputs the app was started
+//with to begin a new entry of the run
+#[derive(Resource)]
+pub struct RestartHook(pub Box<dyn Fn(&mut World, &RunRestarted) + Send + Sync>);
+
+//where the file at `path` of the first run is written for run `run`, like stats-run2.csv
+pub fn run_path(path: &Path, run: usize) -> PathBuf {
+    if run <= 1 {
+        return path.to_path_buf();
+    }
+    let stem = path.file_stem().map_or_else(Default::default, |stem| stem.to_string_lossy());
+    let name = match path.extension() {
+        Some(extension) => format!("{stem}-run{run}.{}", extension.to_string_lossy()),
+        None => format!("{stem}-run{run}"),
+    };
+    path.with_file_name(name)
+}
+
+//starts the run over from generation 0 with a fresh random population drawn from `seed`: the
+//optimizer, the statistics, the kill zones, the timers, the size of the population and what the
+//plugins keep of the run are reset and follow the config, the frozen individuals and the painted
+//rewards stay
+pub fn restart(world: &mut World, seed: u64) {
+    let config = world.resource::<Config>().clone();
+    let rng = SimRng::from_seed(seed);
+    let rng_seed = rng.0.get_seed();
+    world.insert_resource(rng);
+    world.insert_resource(EnvironmentRng::from_seed(rng_seed));
+    world.insert_resource(SensorRng::from_seed(rng_seed));
+    world.insert_non_send_resource(Evolution::new(optimizer(&config)));
+    world.insert_resource(EvolutionTimer(Timer::from_seconds(config.generation_time, TimerMode::Repeating)));
+    world.insert_resource(ThinkTimer(Timer::from_seconds(1.0 / config.think_rate, TimerMode::Repeating)));
+    world.insert_resource(Watchdog::default());
+    world.insert_resource(Actions(config.action_encoding.decoder()));
+    world.insert_resource(InputNormalizer::new(&config));
+    world.remove_resource::<ResumePoint>();
+    world.remove_resource::<SpeciesRegistry>();
+    if config.species_threshold > 0.0 {
+        world.insert_resource(SpeciesRegistry::new(config.species_threshold));
+    }
+    world.remove_resource::<VisitField>();
+    if config.fitness.curiosity_weight > 0.0 {
+        world.insert_resource(VisitField::new(curiosity::CELLS));
+    }
+    //a run resumed mid curriculum starts over at its first stage
+    if let Some(curriculum) = world.remove_resource::<Curriculum>().or_else(|| config.curriculum.then(Curriculum::default)) {
+        world.insert_resource(curriculum.at_stage(0));
+    }
+    if world.contains_resource::<AlleleTracker>() {
+        world.insert_resource(AlleleTracker::default());
+    }
+    resize_population(world, &config);
+
+    let mut state: SystemState<(
+        Commands,
+        Option<Res<Curriculum>>,
+        ResMut<SimRng>,
+        WorldLayout,
+        Query<(&mut Nizm, &mut Transform, &mut Tint, Option<&mut Health>), (Without<KillZone>, Without<Frozen>)>,
+        Query<(&mut Nizm, &mut Transform), (With<Frozen>, Without<KillZone>)>,
+        Query<&mut Statistics>,
+        Query<(Entity, &mut KillZone, &mut Transform)>,
+    )> = SystemState::new(world);
+    let (mut commands, curriculum, mut rng, mut layout, mut individuals, mut frozen, mut statistics, mut killzones) = state.get_mut(world);
+
+    if let Ok(mut statistics) = statistics.get_single_mut() {
+        *statistics = Statistics::default();
+    }
+    for (mut nizm, _, mut tint, health) in individuals.iter_mut() {
+        *nizm = Nizm::random(&mut rng.0, &config);
+        tint.0 = chromosome_to_color(nizm.network.genes());
+        if let Some(mut health) = health {
+            *health = Health::default();
+        }
+    }
+    for (mut nizm, _) in frozen.iter_mut() {
+        nizm.reset();
+    }
+
+    lay_out_generation(
+        &mut commands,
+        &config,
+        curriculum.as_deref(),
+        &mut layout.environment,
+        &mut layout.environment_rng.0,
+        &mut rng.0,
+        layout.obstacles.iter(),
+        individuals.iter_mut().map(|(_, transform, ..)| transform.into_inner()).chain(frozen.iter_mut().map(|(_, transform)| transform.into_inner())),
+        &mut killzones,
+    );
+    state.apply(world);
+}
+
+//spawns or despawns individuals until there are config.individuals besides the frozen ones, the
+//newcomers get their brains and places with everybody else's
+fn resize_population(world: &mut World, config: &Config) {
+    let individuals: Vec<Entity> = world.query_filtered::<Entity, (With<Nizm>, Without<Frozen>)>().iter(world).collect();
+    if individuals.len() == config.individuals {
+        return;
+    }
+
+    let mut queue = CommandQueue::default();
+    let mut commands = Commands::new(&mut queue, world);
+    for &entity in individuals.iter().skip(config.individuals) {
+        commands.entity(entity).despawn_recursive();
+    }
+    //not drawn from the run's rng, restart() draws every brain again
+    let mut placeholder = ChaCha8Rng::seed_from_u64(0);
+    for i in individuals.len()..config.individuals {
+        spawn_individual(&mut commands, config, i, Vec3::ZERO, Nizm::random(&mut placeholder, config));
+    }
+    queue.apply(world);
+}
+
+//ends the current run in the manifest like closing the window would and starts the next one,
+//for the last request of the frame
+pub(crate) fn restart_run(world: &mut World) {
+    let Some(request) = world.resource_mut::<Events<RestartRun>>().drain().last() else {
+        return;
+    };
+    let seed = request.seed.unwrap_or_else(|| world.resource_mut::<SimRng>().0.gen());
+
+    let statistics = world.query::<&Statistics>().get_single(world).ok().cloned().unwrap_or_default();
+    let summary = shutdown::run_summary(&statistics, ShutdownReason::Restarted, world.resource::<CurrentRun>().started.elapsed().as_secs_f32());
+    info!("{summary}");
+    if let Some(mut manifest) = world.get_resource_mut::<RunManifest>() {
+        manifest.summarize(&summary);
+    }
+
+    restart(world, seed);
+    let run = world.resource::<CurrentRun>().number + 1;
+    world.insert_resource(CurrentRun { number: run, started: Instant::now() });
+    let restarted = RunRestarted { run, seed };
+    info!("run {run} started from seed {seed}");
+    if let Some(hook) = world.remove_resource::<RestartHook>() {
+        (hook.0)(world, &restarted);
+        world.insert_resource(hook);
+    }
+    world.send_event(restarted);
+}
+
+//starts the run over with F5, the config console takes `restart` and `restart <seed>`
+pub struct RestartPlugin;
+
+impl Plugin for RestartPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(restart_hotkey);
+    }
+}
+
+fn restart_hotkey(keys: Res<Input<KeyCode>>, mut restarts: EventWriter<RestartRun>) {
+    if keys.just_pressed(KeyCode::F5) {
+        restarts.send(RestartRun { seed: None });
+    }
+}
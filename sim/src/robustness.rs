@@ -0,0 +1,98 @@
+use std::ops::Range;
+
+use lib_natural_selection::Chromosome;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::rules::{SimCore, FRAME};
+use crate::Config;
+
+//the share of the worst worlds the robustness score is the mean survival of
+const WORST_SHARE: f32 = 0.1;
+
+//how a champion did in a random world of every seed, with a generation of its clones in each
+#[derive(Clone, Debug, PartialEq)]
+pub struct Robustness {
+    //per seed, the share of the clones that survived the generation
+    pub survival: Vec<f32>,
+    //per seed, the mean fitness of the clones
+    pub fitness: Vec<f32>,
+}
+
+impl Robustness {
+    pub fn mean_survival(&self) -> f32 {
+        self.survival.iter().sum::<f32>() / self.survival.len().max(1) as f32
+    }
+
+    pub fn worst_survival(&self) -> f32 {
+        self.survival.iter().copied().fold(f32::INFINITY, f32::min)
+    }
+
+    pub fn worst_fitness(&self) -> f32 {
+        self.fitness.iter().copied().fold(f32::INFINITY, f32::min)
+    }
+
+    //the survival rate `quantile` of the worlds ended at or below, by nearest rank
+    pub fn quantile(&self, quantile: f32) -> f32 {
+        let sorted = self.sorted_survival();
+        let rank = (quantile.clamp(0.0, 1.0) * sorted.len() as f32).ceil() as usize;
+        sorted.get(rank.saturating_sub(1)).copied().unwrap_or(0.0)
+    }
+
+    //how many worlds ended with a survival rate in each of `bins` equal parts of 0 to 1
+    pub fn histogram(&self, bins: usize) -> Vec<usize> {
+        let mut counts = vec![0; bins];
+        for &survival in &self.survival {
+            counts[((survival * bins as f32) as usize).min(bins - 1)] += 1;
+        }
+        counts
+    }
+
+    //the mean survival rate of the worst tenth of the worlds, a champion only scores high if it
+    //rarely fails, whatever its average
+    pub fn score(&self) -> f32 {
+        let sorted = self.sorted_survival();
+        let worst = ((sorted.len() as f32 * WORST_SHARE).ceil() as usize).clamp(1, sorted.len().max(1));
+        sorted.iter().take(worst).sum::<f32>() / worst as f32
+    }
+
+    fn sorted_survival(&self) -> Vec<f32> {
+        let mut sorted = self.survival.clone();
+        sorted.sort_by(f32::total_cmp);
+        sorted
+    }
+}
+
+//plays a generation of `clones` copies of `genome` in a random world of every seed of `seeds`,
+//the seeds are split over every core
+pub fn evaluate(config: &Config, genome: &Chromosome, seeds: Range<u64>, clones: usize) -> Robustness {
+    let clones = clones.max(1);
+    let config = Config { individuals: clones, ..config.clone() };
+    let seeds: Vec<u64> = seeds.collect();
+    let threads = std::thread::available_parallelism().map_or(1, usize::from);
+    let chunk = seeds.len().div_ceil(threads).max(1);
+
+    let worlds: Vec<(f32, f32)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = seeds
+            .chunks(chunk)
+            .map(|seeds| scope.spawn(|| seeds.iter().map(|&seed| play(&config, genome, seed)).collect::<Vec<_>>()))
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().expect("a world panicked")).collect()
+    });
+
+    let (survival, fitness) = worlds.into_iter().unzip();
+    Robustness { survival, fitness }
+}
+
+//the survival rate and mean fitness of the clones after a generation in the world of `seed`
+fn play(config: &Config, genome: &Chromosome, seed: u64) -> (f32, f32) {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let genomes = vec![genome.clone(); config.individuals];
+    let mut core = SimCore::new(config.clone(), &mut rng, &config.difficulty(), &genomes);
+    while !core.finished() {
+        core.step(FRAME, &mut rng);
+    }
+
+    let fitness = core.fitness();
+    (core.survivors() as f32 / genomes.len() as f32, fitness.iter().sum::<f32>() / fitness.len() as f32)
+}
@@ -0,0 +1,398 @@
+use std::f32::consts::PI;
+use std::time::Duration;
+use bevy::math::{Vec2, Vec3};
+use lib_natural_selection::Chromosome;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::actions::{perturb, ActionDecoder};
+use crate::curriculum::Difficulty;
+use crate::environment::Environment;
+use crate::fitness::FitnessTerms;
+use crate::noise;
+use crate::normalization::InputNormalizer;
+use crate::sensors;
+use crate::wind;
+use crate::{Config, Health, KillZone, Nizm};
+
+//how wide an individual and an obstacle are, a moving individual is checked a little smaller
+//so it can slip past the others
+const BODY_SIZE: f32 = 0.03;
+const MOVING_BODY: f32 = 0.8;
+//the frame length SimCore::evaluate steps with
+pub const FRAME: f32 = 1.0 / 60.0;
+//slower than this in world units per second counts as idle for the idle penalty
+pub const IDLE_SPEED: f32 = 0.05;
+
+//the rules of the world on plain data, the systems apply them to the components of their
+//entities and SimCore runs whole generations with them without an ecs
+
+//whether an individual moving to `target` overlaps the individual or obstacle at `other`
+pub fn collides(target: Vec3, other: Vec3) -> bool {
+    let (size, other_size) = (Vec2::splat(BODY_SIZE) * MOVING_BODY, Vec2::splat(BODY_SIZE));
+    let (min, max) = (target.truncate() - size / 2.0, target.truncate() + size / 2.0);
+    let (other_min, other_max) = (other.truncate() - other_size / 2.0, other.truncate() + other_size / 2.0);
+
+    min.x < other_max.x && max.x > other_min.x && min.y < other_max.y && max.y > other_min.y
+}
+
+//1.0 for each of left, right, up and down in which a step of `delta` seconds from `position`
+//runs into the individual at `other`
+pub fn blocked_directions(position: Vec3, other: Vec3, delta: f32, speed: f32) -> [f32; 4] {
+    [
+        Vec3::new(-1.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, -1.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+    ]
+    .map(|direction| if collides(position + direction * MOVING_BODY * delta * speed, other) { 1.0 } else { 0.0 })
+}
+
+//how far an individual at `position` moves along `action` in `delta` seconds while the `wind`
+//pushes it, it stops at the edges of the world
+pub fn movement(position: Vec3, action: Vec3, delta: f32, speed: f32, wind: Vec2) -> Vec3 {
+    let mut movement = action * delta * speed + wind.extend(0.0) * delta;
+    let target = position + movement;
+
+    if target.x.abs() > 1.0 { movement.x = 0.0 }
+    if target.y.abs() > 1.0 { movement.y = 0.0 }
+    movement
+}
+
+//`movement` cut short where it would take an individual that moved `total_movement` so far past
+//`budget`, after which it stops moving for the rest of the generation, 0.0 is no budget
+pub fn within_budget(movement: Vec3, total_movement: f32, budget: f32) -> Vec3 {
+    if budget <= 0.0 {
+        return movement;
+    }
+    let left = (budget - total_movement).max(0.0);
+    let length = movement.length();
+    if length <= left { movement } else { movement * (left / length) }
+}
+
+//adds a frame of `delta` seconds to the time spent idle if the individual barely moved in it
+pub fn track_idle(nizm: &mut Nizm, delta: f32) {
+    if nizm.movement.length() < IDLE_SPEED * delta {
+        nizm.time_idle += delta;
+    }
+}
+
+//the inputs of a brain for the sensors that are on, `remaining` is how far the generation has
+//come from 0.0 to 1.0, `time` the seconds since the run started and `reward` the painted reward
+//at `position`
+pub fn senses<'a>(config: &Config, nizm: &Nizm, position: Vec3, remaining: f32, time: f32, reward: f32, killzones: impl IntoIterator<Item = &'a KillZone>) -> Vec<f32> {
+    let osc = (nizm.osc_freq * remaining * PI * 2.0).sin();
+    let killzone = killzones
+        .into_iter()
+        .min_by(|a, b| (a.center() - position.x).abs().total_cmp(&(b.center() - position.x).abs()))
+        .expect("need killzone");
+    let phase = sensors::day_phase(config, time);
+    let mut senses = vec![
+        position.x,
+        position.y,
+        remaining,
+        osc,
+        nizm.movement.x,
+        nizm.movement.y,
+        nizm.can_move_left,
+        nizm.can_move_right,
+        nizm.can_move_up,
+        nizm.can_move_down,
+        if killzone.min < 0.0 { -1.0 } else { 1.0 },
+        time.rem_euclid(config.time_period) / config.time_period,
+        reward,
+        phase,
+    ];
+    if config.day_length > 0.0 {
+        sensors::dim(&mut senses, sensors::visibility(config, phase));
+    }
+    sensors::ablate(config, senses)
+}
+
+//turns the outputs of a brain into the action it holds until the next think tick
+pub fn act(nizm: &mut Nizm, outputs: &[f32], decoder: &dyn ActionDecoder, noise: f32, rng: &mut dyn RngCore) {
+    let action = decoder.decode(outputs, &mut nizm.heading);
+    nizm.action = perturb(rng, action, noise);
+    nizm.osc_freq = outputs[4];
+}
+
+//drifts a moving kill zone for `delta` seconds, it turns around at the edges of the world and
+//the protected start areas
+pub fn drift(killzone: &mut KillZone, delta: f32, environment: &Environment) {
+    if killzone.velocity == 0.0 {
+        return;
+    }
+
+    let shift = killzone.velocity * delta;
+    let (min, max) = (killzone.min + shift, killzone.max + shift);
+    if min < -1.0 || max > 1.0 || environment.is_protected(min, max) {
+        killzone.velocity = -killzone.velocity;
+    } else {
+        killzone.min += shift;
+        killzone.max += shift;
+    }
+}
+
+//takes `amount` health, true if the individual died of it at `at` seconds into the generation
+pub fn damage(health: &mut Health, amount: f32, at: f32) -> bool {
+    health.health -= amount;
+    if health.health > 0.0 {
+        return false;
+    }
+
+    health.health = 0.0;
+    health.died_at = Some(at);
+    true
+}
+
+//the fitness of a trial that ended with the individual at `x`, with killzone_damage it counts
+//how long it lived instead of where it ended up
+pub fn fitness<'a>(config: &Config, nizm: &Nizm, x: f32, health: Option<&Health>, killzones: impl IntoIterator<Item = &'a KillZone>) -> f32 {
+    fitness_terms(config, nizm, x, health, killzones).total()
+}
+
+//the parts of the fitness
+pub fn fitness_terms<'a>(config: &Config, nizm: &Nizm, x: f32, health: Option<&Health>, killzones: impl IntoIterator<Item = &'a KillZone>) -> FitnessTerms {
+    FitnessTerms {
+        distance: x.abs() + 1.0,
+        movement: config.fitness.movement(nizm.total_movement, config.generation_time),
+        survival: match health {
+            Some(health) => health.died_at.map_or(1.0, |at| at / config.generation_time),
+            None => config.fitness.survival(x, killzones),
+        },
+        exposure: config.fitness.exposure(nizm.time_in_killzone, config.generation_time),
+        painted: nizm.painted_reward,
+        curiosity: config.fitness.curiosity_weight * nizm.curiosity,
+        idle: config.fitness.idle_penalty * nizm.time_idle,
+    }
+}
+
+//an individual of a SimCore
+pub struct Body {
+    pub nizm: Nizm,
+    pub position: Vec3,
+    pub health: Option<Health>,
+}
+
+impl Body {
+    pub fn is_alive(&self) -> bool {
+        self.health.as_ref().is_none_or(Health::is_alive)
+    }
+}
+
+//one generation of the world without bevy, it steps like a frame of the systems does, brains
+//think on the cpu and every random draw comes from the rng passed to step
+pub struct SimCore {
+    pub config: Config,
+    pub environment: Environment,
+    pub killzones: Vec<KillZone>,
+    pub bodies: Vec<Body>,
+    decoder: Box<dyn ActionDecoder>,
+    //into the generation and since the last think tick, counted in durations like the timers of
+    //the systems, so both think on the same frames
+    elapsed: Duration,
+    since_thought: Duration,
+    //every observation the brains thought about, while recording
+    recorded: Option<Vec<Vec<f32>>>,
+    normalizer: InputNormalizer,
+}
+
+impl SimCore {
+    //lays out a world for `chromosomes`, each one an individual
+    pub fn new(config: Config, rng: &mut dyn RngCore, difficulty: &Difficulty, chromosomes: &[Chromosome]) -> Self {
+        let environment = Environment::random(rng, &config);
+        let bodies = chromosomes
+            .iter()
+            .enumerate()
+            .map(|(i, chromosome)| Body {
+                nizm: Nizm::from_chromosome(chromosome, &config),
+                position: environment.spawn_position(rng, i),
+                health: (config.killzone_damage > 0.0).then(Health::default),
+            })
+            .collect();
+        let difficulty = environment.difficulty(difficulty);
+        let killzones = (0..difficulty.killzones)
+            .map(|_| environment.protect(KillZone::random(rng, &difficulty)))
+            .collect();
+
+        Self {
+            decoder: config.action_encoding.decoder(),
+            normalizer: InputNormalizer::new(&config),
+            config,
+            environment,
+            killzones,
+            bodies,
+            elapsed: Duration::ZERO,
+            since_thought: Duration::ZERO,
+            recorded: None,
+        }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed >= Duration::from_secs_f32(self.config.generation_time)
+    }
+
+    //moves the world on by `delta` seconds, the brains think when their tick is due
+    pub fn step(&mut self, delta: f32, rng: &mut dyn RngCore) {
+        self.frame(delta, Some(rng));
+    }
+
+    //moves the world on by `delta` seconds, everybody keeps the action they hold
+    pub fn hold(&mut self, delta: f32) {
+        self.frame(delta, None);
+    }
+
+    fn frame(&mut self, delta: f32, rng: Option<&mut dyn RngCore>) {
+        let speed = self.config.movement_speed * self.environment.speed_factor;
+        let tick = Duration::from_secs_f32(delta);
+
+        for killzone in &mut self.killzones {
+            drift(killzone, delta, &self.environment);
+        }
+
+        //like the pairs of the collision system, every individual looks at those after it
+        for i in 0..self.bodies.len() {
+            for j in i + 1..self.bodies.len() {
+                let [left, right, up, down] = blocked_directions(self.bodies[i].position, self.bodies[j].position, delta, speed);
+                let nizm = &mut self.bodies[i].nizm;
+                (nizm.can_move_left, nizm.can_move_right, nizm.can_move_up, nizm.can_move_down) = (left, right, up, down);
+            }
+        }
+
+        if let Some(rng) = rng {
+            self.since_thought += tick;
+            let period = Duration::from_secs_f32(1.0 / self.config.think_rate);
+            if self.since_thought >= period {
+                self.since_thought = Duration::from_nanos((self.since_thought.as_nanos() % period.as_nanos()) as u64);
+                self.think(rng);
+            }
+        }
+
+        for i in 0..self.bodies.len() {
+            if !self.bodies[i].is_alive() {
+                continue;
+            }
+            let position = self.bodies[i].position;
+            let wind = wind::wind_at(&self.config, position.truncate(), self.elapsed.as_secs_f32());
+            let movement = within_budget(movement(position, self.bodies[i].nizm.action, delta, speed, wind), self.bodies[i].nizm.total_movement, self.config.movement_budget);
+            let blocked = self
+                .bodies
+                .iter()
+                .map(|body| body.position)
+                .chain(self.environment.obstacles.iter().map(|obstacle| obstacle.extend(900.0)))
+                .any(|other| position != other && collides(position + movement, other));
+
+            let body = &mut self.bodies[i];
+            if blocked {
+                body.nizm.movement = Vec3::ZERO;
+            } else {
+                body.position = position + movement;
+                body.nizm.total_movement += movement.length();
+                body.nizm.displacement += movement;
+                body.nizm.movement = movement;
+            }
+            track_idle(&mut body.nizm, delta);
+        }
+
+        for body in &mut self.bodies {
+            if !body.is_alive() || !self.killzones.iter().any(|killzone| killzone.contains(body.position.x)) {
+                continue;
+            }
+            body.nizm.time_in_killzone += delta;
+            if let Some(health) = &mut body.health {
+                damage(health, self.config.killzone_damage * delta, self.elapsed.as_secs_f32());
+            }
+        }
+
+        self.elapsed += tick;
+    }
+
+    //keeps the observations of every think tick from now on, see recorded()
+    pub fn record(&mut self) {
+        self.recorded.get_or_insert_with(Vec::new);
+    }
+
+    pub fn recorded(&self) -> &[Vec<f32>] {
+        self.recorded.as_deref().unwrap_or_default()
+    }
+
+    fn think(&mut self, rng: &mut dyn RngCore) {
+        let mut observations = self.observations();
+        noise::add_noise(&self.config, self.bodies.iter().map(|body| &body.nizm), &mut observations, rng);
+        self.normalizer.normalize(&mut observations);
+        let progress = self.elapsed.as_secs_f32() / self.config.generation_time;
+        for (inputs, body) in observations.iter_mut().zip(&self.bodies) {
+            inputs.extend(body.nizm.network.clock(progress));
+        }
+        if let Some(recorded) = &mut self.recorded {
+            recorded.extend(observations.iter().cloned());
+        }
+
+        let outputs: Vec<_> = observations
+            .into_iter()
+            .zip(&mut self.bodies)
+            .map(|(inputs, body)| body.nizm.network.step(inputs))
+            .collect();
+        self.act(&outputs, self.config.action_noise_at(0), rng);
+    }
+
+    //the senses of every individual, what its brain would be given
+    pub fn observations(&self) -> Vec<Vec<f32>> {
+        let progress = self.elapsed.as_secs_f32() / self.config.generation_time;
+        self.bodies
+            .iter()
+            //nobody paints rewards without a window
+            .map(|body| senses(&self.config, &body.nizm, body.position, progress, self.elapsed.as_secs_f32(), 0.0, &self.killzones))
+            .collect()
+    }
+
+    //gives every individual the action decoded from its outputs, laid out like those of a brain
+    pub fn act(&mut self, outputs: &[Vec<f32>], noise: f32, rng: &mut dyn RngCore) {
+        for (body, outputs) in self.bodies.iter_mut().zip(outputs) {
+            act(&mut body.nizm, outputs, &*self.decoder, noise, rng);
+        }
+    }
+
+    //of every individual, as the generation stands
+    pub fn fitness(&self) -> Vec<f32> {
+        self.bodies
+            .iter()
+            .map(|body| fitness(&self.config, &body.nizm, body.position.x, body.health.as_ref(), &self.killzones))
+            .collect()
+    }
+
+    //how many individuals are alive and, without killzone_damage, outside of the kill zones, as
+    //the generation stands
+    pub fn survivors(&self) -> usize {
+        self.bodies
+            .iter()
+            .filter(|body| match &body.health {
+                Some(health) => health.is_alive(),
+                None => !self.killzones.iter().any(|killzone| killzone.contains(body.position.x)),
+            })
+            .count()
+    }
+
+    //the fitness of `genome` alone in a world from `seed` after a whole generation
+    pub fn evaluate(config: &Config, seed: u64, genome: &Chromosome) -> f32 {
+        Self::run_alone(config, seed, genome, false).fitness()[0]
+    }
+
+    //what the brain of `genome` is given over a generation alone in a world from `seed`
+    pub fn observe(config: &Config, seed: u64, genome: &Chromosome) -> Vec<Vec<f32>> {
+        let mut core = Self::run_alone(config, seed, genome, true);
+        core.recorded.take().unwrap_or_default()
+    }
+
+    fn run_alone(config: &Config, seed: u64, genome: &Chromosome, record: bool) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut core = Self::new(config.clone(), &mut rng, &config.difficulty(), std::slice::from_ref(genome));
+        if record {
+            core.record();
+        }
+        while !core.finished() {
+            core.step(FRAME, &mut rng);
+        }
+        core
+    }
+}
@@ -0,0 +1,136 @@
+use std::f32::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Config;
+
+//the senses of an individual in the order rules::senses() lays them out, any of them can be
+//disabled for ablation runs and those that are off by default enabled, the brains have an
+//input for every sense that is on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sensor {
+    Position,
+    //how far the generation has come, from 0.0 to 1.0
+    Progress,
+    Oscillator,
+    //the movement of the last frame
+    Movement,
+    //whether the four directions are blocked
+    CanMove,
+    //the side of the nearest kill zone
+    Killzone,
+    //the seconds since the run started modulo the time period, as a fraction of it, unlike
+    //Progress it does not restart with every generation, off by default
+    Time,
+    //the painted reward where the individual is, see painting::RewardField, off by default
+    Reward,
+    //how far the day/night cycle has come, from 0.0 at noon to 1.0, off by default
+    Daylight,
+}
+
+impl Sensor {
+    pub const ALL: [Sensor; 9] = [
+        Self::Position, Self::Progress, Self::Oscillator, Self::Movement, Self::CanMove, Self::Killzone, Self::Time, Self::Reward, Self::Daylight,
+    ];
+
+    //how many inputs of a brain the sensor takes up
+    pub fn inputs(self) -> usize {
+        match self {
+            Self::Position | Self::Movement => 2,
+            Self::Progress | Self::Oscillator | Self::Killzone | Self::Time | Self::Reward | Self::Daylight => 1,
+            Self::CanMove => 4,
+        }
+    }
+
+    pub fn on_by_default(self) -> bool {
+        !matches!(self, Self::Time | Self::Reward | Self::Daylight)
+    }
+
+    //whether the sensor looks at the world, those fade out at night while the senses of time
+    //keep working in the dark
+    pub fn perceives(self) -> bool {
+        matches!(self, Self::Position | Self::Movement | Self::CanMove | Self::Killzone | Self::Reward)
+    }
+
+    //whether the brains of a run with `config` sense it
+    pub fn is_on(self, config: &Config) -> bool {
+        if self.on_by_default() {
+            !config.disabled_sensors.contains(&self)
+        } else {
+            config.enabled_sensors.contains(&self)
+        }
+    }
+}
+
+impl FromStr for Sensor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|sensor| sensor.to_string() == s)
+            .ok_or_else(|| format!("unknown sensor '{s}', expected position, progress, oscillator, movement, can_move, killzone, time, reward or daylight"))
+    }
+}
+
+impl fmt::Display for Sensor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Position => "position",
+            Self::Progress => "progress",
+            Self::Oscillator => "oscillator",
+            Self::Movement => "movement",
+            Self::CanMove => "can_move",
+            Self::Killzone => "killzone",
+            Self::Time => "time",
+            Self::Reward => "reward",
+            Self::Daylight => "daylight",
+        })
+    }
+}
+
+//how many inputs the brains of a run with `config` have for their senses
+pub fn inputs(config: &Config) -> usize {
+    Sensor::ALL.into_iter().filter(|sensor| sensor.is_on(config)).map(Sensor::inputs).sum()
+}
+
+//how far the day/night cycle of a run with `config` has come `time` seconds into it, from 0.0
+//at noon over midnight at 0.5 to 1.0
+pub fn day_phase(config: &Config, time: f32) -> f32 {
+    if config.day_length > 0.0 { time.rem_euclid(config.day_length) / config.day_length } else { 0.0 }
+}
+
+//how much of what the sensors perceive gets through at `phase`, 1.0 at noon and 1.0 - the night
+//blindness at midnight
+pub fn visibility(config: &Config, phase: f32) -> f32 {
+    let darkness = (1.0 - (phase * 2.0 * PI).cos()) / 2.0;
+    1.0 - config.night_blindness * darkness
+}
+
+//fades out the senses that perceive the world by `visibility`, `senses` are laid out like
+//Sensor::ALL
+pub fn dim(senses: &mut [f32], visibility: f32) {
+    let mut start = 0;
+    for sensor in Sensor::ALL {
+        let end = start + sensor.inputs();
+        if sensor.perceives() {
+            senses[start..end].iter_mut().for_each(|sense| *sense *= visibility);
+        }
+        start = end;
+    }
+}
+
+//drops the values of the sensors that are off from `senses`, which are laid out like Sensor::ALL
+pub fn ablate<T>(config: &Config, senses: Vec<T>) -> Vec<T> {
+    let mut senses = senses.into_iter();
+    let mut kept = Vec::with_capacity(inputs(config));
+    for sensor in Sensor::ALL {
+        let values = senses.by_ref().take(sensor.inputs());
+        if sensor.is_on(config) {
+            kept.extend(values);
+        } else {
+            values.for_each(drop);
+        }
+    }
+    kept
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use crate::headless::world_checkpoint;
+use crate::manifest::RunManifest;
+use crate::persistence::{IoHandle, IoWorker};
+use crate::restart::CurrentRun;
+use crate::{population, Statistics};
+
+//why a run ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShutdownReason {
+    //the window was closed
+    Closed,
+    //by Ctrl-C
+    Interrupted,
+    //to start the run over, see restart::RestartRun
+    Restarted,
+}
+
+impl fmt::Display for ShutdownReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Closed => "window closed",
+            Self::Interrupted => "interrupted",
+            Self::Restarted => "restarted",
+        })
+    }
+}
+
+//set once Ctrl-C was pressed, the handler is installed the first time it is asked for and
+//replaces the default one that kills the process on the spot
+pub fn interrupted() -> Arc<AtomicBool> {
+    static INTERRUPTED: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+    INTERRUPTED
+        .get_or_init(|| {
+            let interrupted = Arc::new(AtomicBool::new(false));
+            let flag = interrupted.clone();
+            if let Err(err) = ctrlc::set_handler(move || flag.store(true, Ordering::SeqCst)) {
+                warn!("Ctrl-C will not save the run: {err}");
+            }
+            interrupted
+        })
+        .clone()
+}
+
+//what a run reached, printed when it ends and appended to the manifest
+pub fn run_summary(statistics: &Statistics, reason: ShutdownReason, seconds: f32) -> String {
+    format!(
+        "run ended ({reason}) after {} generations in {seconds:.0}s: best fitness {:.3}, average {:.3}, survivors {:.1}%, champion fitness {:.3}",
+        statistics.generation,
+        statistics.best_fitness,
+        statistics.average_fitness,
+        statistics.survivors_percentage * 100.0,
+        statistics.champion_fitness,
+    )
+}
+
+#[derive(Resource)]
+struct Shutdown {
+    checkpoint: Option<PathBuf>,
+    interrupted: Arc<AtomicBool>,
+    done: bool,
+}
+
+//ends a windowed run on Ctrl-C like closing the window, and either way saves it before the app
+//exits: the checkpoint of the generation in progress if asked for, which resumes it from its
+//start, the run summary, and every write still pending on the io thread
+pub struct ShutdownPlugin {
+    pub checkpoint: Option<PathBuf>,
+}
+
+impl Plugin for ShutdownPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Shutdown { checkpoint: self.checkpoint.clone(), interrupted: interrupted(), done: false })
+            .add_system_to_stage(CoreStage::PreUpdate, exit_on_interrupt)
+            .add_system_to_stage(CoreStage::Last, shut_down);
+    }
+}
+
+fn exit_on_interrupt(shutdown: Res<Shutdown>, mut exit: EventWriter<AppExit>) {
+    if shutdown.interrupted.load(Ordering::SeqCst) {
+        exit.send(AppExit);
+    }
+}
+
+fn shut_down(world: &mut World) {
+    if world.resource::<Events<AppExit>>().is_empty() || world.resource::<Shutdown>().done {
+        return;
+    }
+    let shutdown = world.resource::<Shutdown>();
+    let reason = if shutdown.interrupted.load(Ordering::SeqCst) { ShutdownReason::Interrupted } else { ShutdownReason::Closed };
+    let (path, seconds) = (shutdown.checkpoint.clone(), world.resource::<CurrentRun>().started.elapsed().as_secs_f32());
+    world.resource_mut::<Shutdown>().done = true;
+
+    let io = world.get_resource::<IoWorker>().map_or_else(IoHandle::default, IoWorker::handle);
+    if let Some(path) = path {
+        let checkpoint = world_checkpoint(world);
+        io.submit(format!("the final checkpoint to {}", path.display()), move || {
+            population::save_checkpoint(&path, &checkpoint)?;
+            info!("saved the run to {}", path.display());
+            Ok(())
+        });
+    }
+
+    let statistics = world.query::<&Statistics>().get_single(world).ok().cloned().unwrap_or_default();
+    let summary = run_summary(&statistics, reason, seconds);
+    info!("{summary}");
+    if let Some(mut manifest) = world.get_resource_mut::<RunManifest>() {
+        manifest.summarize(&summary);
+    }
+    if let Some(worker) = world.get_resource::<IoWorker>() {
+        worker.flush();
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::BTreeSet;
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+use lib_natural_selection::Speciation;
+
+use crate::chromosome_to_color;
+use crate::dashboard::{self, Dashboard};
+
+//what the species panel asks the evolution to do with a species, by id
+pub enum SpeciesCommand {
+    //its fittest member survives every generation unchanged, so it cannot die out
+    Protect(usize),
+    Unprotect(usize),
+    //none of its members become parents in the next generation
+    Cull(usize),
+}
+
+//the species of the population, only with a species_threshold
+#[derive(Resource)]
+pub struct SpeciesRegistry {
+    pub speciation: Speciation,
+    pub protected: BTreeSet<usize>,
+    //culled when the current generation ends
+    pub culled: BTreeSet<usize>,
+}
+
+impl SpeciesRegistry {
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            speciation: Speciation::new(threshold),
+            protected: BTreeSet::new(),
+            culled: BTreeSet::new(),
+        }
+    }
+
+    pub fn apply(&mut self, command: &SpeciesCommand) {
+        match *command {
+            SpeciesCommand::Protect(id) => {
+                self.protected.insert(id);
+            }
+            SpeciesCommand::Unprotect(id) => {
+                self.protected.remove(&id);
+            }
+            SpeciesCommand::Cull(id) => {
+                self.culled.insert(id);
+            }
+        }
+    }
+
+    //speciates a scored generation before it is evolved, zeroes the fitness of the culled
+    //species and returns the genes of the fittest member of every protected species with its
+    //index, to be written back over its offspring
+    pub fn prepare<'a>(&mut self, genomes: impl IntoIterator<Item = &'a [f32]> + Clone, fitness: &mut [f32]) -> Vec<(usize, Vec<f32>)> {
+        self.speciation.speciate(genomes.clone(), fitness);
+
+        for species in self.speciation.species().iter().filter(|species| self.culled.contains(&species.id())) {
+            for &member in species.members() {
+                fitness[member] = 0.0;
+            }
+        }
+        self.culled.clear();
+
+        let genomes: Vec<&[f32]> = genomes.into_iter().collect();
+        self.speciation
+            .species()
+            .iter()
+            .filter(|species| self.protected.contains(&species.id()))
+            .filter_map(|species| {
+                species
+                    .members()
+                    .iter()
+                    .copied()
+                    .max_by(|&a, &b| fitness[a].total_cmp(&fitness[b]))
+                    .map(|best| (best, genomes[best].to_vec()))
+            })
+            .collect()
+    }
+}
+
+//lists the species of the last generation with buttons to protect or cull them
+pub struct SpeciesPlugin;
+
+impl Plugin for SpeciesPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+
+        app.add_system(species_panel);
+    }
+}
+
+fn species_panel(mut egui_context: ResMut<EguiContext>,
+                 dashboard: Option<Res<Dashboard>>,
+                 registry: Option<Res<SpeciesRegistry>>,
+                 mut commands: EventWriter<SpeciesCommand>) {
+    let Some(registry) = registry else {
+        return;
+    };
+
+    let Some(ctx) = dashboard::panels(&mut egui_context, dashboard.as_deref()) else {
+        return;
+    };
+    egui::Window::new("Species").show(ctx, |ui| {
+        egui::Grid::new("species").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.label("species");
+            ui.label("size");
+            ui.label("best");
+            ui.label("");
+            ui.label("");
+            ui.end_row();
+
+            for species in registry.speciation.species() {
+                let color = chromosome_to_color(species.representative()).as_rgba_f32();
+                let [r, g, b] = [color[0], color[1], color[2]].map(|channel| (channel.clamp(0.0, 1.0) * 255.0) as u8);
+                let protected = registry.protected.contains(&species.id());
+
+                ui.colored_label(egui::Color32::from_rgb(r, g, b), "■");
+                ui.label(species.id().to_string());
+                ui.label(species.len().to_string());
+                ui.label(format!("{:.3}", species.best_fitness()));
+                if ui.selectable_label(protected, "protect").clicked() {
+                    commands.send(if protected {
+                        SpeciesCommand::Unprotect(species.id())
+                    } else {
+                        SpeciesCommand::Protect(species.id())
+                    });
+                }
+                let culled = registry.culled.contains(&species.id());
+                if ui.add_enabled(!culled, egui::Button::new("cull")).clicked() {
+                    commands.send(SpeciesCommand::Cull(species.id()));
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
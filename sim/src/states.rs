@@ -0,0 +1,137 @@
+use bevy::ecs::schedule::ShouldRun;
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+
+use crate::presets::Preset;
+use crate::restart::RestartRun;
+use crate::Config;
+
+//where the app is, the simulation only advances while running or replaying
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SimState {
+    //the start menu, the world waits for a preset, a seed and a population size
+    Menu,
+    Running,
+    //paused with space
+    Paused,
+    //paused on the results of the last generation from its summary
+    GenerationSummary,
+    //the brains act but do not evolve, every generation starts over with the same ones
+    Replay,
+}
+
+impl SimState {
+    pub fn simulates(self) -> bool {
+        matches!(self, Self::Running | Self::Replay)
+    }
+}
+
+//the run criteria of the systems that advance the simulation, apps without the states like
+//the headless ones always run them
+pub fn simulating(state: Option<Res<State<SimState>>>) -> ShouldRun {
+    if state.is_none_or(|state| state.current().simulates()) {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+//whether the brains of a run in `state` are kept from one generation to the next
+pub fn replaying(state: Option<&State<SimState>>) -> bool {
+    state.is_some_and(|state| *state.current() == SimState::Replay)
+}
+
+//what the start menu starts the run with
+#[derive(Resource)]
+struct StartMenu {
+    //None keeps the config the app was started with
+    preset: Option<Preset>,
+    seed: u64,
+    individuals: usize,
+}
+
+//the states of a windowed run, the start menu, pausing with space and stopping the clock of
+//everything while nothing is simulated
+pub struct StatesPlugin {
+    pub initial: SimState,
+    //the seed the start menu suggests
+    pub seed: u64,
+}
+
+impl Plugin for StatesPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+        let individuals = app.world.get_resource::<Config>().expect("need config").individuals;
+
+        app.add_state(self.initial)
+            .insert_resource(StartMenu { preset: None, seed: self.seed, individuals })
+            .add_system_set(SystemSet::on_update(SimState::Menu).with_system(start_menu))
+            .add_system(pause_hotkey)
+            .add_system(stop_the_clock);
+    }
+}
+
+fn start_menu(mut egui_context: ResMut<EguiContext>,
+              mut menu: ResMut<StartMenu>,
+              mut state: ResMut<State<SimState>>,
+              mut config: ResMut<Config>,
+              mut restarts: EventWriter<RestartRun>) {
+    let mut start = false;
+    egui::Window::new("Rustism").collapsible(false).show(egui_context.ctx_mut(), |ui| {
+        let selected = menu.preset.map_or_else(|| "as configured".to_string(), |preset| preset.to_string());
+        egui::ComboBox::from_label("preset").selected_text(selected).show_ui(ui, |ui| {
+            ui.selectable_value(&mut menu.preset, None, "as configured");
+            for preset in Preset::ALL {
+                ui.selectable_value(&mut menu.preset, Some(preset), preset.to_string());
+            }
+        });
+        if let Some(preset) = menu.preset {
+            ui.label(preset.description());
+        }
+        ui.horizontal(|ui| {
+            ui.label("seed");
+            ui.add(egui::DragValue::new(&mut menu.seed));
+        });
+        ui.horizontal(|ui| {
+            ui.label("individuals");
+            ui.add(egui::DragValue::new(&mut menu.individuals).clamp_range(1..=10_000));
+        });
+        start = ui.button("Start").clicked();
+    });
+
+    if start {
+        let base = menu.preset.map_or_else(|| config.clone(), Preset::config);
+        *config = Config { individuals: menu.individuals, gpu: config.gpu, ..base };
+        info!("starting with {} individuals from seed {}", menu.individuals, menu.seed);
+        restarts.send(RestartRun { seed: Some(menu.seed) });
+        state.set(SimState::Running).expect("the start menu is left once");
+    }
+}
+
+fn pause_hotkey(keys: Res<Input<KeyCode>>, mut state: ResMut<State<SimState>>) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+    let result = match state.current() {
+        SimState::Paused => state.pop(),
+        current if current.simulates() => state.push(SimState::Paused),
+        _ => Ok(()),
+    };
+    if let Err(err) = result {
+        warn!("could not pause: {err}");
+    }
+}
+
+//timers and movement outside of the simulation systems see no time pass either
+fn stop_the_clock(state: Res<State<SimState>>, mut time: ResMut<Time>) {
+    if state.current().simulates() == time.is_paused() {
+        if state.current().simulates() {
+            time.unpause();
+        } else {
+            time.pause();
+        }
+    }
+}
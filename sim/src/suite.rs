@@ -0,0 +1,136 @@
+use std::fmt;
+
+use bevy::math::Vec2;
+use lib_natural_selection::Chromosome;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::curriculum::Difficulty;
+use crate::environment::SpawnDistribution;
+use crate::rules::{SimCore, FRAME};
+use crate::{Config, KillZone};
+
+//the space between the blocks of a maze wall, a little less than a block so nobody slips through
+const WALL_SPACING: f32 = 0.025;
+
+//a scripted world of the benchmark suite, the same in every run so scores can be compared
+//across code changes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scenario {
+    ZoneLeft,
+    ZoneRight,
+    //a zone on each side, only the middle is safe
+    DoubleZone,
+    //a zone that starts on the left and drifts over the world
+    MovingZone,
+    //a zone on the left with two walls in it, the way out leads through their gaps
+    ObstacleMaze,
+}
+
+impl Scenario {
+    pub const ALL: [Scenario; 5] = [Self::ZoneLeft, Self::ZoneRight, Self::DoubleZone, Self::MovingZone, Self::ObstacleMaze];
+
+    fn killzones(self) -> Vec<KillZone> {
+        let zone = |min, max, velocity| KillZone { min, max, velocity };
+        match self {
+            Self::ZoneLeft | Self::ObstacleMaze => vec![zone(-1.0, 0.0, 0.0)],
+            Self::ZoneRight => vec![zone(0.0, 1.0, 0.0)],
+            Self::DoubleZone => vec![zone(-1.0, -0.4, 0.0), zone(0.4, 1.0, 0.0)],
+            Self::MovingZone => vec![zone(-1.0, -0.4, 0.2)],
+        }
+    }
+
+    fn obstacles(self) -> Vec<Vec2> {
+        if self != Self::ObstacleMaze {
+            return Vec::new();
+        }
+        //a wall with a gap in the middle and one with gaps at the ends
+        let wall = |x: f32, gap: fn(f32) -> bool| {
+            (0..=(2.0 / WALL_SPACING) as usize)
+                .map(move |i| Vec2::new(x, -1.0 + i as f32 * WALL_SPACING))
+                .filter(move |block| !gap(block.y))
+        };
+        wall(-0.6, |y| y.abs() < 0.15).chain(wall(-0.2, |y| y.abs() > 0.75)).collect()
+    }
+
+    //a world of the scenario with `genome` alone in it, everything random about it comes from
+    //`seed`
+    pub fn world(self, config: &Config, seed: u64, genome: &Chromosome) -> (SimCore, ChaCha8Rng) {
+        let config = Config {
+            killzone_width_jitter: 0.0,
+            speed_jitter: 0.0,
+            spawn: SpawnDistribution::Uniform,
+            obstacles: 0,
+            spawn_regions: Vec::new(),
+            protect_spawn: false,
+            ..config.clone()
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut core = SimCore::new(config, &mut rng, &Difficulty::default(), std::slice::from_ref(genome));
+        core.killzones = self.killzones();
+        core.environment.obstacles = self.obstacles();
+        (core, rng)
+    }
+
+    //the fitness of `genome` at the end of a generation in the scenario
+    pub fn evaluate(self, config: &Config, seed: u64, genome: &Chromosome) -> f32 {
+        let (mut core, mut rng) = self.world(config, seed, genome);
+        while !core.finished() {
+            core.step(FRAME, &mut rng);
+        }
+        core.fitness()[0]
+    }
+}
+
+impl fmt::Display for Scenario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::ZoneLeft => "zone_left",
+            Self::ZoneRight => "zone_right",
+            Self::DoubleZone => "double_zone",
+            Self::MovingZone => "moving_zone",
+            Self::ObstacleMaze => "obstacle_maze",
+        })
+    }
+}
+
+//how the genomes did in a scenario over every run
+#[derive(Clone, Debug, PartialEq)]
+pub struct Score {
+    pub scenario: Scenario,
+    pub mean_fitness: f32,
+    //the mean fitness of the best genome
+    pub best_fitness: f32,
+    //the share of runs that ended outside of the kill zones
+    pub survival: f32,
+}
+
+//scores every genome alone in every scenario, `runs` times each with the seeds 0 to runs - 1
+pub fn run_suite(config: &Config, genomes: &[Chromosome], runs: u64) -> Vec<Score> {
+    Scenario::ALL
+        .into_iter()
+        .map(|scenario| {
+            let fitness: Vec<Vec<f32>> = genomes
+                .iter()
+                .map(|genome| (0..runs).map(|seed| scenario.evaluate(config, seed, genome)).collect())
+                .collect();
+            let all = fitness.iter().flatten();
+            let count = (genomes.len() as u64 * runs).max(1) as f32;
+            Score {
+                scenario,
+                mean_fitness: all.clone().sum::<f32>() / count,
+                best_fitness: fitness.iter().map(|runs| runs.iter().sum::<f32>() / runs.len().max(1) as f32).fold(0.0, f32::max),
+                survival: all.filter(|&&fitness| fitness > 0.0).count() as f32 / count,
+            }
+        })
+        .collect()
+}
+
+//the scores as csv, a line per scenario
+pub fn scorecard(scores: &[Score]) -> String {
+    let mut csv = String::from("scenario,mean_fitness,best_fitness,survival\n");
+    for score in scores {
+        csv.push_str(&format!("{},{},{},{}\n", score.scenario, score.mean_fitness, score.best_fitness, score.survival));
+    }
+    csv
+}
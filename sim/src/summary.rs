@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+
+use crate::hud::UiFont;
+use crate::persistence::{IoHandle, IoWorker};
+use crate::population;
+use crate::states::SimState;
+use crate::Statistics;
+
+//how long the summary stays up after a generation
+const SUMMARY_SECONDS: f32 = 4.0;
+const DNA_PREVIEW: usize = 32;
+const BUTTON: Color = Color::rgb(0.25, 0.25, 0.25);
+const BUTTON_HOVERED: Color = Color::rgb(0.35, 0.35, 0.35);
+
+#[derive(Component)]
+struct Summary;
+
+#[derive(Component)]
+struct SummaryText;
+
+#[derive(Component, Clone, Copy)]
+enum SummaryButton {
+    ExportChampion,
+    Pause,
+}
+
+#[derive(Resource)]
+struct SummaryTimer(Timer);
+
+//shows the results of every generation for a few seconds, with buttons to save the champion
+//for `replay` and to pause the simulation to look at them, in SimState::GenerationSummary
+pub struct SummaryPlugin;
+
+impl Plugin for SummaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SummaryTimer(Timer::from_seconds(SUMMARY_SECONDS, TimerMode::Once)))
+            .add_startup_system(add_summary)
+            .add_system(show_summary)
+            .add_system(hide_summary.after(show_summary))
+            .add_system(summary_buttons);
+    }
+}
+
+fn add_summary(mut commands: Commands, font: Res<UiFont>) {
+    let text_style = |font_size| TextStyle { font: font.0.clone(), font_size, color: Color::WHITE };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect { top: Val::Px(15.0), right: Val::Px(15.0), ..default() },
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+                visibility: Visibility { is_visible: false },
+                ..default()
+            },
+            Summary,
+        ))
+        .with_children(|summary| {
+            summary.spawn((TextBundle::from_section("", text_style(18.0)), SummaryText));
+            summary
+                .spawn(NodeBundle { style: Style { margin: UiRect::top(Val::Px(8.0)), ..default() }, ..default() })
+                .with_children(|buttons| {
+                    for (button, label) in [(SummaryButton::ExportChampion, "Export champion"), (SummaryButton::Pause, "Pause")] {
+                        buttons
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style { margin: UiRect::right(Val::Px(8.0)), padding: UiRect::all(Val::Px(4.0)), ..default() },
+                                    background_color: BUTTON.into(),
+                                    ..default()
+                                },
+                                button,
+                            ))
+                            .with_children(|button| {
+                                button.spawn(TextBundle::from_section(label, text_style(16.0)));
+                            });
+                    }
+                });
+        });
+}
+
+fn show_summary(statistics: Query<&Statistics, Changed<Statistics>>,
+                mut timer: ResMut<SummaryTimer>,
+                mut summary: Query<&mut Visibility, With<Summary>>,
+                mut text: Query<&mut Text, With<SummaryText>>) {
+    let Ok(statistics) = statistics.get_single() else {
+        return;
+    };
+    if statistics.generation == 0 {
+        return;
+    }
+
+    //the start of the dna is enough to tell champions apart, the export has all of it
+    let champion = match (&statistics.champion, statistics.new_champion) {
+        (Some(champion), true) => {
+            let dna = champion.to_string();
+            format!("\nnew champion: {:.3}\n{}...", statistics.champion_fitness, &dna[..dna.len().min(DNA_PREVIEW)])
+        }
+        _ => String::new(),
+    };
+    for mut text in text.iter_mut() {
+        text.sections[0].value = format!(
+            "Generation {}\nbest: {:.3}\nsurvivors: {:.1}%\ndiversity: {:.4}{champion}",
+            statistics.generation,
+            statistics.best_fitness,
+            statistics.survivors_percentage * 100.0,
+            statistics.genetic_variance,
+        );
+    }
+
+    for mut visibility in summary.iter_mut() {
+        visibility.is_visible = true;
+    }
+    timer.0.reset();
+}
+
+//stays up while the simulation is paused
+fn hide_summary(time: Res<Time>,
+                mut timer: ResMut<SummaryTimer>,
+                mut summary: Query<&mut Visibility, With<Summary>>) {
+    if time.is_paused() || !timer.0.tick(time.raw_delta()).just_finished() {
+        return;
+    }
+
+    for mut visibility in summary.iter_mut() {
+        visibility.is_visible = false;
+    }
+}
+
+fn summary_buttons(mut state: Option<ResMut<State<SimState>>>,
+                   io: Option<Res<IoWorker>>,
+                   statistics: Query<&Statistics>,
+                   mut buttons: Query<(&Interaction, &SummaryButton, &mut BackgroundColor, &Children), Changed<Interaction>>,
+                   mut labels: Query<&mut Text>) {
+    for (interaction, button, mut color, children) in buttons.iter_mut() {
+        *color = match interaction {
+            Interaction::Hovered => BUTTON_HOVERED.into(),
+            _ => BUTTON.into(),
+        };
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        match button {
+            SummaryButton::ExportChampion => {
+                let Some(statistics) = statistics.iter().next() else { continue };
+                let Some(champion) = &statistics.champion else { continue };
+
+                let (path, champion) = (format!("champion_{}.txt", statistics.generation), champion.clone());
+                let io = io.as_ref().map_or_else(IoHandle::default, |worker| worker.handle());
+                io.submit(format!("the champion to {path}"), move || {
+                    population::save(&path, &[champion])?;
+                    info!("saved the champion to {path}");
+                    Ok(())
+                });
+            }
+            SummaryButton::Pause => {
+                let Some(state) = state.as_mut() else { continue };
+                let (result, label) = match state.current() {
+                    SimState::GenerationSummary => (state.pop(), "Pause"),
+                    current if current.simulates() => (state.push(SimState::GenerationSummary), "Resume"),
+                    _ => continue,
+                };
+                if let Err(err) = result {
+                    warn!("could not pause on the summary: {err}");
+                    continue;
+                }
+                for &child in children.iter() {
+                    if let Ok(mut text) = labels.get_mut(child) {
+                        text.sections[0].value = label.to_string();
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::fmt;
+use std::str::FromStr;
+
+use lib_natural_selection::{KnnSurrogate, RbfSurrogate, Surrogate};
+
+use crate::Config;
+
+//neighbors the k-nearest-neighbor surrogate averages
+const NEIGHBORS: usize = 5;
+//the width of the gaussian of the rbf surrogate, in mean absolute gene differences
+const RBF_WIDTH: f32 = 0.1;
+//generations of samples the surrogate remembers
+const REMEMBERED_GENERATIONS: usize = 4;
+
+//the model that screens the offspring before they enter the world, see Surrogate
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SurrogateKind {
+    #[default]
+    None,
+    //k nearest neighbors
+    Knn,
+    //radial basis function regression
+    Rbf,
+}
+
+impl FromStr for SurrogateKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "knn" => Ok(Self::Knn),
+            "rbf" => Ok(Self::Rbf),
+            _ => Err(format!("unknown surrogate '{s}', expected none, knn or rbf")),
+        }
+    }
+}
+
+impl fmt::Display for SurrogateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Knn => "knn",
+            Self::Rbf => "rbf",
+        })
+    }
+}
+
+//the surrogate of a run with `config`, if it has one
+pub fn surrogate(config: &Config) -> Option<Surrogate> {
+    let capacity = (config.individuals * REMEMBERED_GENERATIONS).max(1);
+    let candidates = config.surrogate_candidates.max(1);
+    match config.surrogate {
+        SurrogateKind::None => None,
+        SurrogateKind::Knn => Some(Surrogate::new(KnnSurrogate::new(NEIGHBORS), capacity, candidates, config.surrogate_exploration)),
+        SurrogateKind::Rbf => Some(Surrogate::new(RbfSurrogate::new(RBF_WIDTH), capacity, candidates, config.surrogate_exploration)),
+    }
+}
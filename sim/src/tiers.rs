@@ -0,0 +1,72 @@
+//how many generations a value of every resolution covers, long runs are charted and written
+//at the coarser ones
+pub const TIERS: [usize; 3] = [1, 10, 100];
+
+//the min, mean and max of a value over consecutive generations
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aggregate {
+    pub first: usize,
+    pub generations: usize,
+    pub min: f32,
+    pub mean: f32,
+    pub max: f32,
+}
+
+impl Aggregate {
+    pub fn new(first: usize, value: f32) -> Self {
+        Self { first, generations: 1, min: value, mean: value, max: value }
+    }
+
+    pub fn add(&mut self, value: f32) {
+        self.generations += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.mean += (value - self.mean) / self.generations as f32;
+    }
+
+    pub fn last(&self) -> usize {
+        self.first + self.generations - 1
+    }
+
+    //where to draw it on a chart of generations, in the middle of the ones it covers
+    pub fn center(&self) -> f64 {
+        self.first as f64 + (self.generations - 1) as f64 / 2.0
+    }
+}
+
+//a value of every generation at every resolution of TIERS, the last aggregate of the coarser
+//ones covers the generations so far
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TieredSeries {
+    tiers: [Vec<Aggregate>; TIERS.len()],
+}
+
+impl TieredSeries {
+    pub fn push(&mut self, generation: usize, value: f32) {
+        for (tier, &every) in self.tiers.iter_mut().zip(&TIERS) {
+            match tier.last_mut() {
+                Some(last) if last.generations < every => last.add(value),
+                _ => tier.push(Aggregate::new(generation, value)),
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiers[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiers[0].is_empty()
+    }
+
+    //the aggregates of every `every` generations, one of TIERS
+    pub fn tier(&self, every: usize) -> &[Aggregate] {
+        let tier = TIERS.iter().position(|&tier| tier == every).unwrap_or_else(|| panic!("no tier of {every} generations"));
+        &self.tiers[tier]
+    }
+
+    //the finest resolution with no more than `points` aggregates, or the coarsest
+    pub fn at_most(&self, points: usize) -> &[Aggregate] {
+        self.tiers.iter().find(|tier| tier.len() <= points).unwrap_or(&self.tiers[TIERS.len() - 1])
+    }
+}
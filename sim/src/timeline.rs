@@ -0,0 +1,225 @@
+use std::fmt;
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+use bevy_inspector_egui::egui::plot::{Line, Plot, PlotPoints, VLine};
+
+use crate::dashboard::{self, Dashboard};
+use crate::manifest::{ConfigChange, ConfigChanged};
+use crate::restart::RunRestarted;
+use crate::tiers::{Aggregate, TieredSeries};
+use crate::watchdog::{Collapse, PopulationCollapsed};
+use crate::Statistics;
+
+//the most points a line of the charts has, longer runs are charted at a coarser tier
+const MAX_POINTS: usize = 500;
+
+//what happened at a point of the run
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimelineEventKind {
+    //with its fitness
+    Champion(f32),
+    //nobody survived the generation
+    Extinction,
+    CurriculumStage(usize),
+    //with the values that changed
+    ConfigChanged(Vec<ConfigChange>),
+    Collapse(Collapse),
+    //the run started over, the first event of the new one
+    Restarted { run: usize, seed: u64 },
+}
+
+impl fmt::Display for TimelineEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Champion(fitness) => write!(f, "new champion {fitness:.3}"),
+            Self::Extinction => f.write_str("extinction"),
+            Self::CurriculumStage(stage) => write!(f, "curriculum stage {}", stage + 1),
+            Self::ConfigChanged(changes) => {
+                write!(f, "config changed: {}", changes.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))
+            }
+            Self::Collapse(collapse) => write!(f, "collapse: {collapse}"),
+            Self::Restarted { run, seed } => write!(f, "run {run} started from seed {seed}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimelineEvent {
+    pub generation: i32,
+    //seconds since the app started
+    pub at: f32,
+    pub kind: TimelineEventKind,
+}
+
+//the key events of the run and the best fitness and kill zone occupancy of every generation they
+//are shown against
+#[derive(Resource, Default)]
+pub struct Timeline {
+    pub events: Vec<TimelineEvent>,
+    pub best_fitness: TieredSeries,
+    //see KillzoneOccupancy
+    pub occupancy_mean: TieredSeries,
+    pub occupancy_median: TieredSeries,
+    pub occupancy_max: TieredSeries,
+    pub never_in_killzone: TieredSeries,
+    //the generation the chart cursor is on
+    pub cursor: Option<i32>,
+    generation: i32,
+    curriculum_stage: usize,
+}
+
+impl Timeline {
+    //records the events of a generation that just ended
+    pub fn observe(&mut self, statistics: &Statistics, at: f32) {
+        if statistics.generation == self.generation {
+            return;
+        }
+        self.generation = statistics.generation;
+        let (generation, occupancy) = (statistics.generation as usize, statistics.killzone_occupancy);
+        self.best_fitness.push(generation, statistics.best_fitness);
+        self.occupancy_mean.push(generation, occupancy.mean);
+        self.occupancy_median.push(generation, occupancy.median);
+        self.occupancy_max.push(generation, occupancy.max);
+        self.never_in_killzone.push(generation, occupancy.never);
+
+        let mut record = |kind| self.events.push(TimelineEvent { generation: statistics.generation, at, kind });
+        if statistics.new_champion {
+            record(TimelineEventKind::Champion(statistics.champion_fitness));
+        }
+        if statistics.survivors_percentage == 0.0 {
+            record(TimelineEventKind::Extinction);
+        }
+        if statistics.curriculum_stage != self.curriculum_stage {
+            record(TimelineEventKind::CurriculumStage(statistics.curriculum_stage));
+        }
+        self.curriculum_stage = statistics.curriculum_stage;
+    }
+
+    pub fn config_changed(&mut self, changed: &ConfigChanged, at: f32) {
+        let kind = TimelineEventKind::ConfigChanged(changed.changes.clone());
+        self.events.push(TimelineEvent { generation: changed.generation, at, kind });
+    }
+
+    //the generations the config changed in, marked on the charts
+    pub fn config_changes(&self) -> impl Iterator<Item = i32> + '_ {
+        self.events
+            .iter()
+            .filter(|event| matches!(event.kind, TimelineEventKind::ConfigChanged(_)))
+            .map(|event| event.generation)
+    }
+
+    pub fn collapsed(&mut self, collapsed: &PopulationCollapsed, at: f32) {
+        self.events.push(TimelineEvent { generation: collapsed.generation, at, kind: TimelineEventKind::Collapse(collapsed.collapse) });
+    }
+
+    //forgets the run that ended, the charts start over with the new one
+    pub fn restarted(&mut self, restarted: &RunRestarted, at: f32) {
+        *self = Self::default();
+        let kind = TimelineEventKind::Restarted { run: restarted.run, seed: restarted.seed };
+        self.events.push(TimelineEvent { generation: 0, at, kind });
+    }
+}
+
+//records the key events of the run and lists them under charts of the best fitness and of the
+//shares of the generations spent inside kill zones, the config changes are marked on the fitness
+//chart, clicking an event moves the chart cursors to its generation
+pub struct TimelinePlugin;
+
+impl Plugin for TimelinePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+
+        app.init_resource::<Timeline>()
+            .add_system(record_events)
+            .add_system(timeline_panel.after(record_events));
+    }
+}
+
+fn record_events(time: Res<Time>,
+                 statistics: Query<&Statistics, Changed<Statistics>>,
+                 mut collapses: EventReader<PopulationCollapsed>,
+                 mut config_changes: EventReader<ConfigChanged>,
+                 mut restarts: EventReader<RunRestarted>,
+                 mut timeline: ResMut<Timeline>) {
+    let at = time.elapsed_seconds();
+    for restarted in restarts.iter() {
+        timeline.restarted(restarted, at);
+    }
+    if let Ok(statistics) = statistics.get_single() {
+        timeline.observe(statistics, at);
+    }
+    for collapsed in collapses.iter() {
+        timeline.collapsed(collapsed, at);
+    }
+    for changed in config_changes.iter() {
+        timeline.config_changed(changed, at);
+    }
+}
+
+fn timeline_panel(mut egui_context: ResMut<EguiContext>, dashboard: Option<Res<Dashboard>>, mut timeline: ResMut<Timeline>) {
+    let Some(ctx) = dashboard::panels(&mut egui_context, dashboard.as_deref()) else {
+        return;
+    };
+    egui::Window::new("Timeline").show(ctx, |ui| {
+        let best_fitness = timeline.best_fitness.at_most(MAX_POINTS);
+        let every = best_fitness.first().map_or(1, |aggregate| aggregate.generations);
+        let (fitness, lowest, highest) = (points(best_fitness, |a| a.mean), points(best_fitness, |a| a.min), points(best_fitness, |a| a.max));
+        let occupancy = |series: &TieredSeries| points(series.at_most(MAX_POINTS), |a| a.mean);
+        let (mean, median, max, never) = (
+            occupancy(&timeline.occupancy_mean),
+            occupancy(&timeline.occupancy_median),
+            occupancy(&timeline.occupancy_max),
+            occupancy(&timeline.never_in_killzone),
+        );
+        let config_changes: Vec<i32> = timeline.config_changes().collect();
+        let cursor = timeline.cursor;
+        if every > 1 {
+            ui.label(format!("means of every {every} generations"));
+        }
+        Plot::new("timeline_fitness").height(120.0).show(ui, |plot| {
+            plot.line(Line::new(fitness).name("best fitness"));
+            if every > 1 {
+                let faint = egui::Color32::from_gray(120);
+                plot.line(Line::new(lowest).color(faint).name("lowest best fitness"));
+                plot.line(Line::new(highest).color(faint).name("highest best fitness"));
+            }
+            for &generation in &config_changes {
+                plot.vline(VLine::new(generation as f64).color(egui::Color32::from_rgb(230, 150, 30)).name("config change"));
+            }
+            if let Some(generation) = cursor {
+                plot.vline(VLine::new(generation as f64));
+            }
+        });
+        ui.label("share of the generation in kill zones");
+        Plot::new("timeline_occupancy").height(100.0).include_y(0.0).include_y(1.0).show(ui, |plot| {
+            plot.line(Line::new(mean).name("mean"));
+            plot.line(Line::new(median).name("median"));
+            plot.line(Line::new(max).name("most"));
+            plot.line(Line::new(never).name("share never inside"));
+            if let Some(generation) = cursor {
+                plot.vline(VLine::new(generation as f64));
+            }
+        });
+
+        egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+            let mut clicked = None;
+            for event in timeline.events.iter().rev() {
+                let label = format!("gen {} ({:.0}s): {}", event.generation, event.at, event.kind);
+                if ui.selectable_label(cursor == Some(event.generation), label).clicked() {
+                    clicked = Some(event.generation);
+                }
+            }
+            if clicked.is_some() {
+                timeline.cursor = if clicked == cursor { None } else { clicked };
+            }
+        });
+    });
+}
+
+//a point per aggregate at the middle of the generations it covers
+fn points(aggregates: &[Aggregate], value: fn(&Aggregate) -> f32) -> PlotPoints {
+    aggregates.iter().map(|aggregate| [aggregate.center(), value(aggregate) as f64]).collect()
+}
@@ -0,0 +1,155 @@
+use std::io;
+use std::time::{Duration, Instant};
+use bevy::prelude::*;
+use ratatui::crossterm::event::{self, Event, KeyCode};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Sparkline};
+use ratatui::{DefaultTerminal, Frame};
+use sim::headless::{generation, killzones, positions, statistics};
+use sim::Statistics;
+
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+const MAP_WIDTH: usize = 60;
+const MAP_HEIGHT: usize = 20;
+//characters for increasing agent density
+const DENSITY: &[char] = &[' ', '.', ':', '+', '*', '#', '@'];
+
+struct Dashboard {
+    target: i32,
+    started: Instant,
+    best_fitness: Vec<u64>,
+    statistics: Statistics,
+    map: Vec<String>,
+}
+
+//runs the given generations while showing a live dashboard, q or esc stop early
+pub fn run(app: &mut App, generations: i32) -> io::Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let result = run_dashboard(&mut terminal, app, generations);
+    ratatui::try_restore()?;
+    result
+}
+
+fn run_dashboard(terminal: &mut DefaultTerminal, app: &mut App, generations: i32) -> io::Result<()> {
+    let mut dashboard = Dashboard {
+        target: generation(app) + generations,
+        started: Instant::now(),
+        best_fitness: vec![],
+        statistics: Statistics::default(),
+        map: vec![],
+    };
+    let mut last_generation = generation(app);
+
+    while generation(app) < dashboard.target {
+        let redraw_at = Instant::now() + REDRAW_INTERVAL;
+        while Instant::now() < redraw_at && generation(app) < dashboard.target {
+            app.update();
+        }
+
+        if generation(app) != last_generation {
+            last_generation = generation(app);
+            dashboard.statistics = statistics(app);
+            dashboard.best_fitness.push((dashboard.statistics.best_fitness * 100.0) as u64);
+        }
+        dashboard.map = density_map(&positions(app), &killzones(app));
+
+        terminal.draw(|frame| draw(frame, &dashboard, last_generation))?;
+
+        if event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()? {
+                if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn draw(frame: &mut Frame, dashboard: &Dashboard, generation: i32) {
+    let [header, chart, map] = Layout::vertical([
+        Constraint::Length(5),
+        Constraint::Length(8),
+        Constraint::Min(MAP_HEIGHT as u16 + 2),
+    ]).areas(frame.area());
+
+    let statistics = &dashboard.statistics;
+    let eta = if generation > 0 {
+        let per_generation = dashboard.started.elapsed() / generation as u32;
+        format!("{:.0}s", (per_generation * (dashboard.target - generation) as u32).as_secs_f32())
+    } else {
+        "-".to_string()
+    };
+
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(format!("generation {generation}/{}    eta {eta}", dashboard.target)),
+            Line::from(format!(
+                "best {:.3}    average {:.3}    stderr {:.3}    survivors {:.1}%    in kill zones {:.2}s ({:.0}%)    diversity {:.4}",
+                statistics.best_fitness,
+                statistics.average_fitness,
+                statistics.fitness_stderr,
+                statistics.survivors_percentage * 100.0,
+                statistics.time_in_killzone,
+                statistics.killzone_occupancy.mean * 100.0,
+                statistics.genetic_variance,
+            )),
+            Line::from(format!(
+                "takeover {:.2}    genotypes {:.1}    selection intensity {:.2}",
+                statistics.takeover,
+                statistics.distinct_genotypes,
+                statistics.selection_intensity,
+            )),
+        ]).block(Block::bordered().title(" rustism (q to quit) ")),
+        header,
+    );
+
+    frame.render_widget(
+        Sparkline::default()
+            .block(Block::bordered().title(" best fitness "))
+            .style(Style::default().fg(Color::Green))
+            .data(last(&dashboard.best_fitness, chart.width.saturating_sub(2) as usize)),
+        chart,
+    );
+
+    let lines: Vec<Line> = dashboard.map.iter().map(|row| Line::from(row.as_str())).collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" agents (| marks the kill zone) ")),
+        map,
+    );
+}
+
+fn last(values: &[u64], count: usize) -> Vec<u64> {
+    values[values.len().saturating_sub(count)..].to_vec()
+}
+
+//world coordinates are -1..1 on both axes
+fn density_map(positions: &[Vec2], killzones: &[(f32, f32)]) -> Vec<String> {
+    let to_cell = |v: f32, cells: usize| (((v + 1.0) / 2.0 * cells as f32) as usize).min(cells - 1);
+
+    let mut counts = vec![[0usize; MAP_WIDTH]; MAP_HEIGHT];
+    for position in positions {
+        // y points up in the world but down on the terminal
+        counts[MAP_HEIGHT - 1 - to_cell(position.y, MAP_HEIGHT)][to_cell(position.x, MAP_WIDTH)] += 1;
+    }
+
+    let killzone_columns: Vec<_> = killzones
+        .iter()
+        .map(|&(min, max)| to_cell(min, MAP_WIDTH)..to_cell(max, MAP_WIDTH))
+        .collect();
+    counts
+        .iter()
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .map(|(column, &count)| match count {
+                    0 if killzone_columns.iter().any(|columns| columns.contains(&column)) => '|',
+                    count => DENSITY[count.min(DENSITY.len() - 1)],
+                })
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,217 @@
+use std::fmt;
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPlugin};
+use bevy_inspector_egui::egui;
+use rand::Rng;
+
+use crate::{Config, Frozen, Health, Nizm, SimRng, Statistics};
+
+//below this the genes or actions of two individuals count as the same
+const EPSILON: f32 = 1e-6;
+//the most the hypermutation response moves a gene either way
+const HYPERMUTATION: f32 = 0.5;
+//seconds the banner stays up after a collapse
+const BANNER_SECONDS: f32 = 8.0;
+
+//a way the population can collapse
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Collapse {
+    //every genome is the same
+    NoDiversity,
+    //every individual decided the same action for watchdog_seconds
+    IdenticalActions,
+    //nobody moved for watchdog_seconds
+    Immobile,
+    //nobody scored any fitness in the generation
+    NoFitness,
+}
+
+impl fmt::Display for Collapse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NoDiversity => "no genetic diversity left",
+            Self::IdenticalActions => "every individual acts the same",
+            Self::Immobile => "nobody moves",
+            Self::NoFitness => "nobody scored any fitness",
+        })
+    }
+}
+
+//sent once per generation and kind of collapse
+#[derive(Clone, Debug, PartialEq)]
+pub struct PopulationCollapsed {
+    pub collapse: Collapse,
+    pub generation: i32,
+}
+
+//what the watchdog does to the offspring of a generation the population collapsed in, besides
+//warning about it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WatchdogResponse {
+    #[default]
+    None,
+    //an extinction: everybody but a copy of the champion of the run is replaced by a random brain
+    Reseed,
+    //noise on every gene of every individual, to shake the population out of it
+    Hypermutate,
+}
+
+impl FromStr for WatchdogResponse {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "reseed" => Ok(Self::Reseed),
+            "hypermutate" => Ok(Self::Hypermutate),
+            _ => Err(format!("unknown watchdog response '{s}', expected none, reseed or hypermutate")),
+        }
+    }
+}
+
+impl fmt::Display for WatchdogResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Reseed => "reseed",
+            Self::Hypermutate => "hypermutate",
+        })
+    }
+}
+
+//what the watchdog saw of the current generation
+#[derive(Resource, Default)]
+pub struct Watchdog {
+    //seconds every individual has been acting the same and standing still
+    identical_for: f32,
+    immobile_for: f32,
+    //the collapses already reported this generation
+    raised: Vec<Collapse>,
+    generation: i32,
+}
+
+impl Watchdog {
+    fn raise(&mut self, collapse: Collapse, generation: i32, collapsed: &mut EventWriter<PopulationCollapsed>) {
+        if self.raised.contains(&collapse) {
+            return;
+        }
+        warn!("generation {generation}: the population collapsed, {collapse}");
+        self.raised.push(collapse);
+        collapsed.send(PopulationCollapsed { collapse, generation });
+    }
+}
+
+//times how long the living individuals act the same and stand still, runs every frame after
+//they moved
+pub(crate) fn watch_behavior(time: Res<Time>,
+                             config: Res<Config>,
+                             mut watchdog: ResMut<Watchdog>,
+                             statistics: Query<&Statistics>,
+                             nizms: Query<(&Nizm, Option<&Health>), Without<Frozen>>,
+                             mut collapsed: EventWriter<PopulationCollapsed>) {
+    let living: Vec<&Nizm> = nizms
+        .iter()
+        .filter(|(_, health)| health.is_none_or(|health| health.is_alive()))
+        .map(|(nizm, _)| nizm)
+        .collect();
+    let Some(first) = living.first() else {
+        return;
+    };
+
+    let delta = time.delta_seconds();
+    let identical = living.len() > 1 && living.iter().all(|nizm| nizm.action.distance(first.action) < EPSILON);
+    let immobile = living.iter().all(|nizm| nizm.movement.length() < EPSILON);
+    watchdog.identical_for = if identical { watchdog.identical_for + delta } else { 0.0 };
+    watchdog.immobile_for = if immobile { watchdog.immobile_for + delta } else { 0.0 };
+
+    //reported as the generation they happen in, the one after the last that ended
+    let generation = statistics.get_single().map_or(0, |statistics| statistics.generation) + 1;
+    if watchdog.identical_for >= config.watchdog_seconds {
+        watchdog.raise(Collapse::IdenticalActions, generation, &mut collapsed);
+    }
+    if watchdog.immobile_for >= config.watchdog_seconds {
+        watchdog.raise(Collapse::Immobile, generation, &mut collapsed);
+    }
+}
+
+//checks the genomes and fitness of every generation that ended and responds to whatever
+//collapsed during it, runs after the evolution so the response lands on the offspring
+pub(crate) fn watch_generations(config: Res<Config>,
+                                mut watchdog: ResMut<Watchdog>,
+                                mut rng: ResMut<SimRng>,
+                                statistics: Query<&Statistics>,
+                                mut nizms: Query<&mut Nizm, Without<Frozen>>,
+                                mut collapsed: EventWriter<PopulationCollapsed>) {
+    let Ok(statistics) = statistics.get_single() else {
+        return;
+    };
+    if statistics.generation == watchdog.generation {
+        return;
+    }
+
+    let generation = statistics.generation;
+    if statistics.genetic_variance < EPSILON && nizms.iter().len() > 1 {
+        watchdog.raise(Collapse::NoDiversity, generation, &mut collapsed);
+    }
+    if statistics.best_fitness <= 0.0 {
+        watchdog.raise(Collapse::NoFitness, generation, &mut collapsed);
+    }
+
+    if !watchdog.raised.is_empty() {
+        respond(&config, statistics, &mut rng.0, nizms.iter_mut());
+    }
+    *watchdog = Watchdog { generation, ..default() };
+}
+
+fn respond<'a>(config: &Config, statistics: &Statistics, rng: &mut impl Rng, nizms: impl Iterator<Item = Mut<'a, Nizm>>) {
+    match config.watchdog_response {
+        WatchdogResponse::None => {}
+        WatchdogResponse::Reseed => {
+            for (i, mut nizm) in nizms.enumerate() {
+                match (&statistics.champion, i) {
+                    (Some(champion), 0) => nizm.network = Nizm::from_chromosome(champion, config).network,
+                    _ => nizm.network = Nizm::random(rng, config).network,
+                }
+            }
+        }
+        WatchdogResponse::Hypermutate => {
+            for mut nizm in nizms {
+                nizm.network.genes_mut().iter_mut().for_each(|gene| *gene += rng.gen_range(-HYPERMUTATION..=HYPERMUTATION));
+            }
+        }
+    }
+}
+
+//a banner over the world while the population collapsed recently
+pub struct WatchdogPlugin;
+
+impl Plugin for WatchdogPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+        app.add_system(collapse_banner);
+    }
+}
+
+fn collapse_banner(time: Res<Time>,
+                   mut egui_context: ResMut<EguiContext>,
+                   mut collapses: EventReader<PopulationCollapsed>,
+                   mut shown: Local<Option<(PopulationCollapsed, f32)>>) {
+    let now = time.elapsed_seconds();
+    if let Some(collapse) = collapses.iter().last() {
+        *shown = Some((collapse.clone(), now));
+    }
+    let Some((collapse, since)) = shown.as_ref().filter(|(_, since)| now - since < BANNER_SECONDS) else {
+        return;
+    };
+
+    egui::Area::new("collapse_banner").anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 8.0)).show(egui_context.ctx_mut(), |ui| {
+        egui::Frame::none().fill(egui::Color32::from_rgb(150, 20, 20)).inner_margin(6.0).show(ui, |ui| {
+            let ago = now - since;
+            ui.colored_label(egui::Color32::WHITE, format!("generation {}: {} ({ago:.0}s ago)", collapse.generation, collapse.collapse));
+        });
+    });
+}
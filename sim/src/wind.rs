@@ -0,0 +1,136 @@
+use std::f32::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+use bevy::prelude::*;
+
+use crate::{Config, EvolutionTimer};
+
+//arrows per side of the world
+const ARROWS: usize = 12;
+//how long an arrow at the full wind speed is drawn
+const ARROW_LENGTH: f32 = 0.12;
+
+//a force that pushes every individual each frame, the brains have to steer against it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Wind {
+    #[default]
+    None,
+    //blowing to the right everywhere
+    Constant,
+    //circling counterclockwise around the center of the world, still there and strongest at
+    //the edges
+    Rotational,
+    //gusts that turn with place and time, the same in every run
+    Noise,
+}
+
+impl Wind {
+    //the velocity the wind adds at `position`, `time` seconds into a generation, at `strength`
+    //world units per second
+    pub fn at(self, position: Vec2, time: f32, strength: f32) -> Vec2 {
+        match self {
+            Self::None => Vec2::ZERO,
+            Self::Constant => Vec2::X * strength,
+            Self::Rotational => position.perp() * strength,
+            Self::Noise => {
+                //a few incommensurate waves, smooth but without an obvious pattern
+                let turn = (position.x * 2.3 + time * 0.7).sin()
+                    + (position.y * 3.1 - time * 0.5).cos()
+                    + ((position.x - position.y) * 1.7 + time * 0.3).sin();
+                let gust = 0.75 + 0.25 * (position.y * 1.3 + time * 1.1).sin();
+                Vec2::from_angle(turn * PI) * strength * gust
+            }
+        }
+    }
+}
+
+impl FromStr for Wind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "constant" => Ok(Self::Constant),
+            "rotational" => Ok(Self::Rotational),
+            "noise" => Ok(Self::Noise),
+            _ => Err(format!("unknown wind '{s}', expected none, constant, rotational or noise")),
+        }
+    }
+}
+
+impl fmt::Display for Wind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Constant => "constant",
+            Self::Rotational => "rotational",
+            Self::Noise => "noise",
+        })
+    }
+}
+
+//the wind of a run with `config`
+pub fn wind_at(config: &Config, position: Vec2, time: f32) -> Vec2 {
+    config.wind.at(position, time, config.wind_strength)
+}
+
+//the fastest the wind of a run with `config` blows
+pub fn max_wind(config: &Config) -> f32 {
+    match config.wind {
+        Wind::None => 0.0,
+        //the corners of the world are further out than 1.0
+        Wind::Rotational => config.wind_strength * 2f32.sqrt(),
+        Wind::Constant | Wind::Noise => config.wind_strength,
+    }
+}
+
+#[derive(Component)]
+struct WindArrow;
+
+//draws the wind as a grid of arrows that follow it
+pub struct WindPlugin;
+
+impl Plugin for WindPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(add_arrows)
+            .add_system(point_arrows);
+    }
+}
+
+fn add_arrows(mut commands: Commands) {
+    let spacing = 2.0 / ARROWS as f32;
+    for y in 0..ARROWS {
+        for x in 0..ARROWS {
+            let position = (Vec2::new(x as f32, y as f32) + 0.5) * spacing - 1.0;
+            commands
+                .spawn((SpatialBundle::from_transform(Transform::from_translation(position.extend(-0.4))), WindArrow))
+                .with_children(|arrow| {
+                    //the shaft lies along x, scaling the parent stretches it and moves the head
+                    arrow.spawn(SpriteBundle {
+                        sprite: Sprite { color: Color::rgba(1.0, 1.0, 1.0, 0.25), custom_size: Some(Vec2::new(1.0, 0.006)), ..default() },
+                        ..default()
+                    });
+                    arrow.spawn(SpriteBundle {
+                        sprite: Sprite { color: Color::rgba(1.0, 1.0, 1.0, 0.4), custom_size: Some(Vec2::new(0.2, 0.015)), ..default() },
+                        transform: Transform::from_xyz(0.4, 0.0, 0.0),
+                        ..default()
+                    });
+                });
+        }
+    }
+}
+
+fn point_arrows(config: Res<Config>,
+                timer: Res<EvolutionTimer>,
+                mut arrows: Query<(&mut Transform, &mut Visibility), With<WindArrow>>) {
+    let max = max_wind(&config);
+    for (mut transform, mut visibility) in arrows.iter_mut() {
+        let wind = wind_at(&config, transform.translation.truncate(), timer.0.elapsed_secs());
+        visibility.is_visible = max > 0.0;
+        if max > 0.0 {
+            transform.rotation = Quat::from_rotation_z(wind.y.atan2(wind.x));
+            transform.scale = Vec3::new(wind.length() / max * ARROW_LENGTH, 1.0, 1.0);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::hash::Hasher;
+use fnv::FnvHasher;
+use sim::headless::{checkpoint, headless_app, resumed_app, run_generations, statistics};
+use sim::population;
+use sim::{Config, Nizm, Statistics};
+
+const SEED: u64 = 42;
+const GENERATIONS: i32 = 20;
+
+//golden values of the run above, only update them when a behavior change is intended
+const EXPECTED_BEST_FITNESS: f32 = 5.546596;
+const EXPECTED_POPULATION_HASH: u64 = 528399816772859975;
+
+fn population_hash(app: &mut bevy::app::App) -> u64 {
+    let mut hasher = FnvHasher::default();
+
+    for nizm in app.world.query::<&Nizm>().iter(&app.world) {
+        for weight in nizm.network.data() {
+            hasher.write_u32(weight.to_bits());
+        }
+    }
+
+    hasher.finish()
+}
+
+#[test]
+fn full_run_is_reproducible() {
+    let config = Config {
+        individuals: 32,
+        ..Config::default()
+    };
+
+    let mut app = headless_app(config, SEED);
+    run_generations(&mut app, GENERATIONS);
+
+    let statistics = app.world.query::<&Statistics>().single(&app.world);
+    assert_eq!(statistics.generation, GENERATIONS);
+    assert_eq!(statistics.best_fitness, EXPECTED_BEST_FITNESS);
+
+    assert_eq!(population_hash(&mut app), EXPECTED_POPULATION_HASH);
+}
+
+#[test]
+fn resumed_run_matches_uninterrupted_run() {
+    const BEFORE: i32 = 3;
+    const AFTER: i32 = 3;
+    let config = Config {
+        individuals: 16,
+        ..Config::default()
+    };
+
+    let mut uninterrupted = headless_app(config.clone(), SEED);
+    run_generations(&mut uninterrupted, BEFORE + AFTER);
+
+    let mut interrupted = headless_app(config.clone(), SEED);
+    run_generations(&mut interrupted, BEFORE);
+    let path = std::env::temp_dir().join(format!("resume_checkpoint_{}.txt", std::process::id()));
+    population::save_checkpoint(&path, &checkpoint(&mut interrupted)).expect("could not save the checkpoint");
+    let loaded = population::load_checkpoint(&path).expect("could not load the checkpoint");
+    let _ = std::fs::remove_file(&path);
+    assert!(loaded.resume.is_some());
+
+    let mut resumed = resumed_app(config, SEED, &loaded);
+    run_generations(&mut resumed, AFTER);
+
+    assert_eq!(statistics(&mut resumed).generation, BEFORE + AFTER);
+    assert_eq!(statistics(&mut resumed).best_fitness, statistics(&mut uninterrupted).best_fitness);
+    assert_eq!(statistics(&mut resumed).average_fitness, statistics(&mut uninterrupted).average_fitness);
+    assert_eq!(population_hash(&mut resumed), population_hash(&mut uninterrupted));
+}
+
+#[test]
+fn old_checkpoints_are_migrated() {
+    let path = std::env::temp_dir().join(format!("versioned_checkpoint_{}.txt", std::process::id()));
+    let dna = "2:cONzUK-cPBvhK";
+
+    //written before checkpoints had a version
+    std::fs::write(&path, format!("# curriculum_stage: 2\n{dna}\n")).unwrap();
+    let loaded = population::load_checkpoint(&path).expect("version 1 loads");
+    assert_eq!(loaded.curriculum_stage, Some(2));
+    assert_eq!(loaded.population.len(), 1);
+
+    std::fs::write(&path, format!("# version: {}\n{dna}\n", population::CHECKPOINT_VERSION + 1)).unwrap();
+    assert!(population::load_checkpoint(&path).is_err());
+
+    let _ = std::fs::remove_file(&path);
+}
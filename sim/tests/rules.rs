@@ -0,0 +1,1108 @@
+use bevy::prelude::{Transform, UVec2, Vec2, Vec3, With};
+use bevy::window::WindowId;
+use lib_natural_selection::{Aggregation, Chromosome};
+use lib_neural_network::LayerKind;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use sim::actions::{self, ActionEncoding};
+use sim::batch::{BatchedRun, WorldSplit};
+use sim::curiosity::{self, VisitField};
+use sim::curriculum::{Curriculum, Difficulty, Stage};
+use sim::field::ScalarField;
+use sim::dashboard::{self, Dashboard};
+use sim::freeze::frozen_copy;
+use sim::gym::GymEnv;
+use sim::headless::{headless_app, headless_app_with_population, population, run_generations, statistics};
+use sim::manifest::{self, RunManifest};
+use sim::noise::{self, SensorNoise};
+use sim::observers::CsvObserver;
+use sim::normalization::{InputNormalization, InputNormalizer};
+use sim::occupancy::KillzoneOccupancy;
+use sim::painting::RewardField;
+use sim::persistence::IoWorker;
+use sim::presets::Preset;
+use sim::render;
+use sim::restart::{self, RestartRun, RunRestarted};
+use sim::robustness;
+use sim::rules::{self, SimCore};
+use sim::sensors::Sensor;
+use sim::shutdown::ShutdownPlugin;
+use sim::tiers::TieredSeries;
+use sim::suite::{self, Scenario};
+use sim::wind::{self, Wind};
+use sim::watchdog::WatchdogResponse;
+use sim::states::SimState;
+use sim::{Config, Evolution, EvolutionTimer, ExternalSelection, Frozen, GenerationScores, InitialPopulation, KillZone, Nizm};
+
+fn genome() -> Chromosome {
+    let mut app = headless_app(Config { individuals: 1, ..Config::default() }, 7);
+    app.update();
+    let nizm = app.world.query::<&Nizm>().single(&app.world);
+    nizm.network.data().collect()
+}
+
+#[test]
+fn genomes_of_other_brains_are_rejected() {
+    let config = Config::default();
+    assert_eq!(sim::check_genome(&config, &genome()), Ok(()));
+
+    let wider = Config { hidden_neurons: config.hidden_neurons + 1, ..config.clone() };
+    let err = sim::check_genome(&wider, &genome()).unwrap_err();
+    assert!(err.starts_with("a brain has "), "{err}");
+    assert!(sim::check_genomes(&Config { recurrent: true, ..config }, [&genome()]).is_err());
+}
+
+#[test]
+fn activity_is_observed_for_fitting_genomes_only() {
+    let config = Config::default();
+    let activity = sim::activity(&config, 7, &genome()).unwrap();
+    assert!(activity.samples > 0);
+    assert_eq!(activity.firing.len(), 2);
+
+    assert!(sim::activity(&Config { clocks: config.clocks + 1, ..config }, 7, &genome()).is_err());
+}
+
+#[test]
+fn only_dense_brains_are_exported() {
+    let config = Config::default();
+    assert!(!sim::to_onnx(&config, &genome()).unwrap().is_empty());
+
+    let recurrent = Config { individuals: 1, recurrent: true, ..config };
+    let mut app = headless_app(recurrent.clone(), 7);
+    app.update();
+    let err = sim::to_onnx(&recurrent, &population(&mut app)[0]).unwrap_err();
+    assert!(err.contains("gru"), "{err}");
+    assert!(sim::to_onnx(&recurrent, &genome()).is_err());
+}
+
+#[test]
+fn pruning_keeps_the_large_weights() {
+    let config = Config::default();
+    let (pruned, connections, total) = sim::prune(&config, &genome(), 0.5).unwrap();
+    assert_eq!(pruned.len(), genome().len());
+    assert!(connections < total);
+    assert!(pruned.iter().zip(genome().iter()).all(|(pruned, gene)| *pruned == 0.0 || pruned == gene));
+
+    let err = sim::prune(&Config { hidden_neurons: 3, ..config }, &genome(), 0.5).unwrap_err();
+    assert!(err.starts_with("a brain has "), "{err}");
+}
+
+//a population of `individuals` distinct genomes
+fn genomes(individuals: usize) -> Vec<Chromosome> {
+    let mut app = headless_app(Config { individuals, ..Config::default() }, 3);
+    app.update();
+    population(&mut app)
+}
+
+//the scores of `genomes` in a world of their own, what the worlds of a batched run have to match
+fn world_scores(config: &Config, seed: u64, genomes: &[Chromosome]) -> GenerationScores {
+    let config = Config { individuals: genomes.len(), ..config.clone() };
+    let mut app = headless_app_with_population(config, seed, Some(InitialPopulation(genomes.to_vec())));
+    app.insert_resource(ExternalSelection::default());
+    run_generations(&mut app, 1);
+    app.world.resource_mut::<ExternalSelection>().scores.take().unwrap()
+}
+
+#[test]
+fn batched_worlds_evaluate_slices_of_the_population() {
+    let (config, genomes) = (Config { individuals: 8, ..Config::default() }, genomes(8));
+    let batched = |worlds| {
+        let mut run = BatchedRun::new(config.clone(), 7, worlds, WorldSplit::Slice, Some(InitialPopulation(genomes.clone()))).unwrap();
+        run.run_generation();
+        (run.fitness().to_vec(), run.statistics().clone())
+    };
+    let (one, _) = batched(1);
+    let (two, statistics) = batched(2);
+    let (first, second) = (world_scores(&config, 7, &genomes[..4]), world_scores(&config, 7, &genomes[4..]));
+
+    //the first world is the start of the single one, every world starts from the same seed
+    assert_eq!(one, world_scores(&config, 7, &genomes).fitness);
+    assert_eq!(two[..4], one[..4]);
+    assert_eq!(two, [first.fitness, second.fitness].concat());
+    assert_eq!(statistics.survivors_percentage, (first.survivors + second.survivors) as f32 / 8.0);
+    assert_eq!(statistics.killzone_occupancy, KillzoneOccupancy::of(&[first.occupancy, second.occupancy].concat()));
+}
+
+#[test]
+fn batched_worlds_with_seeds_of_their_own_are_trials() {
+    let config = Config { individuals: 4, trial_aggregation: Aggregation::Min, ..Config::default() };
+    let genomes = genomes(4);
+    let mut run = BatchedRun::new(config.clone(), 7, 2, WorldSplit::Seeds, Some(InitialPopulation(genomes.clone()))).unwrap();
+    run.run_generation();
+    let worlds = [world_scores(&config, 7, &genomes), world_scores(&config, 8, &genomes)];
+
+    let fitness: Vec<f32> = (0..4).map(|i| worlds[0].fitness[i].min(worlds[1].fitness[i])).collect();
+    assert_eq!(run.fitness(), fitness);
+    let occupancy: Vec<f32> = (0..4).map(|i| (worlds[0].occupancy[i] + worlds[1].occupancy[i]) / 2.0).collect();
+    assert_eq!(run.statistics().killzone_occupancy, KillzoneOccupancy::of(&occupancy));
+    assert_eq!(run.statistics().survivors_percentage, (worlds[0].survivors + worlds[1].survivors) as f32 / 8.0);
+    assert_eq!(run.population().len(), 4);
+
+    let wider = Config { hidden_neurons: 3, ..config };
+    assert!(BatchedRun::new(wider, 7, 2, WorldSplit::Seeds, Some(InitialPopulation(genomes))).is_err());
+}
+
+#[test]
+fn movement_stops_at_the_edges() {
+    let movement = rules::movement(Vec3::new(0.99, 0.0, 0.0), Vec3::new(1.0, 1.0, 0.0), 0.1, 1.0, Vec2::ZERO);
+    assert_eq!(movement.x, 0.0);
+    assert!(movement.y > 0.0);
+}
+
+#[test]
+fn movement_stops_when_the_budget_is_spent() {
+    let step = Vec3::new(0.3, 0.4, 0.0);
+    assert_eq!(rules::within_budget(step, 10.0, 0.0), step);
+    assert_eq!(rules::within_budget(step, 0.5, 1.0), step);
+    assert_eq!(rules::within_budget(step, 0.75, 1.0), step * 0.5);
+    assert_eq!(rules::within_budget(step, 1.0, 1.0), Vec3::ZERO);
+
+    let budgeted = Config { movement_budget: 0.01, ..Config::default() };
+    let idle = Config { fitness: sim::fitness::FitnessConfig { idle_penalty: 0.5, ..Default::default() }, ..budgeted.clone() };
+    //in a world it survives in, standing still for most of the generation costs it fitness
+    let seed = (0..20).find(|&seed| SimCore::evaluate(&budgeted, seed, &genome()) > 0.0).expect("a survivable world");
+    assert!(SimCore::evaluate(&idle, seed, &genome()) < SimCore::evaluate(&budgeted, seed, &genome()));
+}
+
+#[test]
+fn fitness_is_the_sum_of_its_terms() {
+    let config = Config::default();
+    let mut rng = ChaCha8Rng::seed_from_u64(5);
+    let mut core = SimCore::new(config.clone(), &mut rng, &Default::default(), &[genome()]);
+    while !core.finished() {
+        core.step(rules::FRAME, &mut rng);
+    }
+    let terms = |config: &Config| rules::fitness_terms(config, &core.bodies[0].nizm, core.bodies[0].position.x, None, &core.killzones);
+
+    assert_eq!(terms(&config).total(), core.fitness()[0]);
+    assert!(terms(&config).movement > 0.0);
+    let normalized = Config { fitness: sim::fitness::FitnessConfig { normalize_movement: true, movement_weight: 2.0, ..Default::default() }, ..config.clone() };
+    assert!((terms(&normalized).movement - 2.0 * terms(&config).movement / config.generation_time).abs() < 1e-5);
+}
+
+#[test]
+fn decoders_turn_outputs_into_actions() {
+    let decode = |encoding: ActionEncoding, outputs: [f32; 4], heading: &mut f32| encoding.decoder().decode(&outputs, heading);
+    let mut heading = 0.0;
+
+    let continuous = decode(ActionEncoding::Continuous, [1.0, 0.5, 0.0, 2.0], &mut heading);
+    assert!((continuous - Vec3::new(0.5, -1.0, 0.0).normalize()).length() < 1e-6);
+    assert_eq!(decode(ActionEncoding::Continuous, [-1.0; 4], &mut heading), Vec3::ZERO);
+
+    //the stronger output of an axis wins at full speed, unless neither is positive
+    assert_eq!(decode(ActionEncoding::EightDirections, [0.2, 0.1, 0.3, 0.9], &mut heading), Vec3::new(1.0, -1.0, 0.0).normalize());
+    assert_eq!(decode(ActionEncoding::EightDirections, [0.0, -0.5, 0.3, 0.3], &mut heading), Vec3::Y);
+    assert_eq!(decode(ActionEncoding::EightDirections, [-1.0; 4], &mut heading), Vec3::ZERO);
+    assert_eq!(heading, 0.0);
+
+    //thrust moves along the heading, turning keeps it between 0 and a full turn
+    assert_eq!(decode(ActionEncoding::ThrustTurn, [0.5, 0.0, 0.0, 1.0], &mut heading), Vec3::new(0.5, 0.0, 0.0));
+    let turned = decode(ActionEncoding::ThrustTurn, [2.0, 3.0, 0.0, 0.0], &mut heading);
+    assert_eq!(heading, std::f32::consts::FRAC_PI_4);
+    assert!((turned - Vec3::new(1.0, 1.0, 0.0).normalize()).length() < 1e-6);
+    decode(ActionEncoding::ThrustTurn, [0.0, 0.0, 1.0, 0.0], &mut heading);
+    decode(ActionEncoding::ThrustTurn, [0.0, 0.0, 1.0, 0.0], &mut heading);
+    assert!((heading - 7.0 * std::f32::consts::FRAC_PI_4).abs() < 1e-5, "{heading}");
+    assert_eq!(decode(ActionEncoding::ThrustTurn, [-1.0, 0.0, 0.0, 0.0], &mut heading), Vec3::ZERO);
+
+    for encoding in [ActionEncoding::Continuous, ActionEncoding::EightDirections, ActionEncoding::ThrustTurn] {
+        assert_eq!(encoding.to_string().parse::<ActionEncoding>(), Ok(encoding));
+    }
+    assert!("argmax".parse::<ActionEncoding>().is_err());
+}
+
+#[test]
+fn action_noise_anneals() {
+    let config = Config { action_noise: 0.5, action_noise_decay: 0.9, ..Config::default() };
+    assert_eq!(config.action_noise_at(0), 0.5);
+    assert!((config.action_noise_at(1) - 0.45).abs() < 1e-6);
+    assert!((config.action_noise_at(10) - 0.5 * 0.9f32.powi(10)).abs() < 1e-6);
+    assert!(config.action_noise_at(1000) < 1e-6);
+    assert_eq!(Config::default().action_noise_at(1000), 0.0);
+
+    let mut rng = ChaCha8Rng::seed_from_u64(3);
+    let action = Vec3::new(0.3, 0.4, 0.0);
+    assert!((0..100).all(|_| actions::perturb(&mut rng, action, 0.0) == action));
+    assert!((0..100).all(|_| {
+        let random = actions::perturb(&mut rng, action, 1.5);
+        random != action && (random.length() - 1.0).abs() < 1e-5
+    }));
+    //an epsilon is the share of the actions replaced
+    let replaced = (0..1000).filter(|_| actions::perturb(&mut rng, action, 0.25) != action).count();
+    assert!((200..300).contains(&replaced), "{replaced}");
+}
+
+#[test]
+fn curricula_advance_when_the_population_masters_a_stage() {
+    let stage = |killzones, advance_at| Stage { difficulty: Difficulty { killzones, ..Default::default() }, advance_at };
+    let mut curriculum = Curriculum::new(vec![stage(1, 2.0), stage(2, 3.0), stage(3, 1.0)]);
+    assert_eq!((curriculum.stage(), curriculum.stages(), curriculum.difficulty().killzones), (0, 3, 1));
+
+    assert!(!curriculum.advance(1.99));
+    assert!(curriculum.advance(2.0));
+    assert_eq!((curriculum.stage(), curriculum.difficulty().killzones), (1, 2));
+    //one stage per generation, however well it did
+    assert!(curriculum.advance(100.0));
+    assert_eq!(curriculum.stage(), 2);
+    //the last stage is kept forever
+    assert!(!curriculum.advance(100.0));
+    assert_eq!(curriculum.stage(), 2);
+
+    assert_eq!(Curriculum::new(vec![stage(1, 2.0), stage(2, 3.0)]).at_stage(7).stage(), 1);
+    let stages = Curriculum::default().stages();
+    assert!(!Curriculum::default().at_stage(stages - 1).advance(f32::MAX));
+}
+
+#[test]
+fn kill_zones_wear_down_health() {
+    let mut health = sim::Health::default();
+    assert!(!rules::damage(&mut health, 0.25, 1.0));
+    assert_eq!((health.health, health.died_at), (0.75, None));
+    assert!(rules::damage(&mut health, 1.0, 2.5));
+    assert_eq!((health.health, health.died_at), (0.0, Some(2.5)));
+    assert!(!health.is_alive());
+
+    //an individual dropped into a kill zone dies after a second, one outside lives on, neither
+    //moves without thinking
+    let config = Config { killzone_damage: 1.0, ..Config::default() };
+    let mut core = SimCore::new(config.clone(), &mut ChaCha8Rng::seed_from_u64(5), &Default::default(), &[genome(), genome()]);
+    core.killzones = vec![KillZone { min: 0.5, max: 1.0, velocity: 0.0 }];
+    core.bodies[0].position = Vec3::new(0.75, 0.0, 0.0);
+    core.bodies[1].position = Vec3::new(-0.5, 0.0, 0.0);
+    for _ in 0..(1.5 / rules::FRAME) as usize {
+        core.hold(rules::FRAME);
+    }
+
+    let died_at = core.bodies[0].health.as_ref().and_then(|health| health.died_at).expect("dead");
+    assert!((died_at - 1.0).abs() <= rules::FRAME, "{died_at}");
+    assert!(core.bodies[1].is_alive());
+    assert_eq!(core.survivors(), 1);
+    //the dead keep the share of the generation they lived, wherever they ended up
+    let fitness = core.fitness();
+    assert!((fitness[0] / (0.75 + 1.0) - died_at / config.generation_time).abs() < 1e-4, "{fitness:?}");
+}
+
+#[test]
+fn the_core_plays_a_generation_like_the_systems() {
+    for individuals in [1, 16] {
+        let (config, genomes) = (Config { individuals, ..Config::default() }, genomes(individuals));
+        let mut app = headless_app_with_population(config.clone(), 3, Some(InitialPopulation(genomes.clone())));
+        app.insert_resource(ExternalSelection::default());
+        //lays out the world without simulating a frame of it
+        app.add_state(SimState::Paused);
+        app.update();
+
+        let mut core = SimCore::new(config.clone(), &mut ChaCha8Rng::seed_from_u64(0), &config.difficulty(), &genomes);
+        core.environment = app.world.resource::<sim::environment::Environment>().clone();
+        core.killzones = app.world.query::<&KillZone>().iter(&app.world).map(|killzone| KillZone { ..*killzone }).collect();
+        let positions = app.world.query_filtered::<&Transform, With<Nizm>>().iter(&app.world).map(|transform| transform.translation).collect::<Vec<_>>();
+        for (body, position) in core.bodies.iter_mut().zip(positions) {
+            body.position = position;
+        }
+
+        app.world.resource_mut::<bevy::prelude::State<SimState>>().set(SimState::Running).unwrap();
+        app.update();
+        assert_eq!(app.world.resource::<EvolutionTimer>().0.elapsed_secs(), 0.0);
+        run_generations(&mut app, 1);
+        //without noise the brains draw nothing from the rng
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        while !core.finished() {
+            core.step(rules::FRAME, &mut rng);
+        }
+
+        let scores = app.world.resource_mut::<ExternalSelection>().scores.take().unwrap();
+        assert_eq!(scores.fitness, core.fitness());
+        assert_eq!(scores.survivors, core.survivors());
+    }
+}
+
+#[test]
+fn near_misses_keep_part_of_the_fitness() {
+    let killzones = [KillZone { min: -1.0, max: -0.5, velocity: 0.0 }, KillZone { min: 0.5, max: 1.0, velocity: 0.0 }];
+    let hard = sim::fitness::FitnessConfig::default();
+    assert_eq!((hard.survival(0.75, &killzones), hard.survival(0.45, &killzones)), (0.0, 1.0));
+
+    let fitness = sim::fitness::FitnessConfig { near_miss_margin: 0.1, ..Default::default() };
+    let survival = |x: f32| fitness.survival(x, &killzones);
+    //half at the edge, fading linearly over the margin on both sides of it
+    assert_eq!(survival(0.5), 0.5);
+    assert!((survival(0.45) - 0.75).abs() < 1e-5 && (survival(0.55) - 0.25).abs() < 1e-5);
+    assert!((survival(-0.45) - 0.75).abs() < 1e-5);
+    assert_eq!((survival(0.0), survival(0.3), survival(0.7), survival(0.75)), (1.0, 1.0, 0.0, 0.0));
+    //the closest kill zone decides
+    assert!(survival(0.42) < survival(0.0));
+    assert_eq!(fitness.survival(0.0, &[]), 1.0);
+}
+
+#[test]
+fn killzone_occupancy_is_spread_over_the_population() {
+    let occupancy = KillzoneOccupancy::of(&[0.5, 0.0, 1.0, 0.0]);
+    assert_eq!(occupancy, KillzoneOccupancy { mean: 0.375, min: 0.0, median: 0.25, max: 1.0, never: 0.5 });
+
+    let config = Config { individuals: 8, ..Config::default() };
+    let mut app = headless_app(config.clone(), 3);
+    run_generations(&mut app, 1);
+    let statistics = statistics(&mut app);
+    let occupancy = statistics.killzone_occupancy;
+    assert!((occupancy.mean - statistics.time_in_killzone / config.generation_time).abs() < 1e-5);
+    assert!(occupancy.min <= occupancy.median && occupancy.median <= occupancy.max && occupancy.max <= 1.0);
+}
+
+#[test]
+fn wind_pushes_idle_individuals() {
+    let config = Config { wind: Wind::Rotational, wind_strength: 0.5, ..Config::default() };
+    let wind = wind::wind_at(&config, Vec2::new(0.5, 0.0), 0.0);
+    assert_eq!(wind, Vec2::new(0.0, 0.25));
+    assert_eq!(rules::movement(Vec3::new(0.5, 0.0, 0.0), Vec3::ZERO, 0.1, 1.0, wind), Vec3::new(0.0, 0.025, 0.0));
+
+    let noise = Config { wind: Wind::Noise, ..config };
+    let gusts: Vec<_> = (0..10).map(|t| wind::wind_at(&noise, Vec2::ZERO, t as f32)).collect();
+    assert!(gusts.iter().all(|gust| gust.length() <= wind::max_wind(&noise)));
+    assert!(gusts.windows(2).any(|pair| pair[0] != pair[1]));
+}
+
+#[test]
+fn a_world_needs_a_killzone() {
+    let mut config = Config::default();
+    assert_eq!(config.set("killzones", "0"), Err("invalid value '0' for killzones".to_string()));
+    config.set("killzones", "3").unwrap();
+    assert_eq!(config.killzones, 3);
+}
+
+#[test]
+fn killzones_turn_around_at_the_edges() {
+    let environment = Default::default();
+    let mut killzone = KillZone { min: 0.5, max: 0.95, velocity: 1.0 };
+
+    rules::drift(&mut killzone, 0.1, &environment);
+    assert_eq!(killzone.velocity, -1.0);
+    assert_eq!(killzone.max, 0.95);
+
+    rules::drift(&mut killzone, 0.1, &environment);
+    assert!(killzone.max < 0.95);
+}
+
+#[test]
+fn evaluation_is_reproducible() {
+    let config = Config::default();
+    let genome = genome();
+
+    let fitness = SimCore::evaluate(&config, 3, &genome);
+    assert!(fitness >= 0.0);
+    assert_eq!(SimCore::evaluate(&config, 3, &genome), fitness);
+}
+
+#[test]
+fn ranges_scale_every_sense_to_the_same_span() {
+    let config = Config { input_normalization: InputNormalization::Ranges, ..Config::default() };
+    let mut core = SimCore::new(config.clone(), &mut ChaCha8Rng::seed_from_u64(2), &Default::default(), &[genome(), genome()]);
+    core.hold(rules::FRAME);
+
+    let mut observations = core.observations();
+    assert_eq!(observations[0].len(), GymEnv::OBSERVATIONS);
+    InputNormalizer::new(&config).normalize(&mut observations);
+    assert!(observations.iter().flatten().all(|sense| (-1.0..=1.0).contains(sense)));
+}
+
+#[test]
+fn sensor_lists_are_set_as_a_whole() {
+    let mut config = Config::default();
+    config.set("disabled_sensor", "position, can_move").unwrap();
+    config.set("enabled_sensor", "time,daylight").unwrap();
+    assert_eq!((config.disabled_sensors.as_slice(), config.enabled_sensors.as_slice()), ([Sensor::Position, Sensor::CanMove].as_slice(), [Sensor::Time, Sensor::Daylight].as_slice()));
+
+    config.set("disabled_sensor", "movement").unwrap();
+    assert_eq!(config.disabled_sensors, vec![Sensor::Movement]);
+    config.set("enabled_sensor", "").unwrap();
+    assert!(config.enabled_sensors.is_empty());
+    //a list with a typo changes nothing
+    assert!(config.set("disabled_sensor", "position,smell").unwrap_err().contains("smell"));
+    assert_eq!(config.disabled_sensors, vec![Sensor::Movement]);
+}
+
+#[test]
+fn sensors_change_the_inputs_of_the_brains() {
+    let config = Config {
+        individuals: 2,
+        disabled_sensors: vec![Sensor::Position, Sensor::CanMove],
+        enabled_sensors: vec![Sensor::Time],
+        ..Config::default()
+    };
+    let mut app = headless_app(config.clone(), 7);
+    app.update();
+    let genome: Chromosome = app.world.query::<&Nizm>().iter(&app.world).next().unwrap().network.data().collect();
+
+    let mut core = SimCore::new(config, &mut ChaCha8Rng::seed_from_u64(2), &Default::default(), &[genome]);
+    core.hold(2.5);
+    let observations = core.observations();
+    assert_eq!(observations[0].len(), GymEnv::OBSERVATIONS - 6 + 1);
+    //the time sense comes last
+    assert_eq!(observations[0].last(), Some(&0.25));
+}
+
+#[test]
+fn the_world_fades_out_at_night() {
+    let config = Config { individuals: 1, day_length: 4.0, enabled_sensors: vec![Sensor::Daylight], ..Config::default() };
+    let mut app = headless_app(config.clone(), 7);
+    app.update();
+    let genome: Chromosome = app.world.query::<&Nizm>().single(&app.world).network.data().collect();
+
+    let mut core = SimCore::new(config, &mut ChaCha8Rng::seed_from_u64(2), &Default::default(), &[genome]);
+    core.hold(2.0);
+    let observations = core.observations();
+    //blind at midnight but the sense of progress still works
+    assert_eq!(observations[0][..3], [0.0, 0.0, 0.25]);
+    assert_eq!(observations[0].last(), Some(&0.5));
+}
+
+#[test]
+fn evolved_sensor_noise_is_reproducible() {
+    let config = Config { individuals: 1, sensor_noise: SensorNoise::Evolved, sensor_noise_sigma: 0.5, ..Config::default() };
+    let mut app = headless_app(config.clone(), 7);
+    app.update();
+    let nizm = app.world.query::<&Nizm>().single(&app.world);
+    assert!(noise::sigma(&config, nizm) > 0.0);
+
+    let genome: Chromosome = nizm.network.data().collect();
+    let fitness = SimCore::evaluate(&config, 3, &genome);
+    assert_eq!(SimCore::evaluate(&config, 3, &genome), fitness);
+}
+
+#[test]
+fn clocks_replace_the_oscillator() {
+    let config = Config { individuals: 1, clocks: 2, disabled_sensors: vec![Sensor::Oscillator], ..Config::default() };
+    let mut app = headless_app(config.clone(), 7);
+    app.update();
+    let network = &app.world.query::<&Nizm>().single(&app.world).network;
+    assert_eq!((network.inputs(), network.clocks()), (GymEnv::OBSERVATIONS - 1, 2));
+
+    let genome: Chromosome = network.data().collect();
+    assert!(SimCore::evaluate(&config, 3, &genome) >= 0.0);
+}
+
+#[test]
+fn frozen_references_outlive_the_generations() {
+    let config = Config { individuals: 4, ..Config::default() };
+    let mut app = headless_app(config.clone(), 7);
+    app.update();
+    let (nizm, transform) = app.world.query::<(&Nizm, &Transform)>().iter(&app.world).next().unwrap();
+    let (genes, copy) = (nizm.network.data().collect::<Vec<_>>(), frozen_copy(&config, nizm, *transform));
+    app.world.spawn(copy);
+
+    run_generations(&mut app, 2);
+    let frozen = app.world.query_filtered::<&Nizm, With<Frozen>>().single(&app.world);
+    assert_eq!(frozen.network.data().collect::<Vec<_>>(), genes);
+    assert_eq!(population(&mut app).len(), 4);
+}
+
+#[test]
+fn painted_rewards_stay_where_they_are_painted() {
+    let mut field = RewardField::new(32);
+    field.paint(Vec2::new(0.5, 0.5), 0.1, 3.0, (-1.0, 1.0));
+    field.paint(Vec2::new(-0.5, 0.0), 0.1, -0.25, (-1.0, 1.0));
+
+    assert_eq!(field.sample(Vec2::new(0.5, 0.5)), 1.0);
+    assert_eq!(field.sample(Vec2::new(-0.5, 0.0)), -0.25);
+    assert_eq!(field.sample(Vec2::ZERO), 0.0);
+    assert_eq!(field.sample(Vec2::new(2.0, 0.0)), 0.0);
+    assert!(field.gradient(Vec2::new(0.4, 0.5)).x > 0.0);
+
+    let total = field.values().iter().sum::<f32>();
+    assert_eq!(field.sample(Vec2::new(0.5, 0.66)), 0.0);
+    field.diffuse(5.0, 0.1);
+    assert!((field.values().iter().sum::<f32>() - total).abs() < 1e-3);
+    assert!(field.sample(Vec2::new(0.5, 0.66)) > 0.0);
+
+    field.decay(1.0, 1.0);
+    field.clear();
+    assert_eq!(field, RewardField::new(32));
+}
+
+#[test]
+fn fields_interpolate_between_cell_centres() {
+    struct Heat;
+    let close = |a: f32, b: f32| (a - b).abs() < 1e-5;
+    //cells of 0.5 with their centres at -0.75, -0.25, 0.25 and 0.75
+    let mut field = ScalarField::<Heat>::new(4);
+    field.add(Vec2::new(-0.25, -0.25), 1.0);
+    field.add(Vec2::new(0.3, -0.4), 3.0);
+    field.add(Vec2::new(-1.0, -1.0), 4.0);
+    assert_eq!((field.index(Vec2::new(0.3, -0.4)), field.center(6)), (6, Vec2::new(0.25, -0.25)));
+
+    assert_eq!(field.sample(Vec2::new(-0.25, -0.25)), 1.0);
+    assert!(close(field.sample(Vec2::new(0.0, -0.25)), 2.0));
+    assert!(close(field.sample(Vec2::new(0.125, -0.25)), 2.5));
+    assert!(close(field.sample(Vec2::new(-0.25, 0.0)), 0.5));
+    assert!(close(field.sample(Vec2::new(0.0, 0.0)), 1.0));
+    //beyond the outermost centres the value of the edge is kept
+    assert_eq!(field.sample(Vec2::new(-0.9, -0.8)), 4.0);
+    assert_eq!(field.sample(Vec2::new(-5.0, -5.0)), 4.0);
+    assert_eq!(field.sample(Vec2::new(-0.75, -3.0)), 4.0);
+    assert_eq!(field.sample(Vec2::new(5.0, 5.0)), 0.0);
+
+    //the field rises by 4.0 per unit from the centre of one cell to the next, and is flat across
+    let gradient = field.gradient(Vec2::new(0.0, -0.25));
+    assert!(close(gradient.x, 4.0) && close(gradient.y, 0.0), "{gradient}");
+    assert_eq!(field.gradient(Vec2::new(5.0, 5.0)), Vec2::ZERO);
+
+    let mut decayed = field.clone();
+    decayed.decay(std::f32::consts::LN_2, 1.0);
+    assert!(decayed.values().iter().zip(field.values()).all(|(&decayed, &value)| close(decayed, value / 2.0)));
+    decayed.decay(0.0, 1.0);
+    assert!(close(decayed.sample(Vec2::new(-0.25, -0.25)), 0.5));
+
+    //diffusion only moves the values around, even at a rate that exchanges everything
+    let total = field.values().iter().sum::<f32>();
+    for (rate, delta) in [(1.0, 0.1), (100.0, 1.0)] {
+        let mut diffused = field.clone();
+        for _ in 0..10 {
+            diffused.diffuse(rate, delta);
+        }
+        assert!(close(diffused.values().iter().sum::<f32>(), total));
+        assert!(diffused.sample(Vec2::new(0.75, 0.75)) > 0.0);
+        assert!(diffused.values().iter().all(|&value| value >= 0.0));
+    }
+    let mut even = ScalarField::<Heat>::new(4);
+    (0..16).for_each(|index| even.add(even.center(index), 1.0));
+    even.diffuse(1.0, 1.0);
+    assert!(even.values().iter().all(|&value| value == 1.0));
+}
+
+#[test]
+fn curiosity_fades_with_the_visits() {
+    assert_eq!(curiosity::novelty(0.0), 1.0);
+    assert_eq!(curiosity::novelty(3.0), 0.5);
+
+    let mut visits = VisitField::new(4);
+    visits.add(Vec2::new(0.9, -0.9), 2.0);
+    visits.add(Vec2::new(3.0, -3.0), 1.0);
+    assert_eq!(visits.index(Vec2::new(0.9, -0.9)), 3);
+    assert_eq!(visits.values()[3], 3.0);
+
+    let config = Config { individuals: 4, ..Config::default() };
+    let mut app = headless_app(Config { fitness: sim::fitness::FitnessConfig { curiosity_weight: 1.0, ..config.fitness.clone() }, ..config }, 7);
+    app.update();
+    app.update();
+    let visited = app.world.resource::<VisitField>().values().iter().sum::<f32>();
+    assert!(visited > 0.0);
+}
+
+#[test]
+fn robustness_is_scored_over_the_worst_worlds() {
+    let config = Config::default();
+    let robustness = robustness::evaluate(&config, &genome(), 0..10, 3);
+    assert_eq!(robustness, robustness::evaluate(&config, &genome(), 0..10, 3));
+    assert_eq!(robustness.survival.len(), 10);
+    assert_eq!(robustness.histogram(4).iter().sum::<usize>(), 10);
+    assert_eq!(robustness.score(), robustness.worst_survival());
+    assert!(robustness.quantile(0.5) <= robustness.quantile(1.0));
+
+    let path = std::env::temp_dir().join(format!("robust_champion_{}.txt", std::process::id()));
+    let checkpoint = sim::population::Checkpoint { population: vec![genome()], robustness: Some(robustness.score()), ..Default::default() };
+    sim::population::save_checkpoint(&path, &checkpoint).unwrap();
+    let loaded = sim::population::load_checkpoint(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded.robustness, Some(robustness.score()));
+}
+
+#[test]
+fn the_watchdog_reseeds_a_population_of_clones() {
+    let clones = || Some(InitialPopulation(vec![genome(); 4]));
+    let config = Config { individuals: 4, mutation_chance: 0.0, ..Config::default() };
+    let distinct = |app: &mut bevy::prelude::App| {
+        let mut genomes: Vec<String> = population(app).iter().map(ToString::to_string).collect();
+        genomes.dedup();
+        genomes.len()
+    };
+
+    let mut app = headless_app_with_population(config.clone(), 7, clones());
+    run_generations(&mut app, 1);
+    assert_eq!(distinct(&mut app), 1);
+
+    let mut app = headless_app_with_population(Config { watchdog_response: WatchdogResponse::Reseed, ..config }, 7, clones());
+    run_generations(&mut app, 1);
+    assert_eq!(distinct(&mut app), 4);
+}
+
+#[test]
+fn config_changes_are_recorded_in_the_manifest() {
+    let config = Config { individuals: 4, ..Config::default() };
+    let mut changed = config.clone();
+    changed.set("movement_speed", "0.25").unwrap();
+    changed.set("movement_weight", "2").unwrap();
+    changed.set("spawn_region", "left").unwrap();
+    let changes: Vec<String> = manifest::config_changes(&config, &changed).iter().map(|change| change.name.clone()).collect();
+    assert_eq!(changes, ["movement_speed", "spawn_regions", "fitness.movement_weight"]);
+
+    let path = std::env::temp_dir().join(format!("run_manifest_{}.txt", std::process::id()));
+    let mut app = headless_app(config.clone(), 7);
+    app.insert_resource(RunManifest::create(&path, &config, 7).unwrap());
+    run_generations(&mut app, 1);
+    app.world.resource_mut::<Config>().set("mutation_chance", "0.5").unwrap();
+    app.update();
+
+    let recorded = app.world.resource::<RunManifest>().changes.clone();
+    let written = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(recorded[0].generation, 2);
+    assert!(written.ends_with(&format!("generation 2: mutation_chance {} -> 0.5\n", config.mutation_chance)));
+}
+
+#[test]
+fn the_console_refuses_changes_to_the_brains() {
+    let config = Config::default();
+    assert_eq!(manifest::console_change(&config, "mutation_chance", "0.2").unwrap().mutation_chance, 0.2);
+    for (name, value) in [("disabled_sensor", "position"), ("clocks", "2"), ("hidden_neurons", "8"), ("recurrent", "true"), ("sensor_noise", "evolved")] {
+        let err = manifest::console_change(&config, name, value).unwrap_err();
+        assert!(err.contains("changes the brains"), "{name}: {err}");
+    }
+}
+
+#[test]
+fn configs_refuse_values_a_run_can_not_start_with() {
+    assert_eq!(Config::default().check(), Ok(()));
+    for (name, value) in [("think_rate", "0"), ("think_rate", "-1"), ("think_rate", "inf"), ("generation_time", "0"), ("generation_time", "NaN"),
+                          ("mutation_chance", "1.5"), ("mutation_chance", "-0.1"), ("individuals", "0")] {
+        let mut config = Config::default();
+        let err = config.set(name, value).unwrap_err();
+        assert!(err.contains(name), "{name}={value}: {err}");
+        assert_eq!(format!("{config:?}"), format!("{:?}", Config::default()), "{name}={value}");
+    }
+    assert!(Config { think_rate: 0.0, ..Config::default() }.check().is_err());
+    let mut config = Config::default();
+    config.set("mutation_chance", "1").unwrap();
+    assert_eq!(config.mutation_chance, 1.0);
+}
+
+#[test]
+fn population_structures_refuse_what_they_can_not_breed_with() {
+    let layered = Config { age_layers: 3, ..Config::default() };
+    assert_eq!(layered.check(), Ok(()));
+    assert_eq!(Config { crowding: true, surrogate: "knn".parse().unwrap(), ..Config::default() }.check(), Ok(()));
+    assert!(Config { crowding: true, ..layered.clone() }.check().unwrap_err().contains("age_layers"));
+    assert!(Config { surrogate: "rbf".parse().unwrap(), fitness_bands: 2, ..Config::default() }.check().unwrap_err().contains("fitness_bands"));
+    let err = manifest::console_change(&layered, "crowding", "true").unwrap_err();
+    assert!(err.contains("crowding"), "{err}");
+}
+
+#[test]
+fn long_runs_are_aggregated_into_tiers() {
+    let mut series = TieredSeries::default();
+    (1..=250).for_each(|generation| series.push(generation, generation as f32));
+    assert_eq!(series.tier(10).len(), 25);
+    assert_eq!(series.at_most(100), series.tier(10));
+    let last = series.tier(100)[2];
+    assert_eq!((last.first, last.generations, last.min, last.mean, last.max), (201, 50, 201.0, 225.5, 250.0));
+
+    let path = std::env::temp_dir().join(format!("tiered_stats_{}.csv", std::process::id()));
+    let coarse = path.with_extension("2.csv");
+    let mut app = headless_app(Config { individuals: 4, ..Config::default() }, 7);
+    app.world.non_send_resource_mut::<Evolution>().add_csv_observer(CsvObserver::with_tiers(&path, &[1, 2]).unwrap());
+    run_generations(&mut app, 5);
+    let (every, pairs) = (std::fs::read_to_string(&path).unwrap(), std::fs::read_to_string(&coarse).unwrap());
+    let _ = (std::fs::remove_file(&path), std::fs::remove_file(&coarse));
+    assert_eq!(every.lines().count(), 6);
+    assert_eq!(pairs.lines().count(), 3);
+    assert!(pairs.starts_with("first_generation,last_generation,min_fitness_min,min_fitness_mean,min_fitness_max,"));
+}
+
+#[test]
+fn the_io_thread_writes_in_order_and_reports_errors() {
+    let path = std::env::temp_dir().join(format!("io_thread_stats_{}.csv", std::process::id()));
+    let worker = IoWorker::spawn();
+    let mut app = headless_app(Config { individuals: 4, ..Config::default() }, 7);
+    app.world.non_send_resource_mut::<Evolution>().add_csv_observer(CsvObserver::create(&path).unwrap().with_io(worker.handle()));
+    run_generations(&mut app, 3);
+    worker.handle().submit("nowhere", || Err(std::io::Error::other("disk full")));
+    worker.flush();
+
+    let written = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let generations: Vec<&str> = written.lines().skip(1).map(|line| line.split(',').next().unwrap()).collect();
+    assert_eq!(generations, ["0", "1", "2"]);
+    let completed = worker.completed();
+    assert_eq!(completed.len(), 4);
+    assert_eq!(completed[3].error.as_deref(), Some("disk full"));
+    assert_eq!(worker.pending(), 0);
+}
+
+#[test]
+fn a_panicking_write_does_not_stop_the_io_thread() {
+    let worker = IoWorker::spawn();
+    worker.handle().submit("broken", || panic!("the disk caught fire"));
+    worker.handle().submit("fine", || Ok(()));
+    worker.flush();
+
+    let completed = worker.completed();
+    assert_eq!(completed.iter().map(|job| job.label.as_str()).collect::<Vec<_>>(), ["broken", "fine"]);
+    assert_eq!((completed[0].error.as_deref(), completed[1].error.as_deref()), (Some("the write panicked"), None));
+    assert_eq!(worker.pending(), 0);
+}
+
+#[test]
+fn closing_the_app_saves_the_run() {
+    let path = std::env::temp_dir().join(format!("final_checkpoint_{}.txt", std::process::id()));
+    let manifest_path = path.with_extension("manifest");
+    let config = Config { individuals: 4, ..Config::default() };
+    let mut app = headless_app(config.clone(), 7);
+    app.insert_resource(RunManifest::create(&manifest_path, &config, 7).unwrap())
+        .add_plugin(ShutdownPlugin { checkpoint: Some(path.clone()) });
+    run_generations(&mut app, 2);
+    app.world.send_event(bevy::app::AppExit);
+    app.update();
+
+    let checkpoint = sim::population::load_checkpoint(&path).unwrap();
+    let manifest = std::fs::read_to_string(&manifest_path).unwrap();
+    let _ = (std::fs::remove_file(&path), std::fs::remove_file(&manifest_path));
+    let genomes = |population: &[Chromosome]| population.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(genomes(&checkpoint.population), genomes(&population(&mut app)));
+    assert!(checkpoint.resume.is_some());
+    assert!(manifest.lines().last().unwrap().starts_with("summary: run ended (window closed) after 2 generations"));
+}
+
+#[test]
+fn restarting_starts_a_fresh_run_from_the_seed() {
+    let path = std::env::temp_dir().join(format!("restart_manifest_{}.txt", std::process::id()));
+    let config = Config { individuals: 4, ..Config::default() };
+    let mut app = headless_app(config.clone(), 7);
+    app.insert_resource(RunManifest::create(&path, &config, 7).unwrap());
+    run_generations(&mut app, 2);
+    let genomes = |app: &mut bevy::prelude::App| population(app).iter().map(ToString::to_string).collect::<Vec<_>>();
+    let before = genomes(&mut app);
+
+    app.world.send_event(RestartRun { seed: Some(11) });
+    app.update();
+    let restarted = app.world.resource::<bevy::prelude::Events<RunRestarted>>().iter_current_update_events().cloned().collect::<Vec<_>>();
+    assert_eq!(restarted, [RunRestarted { run: 2, seed: 11 }]);
+    assert_eq!(statistics(&mut app).generation, 0);
+    assert_ne!(genomes(&mut app), before);
+    run_generations(&mut app, 2);
+    let first = (genomes(&mut app), statistics(&mut app).best_fitness);
+
+    app.world.send_event(RestartRun { seed: Some(11) });
+    app.update();
+    run_generations(&mut app, 2);
+    assert_eq!((genomes(&mut app), statistics(&mut app).best_fitness), first);
+
+    let manifest = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert!(manifest.lines().nth_back(1).unwrap().starts_with("summary: run ended (restarted) after 2 generations"));
+    let stats = std::path::Path::new("out/stats.csv");
+    assert_eq!(restart::run_path(stats, 1), stats);
+    assert_eq!(restart::run_path(stats, 3), std::path::Path::new("out/stats-run3.csv"));
+}
+
+#[test]
+fn only_running_and_replays_advance_the_simulation() {
+    let config = Config { individuals: 4, ..Config::default() };
+    let mut paused = headless_app(config.clone(), 7);
+    paused.add_state(SimState::Paused);
+    for _ in 0..20 {
+        paused.update();
+    }
+    assert_eq!(paused.world.resource::<EvolutionTimer>().0.elapsed_secs(), 0.0);
+
+    let mut replay = headless_app(config, 7);
+    replay.add_state(SimState::Replay);
+    run_generations(&mut replay, 1);
+    let genomes = |app: &mut bevy::prelude::App| population(app).iter().map(ToString::to_string).collect::<Vec<_>>();
+    let brains = genomes(&mut replay);
+    run_generations(&mut replay, 2);
+    assert_eq!(genomes(&mut replay), brains);
+}
+
+#[test]
+fn restarting_resizes_the_population() {
+    let mut app = headless_app(Config { individuals: 4, ..Config::default() }, 7);
+    run_generations(&mut app, 1);
+    app.world.resource_mut::<Config>().individuals = 6;
+    app.world.send_event(RestartRun { seed: Some(3) });
+    app.update();
+    assert_eq!(population(&mut app).len(), 6);
+    run_generations(&mut app, 1);
+
+    app.world.resource_mut::<Config>().individuals = 2;
+    app.world.send_event(RestartRun { seed: Some(3) });
+    app.update();
+    assert_eq!(population(&mut app).len(), 2);
+    run_generations(&mut app, 1);
+}
+
+#[test]
+fn panels_move_to_the_dashboard_while_it_is_open() {
+    let dashboard = Dashboard(WindowId::new());
+    assert_eq!(dashboard::panel_window(Some(&dashboard)), dashboard.0);
+    assert_eq!(dashboard::panel_window(None), WindowId::primary());
+}
+
+#[test]
+fn every_preset_runs() {
+    for preset in Preset::ALL {
+        assert_eq!(preset.to_string().parse(), Ok(preset));
+        let mut app = headless_app(Config { individuals: 4, ..preset.config() }, 7);
+        run_generations(&mut app, 1);
+        let killzones: Vec<f32> = app.world.query::<&KillZone>().iter(&app.world).map(|killzone| killzone.velocity).collect();
+        assert_eq!(killzones.len(), preset.config().killzones, "{preset}");
+        if preset == Preset::DriftingZones {
+            assert!(killzones.iter().all(|&velocity| velocity != 0.0));
+        }
+    }
+    assert!("labyrinth".parse::<Preset>().is_err());
+}
+
+#[test]
+fn recurrent_brains_remember_within_a_generation() {
+    let mut app = headless_app(Config { individuals: 4, recurrent: true, ..Config::default() }, 7);
+    run_generations(&mut app, 1);
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let mut nizms = app.world.query::<&Nizm>();
+    for nizm in nizms.iter(&app.world) {
+        assert_eq!(nizm.network.topology()[1].kind, LayerKind::Gru);
+        assert_eq!(nizm.network.state()[0].len(), Config::default().hidden_neurons);
+    }
+    assert!(nizms.iter(&app.world).any(|nizm| nizm.network.state()[0].iter().any(|&value| value != 0.0)));
+}
+
+#[test]
+fn the_benchmark_suite_is_the_same_every_time() {
+    let genomes = [genome(), genome()];
+    let scores = suite::run_suite(&Config::default(), &genomes, 1);
+    assert_eq!(scores.iter().map(|score| score.scenario).collect::<Vec<_>>(), Scenario::ALL);
+    assert!(scores.iter().all(|score| score.best_fitness >= score.mean_fitness));
+    assert_eq!(suite::run_suite(&Config::default(), &genomes, 1), scores);
+    assert_eq!(suite::scorecard(&scores).lines().count(), 1 + Scenario::ALL.len());
+}
+
+#[test]
+fn gym_episodes_end_with_the_fitness() {
+    let mut gym = GymEnv::new(Config::default(), Default::default(), 4, 5);
+    let observations = gym.reset();
+    assert_eq!(observations.len(), 4);
+    assert!(observations.iter().all(|observation| observation.len() == GymEnv::OBSERVATIONS));
+
+    let actions = vec![vec![1.0, 0.0, 0.0, 0.0, 1.0]; 4];
+    let mut step = gym.step(&actions);
+    let mut steps = 1;
+    while !step.done {
+        assert!(step.rewards.iter().all(|&reward| reward == 0.0));
+        step = gym.step(&actions);
+        steps += 1;
+    }
+
+    assert_eq!(steps, (Config::default().generation_time * Config::default().think_rate).ceil() as usize);
+    assert_eq!(step.rewards, gym.core().fitness());
+    assert!(step.rewards.iter().all(|&reward| reward >= 0.0));
+}
+
+#[test]
+fn letterboxing_keeps_the_world_centered_and_undistorted() {
+    assert_eq!(render::letterbox(1600, 900), (UVec2::new(350, 0), UVec2::new(900, 900)));
+    assert_eq!(render::letterbox(900, 1600), (UVec2::new(0, 350), UVec2::new(900, 900)));
+    assert_eq!(render::letterbox(800, 800), (UVec2::ZERO, UVec2::new(800, 800)));
+}
+
+#[cfg(feature = "zstd")]
+mod compressed_checkpoints {
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use sim::population::{self, Checkpoint};
+    use sim::ResumePoint;
+
+    use super::*;
+
+    //magic and version, then the stage, the count and the length of the chromosomes
+    const HEADER: usize = 4 + 24;
+    //two rngs, the generation and two timers
+    const RESUME_BYTES: usize = 2 * (32 + 8 + 16) + 4 + 2 * 8;
+
+    fn path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{name}_{}.zst", std::process::id()))
+    }
+
+    fn checkpoint(resume: bool) -> Checkpoint {
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        rng.set_word_pos(77);
+        Checkpoint {
+            population: vec![genome(), genome()],
+            curriculum_stage: Some(2),
+            resume: resume.then(|| ResumePoint {
+                rng,
+                environment_rng: ChaCha8Rng::seed_from_u64(6),
+                generation: 12,
+                evolution_timer: Duration::from_millis(1500),
+                think_timer: Duration::from_nanos(42),
+            }),
+            robustness: Some(0.75),
+        }
+    }
+
+    //the uncompressed bytes of `checkpoint` as the current version writes them
+    fn encoded(checkpoint: &Checkpoint) -> Vec<u8> {
+        let path = path("encoded");
+        population::save_checkpoint(&path, checkpoint).unwrap();
+        let bytes = zstd::decode_all(std::fs::read(&path).unwrap().as_slice()).unwrap();
+        let _ = std::fs::remove_file(&path);
+        bytes
+    }
+
+    fn load(name: &str, bytes: &[u8]) -> std::io::Result<Checkpoint> {
+        let path = path(name);
+        std::fs::write(&path, zstd::encode_all(bytes, population::COMPRESSION_LEVEL).unwrap()).unwrap();
+        let checkpoint = population::load_checkpoint(&path);
+        let _ = std::fs::remove_file(&path);
+        checkpoint
+    }
+
+    fn genomes(checkpoint: &Checkpoint) -> Vec<String> {
+        checkpoint.population.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn checkpoints_survive_a_round_trip() {
+        for resume in [false, true] {
+            let saved = checkpoint(resume);
+            let path = path("round_trip");
+            population::save_checkpoint(&path, &saved).unwrap();
+            let loaded = population::load_checkpoint(&path).unwrap();
+            let _ = std::fs::remove_file(&path);
+
+            assert_eq!(genomes(&loaded), genomes(&saved));
+            assert_eq!((loaded.curriculum_stage, loaded.robustness), (Some(2), Some(0.75)));
+            assert_eq!(loaded.resume.is_some(), resume);
+            if let (Some(loaded), Some(saved)) = (loaded.resume, saved.resume) {
+                assert_eq!((loaded.rng, loaded.environment_rng), (saved.rng, saved.environment_rng));
+                assert_eq!((loaded.generation, loaded.evolution_timer, loaded.think_timer), (12, saved.evolution_timer, saved.think_timer));
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_checkpoints_are_errors() {
+        let bytes = encoded(&checkpoint(true));
+        for len in [0, 3, 4, 20, HEADER, HEADER + 1, HEADER + 1 + RESUME_BYTES / 2, HEADER + 1 + RESUME_BYTES + 2, bytes.len() - 1] {
+            assert!(load("truncated", &bytes[..len]).is_err(), "{len} bytes");
+        }
+        let mut unknown = bytes.clone();
+        unknown[3] = b'9';
+        assert!(load("unknown", &unknown).is_err());
+    }
+
+    #[test]
+    fn checkpoints_without_genes_hold_nobody() {
+        let mut bytes = encoded(&checkpoint(false));
+        bytes.truncate(HEADER + 1 + 4);
+        bytes[HEADER - 8..HEADER].copy_from_slice(&0u64.to_le_bytes());
+        bytes[HEADER - 16..HEADER - 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(load("no_genes", &bytes).is_err());
+
+        bytes[HEADER - 16..HEADER - 8].copy_from_slice(&0u64.to_le_bytes());
+        assert!(load("empty", &bytes).unwrap().population.is_empty());
+    }
+
+    #[test]
+    fn older_checkpoints_are_migrated() {
+        //version 2 had no robustness after the resume point
+        for resume in [false, true] {
+            let mut bytes = encoded(&checkpoint(resume));
+            let robustness = HEADER + 1 + if resume { RESUME_BYTES } else { 0 };
+            bytes.drain(robustness..robustness + 4);
+            bytes[3] = b'2';
+            let migrated = load("version2", &bytes).unwrap();
+            assert_eq!(genomes(&migrated), genomes(&checkpoint(resume)));
+            assert_eq!((migrated.curriculum_stage, migrated.robustness), (Some(2), None));
+            assert_eq!(migrated.resume.map(|resume| resume.generation), resume.then_some(12));
+        }
+
+        //version 1 had neither the resume point nor the robustness
+        let mut bytes = encoded(&checkpoint(false));
+        bytes.drain(HEADER..HEADER + 5);
+        bytes[3] = b'1';
+        let migrated = load("version1", &bytes).unwrap();
+        assert_eq!(genomes(&migrated), genomes(&checkpoint(false)));
+        assert_eq!((migrated.curriculum_stage, migrated.robustness), (Some(2), None));
+        assert!(migrated.resume.is_none());
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod experiment_database {
+    use lib_natural_selection::{Observer, Statistics};
+    use sim::database::{ExperimentDatabase, GenerationFitness};
+
+    use super::*;
+
+    fn path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{name}_{}.sqlite", std::process::id()))
+    }
+
+    fn statistics(generation: usize, max_fitness: f32, avg_fitness: f32) -> Statistics {
+        Statistics {
+            generation,
+            min_fitness: 0.0,
+            max_fitness,
+            avg_fitness,
+            avg_fitness_stderr: 0.0,
+            invalid: 0,
+            invalid_offspring: 0,
+            takeover: 0.5,
+            distinct_genotypes: 2.0,
+            selection_intensity: 1.0,
+            avg_regularization: 0.0,
+            mutations: None,
+            provenance: None,
+            crowded_out: 0,
+            surrogate_error: None,
+        }
+    }
+
+    #[test]
+    fn runs_keep_their_champions_and_fitness_curves() {
+        let (first, second) = (statistics(0, 3.0, 1.5), statistics(1, 5.0, 2.5));
+        let mut database = ExperimentDatabase::create(":memory:", &Config::default(), 7).unwrap();
+        let (weak, strong): (Chromosome, Chromosome) = (genome(), genome().iter().map(|gene| gene + 1.0).collect());
+        database.on_generation_end(&first);
+        database.on_new_champion(first.generation, &weak, first.max_fitness);
+        database.on_generation_end(&second);
+        database.on_new_champion(second.generation, &strong, second.max_fitness);
+
+        let (best, fitness) = database.best_genome(database.run()).unwrap().unwrap();
+        assert_eq!((best.to_string(), fitness), (strong.to_string(), second.max_fitness));
+        let curve = |statistics: &Statistics| GenerationFitness {
+            generation: statistics.generation,
+            max_fitness: statistics.max_fitness,
+            avg_fitness: statistics.avg_fitness,
+        };
+        assert_eq!(database.fitness_curve(database.run()).unwrap(), vec![curve(&first), curve(&second)]);
+        //other runs see none of it
+        assert!(database.best_genome(database.run() + 1).unwrap().is_none());
+        assert!(database.fitness_curve(database.run() + 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn runs_are_added_to_the_same_file() {
+        let path = path("experiment_runs");
+        let _ = std::fs::remove_file(&path);
+        let first = ExperimentDatabase::create(&path, &Config::default(), 1).unwrap().run();
+        let second = ExperimentDatabase::create(&path, &Config::default(), u64::MAX).unwrap().run();
+        let database = ExperimentDatabase::open(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(database.runs().unwrap(), vec![first, second]);
+        assert_eq!(database.run(), second);
+    }
+
+    #[test]
+    fn newer_schemas_are_not_opened() {
+        let path = path("experiment_schema");
+        let _ = std::fs::remove_file(&path);
+        rusqlite::Connection::open(&path).unwrap().pragma_update(None, "user_version", 99).unwrap();
+        let opened = ExperimentDatabase::open(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(opened.err().unwrap().to_string().contains("version 99"));
+    }
+}